@@ -57,6 +57,7 @@ enum VariadicType {
   And,
   Or,
   StructExpression,
+  Coalesce,
 };
 enum UnaryType { Not, IsNull };
 typedef struct {
@@ -274,6 +275,7 @@ void visit_expr_variadic(void* data,
 DEFINE_VARIADIC(visit_expr_and, And)
 DEFINE_VARIADIC(visit_expr_or, Or)
 DEFINE_VARIADIC(visit_expr_struct_expr, StructExpression)
+DEFINE_VARIADIC(visit_expr_coalesce, Coalesce)
 #undef DEFINE_VARIADIC
 
 void visit_opaque_expr(
@@ -404,6 +406,7 @@ ExpressionItemList construct_expression(SharedExpression* expression) {
     .visit_minus = visit_expr_minus,
     .visit_multiply = visit_expr_multiply,
     .visit_divide = visit_expr_divide,
+    .visit_coalesce = visit_expr_coalesce,
     .visit_column = visit_expr_column,
     .visit_struct_expr = visit_expr_struct_expr,
     .visit_opaque_pred = visit_opaque_pred,
@@ -450,6 +453,7 @@ ExpressionItemList construct_predicate(SharedPredicate* predicate) {
     .visit_minus = visit_expr_minus,
     .visit_multiply = visit_expr_multiply,
     .visit_divide = visit_expr_divide,
+    .visit_coalesce = visit_expr_coalesce,
     .visit_column = visit_expr_column,
     .visit_struct_expr = visit_expr_struct_expr,
     .visit_opaque_pred = visit_opaque_pred,
@@ -6,7 +6,7 @@ use delta_kernel::expressions::{
     ArrayData, BinaryExpression, BinaryExpressionOp, BinaryPredicate, BinaryPredicateOp,
     Expression, JunctionPredicate, JunctionPredicateOp, MapData, OpaqueExpression,
     OpaqueExpressionOpRef, OpaquePredicate, OpaquePredicateOpRef, Predicate, Scalar, StructData,
-    UnaryPredicate, UnaryPredicateOp,
+    UnaryPredicate, UnaryPredicateOp, VariadicExpression, VariadicExpressionOp,
 };
 
 use crate::expressions::{
@@ -19,6 +19,8 @@ type VisitUnaryFn = extern "C" fn(data: *mut c_void, sibling_list_id: usize, chi
 type VisitBinaryFn = extern "C" fn(data: *mut c_void, sibling_list_id: usize, child_list_id: usize);
 type VisitJunctionFn =
     extern "C" fn(data: *mut c_void, sibling_list_id: usize, child_list_id: usize);
+type VisitVariadicFn =
+    extern "C" fn(data: *mut c_void, sibling_list_id: usize, child_list_id: usize);
 
 /// The [`EngineExpressionVisitor`] defines a visitor system to allow engines to build their own
 /// representation of a kernel expression or predicate.
@@ -159,6 +161,9 @@ pub struct EngineExpressionVisitor {
     /// Visits the `Divide` binary operator belonging to the list identified by `sibling_list_id`.
     /// The operands will be in a _two_ item list identified by `child_list_id`
     pub visit_divide: VisitBinaryFn,
+    /// Visits the `Coalesce` variadic operator belonging to the list identified by
+    /// `sibling_list_id`. The operands will be in a list identified by `child_list_id`
+    pub visit_coalesce: VisitVariadicFn,
     /// Visits the `column` belonging to the list identified by `sibling_list_id`.
     pub visit_column:
         extern "C" fn(data: *mut c_void, sibling_list_id: usize, name: KernelStringSlice),
@@ -479,6 +484,16 @@ fn visit_expression_impl(
             };
             visit_fn(visitor.data, sibling_list_id, child_list_id);
         }
+        Expression::Variadic(VariadicExpression { op, exprs }) => {
+            let child_list_id = call!(visitor, make_field_list, exprs.len());
+            for expr in exprs {
+                visit_expression_impl(visitor, expr, child_list_id);
+            }
+            let visit_fn = match op {
+                VariadicExpressionOp::Coalesce => visitor.visit_coalesce,
+            };
+            visit_fn(visitor.data, sibling_list_id, child_list_id);
+        }
         Expression::Opaque(OpaqueExpression { op, exprs }) => {
             visit_expression_opaque(visitor, op, exprs, sibling_list_id)
         }
@@ -12,6 +12,15 @@ use delta_kernel_derive::ToSchema;
 use crate::utils::require;
 use crate::{DeltaResult, Error, StorageHandler};
 
+/// Magic number at the start of a deletion vector serialized in the portable `RoaringBitmapArray`
+/// format.
+const PORTABLE_ROARING_BITMAP_MAGIC: u32 = 1681511377;
+/// Magic number at the start of a deletion vector serialized in the native `RoaringBitmapArray`
+/// format.
+const NATIVE_ROARING_BITMAP_MAGIC: u32 = 1681511376;
+/// Version of the deletion vector file format, stored in the first byte of the file.
+const DV_FILE_FORMAT_VERSION: u8 = 1;
+
 #[derive(Debug, Clone, PartialEq, Eq, ToSchema)]
 #[cfg_attr(test, derive(serde::Serialize), serde(rename_all = "camelCase"))]
 pub struct DeletionVectorDescriptor {
@@ -113,9 +122,11 @@ impl DeletionVectorDescriptor {
                     .map_err(|_| Error::deletion_vector("Failed to decode DV"))?;
                 let magic = slice_to_u32(&byte_slice[0..4], Endian::Little)?;
                 match magic {
-                    1681511377 => RoaringTreemap::deserialize_from(&byte_slice[4..])
-                        .map_err(|err| Error::DeletionVector(err.to_string())),
-                    1681511376 => {
+                    PORTABLE_ROARING_BITMAP_MAGIC => {
+                        RoaringTreemap::deserialize_from(&byte_slice[4..])
+                            .map_err(|err| Error::DeletionVector(err.to_string()))
+                    }
+                    NATIVE_ROARING_BITMAP_MAGIC => {
                         todo!("Don't support native serialization in inline bitmaps yet");
                     }
                     _ => Err(Error::DeletionVector(format!("Invalid magic {magic}"))),
@@ -137,7 +148,7 @@ impl DeletionVectorDescriptor {
                     .map_err(|err| Error::DeletionVector(err.to_string()))?;
                 let version = u8::from_be_bytes(version_buf);
                 require!(
-                    version == 1,
+                    version == DV_FILE_FORMAT_VERSION,
                     Error::DeletionVector(format!("Invalid version: {version}"))
                 );
 
@@ -153,7 +164,7 @@ impl DeletionVectorDescriptor {
                 );
                 let magic = read_u32(&mut cursor, Endian::Little)?;
                 require!(
-                    magic == 1681511377,
+                    magic == PORTABLE_ROARING_BITMAP_MAGIC,
                     Error::DeletionVector(format!("Invalid magic: {magic}"))
                 );
 
@@ -183,6 +194,110 @@ impl DeletionVectorDescriptor {
     ) -> DeltaResult<Vec<u64>> {
         Ok(self.read(storage, parent)?.into_iter().collect())
     }
+
+    /// Create a descriptor that stores `treemap` inline in the log (storage type `i`). Inline
+    /// deletion vectors avoid an extra file, but make the log larger, so they are best suited to
+    /// deletion vectors with few set bits.
+    pub fn try_new_inline(treemap: &RoaringTreemap) -> DeltaResult<Self> {
+        let mut dv_data = serialize_treemap(treemap)?;
+        let size_in_bytes = dv_data.len();
+        // z85 encodes 4 bytes at a time. Other Delta implementations expect the data to be padded
+        // with zeros (rather than using a shorter encoding for the final bytes), and truncate the
+        // decoded data to `sizeInBytes`.
+        dv_data.resize(size_in_bytes.next_multiple_of(4), 0);
+        Ok(Self {
+            storage_type: "i".to_string(),
+            path_or_inline_dv: z85::encode(&dv_data),
+            offset: None,
+            size_in_bytes: dv_size(size_in_bytes)?,
+            cardinality: treemap.len() as i64,
+        })
+    }
+}
+
+/// Writes deletion vectors to a new deletion vector file in the table root. Any number of deletion
+/// vectors can be stored in the same file: each call to [`append`] returns a descriptor (with
+/// storage type `u`) pointing at its deletion vector in the file. The file is written by
+/// [`finish`], which must be called before committing any action that references the returned
+/// descriptors.
+///
+/// [`append`]: Self::append
+/// [`finish`]: Self::finish
+// The file starts with a version byte, followed by the deletion vectors. Each deletion vector is
+// the (big endian) size of its data, the data itself (the little endian magic and the serialized
+// bitmap), and a (big endian) CRC32 checksum of the data.
+#[derive(Debug)]
+pub struct DeletionVectorWriter {
+    path: Url,
+    path_or_inline_dv: String,
+    buffer: Vec<u8>,
+}
+
+impl DeletionVectorWriter {
+    /// Create a writer for a new deletion vector file with a random name in `table_root`.
+    pub fn try_new(table_root: &Url) -> DeltaResult<Self> {
+        let uuid = uuid::Uuid::new_v4();
+        let path = table_root.join(&format!("deletion_vector_{uuid}.bin"))?;
+        Ok(Self {
+            path,
+            path_or_inline_dv: z85::encode(uuid.as_bytes()),
+            buffer: vec![DV_FILE_FORMAT_VERSION],
+        })
+    }
+
+    /// The location of the deletion vector file.
+    pub fn path(&self) -> &Url {
+        &self.path
+    }
+
+    /// Returns `true` if no deletion vector has been appended to the file.
+    pub fn is_empty(&self) -> bool {
+        self.buffer.len() == 1
+    }
+
+    /// Append `treemap` to the file, and return the descriptor of the new deletion vector.
+    pub fn append(&mut self, treemap: &RoaringTreemap) -> DeltaResult<DeletionVectorDescriptor> {
+        let offset = self
+            .buffer
+            .len()
+            .try_into()
+            .map_err(|_| Error::deletion_vector("Deletion vector file is too large"))?;
+        let dv_data = serialize_treemap(treemap)?;
+        let size_in_bytes = dv_size(dv_data.len())?;
+        self.buffer
+            .extend_from_slice(&(size_in_bytes as u32).to_be_bytes());
+        self.buffer.extend_from_slice(&dv_data);
+        self.buffer
+            .extend_from_slice(&crc32fast::hash(&dv_data).to_be_bytes());
+        Ok(DeletionVectorDescriptor {
+            storage_type: "u".to_string(),
+            path_or_inline_dv: self.path_or_inline_dv.clone(),
+            offset: Some(offset),
+            size_in_bytes,
+            cardinality: treemap.len() as i64,
+        })
+    }
+
+    /// Write the deletion vector file. Fails if the file already exists.
+    pub fn finish(self, storage: &dyn StorageHandler) -> DeltaResult<()> {
+        storage.write_file(&self.path, self.buffer.into(), false)
+    }
+}
+
+/// Serialize a treemap as deletion vector data: the (little endian) magic followed by the bitmap in
+/// the portable `RoaringBitmapArray` format.
+fn serialize_treemap(treemap: &RoaringTreemap) -> DeltaResult<Vec<u8>> {
+    let mut dv_data = Vec::with_capacity(4 + treemap.serialized_size());
+    dv_data.extend_from_slice(&PORTABLE_ROARING_BITMAP_MAGIC.to_le_bytes());
+    treemap
+        .serialize_into(&mut dv_data)
+        .map_err(|err| Error::DeletionVector(err.to_string()))?;
+    Ok(dv_data)
+}
+
+fn dv_size(size: usize) -> DeltaResult<i32> {
+    size.try_into()
+        .map_err(|_| Error::deletion_vector("Deletion vector is too large"))
 }
 
 enum Endian {
@@ -284,6 +399,7 @@ pub fn split_vector(
 mod tests {
     use std::path::PathBuf;
 
+    use itertools::Itertools as _;
     use roaring::RoaringTreemap;
 
     use crate::{engine::sync::SyncEngine, Engine};
@@ -451,4 +567,107 @@ mod tests {
         assert_eq!(row_idx.len(), 6);
         assert_eq!(&row_idx, &[3, 4, 7, 11, 18, 29]);
     }
+
+    fn dv_short() -> DeletionVectorDescriptor {
+        DeletionVectorDescriptor {
+            storage_type: "u".to_string(),
+            path_or_inline_dv: "U5OWRz5k%CFT.Td}yCPW".to_string(),
+            offset: Some(1),
+            size_in_bytes: 38,
+            cardinality: 3,
+        }
+    }
+
+    #[test]
+    fn test_inline_round_trip() {
+        let inline = dv_inline();
+        let sync_engine = SyncEngine::new();
+        let storage = sync_engine.storage_handler();
+        let parent = Url::parse("http://not.used").unwrap();
+        let tree_map = inline.read(storage.clone(), &parent).unwrap();
+        assert_eq!(
+            DeletionVectorDescriptor::try_new_inline(&tree_map).unwrap(),
+            inline
+        );
+
+        // data that isn't a multiple of 4 bytes is zero-padded before encoding
+        let tree_map = RoaringTreemap::from_iter([5]);
+        let dv = DeletionVectorDescriptor::try_new_inline(&tree_map).unwrap();
+        assert_eq!(dv.storage_type, "i");
+        assert_eq!(dv.offset, None);
+        assert_eq!(dv.size_in_bytes, 34);
+        assert_eq!(dv.path_or_inline_dv.len(), 45);
+        assert_eq!(dv.cardinality, 1);
+        assert_eq!(dv.read(storage, &parent).unwrap(), tree_map);
+    }
+
+    #[test]
+    fn test_writer_round_trip() {
+        let sync_engine = SyncEngine::new();
+        let storage = sync_engine.storage_handler();
+        for (table, golden_dv) in [
+            ("table-with-dv-small", dv_example()),
+            ("with-short-dv", dv_short()),
+        ] {
+            let path = std::fs::canonicalize(PathBuf::from(format!("./tests/data/{table}/")));
+            let golden_parent = url::Url::from_directory_path(path.unwrap()).unwrap();
+            let tree_map = golden_dv.read(storage.clone(), &golden_parent).unwrap();
+
+            let tmp_dir = tempfile::tempdir().unwrap();
+            let parent = url::Url::from_directory_path(tmp_dir.path()).unwrap();
+            let mut writer = DeletionVectorWriter::try_new(&parent).unwrap();
+            assert!(writer.is_empty());
+            let dv = writer.append(&tree_map).unwrap();
+            assert!(!writer.is_empty());
+            let dv_path = writer.path().clone();
+            writer.finish(storage.as_ref()).unwrap();
+
+            // the written file is identical to the golden one
+            assert_eq!(dv.absolute_path(&parent).unwrap().unwrap(), dv_path);
+            assert_eq!(dv.storage_type, golden_dv.storage_type);
+            assert_eq!(dv.offset, golden_dv.offset);
+            assert_eq!(dv.size_in_bytes, golden_dv.size_in_bytes);
+            assert_eq!(dv.cardinality, golden_dv.cardinality);
+            let golden_path = golden_dv.absolute_path(&golden_parent).unwrap().unwrap();
+            assert_eq!(
+                std::fs::read(dv_path.path()).unwrap(),
+                std::fs::read(golden_path.path()).unwrap()
+            );
+            assert_eq!(dv.read(storage.clone(), &parent).unwrap(), tree_map);
+        }
+    }
+
+    #[test]
+    fn test_writer_multiple_dvs() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let parent = url::Url::from_directory_path(tmp_dir.path()).unwrap();
+        let sync_engine = SyncEngine::new();
+        let storage = sync_engine.storage_handler();
+
+        let tree_maps = [
+            RoaringTreemap::from_iter([0, 9]),
+            RoaringTreemap::from_iter([3, 4, 7, 11, 18, 29]),
+            RoaringTreemap::from_iter([1 << 33, (1 << 33) + 5]),
+        ];
+        let mut writer = DeletionVectorWriter::try_new(&parent).unwrap();
+        let dvs: Vec<_> = tree_maps
+            .iter()
+            .map(|tree_map| writer.append(tree_map).unwrap())
+            .collect();
+        writer.finish(storage.as_ref()).unwrap();
+
+        // each dv starts after the previous one's size, data and checksum
+        assert_eq!(dvs[0].offset, Some(1));
+        for (prev, dv) in dvs.iter().tuple_windows() {
+            assert_eq!(
+                dv.offset,
+                Some(prev.offset.unwrap() + 4 + prev.size_in_bytes + 4)
+            );
+            assert_eq!(dv.path_or_inline_dv, prev.path_or_inline_dv);
+        }
+        for (dv, tree_map) in dvs.iter().zip(&tree_maps) {
+            assert_eq!(dv.cardinality, tree_map.len() as i64);
+            assert_eq!(&dv.read(storage.clone(), &parent).unwrap(), tree_map);
+        }
+    }
 }
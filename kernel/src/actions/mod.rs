@@ -8,13 +8,20 @@ use std::str::FromStr;
 use std::sync::{Arc, LazyLock};
 
 use self::deletion_vector::DeletionVectorDescriptor;
-use crate::schema::{SchemaRef, StructField, StructType, ToSchema as _};
+use crate::expressions::{ArrayData, MapData, Scalar};
+use crate::schema::{
+    ArrayType, DataType, MapType, SchemaRef, StructField, StructType, ToSchema as _,
+};
 use crate::table_features::{
-    ReaderFeature, WriterFeature, SUPPORTED_READER_FEATURES, SUPPORTED_WRITER_FEATURES,
+    ReaderFeature, WriterFeature, LEGACY_WRITER_FEATURES, SUPPORTED_READER_FEATURES,
+    SUPPORTED_WRITER_FEATURES,
 };
 use crate::table_properties::TableProperties;
 use crate::utils::require;
-use crate::{DeltaResult, EngineData, Error, FileMeta, RowVisitor as _};
+use crate::{
+    DeltaResult, Engine, EngineData, Error, EvaluationHandlerExtension as _, FileMeta,
+    IntoEngineData, RowVisitor as _,
+};
 
 use url::Url;
 use visitors::{MetadataVisitor, ProtocolVisitor};
@@ -80,6 +87,13 @@ static LOG_SCHEMA: LazyLock<SchemaRef> = LazyLock::new(|| {
     ]))
 });
 
+static LOG_REMOVE_SCHEMA: LazyLock<SchemaRef> = LazyLock::new(|| {
+    Arc::new(StructType::new([StructField::nullable(
+        REMOVE_NAME,
+        Remove::to_schema(),
+    )]))
+});
+
 static LOG_COMMIT_INFO_SCHEMA: LazyLock<SchemaRef> = LazyLock::new(|| {
     Arc::new(StructType::new([StructField::nullable(
         COMMIT_INFO_NAME,
@@ -94,6 +108,20 @@ static LOG_TXN_SCHEMA: LazyLock<SchemaRef> = LazyLock::new(|| {
     )]))
 });
 
+static LOG_METADATA_SCHEMA: LazyLock<SchemaRef> = LazyLock::new(|| {
+    Arc::new(StructType::new([StructField::nullable(
+        METADATA_NAME,
+        Metadata::to_schema(),
+    )]))
+});
+
+static LOG_PROTOCOL_SCHEMA: LazyLock<SchemaRef> = LazyLock::new(|| {
+    Arc::new(StructType::new([StructField::nullable(
+        PROTOCOL_NAME,
+        Protocol::to_schema(),
+    )]))
+});
+
 static LOG_DOMAIN_METADATA_SCHEMA: LazyLock<SchemaRef> = LazyLock::new(|| {
     Arc::new(StructType::new([StructField::nullable(
         DOMAIN_METADATA_NAME,
@@ -111,6 +139,10 @@ pub(crate) fn get_log_add_schema() -> &'static SchemaRef {
     &LOG_ADD_SCHEMA
 }
 
+pub(crate) fn get_log_remove_schema() -> &'static SchemaRef {
+    &LOG_REMOVE_SCHEMA
+}
+
 pub(crate) fn get_log_commit_info_schema() -> &'static SchemaRef {
     &LOG_COMMIT_INFO_SCHEMA
 }
@@ -119,6 +151,14 @@ pub(crate) fn get_log_txn_schema() -> &'static SchemaRef {
     &LOG_TXN_SCHEMA
 }
 
+pub(crate) fn get_log_metadata_schema() -> &'static SchemaRef {
+    &LOG_METADATA_SCHEMA
+}
+
+pub(crate) fn get_log_protocol_schema() -> &'static SchemaRef {
+    &LOG_PROTOCOL_SCHEMA
+}
+
 pub(crate) fn get_log_domain_metadata_schema() -> &'static SchemaRef {
     &LOG_DOMAIN_METADATA_SCHEMA
 }
@@ -221,6 +261,25 @@ impl Metadata {
         &self.partition_columns
     }
 
+    /// Create the metadata of a new table with a random id.
+    pub(crate) fn try_new(
+        schema: &StructType,
+        partition_columns: Vec<String>,
+        configuration: HashMap<String, String>,
+        created_time: i64,
+    ) -> DeltaResult<Self> {
+        Ok(Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            name: None,
+            description: None,
+            format: Format::default(),
+            schema_string: serde_json::to_string(schema)?,
+            partition_columns,
+            created_time: Some(created_time),
+            configuration,
+        })
+    }
+
     /// Parse the metadata configuration HashMap<String, String> into a TableProperties struct.
     /// Note that parsing is infallible -- any items that fail to parse are simply propagated
     /// through to the `TableProperties.unknown_properties` field.
@@ -252,6 +311,64 @@ pub(crate) struct Protocol {
     writer_features: Option<Vec<WriterFeature>>,
 }
 
+impl IntoEngineData for Protocol {
+    fn into_engine_data(
+        self,
+        schema: SchemaRef,
+        engine: &dyn Engine,
+    ) -> DeltaResult<Box<dyn EngineData>> {
+        let values = [
+            self.min_reader_version.into(),
+            self.min_writer_version.into(),
+            features_to_scalar(self.reader_features)?,
+            features_to_scalar(self.writer_features)?,
+        ];
+        engine.evaluation_handler().create_one(schema, &values)
+    }
+}
+
+impl IntoEngineData for Metadata {
+    fn into_engine_data(
+        self,
+        schema: SchemaRef,
+        engine: &dyn Engine,
+    ) -> DeltaResult<Box<dyn EngineData>> {
+        let string_map = |map: HashMap<String, String>| -> DeltaResult<Scalar> {
+            let map_type = MapType::new(DataType::STRING, DataType::STRING, false);
+            Ok(Scalar::Map(MapData::try_new(map_type, map)?))
+        };
+        let partition_columns = ArrayData::try_new(
+            ArrayType::new(DataType::STRING, false),
+            self.partition_columns,
+        )?;
+        // NB: the order of the values must match the leaves of the metadata schema
+        let values = [
+            self.id.into(),
+            self.name.into(),
+            self.description.into(),
+            self.format.provider.into(),
+            string_map(self.format.options)?,
+            self.schema_string.into(),
+            Scalar::Array(partition_columns),
+            self.created_time.into(),
+            string_map(self.configuration)?,
+        ];
+        engine.evaluation_handler().create_one(schema, &values)
+    }
+}
+
+// convert optional table features into an array scalar (or null)
+fn features_to_scalar<T: ToString>(features: Option<Vec<T>>) -> DeltaResult<Scalar> {
+    let array_type = ArrayType::new(DataType::STRING, false);
+    match features {
+        Some(features) => {
+            let features = features.iter().map(ToString::to_string);
+            Ok(Scalar::Array(ArrayData::try_new(array_type, features)?))
+        }
+        None => Ok(Scalar::Null(array_type.into())),
+    }
+}
+
 fn parse_features<T>(features: Option<impl IntoIterator<Item = impl ToString>>) -> Option<Vec<T>>
 where
     T: FromStr,
@@ -348,6 +465,49 @@ impl Protocol {
             .is_some_and(|features| features.contains(feature))
     }
 
+    /// Returns a copy of this protocol that also supports `feature` (and its reader feature, if it
+    /// has one). A protocol with legacy versions is upgraded to writer version 7, listing the
+    /// legacy features its writer version implies.
+    pub(crate) fn with_writer_feature(&self, feature: WriterFeature) -> DeltaResult<Protocol> {
+        let mut writer_features = match self.writer_features() {
+            Some(features) => features.to_vec(),
+            None => LEGACY_WRITER_FEATURES
+                .iter()
+                .filter(|legacy| {
+                    legacy
+                        .legacy_writer_version()
+                        .is_some_and(|version| version <= self.min_writer_version)
+                })
+                .cloned()
+                .collect(),
+        };
+        if !writer_features.contains(&feature) {
+            writer_features.push(feature.clone());
+        }
+
+        let (min_reader_version, reader_features) = match feature.reader_feature() {
+            Some(reader_feature) => {
+                let mut reader_features = match self.reader_features() {
+                    Some(features) => features.to_vec(),
+                    // column mapping is the only feature implied by legacy reader version 2
+                    None if self.min_reader_version == 2 => vec![ReaderFeature::ColumnMapping],
+                    None => vec![],
+                };
+                if !reader_features.contains(&reader_feature) {
+                    reader_features.push(reader_feature);
+                }
+                (3, Some(reader_features))
+            }
+            None => (self.min_reader_version, self.reader_features.clone()),
+        };
+        Protocol::try_new(
+            min_reader_version,
+            7,
+            reader_features,
+            Some(writer_features),
+        )
+    }
+
     /// Check if reading a table with this protocol is supported. That is: does the kernel support
     /// the specified protocol reader version and all enabled reader features? If yes, returns unit
     /// type, otherwise will return an error.
@@ -394,12 +554,15 @@ impl Protocol {
                 ))
             }
             None => {
-                // no features, we currently only support version 1 or 2 in this case
+                // no features: legacy writer versions imply the features of all lower versions.
+                // Implied features that the kernel can't write must not be in use, which is
+                // checked by the table configuration.
                 require!(
-                    self.min_writer_version == 1 || self.min_writer_version == 2,
-                    Error::unsupported(
-                        "Currently delta-kernel-rs can only write to tables with protocol.minWriterVersion = 1, 2, or 7"
-                    )
+                    (1..=6).contains(&self.min_writer_version),
+                    Error::unsupported(format!(
+                        "Unsupported minimum writer version {}",
+                        self.min_writer_version
+                    ))
                 );
                 Ok(())
             }
@@ -444,10 +607,12 @@ where
     )))
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, ToSchema)]
-#[internal_api]
-#[cfg_attr(test, derive(Serialize, Default), serde(rename_all = "camelCase"))]
-pub(crate) struct CommitInfo {
+/// The commitInfo action of a commit. This contains information about the commit such as the
+/// operation that was performed and the engine that performed it. All fields are optional since
+/// the contents of commitInfo are not specified by the Delta protocol.
+#[derive(Debug, Clone, Default, PartialEq, Eq, ToSchema)]
+#[cfg_attr(test, derive(Serialize), serde(rename_all = "camelCase"))]
+pub struct CommitInfo {
     /// The time this logical file was created, as milliseconds since the epoch.
     /// Read: optional, write: required (that is, kernel always writes).
     pub(crate) timestamp: Option<i64>,
@@ -470,6 +635,62 @@ pub(crate) struct CommitInfo {
     /// A place for the engine to store additional metadata associated with this commit encoded as
     /// a map of strings.
     pub(crate) engine_commit_info: Option<HashMap<String, String>>,
+    /// A string identifying the engine (and its version) that wrote this commit. Kernel does not
+    /// write this field.
+    pub(crate) engine_info: Option<String>,
+    /// A unique identifier for the transaction that wrote this commit. Kernel does not write this
+    /// field.
+    pub(crate) txn_id: Option<String>,
+    /// Map of metrics about the operation, such as the number of files and rows added or removed.
+    /// Kernel does not write this field.
+    pub(crate) operation_metrics: Option<HashMap<String, String>>,
+}
+
+impl CommitInfo {
+    /// The timestamp of the commit in milliseconds since the epoch, as recorded by the writer.
+    pub fn timestamp(&self) -> Option<i64> {
+        self.timestamp
+    }
+
+    /// The in-commit timestamp of the commit in milliseconds since the epoch, if present.
+    pub fn in_commit_timestamp(&self) -> Option<i64> {
+        self.in_commit_timestamp
+    }
+
+    /// The operation associated with this commit, such as `WRITE` or `DELETE`.
+    pub fn operation(&self) -> Option<&str> {
+        self.operation.as_deref()
+    }
+
+    /// The parameters of the operation associated with this commit.
+    pub fn operation_parameters(&self) -> Option<&HashMap<String, String>> {
+        self.operation_parameters.as_ref()
+    }
+
+    /// The version of delta_kernel that wrote this commit, if it was written by kernel.
+    pub fn kernel_version(&self) -> Option<&str> {
+        self.kernel_version.as_deref()
+    }
+
+    /// Additional metadata the engine stored with this commit.
+    pub fn engine_commit_info(&self) -> Option<&HashMap<String, String>> {
+        self.engine_commit_info.as_ref()
+    }
+
+    /// The engine (and its version) that wrote this commit.
+    pub fn engine_info(&self) -> Option<&str> {
+        self.engine_info.as_deref()
+    }
+
+    /// The unique identifier of the transaction that wrote this commit.
+    pub fn txn_id(&self) -> Option<&str> {
+        self.txn_id.as_deref()
+    }
+
+    /// Metrics about the operation associated with this commit.
+    pub fn operation_metrics(&self) -> Option<&HashMap<String, String>> {
+        self.operation_metrics.as_ref()
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, ToSchema)]
@@ -652,7 +873,8 @@ impl SetTransaction {
 /// file actions. This action is only allowed in checkpoints following the V2 spec.
 ///
 /// [More info]: https://github.com/delta-io/delta/blob/master/PROTOCOL.md#sidecar-file-information
-#[derive(ToSchema, Debug, PartialEq)]
+#[derive(ToSchema, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 #[internal_api]
 pub(crate) struct Sidecar {
     /// A path to a sidecar file that can be either:
@@ -718,7 +940,7 @@ pub(crate) struct CheckpointMetadata {
 /// Note that the `delta.*` domain is reserved for internal use.
 ///
 /// [DomainMetadata]: https://github.com/delta-io/delta/blob/master/PROTOCOL.md#domain-metadata
-#[derive(Debug, Clone, PartialEq, Eq, ToSchema)]
+#[derive(Debug, Clone, PartialEq, Eq, ToSchema, IntoEngineData)]
 #[internal_api]
 pub(crate) struct DomainMetadata {
     domain: String,
@@ -727,10 +949,36 @@ pub(crate) struct DomainMetadata {
 }
 
 impl DomainMetadata {
+    /// Create a domain metadata action setting the configuration of `domain`.
+    pub(crate) fn new(domain: String, configuration: String) -> Self {
+        Self {
+            domain,
+            configuration,
+            removed: false,
+        }
+    }
+
+    /// Create a tombstone removing `domain`. The tombstone keeps the domain's latest
+    /// `configuration`, as required by the protocol.
+    pub(crate) fn remove(domain: String, configuration: String) -> Self {
+        Self {
+            domain,
+            configuration,
+            removed: true,
+        }
+    }
+
+    pub(crate) fn domain(&self) -> &str {
+        &self.domain
+    }
+
+    pub(crate) fn is_removed(&self) -> bool {
+        self.removed
+    }
+
     // returns true if the domain metadata is an system-controlled domain (all domains that start
     // with "delta.")
-    #[allow(unused)]
-    fn is_internal(&self) -> bool {
+    pub(crate) fn is_internal(&self) -> bool {
         self.domain.starts_with(INTERNAL_DOMAIN_PREFIX)
     }
 }
@@ -948,6 +1196,12 @@ mod tests {
                     "engineCommitInfo",
                     MapType::new(DataType::STRING, DataType::STRING, false),
                 ),
+                StructField::nullable("engineInfo", DataType::STRING),
+                StructField::nullable("txnId", DataType::STRING),
+                StructField::nullable(
+                    "operationMetrics",
+                    MapType::new(DataType::STRING, DataType::STRING, false),
+                ),
             ]),
         )]));
         assert_eq!(schema, expected);
@@ -1105,12 +1359,57 @@ mod tests {
             3,
             7,
             Some([ReaderFeature::DeletionVectors]),
-            Some([WriterFeature::RowTracking]),
+            Some([WriterFeature::IdentityColumns]),
         )
         .unwrap();
         assert!(protocol.ensure_write_supported().is_err());
     }
 
+    #[test]
+    fn test_with_writer_feature() {
+        // legacy protocols are upgraded to table features
+        let protocol = Protocol::try_new(1, 2, None::<Vec<String>>, None::<Vec<String>>).unwrap();
+        let upgraded = protocol
+            .with_writer_feature(WriterFeature::InCommitTimestamp)
+            .unwrap();
+        let expected = Protocol::try_new(
+            1,
+            7,
+            None::<Vec<String>>,
+            Some([
+                WriterFeature::AppendOnly,
+                WriterFeature::Invariants,
+                WriterFeature::InCommitTimestamp,
+            ]),
+        )
+        .unwrap();
+        assert_eq!(upgraded, expected);
+        assert_eq!(
+            upgraded
+                .with_writer_feature(WriterFeature::InCommitTimestamp)
+                .unwrap(),
+            expected
+        );
+
+        // features with a reader feature require reader version 3
+        let upgraded = upgraded
+            .with_writer_feature(WriterFeature::DeletionVectors)
+            .unwrap();
+        let expected = Protocol::try_new(
+            3,
+            7,
+            Some([ReaderFeature::DeletionVectors]),
+            Some([
+                WriterFeature::AppendOnly,
+                WriterFeature::Invariants,
+                WriterFeature::InCommitTimestamp,
+                WriterFeature::DeletionVectors,
+            ]),
+        )
+        .unwrap();
+        assert_eq!(upgraded, expected);
+    }
+
     #[test]
     fn test_ensure_supported_features() {
         let supported_features = [ReaderFeature::ColumnMapping, ReaderFeature::DeletionVectors];
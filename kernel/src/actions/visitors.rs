@@ -618,14 +618,12 @@ pub(crate) fn visit_protocol_at<'a>(
 ///
 /// Only the a single row of the engine data is checked (the first row). This is because in-commit
 /// timestamps requires that the CommitInfo containing the ICT be the first action in the log.
-#[allow(unused)]
 #[derive(Default)]
 pub(crate) struct InCommitTimestampVisitor {
     pub(crate) in_commit_timestamp: Option<i64>,
 }
 
 impl InCommitTimestampVisitor {
-    #[allow(unused)]
     /// Get the schema that the visitor expects the data to have.
     pub(crate) fn schema() -> Arc<Schema> {
         static SCHEMA: LazyLock<Arc<Schema>> = LazyLock::new(|| {
@@ -677,6 +675,55 @@ impl RowVisitor for InCommitTimestampVisitor {
     }
 }
 
+/// Extracts the [`CommitInfo`] action from the actions of a single commit. The visitor should be
+/// given data read with the [`get_log_commit_info_schema`] schema.
+///
+/// The commitInfo action has no required fields, so a row is considered to contain a commitInfo
+/// action if any of its fields is non-null. Only the first commitInfo action found is kept.
+#[derive(Default)]
+pub(crate) struct CommitInfoVisitor {
+    pub(crate) commit_info: Option<CommitInfo>,
+}
+
+impl RowVisitor for CommitInfoVisitor {
+    fn selected_column_names_and_types(&self) -> (&'static [ColumnName], &'static [DataType]) {
+        static NAMES_AND_TYPES: LazyLock<ColumnNamesAndTypes> =
+            LazyLock::new(|| CommitInfo::to_schema().leaves(COMMIT_INFO_NAME));
+        NAMES_AND_TYPES.as_ref()
+    }
+
+    fn visit<'a>(&mut self, row_count: usize, getters: &[&'a dyn GetData<'a>]) -> DeltaResult<()> {
+        require!(
+            getters.len() == 9,
+            Error::InternalError(format!(
+                "Wrong number of CommitInfoVisitor getters: {}",
+                getters.len()
+            ))
+        );
+        if self.commit_info.is_some() {
+            return Ok(());
+        }
+        for i in 0..row_count {
+            let commit_info = CommitInfo {
+                timestamp: getters[0].get_opt(i, "commitInfo.timestamp")?,
+                in_commit_timestamp: getters[1].get_opt(i, "commitInfo.inCommitTimestamp")?,
+                operation: getters[2].get_opt(i, "commitInfo.operation")?,
+                operation_parameters: getters[3].get_opt(i, "commitInfo.operationParameters")?,
+                kernel_version: getters[4].get_opt(i, "commitInfo.kernelVersion")?,
+                engine_commit_info: getters[5].get_opt(i, "commitInfo.engineCommitInfo")?,
+                engine_info: getters[6].get_opt(i, "commitInfo.engineInfo")?,
+                txn_id: getters[7].get_opt(i, "commitInfo.txnId")?,
+                operation_metrics: getters[8].get_opt(i, "commitInfo.operationMetrics")?,
+            };
+            if commit_info != CommitInfo::default() {
+                self.commit_info = Some(commit_info);
+                break;
+            }
+        }
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
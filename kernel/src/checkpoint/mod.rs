@@ -1,22 +1,36 @@
-//! This module implements the API for writing single-file checkpoints.
+//! This module implements the API for writing checkpoints.
 //!
 //! The entry point for this API is [`Snapshot::checkpoint`].
 //!
 //! ## Checkpoint Types and Selection Logic
-//! This API supports two checkpoint types, selected based on table features:
+//! A checkpoint written to a single file is of one of two types, selected based on table features:
 //!
 //! | Table Feature    | Resulting Checkpoint Type    | Description                                                                 |
 //! |------------------|-------------------------------|-----------------------------------------------------------------------------|
 //! | No v2Checkpoints | Single-file Classic-named V1 | Follows V1 specification without [`CheckpointMetadata`] action             |
 //! | v2Checkpoints    | Single-file Classic-named V2 | Follows V2 specification with [`CheckpointMetadata`] action while maintaining backward compatibility via classic naming |
 //!
+//! Large tables can split the checkpoint into multiple files with [`CheckpointWriter::parts`]:
+//!
+//! | Table Feature    | Resulting Checkpoint Type    | Description                                                                 |
+//! |------------------|-------------------------------|-----------------------------------------------------------------------------|
+//! | No v2Checkpoints | Classic-named multi-part V1  | Actions are split over `n` files named `<version>.checkpoint.<part>.<n>.parquet` |
+//! | v2Checkpoints    | Classic-named V2 with sidecars | File actions are split over `n` sidecar files in `_delta_log/_sidecars/`, referenced by [`Sidecar`] actions in the top-level checkpoint file |
+//!
 //! For more information on the V1/V2 specifications, see the following protocol section:
 //! <https://github.com/delta-io/delta/blob/master/PROTOCOL.md#checkpoint-specs>
 //!
+//! ## File Statistics
+//! The statistics of `add` actions are always written as JSON (the `stats` column). Typed
+//! `stats_parsed` and `partitionValues_parsed` columns are not written yet, so tables that set
+//! `delta.checkpoint.writeStatsAsStruct` to `true` or `delta.checkpoint.writeStatsAsJson` to
+//! `false` get JSON-only statistics (with a warning).
+//!
 //! ## Architecture
 //!
 //! - [`CheckpointWriter`] - Core component that manages the checkpoint creation workflow
 //! - [`CheckpointDataIterator`] - Iterator over the checkpoint data to be written
+//! - [`CheckpointPartWriter`] - Produces the data of one file of a checkpoint split into parts
 //!
 //! ## Usage
 //!
@@ -66,6 +80,23 @@
 //! # Ok::<_, Error>(())
 //! ```
 //!
+//! ## Checkpoints with Multiple Parts
+//!
+//! 1. Create the part writers with [`CheckpointWriter::parts`]
+//! 2. For each part (possibly in parallel), write the data from
+//!    [`CheckpointPartWriter::checkpoint_data`] to [`CheckpointPartWriter::path`], and pass the
+//!    written file's metadata to [`CheckpointPartWriter::finalize`]
+//! 3. For a multi-part checkpoint, pass all parts to [`CheckpointWriter::finalize_parts`]
+//! 4. For a V2 checkpoint with sidecars, write the data from
+//!    [`CheckpointWriter::checkpoint_data_with_sidecars`] to [`CheckpointWriter::checkpoint_path`],
+//!    and then call [`CheckpointWriter::finalize`]
+//!
+//! ## The `_last_checkpoint` File
+//! Finalizing a checkpoint writes the `_last_checkpoint` file (see [`LastCheckpointHint`]), which
+//! records the checkpoint's version, size and schema, any tags set with
+//! [`CheckpointWriter::with_tags`], and, for V2 checkpoints, the top-level checkpoint file and its
+//! sidecars. It also holds the checksum of its contents, so that readers can detect torn writes.
+//!
 //! ## Warning
 //! Multi-part (V1) checkpoints are DEPRECATED and UNSAFE.
 //!
@@ -77,10 +108,9 @@
 //! in the future, we can revisit this decision.
 //!
 //! [`CheckpointMetadata`]: crate::actions::CheckpointMetadata
+//! [`Sidecar`]: crate::actions::Sidecar
 //! [`LastCheckpointHint`]: crate::snapshot::LastCheckpointHint
-// Future extensions:
-// - TODO(#837): Multi-file V2 checkpoints are not supported yet. The API is designed to be extensible for future
-//   multi-file support, but the current implementation only supports single-file checkpoints.
+use std::collections::HashMap;
 use std::sync::{Arc, LazyLock};
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
@@ -93,14 +123,22 @@ use crate::expressions::Scalar;
 use crate::log_replay::LogReplayProcessor;
 use crate::path::ParsedLogPath;
 use crate::schema::{DataType, SchemaRef, StructField, StructType, ToSchema as _};
-use crate::snapshot::{Snapshot, LAST_CHECKPOINT_FILE_NAME};
+use crate::snapshot::{
+    LastCheckpointHint, LastCheckpointV2Info, Snapshot, LAST_CHECKPOINT_FILE_NAME,
+};
 use crate::utils::calculate_transaction_expiration_timestamp;
-use crate::{DeltaResult, Engine, EngineData, Error, EvaluationHandlerExtension, FileMeta};
+use crate::{DeltaResult, Engine, Error, EvaluationHandlerExtension, FileMeta};
 use log_replay::{CheckpointBatch, CheckpointLogReplayProcessor};
+use parts::{validate_parts, CheckpointPartKind, CheckpointPartSelection};
 
+use itertools::Itertools as _;
+use tracing::warn;
 use url::Url;
 
-mod log_replay;
+pub use parts::{CheckpointPart, CheckpointPartWriter};
+
+pub(crate) mod log_replay;
+mod parts;
 #[cfg(test)]
 mod tests;
 
@@ -111,22 +149,8 @@ const HOURS_PER_DAY: u64 = 24;
 /// This is set to 7 days, which is the default in delta-spark.
 const DEFAULT_RETENTION_SECS: u64 = 7 * HOURS_PER_DAY * MINUTES_PER_HOUR * SECONDS_PER_MINUTE;
 
-/// Schema of the `_last_checkpoint` file
-/// We cannot use `LastCheckpointInfo::to_schema()` as it would include the 'checkpoint_schema'
-/// field, which is only known at runtime.
-static LAST_CHECKPOINT_SCHEMA: LazyLock<SchemaRef> = LazyLock::new(|| {
-    StructType::new([
-        StructField::not_null("version", DataType::LONG),
-        StructField::not_null("size", DataType::LONG),
-        StructField::nullable("parts", DataType::LONG),
-        StructField::nullable("sizeInBytes", DataType::LONG),
-        StructField::nullable("numOfAddFiles", DataType::LONG),
-    ])
-    .into()
-});
-
 /// Schema for extracting relevant actions from log files for checkpoint creation
-static CHECKPOINT_ACTIONS_SCHEMA: LazyLock<SchemaRef> = LazyLock::new(|| {
+pub(crate) static CHECKPOINT_ACTIONS_SCHEMA: LazyLock<SchemaRef> = LazyLock::new(|| {
     Arc::new(StructType::new([
         StructField::nullable(ADD_NAME, Add::to_schema()),
         StructField::nullable(REMOVE_NAME, Remove::to_schema()),
@@ -147,6 +171,16 @@ static CHECKPOINT_METADATA_ACTION_SCHEMA: LazyLock<SchemaRef> = LazyLock::new(||
     )]))
 });
 
+// Schema of the [`Sidecar`] actions that reference the sidecar files of V2 checkpoints
+static SIDECAR_ACTION_SCHEMA: LazyLock<SchemaRef> = LazyLock::new(|| {
+    Arc::new(StructType::new([StructField::nullable(
+        SIDECAR_NAME,
+        Sidecar::to_schema(),
+    )]))
+});
+
+type CheckpointBatchIterator = Box<dyn Iterator<Item = DeltaResult<CheckpointBatch>> + Send>;
+
 /// An iterator over the checkpoint data to be written to the file.
 ///
 /// This iterator yields filtered checkpoint data batches ([`FilteredEngineData`]) and
@@ -158,11 +192,24 @@ static CHECKPOINT_METADATA_ACTION_SCHEMA: LazyLock<SchemaRef> = LazyLock::new(||
 /// [`CheckpointWriter::finalize`]. Failing to do so may result in data loss or corruption.
 pub struct CheckpointDataIterator {
     /// The nested iterator that yields checkpoint batches with action counts
-    checkpoint_batch_iterator: Box<dyn Iterator<Item = DeltaResult<CheckpointBatch>> + Send>,
+    checkpoint_batch_iterator: CheckpointBatchIterator,
     /// Running total of actions included in the checkpoint
     actions_count: i64,
     /// Running total of add actions included in the checkpoint
     add_actions_count: i64,
+    /// The sidecar files referenced by the checkpoint
+    sidecars: Vec<Sidecar>,
+}
+
+impl CheckpointDataIterator {
+    fn new(checkpoint_batch_iterator: CheckpointBatchIterator) -> Self {
+        Self {
+            checkpoint_batch_iterator,
+            actions_count: 0,
+            add_actions_count: 0,
+            sidecars: vec![],
+        }
+    }
 }
 
 impl Iterator for CheckpointDataIterator {
@@ -203,6 +250,9 @@ pub struct CheckpointWriter {
     /// Note: Although the version is stored as a u64 in the snapshot, it is stored as an i64
     /// field here to avoid multiple type conversions.
     version: i64,
+
+    /// Additional metadata about the checkpoint, written to the `_last_checkpoint` file.
+    tags: Option<HashMap<String, String>>,
 }
 
 impl CheckpointWriter {
@@ -216,7 +266,21 @@ impl CheckpointWriter {
             ))
         })?;
 
-        Ok(Self { snapshot, version })
+        let table_properties = snapshot.table_properties();
+        if table_properties.checkpoint_write_stats_as_struct == Some(true)
+            || table_properties.checkpoint_write_stats_as_json == Some(false)
+        {
+            warn!(
+                "Writing typed file statistics to checkpoints is not supported yet; checkpoints \
+                 of this table keep the JSON statistics only"
+            );
+        }
+
+        Ok(Self {
+            snapshot,
+            version,
+            tags: None,
+        })
     }
 
     fn get_transaction_expiration_timestamp(&self) -> DeltaResult<Option<i64>> {
@@ -262,6 +326,78 @@ impl CheckpointWriter {
             .table_configuration()
             .is_v2_checkpoint_write_supported();
 
+        let checkpoint_data = self.checkpoint_batches(engine, None)?;
+
+        let checkpoint_metadata =
+            is_v2_checkpoints_supported.then(|| self.create_checkpoint_metadata_batch(engine));
+
+        // Wrap the iterator in a CheckpointDataIterator to track action counts
+        Ok(CheckpointDataIterator::new(Box::new(
+            checkpoint_data.chain(checkpoint_metadata),
+        )))
+    }
+
+    /// Returns the data of the top-level file of a V2 checkpoint whose file actions were written
+    /// to the given sidecar files (see [`CheckpointWriter::parts`]). The data is to be written to
+    /// [`CheckpointWriter::checkpoint_path`], and holds the non-file actions, a [`Sidecar`] action
+    /// for each sidecar file, and the [`CheckpointMetadata`] action.
+    ///
+    /// # Parameters
+    /// - `engine`: Implementation of [`Engine`] APIs.
+    /// - `parts`: All the parts returned by [`CheckpointWriter::parts`], once finalized
+    ///
+    /// # Returns: [`CheckpointDataIterator`] containing the checkpoint data
+    ///
+    /// [`Sidecar`]: crate::actions::Sidecar
+    /// [`CheckpointMetadata`]: crate::actions::CheckpointMetadata
+    pub fn checkpoint_data_with_sidecars(
+        &self,
+        engine: &dyn Engine,
+        parts: &[CheckpointPart],
+    ) -> DeltaResult<CheckpointDataIterator> {
+        if !self
+            .snapshot
+            .table_configuration()
+            .is_v2_checkpoint_write_supported()
+        {
+            return Err(Error::checkpoint_write(
+                "Sidecar files can only be written for tables that support v2Checkpoints",
+            ));
+        }
+        validate_parts(parts, CheckpointPartKind::Sidecar)?;
+
+        let non_file_actions =
+            self.checkpoint_batches(engine, Some(CheckpointPartSelection::non_file_actions()))?;
+        let sidecars: Vec<_> = parts.iter().map(CheckpointPart::to_sidecar).try_collect()?;
+        let sidecar_actions: Vec<_> = sidecars
+            .iter()
+            .map(|sidecar| self.create_sidecar_batch(engine, sidecar))
+            .collect();
+        let checkpoint_metadata = self.create_checkpoint_metadata_batch(engine);
+
+        let mut checkpoint_data = CheckpointDataIterator::new(Box::new(
+            non_file_actions
+                .chain(sidecar_actions)
+                .chain(std::iter::once(checkpoint_metadata)),
+        ));
+        // The file actions in the sidecar files are part of the checkpoint too
+        for part in parts {
+            checkpoint_data.actions_count += part.actions_count;
+            checkpoint_data.add_actions_count += part.add_actions_count;
+        }
+        checkpoint_data.sidecars = sidecars;
+        Ok(checkpoint_data)
+    }
+
+    /// Reads the actions of the log segment and selects those to include in the checkpoint (or in
+    /// the given part of it), without the V2 [`CheckpointMetadata`] action.
+    ///
+    /// [`CheckpointMetadata`]: crate::actions::CheckpointMetadata
+    fn checkpoint_batches(
+        &self,
+        engine: &dyn Engine,
+        part_selection: Option<CheckpointPartSelection>,
+    ) -> DeltaResult<CheckpointBatchIterator> {
         let actions = self.snapshot.log_segment().read_actions(
             engine,
             CHECKPOINT_ACTIONS_SCHEMA.clone(),
@@ -276,15 +412,15 @@ impl CheckpointWriter {
         )
         .process_actions_iter(actions);
 
-        let checkpoint_metadata =
-            is_v2_checkpoints_supported.then(|| self.create_checkpoint_metadata_batch(engine));
-
-        // Wrap the iterator in a CheckpointDataIterator to track action counts
-        Ok(CheckpointDataIterator {
-            checkpoint_batch_iterator: Box::new(checkpoint_data.chain(checkpoint_metadata)),
-            actions_count: 0,
-            add_actions_count: 0,
-        })
+        // Batches left without selected rows are kept, so that the data of a part always has at
+        // least one batch (and thus a schema) even if no actions belong to the part
+        let checkpoint_data: CheckpointBatchIterator = match part_selection {
+            Some(part_selection) => {
+                Box::new(checkpoint_data.map(move |batch| part_selection.apply(batch?)))
+            }
+            None => Box::new(checkpoint_data),
+        };
+        Ok(checkpoint_data)
     }
 
     /// Finalizes checkpoint creation by saving metadata about the checkpoint.
@@ -302,8 +438,9 @@ impl CheckpointWriter {
     /// # Returns: `Ok` if the checkpoint was successfully finalized
     // Internally, this method:
     // 1. Validates that the checkpoint data iterator is fully exhausted
-    // 2. Creates the `_last_checkpoint` data with `create_last_checkpoint_data`
+    // 2. Creates the `_last_checkpoint` data with `create_last_checkpoint_hint`
     // 3. Writes the `_last_checkpoint` data to the `_last_checkpoint` file in the delta log
+    // 4. Cleans up expired log files, unless disabled by `delta.enableExpiredLogCleanup`
     pub fn finalize(
         self,
         engine: &dyn Engine,
@@ -317,21 +454,135 @@ impl CheckpointWriter {
             ));
         }
 
-        let size_in_bytes = i64::try_from(metadata.size).map_err(|e| {
-            Error::CheckpointWrite(format!(
-                "Failed to convert checkpoint size in bytes from u64 {} to i64: {}, when writing _last_checkpoint",
-                metadata.size, e
-            ))
-        })?;
-
-        let data = create_last_checkpoint_data(
-            engine,
-            self.version,
+        let v2_checkpoint = self
+            .snapshot
+            .table_configuration()
+            .is_v2_checkpoint_write_supported()
+            .then(|| -> DeltaResult<_> {
+                let sidecars = checkpoint_data.sidecars;
+                Ok(LastCheckpointV2Info {
+                    path: file_name(&metadata.location)?.to_string(),
+                    size_in_bytes: to_i64_size(metadata.size)?,
+                    modification_time: metadata.last_modified,
+                    sidecar_files: (!sidecars.is_empty()).then_some(sidecars),
+                })
+            })
+            .transpose()?;
+        // The sidecar files referenced by a V2 checkpoint are part of its size
+        let sidecars_size_in_bytes: i64 = v2_checkpoint
+            .iter()
+            .flat_map(|v2_checkpoint| v2_checkpoint.sidecar_files.iter().flatten())
+            .map(|sidecar| sidecar.size_in_bytes)
+            .sum();
+
+        let hint = self.create_last_checkpoint_hint(
             checkpoint_data.actions_count,
             checkpoint_data.add_actions_count,
+            to_i64_size(metadata.size)? + sidecars_size_in_bytes,
+            None,
+            v2_checkpoint,
+        );
+        self.write_last_checkpoint(engine, &hint)?;
+        self.cleanup_expired_logs(engine);
+        Ok(())
+    }
+
+    /// Finalizes the creation of a multi-part checkpoint by saving metadata about the checkpoint.
+    ///
+    /// # Important
+    /// This method **must** be called only after all parts have been written to object storage
+    /// and finalized with [`CheckpointPartWriter::finalize`].
+    ///
+    /// # Parameters
+    /// - `engine`: Implementation of [`Engine`] apis.
+    /// - `parts`: All the parts returned by [`CheckpointWriter::parts`], once finalized
+    ///
+    /// # Returns: `Ok` if the checkpoint was successfully finalized
+    pub fn finalize_parts(
+        self,
+        engine: &dyn Engine,
+        parts: Vec<CheckpointPart>,
+    ) -> DeltaResult<()> {
+        validate_parts(&parts, CheckpointPartKind::MultiPart)?;
+        let actions_count = parts.iter().map(|part| part.actions_count).sum();
+        let add_actions_count = parts.iter().map(|part| part.add_actions_count).sum();
+        let size_in_bytes = parts
+            .iter()
+            .map(|part| to_i64_size(part.size_in_bytes))
+            .sum::<DeltaResult<_>>()?;
+        let hint = self.create_last_checkpoint_hint(
+            actions_count,
+            add_actions_count,
             size_in_bytes,
+            Some(parts.len()),
+            None,
+        );
+        self.write_last_checkpoint(engine, &hint)?;
+        self.cleanup_expired_logs(engine);
+        Ok(())
+    }
+
+    /// Sets the `tags` of the checkpoint, i.e. additional metadata written to the
+    /// `_last_checkpoint` file when the checkpoint is finalized.
+    pub fn with_tags(mut self, tags: HashMap<String, String>) -> Self {
+        self.tags = Some(tags);
+        self
+    }
+
+    /// Creates the contents of the `_last_checkpoint` file for the completed checkpoint. Factored
+    /// out to facilitate testing.
+    ///
+    /// # Parameters
+    /// - `actions_count`: Total actions count
+    /// - `add_actions_count`: Add actions count
+    /// - `size_in_bytes`: Size of the checkpoint file(s) in bytes
+    /// - `parts`: Number of parts of a multi-part checkpoint
+    /// - `v2_checkpoint`: The top-level file of a V2 checkpoint
+    ///
+    /// The `checkpointSchema` is the schema of the checkpoint data: the actions, and the
+    /// `checkpointMetadata` action of V2 checkpoints. The `checksum` is added when the hint is
+    /// serialized.
+    fn create_last_checkpoint_hint(
+        &self,
+        actions_count: i64,
+        add_actions_count: i64,
+        size_in_bytes: i64,
+        parts: Option<usize>,
+        v2_checkpoint: Option<LastCheckpointV2Info>,
+    ) -> LastCheckpointHint {
+        let checkpoint_metadata = self
+            .snapshot
+            .table_configuration()
+            .is_v2_checkpoint_write_supported()
+            .then(|| CHECKPOINT_METADATA_ACTION_SCHEMA.fields().cloned())
+            .into_iter()
+            .flatten();
+        let checkpoint_schema = StructType::new(
+            CHECKPOINT_ACTIONS_SCHEMA
+                .fields()
+                .cloned()
+                .chain(checkpoint_metadata),
         );
 
+        LastCheckpointHint {
+            version: self.snapshot.version(),
+            size: actions_count,
+            parts,
+            size_in_bytes: Some(size_in_bytes),
+            num_of_add_files: Some(add_actions_count),
+            checkpoint_schema: Some(checkpoint_schema),
+            checksum: None,
+            tags: self.tags.clone(),
+            v2_checkpoint,
+        }
+    }
+
+    /// Writes the `_last_checkpoint` file, with the checksum of its contents.
+    fn write_last_checkpoint(
+        &self,
+        engine: &dyn Engine,
+        hint: &LastCheckpointHint,
+    ) -> DeltaResult<()> {
         let last_checkpoint_path = self
             .snapshot
             .log_segment()
@@ -339,13 +590,58 @@ impl CheckpointWriter {
             .join(LAST_CHECKPOINT_FILE_NAME)?;
 
         // Write the `_last_checkpoint` file to `table/_delta_log/_last_checkpoint`
-        engine.json_handler().write_json_file(
+        engine.storage_handler().write_file(
             &last_checkpoint_path,
-            Box::new(std::iter::once(data)),
+            hint.to_json_with_checksum()?.into(),
             true,
+        )
+    }
+
+    /// Deletes the log files that expired with the completed checkpoint (see
+    /// [`Snapshot::cleanup_expired_logs`]), unless the `delta.enableExpiredLogCleanup` table
+    /// property is `false`. A failed cleanup does not fail the checkpoint, since the remaining
+    /// expired files are deleted after a later checkpoint.
+    fn cleanup_expired_logs(&self, engine: &dyn Engine) {
+        let table_properties = self.snapshot.table_properties();
+        if table_properties.enable_expired_log_cleanup == Some(false) {
+            return;
+        }
+        if let Err(e) = self.snapshot.cleanup_expired_logs(engine) {
+            warn!("Failed to clean up expired log files: {e}");
+        }
+    }
+
+    /// Creates the [`Sidecar`] action referencing the given sidecar file of a V2 checkpoint.
+    ///
+    /// [`Sidecar`]: crate::actions::Sidecar
+    fn create_sidecar_batch(
+        &self,
+        engine: &dyn Engine,
+        sidecar: &Sidecar,
+    ) -> DeltaResult<CheckpointBatch> {
+        let tags_type = Sidecar::to_schema()
+            .field("tags")
+            .map(|field| field.data_type().clone())
+            .ok_or_else(|| Error::internal_error("Sidecar schema has no tags field"))?;
+        let sidecar_batch = engine.evaluation_handler().create_one(
+            SIDECAR_ACTION_SCHEMA.clone(),
+            &[
+                sidecar.path.clone().into(),
+                sidecar.size_in_bytes.into(),
+                sidecar.modification_time.into(),
+                Scalar::Null(tags_type),
+            ],
         )?;
 
-        Ok(())
+        // The file actions are counted by the sidecar itself
+        Ok(CheckpointBatch {
+            filtered_data: FilteredEngineData {
+                data: sidecar_batch,
+                selection_vector: vec![true],
+            },
+            actions_count: 1,
+            add_actions_count: 0,
+        })
     }
 
     /// Creates the checkpoint metadata action for V2 checkpoints.
@@ -424,7 +720,7 @@ impl CheckpointWriter {
 ///   a specific time instead of using `SystemTime::now()`.
 ///
 /// # Returns: The timestamp in milliseconds since epoch
-fn deleted_file_retention_timestamp_with_time(
+pub(crate) fn deleted_file_retention_timestamp_with_time(
     retention_duration: Option<Duration>,
     now_duration: Duration,
 ) -> DeltaResult<i64> {
@@ -443,43 +739,19 @@ fn deleted_file_retention_timestamp_with_time(
     Ok(now_ms - retention_ms)
 }
 
-/// Creates the data for the _last_checkpoint file containing checkpoint
-/// metadata with the `create_one` method. Factored out to facilitate testing.
-///
-/// # Parameters
-/// - `engine`: Engine for data processing
-/// - `version`: Table version number
-/// - `actions_counter`: Total actions count
-/// - `add_actions_counter`: Add actions count
-/// - `size_in_bytes`: Size of the checkpoint file in bytes
-///
-/// # Returns
-/// A new [`EngineData`] batch with the `_last_checkpoint` fields:
-/// - `version` (i64, required): Table version number
-/// - `size` (i64, required): Total actions count
-/// - `parts` (i64, optional): Always None for single-file checkpoints
-/// - `sizeInBytes` (i64, optional): Size of checkpoint file in bytes
-/// - `numOfAddFiles` (i64, optional): Number of Add actions
-///
-/// TODO(#838): Add `checksum` field to `_last_checkpoint` file
-/// TODO(#839): Add `checkpoint_schema` field to `_last_checkpoint` file
-/// TODO(#1054): Add `tags` field to `_last_checkpoint` file
-/// TODO(#1052): Add `v2Checkpoint` field to `_last_checkpoint` file
-pub(crate) fn create_last_checkpoint_data(
-    engine: &dyn Engine,
-    version: i64,
-    actions_counter: i64,
-    add_actions_counter: i64,
-    size_in_bytes: i64,
-) -> DeltaResult<Box<dyn EngineData>> {
-    engine.evaluation_handler().create_one(
-        LAST_CHECKPOINT_SCHEMA.clone(),
-        &[
-            version.into(),
-            actions_counter.into(),
-            None::<i64>.into(), // parts = None since we only support single-part checkpoints
-            size_in_bytes.into(),
-            add_actions_counter.into(),
-        ],
-    )
+/// Converts the size of a checkpoint file to the `i64` written to the `_last_checkpoint` file.
+fn to_i64_size(size_in_bytes: u64) -> DeltaResult<i64> {
+    i64::try_from(size_in_bytes).map_err(|e| {
+        Error::CheckpointWrite(format!(
+            "Failed to convert checkpoint size in bytes from u64 {size_in_bytes} to i64: {e}"
+        ))
+    })
+}
+
+/// Returns the name of the file at the given URL.
+fn file_name(location: &Url) -> DeltaResult<&str> {
+    location
+        .path_segments()
+        .and_then(|mut segments| segments.next_back())
+        .ok_or_else(|| Error::generic(format!("Invalid file path: {location}")))
 }
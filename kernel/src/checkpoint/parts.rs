@@ -0,0 +1,331 @@
+//! Checkpoints written as multiple files, see [`CheckpointWriter::parts`].
+//!
+//! The file actions of the checkpoint are distributed over the parts by hashing their paths, so
+//! that each part can produce its data independently of the others (e.g. in parallel on different
+//! executors). Depending on the table, the parts are either:
+//! - the files of a classic multi-part checkpoint (without the `v2Checkpoint` feature), which also
+//!   hold the non-file actions (in the first part), or
+//! - sidecar files in `_delta_log/_sidecars/` (with the `v2Checkpoint` feature), which hold only
+//!   file actions. The top-level V2 checkpoint file holds the non-file actions and references the
+//!   sidecars, see [`CheckpointWriter::checkpoint_data_with_sidecars`].
+use std::sync::LazyLock;
+
+use url::Url;
+use uuid::Uuid;
+
+use super::log_replay::CheckpointBatch;
+use super::{CheckpointDataIterator, CheckpointWriter};
+use crate::actions::Sidecar;
+use crate::engine_data::{GetData, RowVisitor, TypedGetData as _};
+use crate::path::ParsedLogPath;
+use crate::schema::{column_name, ColumnName, ColumnNamesAndTypes, DataType};
+use crate::utils::require;
+use crate::{DeltaResult, Engine, Error, FileMeta};
+
+/// The directory of sidecar files, relative to the `_delta_log` directory.
+const SIDECARS_DIR: &str = "_sidecars/";
+
+/// The kind of file a checkpoint part is written to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum CheckpointPartKind {
+    /// A part of a classic multi-part checkpoint
+    MultiPart,
+    /// A sidecar file of a V2 checkpoint
+    Sidecar,
+}
+
+/// Selects the actions that belong to one file of a checkpoint.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct CheckpointPartSelection {
+    /// The part (index, number of parts) whose file actions are selected, if any. File actions are
+    /// assigned to parts by the hash of their path.
+    file_actions: Option<(u32, u32)>,
+    /// Whether non-file actions (e.g. protocol, metadata, txn) are selected.
+    non_file_actions: bool,
+}
+
+impl CheckpointPartSelection {
+    /// Selects the non-file actions of the top-level file of a V2 checkpoint with sidecars.
+    pub(crate) fn non_file_actions() -> Self {
+        Self {
+            file_actions: None,
+            non_file_actions: true,
+        }
+    }
+
+    fn for_part(kind: CheckpointPartKind, part_index: u32, num_parts: u32) -> Self {
+        Self {
+            file_actions: Some((part_index, num_parts)),
+            non_file_actions: kind == CheckpointPartKind::MultiPart && part_index == 0,
+        }
+    }
+
+    /// Deselects the actions of the batch that do not belong to this selection, and recounts the
+    /// selected actions.
+    pub(crate) fn apply(&self, batch: CheckpointBatch) -> DeltaResult<CheckpointBatch> {
+        let CheckpointBatch {
+            mut filtered_data, ..
+        } = batch;
+        let mut visitor = CheckpointPartVisitor {
+            selection: *self,
+            selection_vector: filtered_data.selection_vector,
+            actions_count: 0,
+            add_actions_count: 0,
+        };
+        visitor.visit_rows_of(filtered_data.data.as_ref())?;
+        filtered_data.selection_vector = visitor.selection_vector;
+        Ok(CheckpointBatch {
+            filtered_data,
+            actions_count: visitor.actions_count,
+            add_actions_count: visitor.add_actions_count,
+        })
+    }
+}
+
+/// Assigns a file action to one of `num_parts` parts, by the hash of its path. The hash must be
+/// stable, since the parts may be produced by different processes.
+fn part_index_of(path: &str, num_parts: u32) -> u32 {
+    crc32fast::hash(path.as_bytes()) % num_parts
+}
+
+/// Updates the selection vector of a checkpoint batch to the actions of a
+/// [`CheckpointPartSelection`], and counts the remaining actions.
+struct CheckpointPartVisitor {
+    selection: CheckpointPartSelection,
+    selection_vector: Vec<bool>,
+    actions_count: i64,
+    add_actions_count: i64,
+}
+
+impl RowVisitor for CheckpointPartVisitor {
+    fn selected_column_names_and_types(&self) -> (&'static [ColumnName], &'static [DataType]) {
+        static NAMES_AND_TYPES: LazyLock<ColumnNamesAndTypes> = LazyLock::new(|| {
+            const STRING: DataType = DataType::STRING;
+            let names_and_types = [
+                (STRING, column_name!("add.path")),
+                (STRING, column_name!("remove.path")),
+            ];
+            let (types, names) = names_and_types.into_iter().unzip();
+            (names, types).into()
+        });
+        NAMES_AND_TYPES.as_ref()
+    }
+
+    fn visit<'a>(&mut self, row_count: usize, getters: &[&'a dyn GetData<'a>]) -> DeltaResult<()> {
+        require!(
+            getters.len() == 2,
+            Error::InternalError(format!(
+                "Wrong number of CheckpointPartVisitor getters: {}",
+                getters.len()
+            ))
+        );
+        self.selection_vector.resize(row_count, true);
+        for i in 0..row_count {
+            if !self.selection_vector[i] {
+                continue;
+            }
+            let add_path: Option<&str> = getters[0].get_opt(i, "add.path")?;
+            let remove_path: Option<&str> = getters[1].get_opt(i, "remove.path")?;
+            let selected = match (add_path.or(remove_path), self.selection.file_actions) {
+                (Some(path), Some((part_index, num_parts))) => {
+                    part_index_of(path, num_parts) == part_index
+                }
+                (Some(_), None) => false,
+                (None, _) => self.selection.non_file_actions,
+            };
+            self.selection_vector[i] = selected;
+            if selected {
+                self.actions_count += 1;
+                if add_path.is_some() {
+                    self.add_actions_count += 1;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Produces the data of one part of a checkpoint, see [`CheckpointWriter::parts`].
+///
+/// The data of each part is produced independently, so parts can be written in parallel. Once its
+/// data is written, [`CheckpointPartWriter::finalize`] returns the [`CheckpointPart`] to pass back
+/// to the [`CheckpointWriter`].
+pub struct CheckpointPartWriter {
+    writer: CheckpointWriter,
+    kind: CheckpointPartKind,
+    /// The index of this part, starting from 0
+    part_index: u32,
+    num_parts: u32,
+    path: Url,
+}
+
+impl CheckpointPartWriter {
+    /// Returns the URL where the data of this part should be written. This is either a part of a
+    /// classic multi-part checkpoint, e.g.
+    /// `<table_root>/_delta_log/00000000000000000010.checkpoint.0000000001.0000000004.parquet`,
+    /// or a sidecar file, e.g. `<table_root>/_delta_log/_sidecars/<uuid>.parquet`.
+    pub fn path(&self) -> &Url {
+        &self.path
+    }
+
+    /// Returns the data of this part, to be written to [`CheckpointPartWriter::path`].
+    pub fn checkpoint_data(&self, engine: &dyn Engine) -> DeltaResult<CheckpointDataIterator> {
+        let selection =
+            CheckpointPartSelection::for_part(self.kind, self.part_index, self.num_parts);
+        let batches = self.writer.checkpoint_batches(engine, Some(selection))?;
+        Ok(CheckpointDataIterator::new(batches))
+    }
+
+    /// Completes this part once its data has been fully written.
+    ///
+    /// # Parameters
+    /// - `metadata`: The metadata of the written part file
+    /// - `checkpoint_data`: The exhausted data iterator of this part
+    pub fn finalize(
+        self,
+        metadata: &FileMeta,
+        mut checkpoint_data: CheckpointDataIterator,
+    ) -> DeltaResult<CheckpointPart> {
+        if checkpoint_data.checkpoint_batch_iterator.next().is_some() {
+            return Err(Error::checkpoint_write(
+                "The checkpoint part data iterator must be fully consumed and written to storage before calling finalize"
+            ));
+        }
+        Ok(CheckpointPart {
+            kind: self.kind,
+            part_num: self.part_index + 1,
+            num_parts: self.num_parts,
+            path: self.path,
+            size_in_bytes: metadata.size,
+            modification_time: metadata.last_modified,
+            actions_count: checkpoint_data.actions_count,
+            add_actions_count: checkpoint_data.add_actions_count,
+        })
+    }
+}
+
+/// A checkpoint part whose data has been written, see [`CheckpointPartWriter::finalize`].
+#[derive(Debug, Clone)]
+pub struct CheckpointPart {
+    pub(crate) kind: CheckpointPartKind,
+    /// The number of this part, starting from 1
+    pub(crate) part_num: u32,
+    pub(crate) num_parts: u32,
+    pub(crate) path: Url,
+    pub(crate) size_in_bytes: u64,
+    pub(crate) modification_time: i64,
+    pub(crate) actions_count: i64,
+    pub(crate) add_actions_count: i64,
+}
+
+impl CheckpointPart {
+    /// Returns the [`Sidecar`] action referencing this part, with its path relative to the
+    /// `_delta_log/_sidecars` directory.
+    pub(crate) fn to_sidecar(&self) -> DeltaResult<Sidecar> {
+        let path = self
+            .path
+            .path_segments()
+            .and_then(|mut segments| segments.next_back())
+            .ok_or_else(|| Error::generic(format!("Invalid sidecar path: {}", self.path)))?;
+        let size_in_bytes = i64::try_from(self.size_in_bytes).map_err(|e| {
+            Error::CheckpointWrite(format!(
+                "Failed to convert sidecar size in bytes from u64 {} to i64: {e}",
+                self.size_in_bytes
+            ))
+        })?;
+        Ok(Sidecar {
+            path: path.to_string(),
+            size_in_bytes,
+            modification_time: self.modification_time,
+            tags: None,
+        })
+    }
+}
+
+impl CheckpointWriter {
+    /// Splits the checkpoint into `num_parts` files that can be written in parallel, for tables too
+    /// large to checkpoint into a single file.
+    ///
+    /// - Without the `v2Checkpoint` table feature, the parts form a classic multi-part checkpoint.
+    ///   Once all parts are written, pass them to [`CheckpointWriter::finalize_parts`].
+    /// - With the `v2Checkpoint` table feature, the parts are sidecar files holding the file
+    ///   actions. Once all parts are written, pass them to
+    ///   [`CheckpointWriter::checkpoint_data_with_sidecars`] to get the data of the top-level
+    ///   checkpoint file, then write it and call [`CheckpointWriter::finalize`].
+    ///
+    /// # Warning
+    /// Multi-part (V1) checkpoints are DEPRECATED: readers only see the checkpoint once all of its
+    /// parts exist, and concurrent writers of the same checkpoint may produce inconsistent parts.
+    pub fn parts(&self, num_parts: u32) -> DeltaResult<Vec<CheckpointPartWriter>> {
+        require!(
+            num_parts > 0,
+            Error::checkpoint_write("A checkpoint must have at least one part")
+        );
+        let table_root = self.snapshot.table_root();
+        let is_v2_checkpoint = self
+            .snapshot
+            .table_configuration()
+            .is_v2_checkpoint_write_supported();
+        let sidecars_dir = self.snapshot.log_segment().log_root.join(SIDECARS_DIR)?;
+        (0..num_parts)
+            .map(|part_index| {
+                let (kind, path) = if is_v2_checkpoint {
+                    let path = sidecars_dir.join(&format!("{}.parquet", Uuid::new_v4()))?;
+                    (CheckpointPartKind::Sidecar, path)
+                } else {
+                    let path = ParsedLogPath::new_multi_part_parquet_checkpoint(
+                        table_root,
+                        self.snapshot.version(),
+                        part_index + 1,
+                        num_parts,
+                    )?;
+                    (CheckpointPartKind::MultiPart, path.location)
+                };
+                Ok(CheckpointPartWriter {
+                    writer: CheckpointWriter {
+                        snapshot: self.snapshot.clone(),
+                        version: self.version,
+                        tags: self.tags.clone(),
+                    },
+                    kind,
+                    part_index,
+                    num_parts,
+                    path,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Checks that the given parts are exactly the parts of one checkpoint, of the given kind.
+pub(crate) fn validate_parts(
+    parts: &[CheckpointPart],
+    kind: CheckpointPartKind,
+) -> DeltaResult<()> {
+    let num_parts = parts.len();
+    require!(
+        num_parts > 0,
+        Error::checkpoint_write("A checkpoint must have at least one part")
+    );
+    let mut seen = vec![false; num_parts];
+    for part in parts {
+        require!(
+            part.kind == kind,
+            Error::checkpoint_write(format!("Unexpected checkpoint part kind {:?}", part.kind))
+        );
+        require!(
+            part.num_parts as usize == num_parts,
+            Error::checkpoint_write(format!(
+                "Expected {} checkpoint parts, but got {num_parts}",
+                part.num_parts
+            ))
+        );
+        let index = part.part_num as usize - 1;
+        require!(
+            !seen[index],
+            Error::checkpoint_write(format!("Duplicate checkpoint part {}", part.part_num))
+        );
+        seen[index] = true;
+    }
+    Ok(())
+}
@@ -1,13 +1,19 @@
+use std::collections::HashMap;
 use std::{sync::Arc, time::Duration};
 
-use super::DEFAULT_RETENTION_SECS;
+use super::{CHECKPOINT_ACTIONS_SCHEMA, DEFAULT_RETENTION_SECS};
 use crate::actions::{Add, Metadata, Protocol, Remove};
-use crate::arrow::array::{ArrayRef, StructArray};
+use crate::arrow::array::{new_null_array, Array, ArrayRef, AsArray as _, StructArray};
 use crate::arrow::datatypes::{DataType, Schema};
-use crate::checkpoint::{create_last_checkpoint_data, deleted_file_retention_timestamp_with_time};
+use crate::checkpoint::deleted_file_retention_timestamp_with_time;
+use crate::checkpoint::{CheckpointDataIterator, CheckpointPart};
 use crate::engine::arrow_data::ArrowEngineData;
 use crate::engine::default::{executor::tokio::TokioBackgroundExecutor, DefaultEngine};
+use crate::expressions::{column_expr, Expression, ExpressionRef, Predicate};
 use crate::object_store::{memory::InMemory, path::Path, ObjectStore};
+use crate::parquet::arrow::ArrowWriter;
+use crate::scan::state::{DvInfo, Stats};
+use crate::snapshot::LastCheckpointHint;
 use crate::utils::test_utils::Action;
 use crate::{DeltaResult, FileMeta, Snapshot};
 
@@ -16,6 +22,7 @@ use arrow_55::{
     datatypes::Field,
 };
 
+use itertools::Itertools as _;
 use serde_json::{from_slice, json, Value};
 use test_utils::delta_path_for_version;
 use url::Url;
@@ -108,48 +115,66 @@ fn test_create_checkpoint_metadata_batch() -> DeltaResult<()> {
 }
 
 #[test]
-fn test_create_last_checkpoint_data() -> DeltaResult<()> {
-    let version = 10;
-    let total_actions_counter = 100;
-    let add_actions_counter = 75;
-    let size_in_bytes: i64 = 1024 * 1024; // 1MB
+fn test_create_last_checkpoint_hint() -> DeltaResult<()> {
     let (store, _) = new_in_memory_store();
     let engine = DefaultEngine::new(store.clone(), Arc::new(TokioBackgroundExecutor::new()));
-
-    // Create last checkpoint metadata
-    let last_checkpoint_batch = create_last_checkpoint_data(
-        &engine,
-        version,
-        total_actions_counter,
-        add_actions_counter,
-        size_in_bytes,
+    write_commit_to_store(
+        &store,
+        vec![create_basic_protocol_action(), create_metadata_action()],
+        0,
     )?;
+    let snapshot = Arc::new(Snapshot::try_new(Url::parse("memory:///")?, &engine, None)?);
+    let tags = HashMap::from([("engine".to_string(), "test".to_string())]);
+    let writer = snapshot.checkpoint()?.with_tags(tags.clone());
+
+    let hint = writer.create_last_checkpoint_hint(100, 75, 1024 * 1024, Some(2), None);
+    let expected = LastCheckpointHint {
+        version: 0,
+        size: 100,
+        parts: Some(2),
+        size_in_bytes: Some(1024 * 1024),
+        num_of_add_files: Some(75),
+        checkpoint_schema: Some(CHECKPOINT_ACTIONS_SCHEMA.as_ref().clone()),
+        checksum: None,
+        tags: Some(tags),
+        v2_checkpoint: None,
+    };
+    assert_eq!(hint, expected);
+    Ok(())
+}
 
-    // Verify the underlying EngineData contains the expected `LastCheckpointInfo` schema and data
-    let arrow_engine_data = ArrowEngineData::try_from_engine_data(last_checkpoint_batch)?;
-    let record_batch = arrow_engine_data.record_batch();
-
-    // Build the expected RecordBatch
-    let expected_schema = Arc::new(Schema::new(vec![
-        Field::new("version", DataType::Int64, false),
-        Field::new("size", DataType::Int64, false),
-        Field::new("parts", DataType::Int64, true),
-        Field::new("sizeInBytes", DataType::Int64, true),
-        Field::new("numOfAddFiles", DataType::Int64, true),
-    ]));
-    let expected = RecordBatch::try_new(
-        expected_schema,
+#[test]
+fn test_create_last_checkpoint_hint_v2_schema() -> DeltaResult<()> {
+    let (store, _) = new_in_memory_store();
+    let engine = DefaultEngine::new(store.clone(), Arc::new(TokioBackgroundExecutor::new()));
+    write_commit_to_store(
+        &store,
         vec![
-            create_array!(Int64, [version]),
-            create_array!(Int64, [total_actions_counter]),
-            create_array!(Int64, [None]),
-            create_array!(Int64, [size_in_bytes]),
-            create_array!(Int64, [add_actions_counter]),
+            create_v2_checkpoint_protocol_action(),
+            create_metadata_action(),
         ],
-    )
-    .unwrap();
-
-    assert_eq!(*record_batch, expected);
+        0,
+    )?;
+    let snapshot = Arc::new(Snapshot::try_new(Url::parse("memory:///")?, &engine, None)?);
+    let hint = snapshot
+        .checkpoint()?
+        .create_last_checkpoint_hint(3, 0, 10, None, None);
+
+    // V2 checkpoints also hold the checkpointMetadata action
+    let checkpoint_schema = hint.checkpoint_schema.unwrap();
+    let field_names = checkpoint_schema.fields().map(|f| f.name().as_str());
+    assert_eq!(
+        field_names.collect_vec(),
+        [
+            "add",
+            "remove",
+            "metaData",
+            "protocol",
+            "txn",
+            "sidecar",
+            "checkpointMetadata"
+        ]
+    );
     Ok(())
 }
 
@@ -233,7 +258,8 @@ fn create_remove_action(path: &str) -> Action {
     })
 }
 
-/// Helper to verify the contents of the `_last_checkpoint` file
+/// Helper to verify the contents of the `_last_checkpoint` file, besides its checksum and
+/// checkpoint schema (see [`read_last_checkpoint_file`])
 fn assert_last_checkpoint_contents(
     store: &Arc<InMemory>,
     expected_version: u64,
@@ -252,7 +278,8 @@ fn assert_last_checkpoint_contents(
     Ok(())
 }
 
-/// Reads the `_last_checkpoint` file from storage
+/// Reads the `_last_checkpoint` file from storage, checking that it has a checksum and a
+/// checkpoint schema. These fields and the `v2Checkpoint` field are removed from the result.
 fn read_last_checkpoint_file(store: &Arc<InMemory>) -> DeltaResult<Value> {
     let path = Path::from("_delta_log/_last_checkpoint");
     let rt = tokio::runtime::Runtime::new().expect("create tokio runtime");
@@ -260,7 +287,12 @@ fn read_last_checkpoint_file(store: &Arc<InMemory>) -> DeltaResult<Value> {
         let data = store.get(&path).await?;
         data.bytes().await
     })?;
-    Ok(from_slice(&byte_data)?)
+    let mut data: Value = from_slice(&byte_data)?;
+    let fields = data.as_object_mut().unwrap();
+    assert!(fields.remove("checksum").unwrap().is_string());
+    assert!(fields.remove("checkpointSchema").unwrap().is_object());
+    fields.remove("v2Checkpoint");
+    Ok(data)
 }
 
 /// Tests the `checkpoint()` API with:
@@ -505,3 +537,587 @@ fn test_v2_checkpoint_supported_table() -> DeltaResult<()> {
 
     Ok(())
 }
+
+type TestEngine = DefaultEngine<TokioBackgroundExecutor>;
+
+/// Writes a table partitioned by the date column `part`, with the given table properties and two
+/// files: `a` (part 2021-02-01, with JSON stats for `id` between 1 and 5) and `b` (NULL part, no
+/// stats).
+fn write_partitioned_table_with_stats(
+    table_properties: &[(&str, &str)],
+) -> DeltaResult<(Arc<InMemory>, TestEngine)> {
+    let (store, _) = new_in_memory_store();
+    let engine = DefaultEngine::new(store.clone(), Arc::new(TokioBackgroundExecutor::new()));
+    let metadata = Metadata {
+        id: "test-table".into(),
+        schema_string: json!({
+            "type": "struct",
+            "fields": [
+                {"name": "id", "type": "long", "nullable": true, "metadata": {}},
+                {"name": "part", "type": "date", "nullable": true, "metadata": {}},
+            ]
+        })
+        .to_string(),
+        partition_columns: vec!["part".to_string()],
+        configuration: table_properties
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect(),
+        ..Default::default()
+    };
+    write_commit_to_store(
+        &store,
+        vec![create_basic_protocol_action(), Action::Metadata(metadata)],
+        0,
+    )?;
+    let add = |path: &str, part: &str, stats: Option<&str>| {
+        Action::Add(Add {
+            path: path.into(),
+            partition_values: [("part".to_string(), part.to_string())].into(),
+            data_change: true,
+            stats: stats.map(String::from),
+            ..Default::default()
+        })
+    };
+    write_commit_to_store(
+        &store,
+        vec![
+            add(
+                "a",
+                "2021-02-01",
+                Some(
+                    r#"{"numRecords":2,"nullCount":{"id":0},"minValues":{"id":1},"maxValues":{"id":5}}"#,
+                ),
+            ),
+            add("b", "", None),
+        ],
+        1,
+    )?;
+    Ok((store, engine))
+}
+
+/// Returns the selected rows of the checkpoint data, as a single batch.
+fn collect_checkpoint_data(data: &mut CheckpointDataIterator) -> DeltaResult<RecordBatch> {
+    let batches: Vec<RecordBatch> = data
+        .map(|data| -> DeltaResult<_> {
+            let data = data?;
+            let batch: RecordBatch = ArrowEngineData::try_from_engine_data(data.data)?.into();
+            let selection_vector = data.selection_vector.into();
+            Ok(crate::arrow::compute::filter_record_batch(
+                &batch,
+                &selection_vector,
+            )?)
+        })
+        .try_collect()?;
+    Ok(crate::arrow::compute::concat_batches(
+        &batches[0].schema(),
+        &batches,
+    )?)
+}
+
+/// Writes a partitioned table with the given table properties (see
+/// [`write_partitioned_table_with_stats`]) and returns the `add` column of its checkpoint actions.
+fn checkpoint_add_column(table_properties: &[(&str, &str)]) -> DeltaResult<StructArray> {
+    let (_store, engine) = write_partitioned_table_with_stats(table_properties)?;
+    let snapshot = Arc::new(Snapshot::try_new(Url::parse("memory:///")?, &engine, None)?);
+    let batch = collect_checkpoint_data(&mut snapshot.checkpoint()?.checkpoint_data(&engine)?)?;
+    Ok(batch.column_by_name("add").unwrap().as_struct().clone())
+}
+
+#[test]
+fn test_checkpoint_stats_as_struct_falls_back_to_json() -> DeltaResult<()> {
+    // typed stats are not written yet, so the checkpoint keeps the JSON stats only
+    let add = checkpoint_add_column(&[("delta.checkpoint.writeStatsAsStruct", "true")])?;
+    // metadata, protocol, and two adds
+    assert_eq!(add.len(), 4);
+    assert_eq!(add.null_count(), 2);
+    let add_rows: Vec<_> = (0..add.len()).filter(|&i| add.is_valid(i)).collect();
+
+    let paths = add.column_by_name("path").unwrap().as_string::<i32>();
+    assert_eq!(
+        add_rows.iter().map(|&i| paths.value(i)).collect_vec(),
+        ["a", "b"]
+    );
+    let stats = add.column_by_name("stats").unwrap();
+    assert_eq!(
+        add_rows.iter().map(|&i| stats.is_valid(i)).collect_vec(),
+        [true, false]
+    );
+    assert!(add.column_by_name("stats_parsed").is_none());
+    assert!(add.column_by_name("partitionValues_parsed").is_none());
+    Ok(())
+}
+
+#[test]
+fn test_checkpoint_stats_without_json_falls_back_to_json() -> DeltaResult<()> {
+    // without typed stats, dropping the JSON stats would leave the files without any stats
+    for table_properties in [
+        &[("delta.checkpoint.writeStatsAsJson", "false")][..],
+        &[
+            ("delta.checkpoint.writeStatsAsJson", "false"),
+            ("delta.checkpoint.writeStatsAsStruct", "true"),
+        ],
+    ] {
+        let add = checkpoint_add_column(table_properties)?;
+        assert_eq!(add.null_count(), 2);
+        assert_eq!(add.column_by_name("stats").unwrap().null_count(), 3);
+        assert!(add.column_by_name("stats_parsed").is_none());
+    }
+    Ok(())
+}
+
+#[test]
+fn test_checkpoint_stats_json_only_by_default() -> DeltaResult<()> {
+    let add = checkpoint_add_column(&[])?;
+    assert_eq!(add.column_by_name("stats").unwrap().null_count(), 3);
+    assert!(add.column_by_name("stats_parsed").is_none());
+    assert!(add.column_by_name("partitionValues_parsed").is_none());
+    Ok(())
+}
+
+#[test]
+fn test_checkpoint_stats_fallback_data_skipping() -> DeltaResult<()> {
+    // The checkpoint keeps the JSON stats of a table that asks for typed stats only, so files can
+    // still be skipped using it
+    let (store, engine) = write_partitioned_table_with_stats(&[
+        ("delta.checkpoint.writeStatsAsJson", "false"),
+        ("delta.checkpoint.writeStatsAsStruct", "true"),
+    ])?;
+    let table_root = Url::parse("memory:///")?;
+    let writer = Arc::new(Snapshot::try_new(table_root.clone(), &engine, None)?).checkpoint()?;
+    let checkpoint_path = writer.checkpoint_path()?;
+    let mut data = writer.checkpoint_data(&engine)?;
+    let batch = collect_checkpoint_data(&mut data)?;
+    let mut buffer = vec![];
+    let mut parquet_writer = ArrowWriter::try_new(&mut buffer, batch.schema(), None)?;
+    parquet_writer.write(&batch)?;
+    parquet_writer.close()?;
+    let size = buffer.len() as u64;
+    tokio::runtime::Runtime::new()
+        .expect("create tokio runtime")
+        .block_on(store.put(&Path::from(checkpoint_path.path()), buffer.into()))?;
+    let metadata = FileMeta {
+        location: checkpoint_path,
+        last_modified: 0,
+        size,
+    };
+    writer.finalize(&engine, &metadata, data)?;
+
+    let snapshot = Arc::new(Snapshot::try_new(table_root, &engine, None)?);
+    assert_eq!(snapshot.log_segment().checkpoint_version, Some(1));
+    let scan_files = |predicate: Predicate| -> DeltaResult<Vec<String>> {
+        fn visit_path(
+            paths: &mut Vec<String>,
+            path: &str,
+            _: i64,
+            _: Option<Stats>,
+            _: DvInfo,
+            _: Option<ExpressionRef>,
+            _: HashMap<String, String>,
+        ) {
+            paths.push(path.to_string());
+        }
+        let scan = snapshot
+            .clone()
+            .scan_builder()
+            .with_predicate(Arc::new(predicate))
+            .build()?;
+        let mut paths = vec![];
+        for scan_metadata in scan.scan_metadata(&engine)? {
+            paths = scan_metadata?.visit_scan_files(paths, visit_path)?;
+        }
+        paths.sort();
+        Ok(paths)
+    };
+    // file `b` has no stats, so it is never skipped
+    let id = || column_expr!("id");
+    assert_eq!(
+        scan_files(Predicate::gt(id(), Expression::literal(10i64)))?,
+        ["b"]
+    );
+    assert_eq!(
+        scan_files(Predicate::lt(id(), Expression::literal(3i64)))?,
+        ["a", "b"]
+    );
+
+    // the number of records of `a` comes from its JSON stats, so `a` alone satisfies a limit
+    fn visit_num_records(
+        files: &mut Vec<(String, Option<u64>)>,
+        path: &str,
+        _: i64,
+        stats: Option<Stats>,
+        _: DvInfo,
+        _: Option<ExpressionRef>,
+        _: HashMap<String, String>,
+    ) {
+        files.push((path.to_string(), stats.map(|stats| stats.num_records)));
+    }
+    let scan = snapshot.scan_builder().with_limit(2).build()?;
+    let mut files = vec![];
+    for scan_metadata in scan.scan_metadata(&engine)? {
+        files = scan_metadata?.visit_scan_files(files, visit_num_records)?;
+    }
+    assert_eq!(files, [("a".to_string(), Some(2))]);
+    Ok(())
+}
+
+/// Writes the selected rows of the checkpoint data to a parquet file at `location` in the store.
+/// Batches with different schemas (e.g. the sidecar and checkpointMetadata actions of a V2
+/// checkpoint) are unified by filling the missing top-level columns with nulls.
+fn write_checkpoint_file(
+    store: &Arc<InMemory>,
+    location: &Url,
+    data: &mut CheckpointDataIterator,
+) -> DeltaResult<FileMeta> {
+    let batches: Vec<RecordBatch> = data
+        .map(|data| -> DeltaResult<_> {
+            let data = data?;
+            let batch: RecordBatch = ArrowEngineData::try_from_engine_data(data.data)?.into();
+            let selection_vector = data.selection_vector.into();
+            Ok(crate::arrow::compute::filter_record_batch(
+                &batch,
+                &selection_vector,
+            )?)
+        })
+        .try_collect()?;
+    let fields = batches
+        .iter()
+        .flat_map(|batch| batch.schema().fields().iter().cloned().collect_vec())
+        .unique_by(|field| field.name().clone())
+        .collect_vec();
+    let schema = Arc::new(Schema::new(fields));
+    let batches: Vec<RecordBatch> = batches
+        .iter()
+        .map(|batch| {
+            let columns = schema.fields().iter().map(|field| {
+                batch
+                    .column_by_name(field.name())
+                    .cloned()
+                    .unwrap_or_else(|| new_null_array(field.data_type(), batch.num_rows()))
+            });
+            RecordBatch::try_new(schema.clone(), columns.collect())
+        })
+        .try_collect()?;
+
+    let mut buffer = vec![];
+    let mut parquet_writer = ArrowWriter::try_new(&mut buffer, schema, None)?;
+    for batch in &batches {
+        parquet_writer.write(batch)?;
+    }
+    parquet_writer.close()?;
+    let size = buffer.len() as u64;
+    tokio::runtime::Runtime::new()
+        .expect("create tokio runtime")
+        .block_on(store.put(&Path::from(location.path()), buffer.into()))?;
+    Ok(FileMeta {
+        location: location.clone(),
+        last_modified: 0,
+        size,
+    })
+}
+
+/// Writes a table with the given protocol, where version 1 adds files `file1` to `file6` and
+/// version 2 removes `file1`.
+fn write_table_with_files(protocol: Action) -> DeltaResult<(Arc<InMemory>, TestEngine)> {
+    let (store, _) = new_in_memory_store();
+    let engine = DefaultEngine::new(store.clone(), Arc::new(TokioBackgroundExecutor::new()));
+    write_commit_to_store(&store, vec![protocol, create_metadata_action()], 0)?;
+    let adds = (1..=6)
+        .map(|i| create_add_action(&format!("file{i}")))
+        .collect();
+    write_commit_to_store(&store, adds, 1)?;
+    write_commit_to_store(&store, vec![create_remove_action("file1")], 2)?;
+    Ok((store, engine))
+}
+
+/// Returns the paths of the files of a scan over the latest snapshot of the table.
+fn scan_file_paths(engine: &TestEngine) -> DeltaResult<Vec<String>> {
+    fn visit_path(
+        paths: &mut Vec<String>,
+        path: &str,
+        _: i64,
+        _: Option<Stats>,
+        _: DvInfo,
+        _: Option<ExpressionRef>,
+        _: HashMap<String, String>,
+    ) {
+        paths.push(path.to_string());
+    }
+    let snapshot = Arc::new(Snapshot::try_new(Url::parse("memory:///")?, engine, None)?);
+    let scan = snapshot.scan_builder().build()?;
+    let mut paths = vec![];
+    for scan_metadata in scan.scan_metadata(engine)? {
+        paths = scan_metadata?.visit_scan_files(paths, visit_path)?;
+    }
+    paths.sort();
+    Ok(paths)
+}
+
+#[test]
+fn test_multi_part_checkpoint() -> DeltaResult<()> {
+    let (store, engine) = write_table_with_files(create_basic_protocol_action())?;
+    let table_root = Url::parse("memory:///")?;
+    let snapshot = Arc::new(Snapshot::try_new(table_root.clone(), &engine, None)?);
+    let writer = snapshot.checkpoint()?;
+
+    let part_writers = writer.parts(3)?;
+    let paths = part_writers
+        .iter()
+        .map(|part| part.path().as_str())
+        .collect_vec();
+    assert_eq!(
+        paths,
+        [
+            "memory:///_delta_log/00000000000000000002.checkpoint.0000000001.0000000003.parquet",
+            "memory:///_delta_log/00000000000000000002.checkpoint.0000000002.0000000003.parquet",
+            "memory:///_delta_log/00000000000000000002.checkpoint.0000000003.0000000003.parquet",
+        ]
+    );
+
+    // Parts are independent, so they may be written in any order
+    let parts: Vec<_> = part_writers
+        .into_iter()
+        .rev()
+        .map(|part_writer| {
+            let mut data = part_writer.checkpoint_data(&engine)?;
+            let metadata = write_checkpoint_file(&store, part_writer.path(), &mut data)?;
+            part_writer.finalize(&metadata, data)
+        })
+        .try_collect()?;
+    // Non-file actions are only in the first part, and each file action is in exactly one part
+    let non_file_actions = |part: &CheckpointPart| part.actions_count - part.add_actions_count;
+    // (besides the remove of file1, which is in any one of the parts)
+    assert!(parts
+        .iter()
+        .all(|part| part.part_num == 1 || non_file_actions(part) <= 1));
+    assert_eq!(parts.iter().map(non_file_actions).sum::<i64>(), 3);
+    assert_eq!(
+        parts.iter().map(|part| part.add_actions_count).sum::<i64>(),
+        5
+    );
+    let size_in_bytes: u64 = parts.iter().map(|part| part.size_in_bytes).sum();
+
+    writer.finalize_parts(&engine, parts)?;
+    // - size: 1 metadata + 1 protocol + 5 add actions + 1 remove action
+    // - numOfAddFiles: file2 to file6
+    assert_eq!(
+        read_last_checkpoint_file(&store)?,
+        json!({
+            "version": 2,
+            "size": 8,
+            "parts": 3,
+            "sizeInBytes": size_in_bytes,
+            "numOfAddFiles": 5,
+        })
+    );
+
+    let snapshot = Snapshot::try_new(table_root, &engine, None)?;
+    assert_eq!(snapshot.log_segment().checkpoint_version, Some(2));
+    assert_eq!(snapshot.log_segment().checkpoint_parts.len(), 3);
+    assert!(snapshot.log_segment().ascending_commit_files.is_empty());
+    assert_eq!(
+        scan_file_paths(&engine)?,
+        ["file2", "file3", "file4", "file5", "file6"]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_v2_checkpoint_with_sidecars() -> DeltaResult<()> {
+    let (store, engine) = write_table_with_files(create_v2_checkpoint_protocol_action())?;
+    let table_root = Url::parse("memory:///")?;
+    let snapshot = Arc::new(Snapshot::try_new(table_root.clone(), &engine, None)?);
+    let writer = snapshot.checkpoint()?;
+
+    let part_writers = writer.parts(2)?;
+    assert!(part_writers.iter().all(|part| part
+        .path()
+        .as_str()
+        .starts_with("memory:///_delta_log/_sidecars/")));
+    let sidecars: Vec<_> = part_writers
+        .into_iter()
+        .map(|part_writer| {
+            let mut data = part_writer.checkpoint_data(&engine)?;
+            let metadata = write_checkpoint_file(&store, part_writer.path(), &mut data)?;
+            part_writer.finalize(&metadata, data)
+        })
+        .try_collect()?;
+    // Sidecars only hold file actions: 5 adds and 1 remove
+    let actions_count: i64 = sidecars.iter().map(|sidecar| sidecar.actions_count).sum();
+    let add_actions_count: i64 = sidecars
+        .iter()
+        .map(|sidecar| sidecar.add_actions_count)
+        .sum();
+    assert_eq!((actions_count, add_actions_count), (6, 5));
+    let sidecars_size_in_bytes: u64 = sidecars.iter().map(|part| part.size_in_bytes).sum();
+
+    let mut data = writer.checkpoint_data_with_sidecars(&engine, &sidecars)?;
+    let metadata = write_checkpoint_file(&store, &writer.checkpoint_path()?, &mut data)?;
+    writer.finalize(&engine, &metadata, data)?;
+    // - size: 1 metadata + 1 protocol + 2 sidecar actions + 1 checkpointMetadata, and the
+    //   5 add actions + 1 remove action in the sidecars
+    // - numOfAddFiles: file2 to file6
+    // - sizeInBytes: the checkpoint file and its sidecars
+    assert_last_checkpoint_contents(&store, 2, 11, 5, metadata.size + sidecars_size_in_bytes)?;
+
+    // The `_last_checkpoint` file describes the top-level checkpoint file and its sidecars
+    let last_checkpoint: LastCheckpointHint = from_slice(
+        &tokio::runtime::Runtime::new()
+            .expect("create tokio runtime")
+            .block_on(async {
+                let data = store
+                    .get(&Path::from("_delta_log/_last_checkpoint"))
+                    .await?;
+                data.bytes().await
+            })?,
+    )?;
+    let v2_checkpoint = last_checkpoint.v2_checkpoint.unwrap();
+    assert_eq!(
+        v2_checkpoint.path,
+        "00000000000000000002.checkpoint.parquet"
+    );
+    assert_eq!(v2_checkpoint.size_in_bytes, metadata.size as i64);
+    assert_eq!(v2_checkpoint.sidecar_files.unwrap().len(), 2);
+
+    // The checkpoint schema is read back from the `_last_checkpoint` file, whose checksum matches
+    let snapshot = Snapshot::try_new(table_root, &engine, None)?;
+    assert_eq!(snapshot.log_segment().checkpoint_version, Some(2));
+    let checkpoint_schema = snapshot.log_segment().checkpoint_schema.as_ref().unwrap();
+    assert!(checkpoint_schema.field("checkpointMetadata").is_some());
+    assert_eq!(
+        scan_file_paths(&engine)?,
+        ["file2", "file3", "file4", "file5", "file6"]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_checkpoint_parts_errors() -> DeltaResult<()> {
+    let (_store, engine) = write_table_with_files(create_basic_protocol_action())?;
+    let table_root = Url::parse("memory:///")?;
+    let snapshot = Arc::new(Snapshot::try_new(table_root, &engine, None)?);
+
+    assert!(snapshot.clone().checkpoint()?.parts(0).is_err());
+
+    let writer = snapshot.clone().checkpoint()?;
+    let parts: Vec<_> = writer
+        .parts(2)?
+        .into_iter()
+        .map(|part_writer| {
+            let mut data = part_writer.checkpoint_data(&engine)?;
+            data.by_ref().try_for_each(|batch| batch.map(|_| ()))?;
+            let metadata = FileMeta {
+                location: part_writer.path().clone(),
+                last_modified: 0,
+                size: 10,
+            };
+            part_writer.finalize(&metadata, data)
+        })
+        .try_collect()?;
+
+    // Multi-part checkpoint parts cannot be referenced as sidecars
+    let err = writer
+        .checkpoint_data_with_sidecars(&engine, &parts)
+        .err()
+        .unwrap();
+    assert!(err.to_string().contains("support v2Checkpoints"));
+
+    // All parts are required
+    let err = snapshot
+        .clone()
+        .checkpoint()?
+        .finalize_parts(&engine, parts[..1].to_vec())
+        .unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("Expected 2 checkpoint parts, but got 1"));
+
+    // Parts cannot be duplicated
+    let err = snapshot
+        .clone()
+        .checkpoint()?
+        .finalize_parts(&engine, vec![parts[0].clone(), parts[0].clone()])
+        .unwrap_err();
+    assert!(err.to_string().contains("Duplicate checkpoint part 1"));
+    Ok(())
+}
+
+/// Writes a table with the given table properties and two commits adding a file each, then writes
+/// and finalizes a checkpoint at version 2. Returns the names of the files left in the log.
+fn checkpoint_with_table_properties(table_properties: &[(&str, &str)]) -> DeltaResult<Vec<String>> {
+    let (store, _) = new_in_memory_store();
+    let engine = DefaultEngine::new(store.clone(), Arc::new(TokioBackgroundExecutor::new()));
+    let metadata = Metadata {
+        configuration: table_properties
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect(),
+        ..match create_metadata_action() {
+            Action::Metadata(metadata) => metadata,
+            _ => unreachable!(),
+        }
+    };
+    write_commit_to_store(
+        &store,
+        vec![create_basic_protocol_action(), Action::Metadata(metadata)],
+        0,
+    )?;
+    write_commit_to_store(&store, vec![create_add_action("file1")], 1)?;
+    write_commit_to_store(&store, vec![create_add_action("file2")], 2)?;
+    // Make sure all commits are older than the (zero) log retention period
+    std::thread::sleep(Duration::from_millis(10));
+
+    let table_root = Url::parse("memory:///")?;
+    let snapshot = Arc::new(Snapshot::try_new(table_root, &engine, None)?);
+    let writer = snapshot.checkpoint()?;
+    let mut data = writer.checkpoint_data(&engine)?;
+    let metadata = write_checkpoint_file(&store, &writer.checkpoint_path()?, &mut data)?;
+    writer.finalize(&engine, &metadata, data)?;
+
+    assert_eq!(scan_file_paths(&engine)?, ["file1", "file2"]);
+    let files: Vec<_> = tokio::runtime::Runtime::new()
+        .expect("create tokio runtime")
+        .block_on(futures::TryStreamExt::try_collect::<Vec<_>>(
+            store.list(None),
+        ))?;
+    Ok(files
+        .into_iter()
+        .filter_map(|file| Some(file.location.filename()?.to_string()))
+        .sorted()
+        .collect())
+}
+
+#[test]
+fn test_checkpoint_cleans_up_expired_logs() -> DeltaResult<()> {
+    let files =
+        checkpoint_with_table_properties(&[("delta.logRetentionDuration", "interval 0 seconds")])?;
+    assert_eq!(
+        files,
+        [
+            "00000000000000000002.checkpoint.parquet",
+            "00000000000000000002.json",
+            "_last_checkpoint",
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_checkpoint_keeps_logs() -> DeltaResult<()> {
+    let expected_files = [
+        "00000000000000000000.json",
+        "00000000000000000001.json",
+        "00000000000000000002.checkpoint.parquet",
+        "00000000000000000002.json",
+        "_last_checkpoint",
+    ];
+    // Commits within the (default) log retention period are kept
+    assert_eq!(checkpoint_with_table_properties(&[])?, expected_files);
+    // Expired commits are kept if the cleanup is disabled
+    let files = checkpoint_with_table_properties(&[
+        ("delta.logRetentionDuration", "interval 0 seconds"),
+        ("delta.enableExpiredLogCleanup", "false"),
+    ])?;
+    assert_eq!(files, expected_files);
+    Ok(())
+}
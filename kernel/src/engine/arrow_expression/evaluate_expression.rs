@@ -6,6 +6,7 @@ use crate::arrow::array::{
 use crate::arrow::compute::kernels::cmp::{distinct, eq, gt, gt_eq, lt, lt_eq, neq, not_distinct};
 use crate::arrow::compute::kernels::comparison::in_list_utf8;
 use crate::arrow::compute::kernels::numeric::{add, div, mul, sub};
+use crate::arrow::compute::kernels::zip::zip;
 use crate::arrow::compute::{and_kleene, is_not_null, is_null, not, or_kleene};
 use crate::arrow::datatypes::{
     DataType as ArrowDataType, Field as ArrowField, IntervalUnit, TimeUnit,
@@ -19,7 +20,7 @@ use crate::error::{DeltaResult, Error};
 use crate::expressions::{
     BinaryExpression, BinaryExpressionOp, BinaryPredicate, BinaryPredicateOp, Expression,
     JunctionPredicate, JunctionPredicateOp, OpaqueExpression, OpaquePredicate, Predicate, Scalar,
-    UnaryPredicate, UnaryPredicateOp,
+    UnaryPredicate, UnaryPredicateOp, VariadicExpression, VariadicExpressionOp,
 };
 use crate::schema::DataType;
 use itertools::Itertools;
@@ -132,6 +133,20 @@ pub fn evaluate_expression(
 
             Ok(eval(&left_arr, &right_arr)?)
         }
+        (Variadic(VariadicExpression { op, exprs }), _) => match op {
+            VariadicExpressionOp::Coalesce => {
+                let mut arrays = exprs
+                    .iter()
+                    .map(|expr| evaluate_expression(expr, batch, result_type));
+                let Some(first) = arrays.next() else {
+                    return Err(Error::generic("COALESCE requires at least one input"));
+                };
+                // each row takes the value of the first input that is not null in that row
+                arrays.try_fold(first?, |result, array| -> DeltaResult<_> {
+                    Ok(zip(&is_not_null(&result)?, &result, &array?)?)
+                })
+            }
+        },
         (Opaque(OpaqueExpression { op, exprs }), _) => {
             match op
                 .any_ref()
@@ -399,6 +399,32 @@ fn test_binary_op() {
     assert_eq!(results.as_ref(), expected.as_ref());
 }
 
+#[test]
+fn test_coalesce() {
+    let schema = Schema::new(vec![
+        Field::new("a", DataType::Int32, true),
+        Field::new("b", DataType::Int32, true),
+    ]);
+    let batch = RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            Arc::new(Int32Array::from(vec![Some(1), None, None])),
+            Arc::new(Int32Array::from(vec![Some(10), Some(20), None])),
+        ],
+    )
+    .unwrap();
+
+    let expression = Expr::coalesce([column_expr!("a"), column_expr!("b")]);
+    let results = evaluate_expression(&expression, &batch, None).unwrap();
+    let expected = Arc::new(Int32Array::from(vec![Some(1), Some(20), None]));
+    assert_eq!(results.as_ref(), expected.as_ref());
+
+    let expression = Expr::coalesce([column_expr!("a"), column_expr!("b"), Expr::literal(0)]);
+    let results = evaluate_expression(&expression, &batch, None).unwrap();
+    let expected = Arc::new(Int32Array::from(vec![1, 20, 0]));
+    assert_eq!(results.as_ref(), expected.as_ref());
+}
+
 #[test]
 fn test_binary_cmp() {
     let schema = Schema::new(vec![Field::new("a", DataType::Int32, false)]);
@@ -2,20 +2,21 @@
 
 use std::collections::HashSet;
 use std::io::{BufRead, BufReader};
+use std::ops::Range;
 use std::sync::Arc;
 
 use crate::engine::arrow_conversion::{TryFromKernel as _, TryIntoArrow as _};
 use crate::engine::ensure_data_types::DataTypeCompat;
 use crate::{
     engine::arrow_data::ArrowEngineData,
-    schema::{DataType, Schema, SchemaRef, StructField, StructType},
+    schema::{DataType, MetadataColumnSpec, Schema, SchemaRef, StructField, StructType},
     utils::require,
     DeltaResult, EngineData, Error,
 };
 
 use crate::arrow::array::{
-    cast::AsArray, make_array, new_null_array, Array as ArrowArray, GenericListArray, MapArray,
-    OffsetSizeTrait, RecordBatch, StringArray, StructArray,
+    cast::AsArray, make_array, new_null_array, Array as ArrowArray, GenericListArray, Int64Array,
+    MapArray, OffsetSizeTrait, RecordBatch, StringArray, StructArray,
 };
 use crate::arrow::buffer::NullBuffer;
 use crate::arrow::compute::concat_batches;
@@ -24,6 +25,7 @@ use crate::arrow::datatypes::{
     Schema as ArrowSchema, SchemaRef as ArrowSchemaRef,
 };
 use crate::arrow::json::{LineDelimitedWriter, ReaderBuilder};
+use crate::parquet::file::metadata::RowGroupMetaData;
 use crate::parquet::{arrow::ProjectionMask, schema::types::SchemaDescriptor};
 use delta_kernel_derive::internal_api;
 use itertools::Itertools;
@@ -68,17 +70,82 @@ pub(crate) fn make_arrow_error(s: impl Into<String>) -> Error {
     .with_backtrace()
 }
 
+/// The row indexes (in the file) of the rows a parquet reader returns, in the order it returns them.
+pub(crate) type RowIndexIterator = std::iter::Flatten<std::vec::IntoIter<Range<i64>>>;
+
+/// Computes the row indexes of the rows read from a parquet file, for the
+/// [`MetadataColumnSpec::RowIndex`] metadata column. The row indexes of a row group follow from
+/// the number of rows in the row groups before it, so the builder must be told which row groups the
+/// reader skips.
+pub(crate) struct RowIndexBuilder {
+    // the range of row indexes of each row group of the file
+    row_group_row_index_ranges: Vec<Range<i64>>,
+    // the row groups the reader reads, or `None` if it reads all of them
+    row_group_ordinals: Option<Vec<usize>>,
+}
+
+impl RowIndexBuilder {
+    pub(crate) fn new(row_groups: &[RowGroupMetaData]) -> Self {
+        let mut offset = 0;
+        let row_group_row_index_ranges = row_groups
+            .iter()
+            .map(|row_group| {
+                let start = offset;
+                offset += row_group.num_rows();
+                start..offset
+            })
+            .collect();
+        Self {
+            row_group_row_index_ranges,
+            row_group_ordinals: None,
+        }
+    }
+
+    /// Only compute row indexes for the given row groups (in the order they are given).
+    pub(crate) fn select_row_groups(&mut self, ordinals: &[usize]) {
+        self.row_group_ordinals = Some(ordinals.to_vec());
+    }
+
+    pub(crate) fn build(self) -> DeltaResult<RowIndexIterator> {
+        let ranges = match self.row_group_ordinals {
+            Some(ordinals) => ordinals
+                .iter()
+                .map(|&ordinal| {
+                    self.row_group_row_index_ranges
+                        .get(ordinal)
+                        .cloned()
+                        .ok_or_else(|| {
+                            Error::internal_error(format!("Row group {ordinal} does not exist"))
+                        })
+                })
+                .try_collect()?,
+            None => self.row_group_row_index_ranges,
+        };
+        Ok(ranges.into_iter().flatten())
+    }
+}
+
+/// Whether reading with `requested_ordering` produces a row index column, in which case the row
+/// indexes must be passed to [`fixup_parquet_read`].
+pub(crate) fn ordering_needs_row_indexes(requested_ordering: &[ReorderIndex]) -> bool {
+    requested_ordering
+        .iter()
+        .any(|reorder_index| matches!(reorder_index.transform, ReorderIndexTransform::RowIndex(_)))
+}
+
 /// Applies post-processing to data read from parquet files. This includes `reorder_struct_array` to
 /// ensure schema compatibility, as well as `fix_nested_null_masks` to ensure that leaf columns have
-/// accurate null masks that row visitors rely on for correctness.
+/// accurate null masks that row visitors rely on for correctness. If the requested schema has a row
+/// index column, `row_indexes` must provide the row indexes of the batch's rows.
 pub(crate) fn fixup_parquet_read<T>(
     batch: RecordBatch,
     requested_ordering: &[ReorderIndex],
+    row_indexes: Option<&mut RowIndexIterator>,
 ) -> DeltaResult<T>
 where
     StructArray: Into<T>,
 {
-    let data = reorder_struct_array(batch.into(), requested_ordering)?;
+    let data = reorder_struct_array(batch.into(), requested_ordering, row_indexes)?;
     let data = fix_nested_null_masks(data);
     Ok(data.into())
 }
@@ -206,6 +273,8 @@ pub(crate) enum ReorderIndexTransform {
     Identity,
     /// Data is missing, fill in with a null column
     Missing(ArrowFieldRef),
+    /// Not read from the file, fill in with the row index of each row
+    RowIndex(ArrowFieldRef),
 }
 
 impl ReorderIndex {
@@ -229,12 +298,18 @@ impl ReorderIndex {
         ReorderIndex::new(index, ReorderIndexTransform::Missing(field))
     }
 
+    fn row_index(index: usize, field: ArrowFieldRef) -> Self {
+        ReorderIndex::new(index, ReorderIndexTransform::RowIndex(field))
+    }
+
     /// Check if this reordering requires a transformation anywhere. See comment below on
     /// [`ordering_needs_transform`] to understand why this is needed.
     fn needs_transform(&self) -> bool {
         match self.transform {
-            // if we're casting or inserting null, we need to transform
-            ReorderIndexTransform::Cast(_) | ReorderIndexTransform::Missing(_) => true,
+            // if we're casting or inserting null or row indexes, we need to transform
+            ReorderIndexTransform::Cast(_)
+            | ReorderIndexTransform::Missing(_)
+            | ReorderIndexTransform::RowIndex(_) => true,
             // if our nested ordering needs a transform, we need a transform
             ReorderIndexTransform::Nested(ref children) => ordering_needs_transform(children),
             // no transform needed
@@ -277,8 +352,12 @@ fn get_indices(
     // for each field, get its position in the parquet (via enumerate), a reference to the arrow
     // field, and info about where it appears in the requested_schema, or None if the field is not
     // requested
+    // metadata columns are never read from the file, even if it has a column of the same name
     let all_field_info = fields.iter().enumerate().map(|(parquet_index, field)| {
-        let field_info = requested_schema.fields.get_full(field.name());
+        let field_info = requested_schema
+            .fields
+            .get_full(field.name())
+            .filter(|(_, _, requested_field)| !requested_field.is_metadata_column());
         (parquet_index, field, field_info)
     });
     for (parquet_index, field, field_info) in all_field_info {
@@ -438,7 +517,18 @@ fn get_indices(
         // some fields are missing, but they might be nullable, need to insert them into the reorder_indices
         for (requested_position, field) in requested_schema.fields().enumerate() {
             if !found_fields.contains(field.name()) {
-                if field.nullable {
+                if let Some(spec) = field.get_metadata_column_spec() {
+                    if spec != MetadataColumnSpec::RowIndex {
+                        return Err(Error::unsupported(format!(
+                            "Metadata column {spec:?} cannot be read from a parquet file: {}",
+                            field.name()
+                        )));
+                    }
+                    reorder_indices.push(ReorderIndex::row_index(
+                        requested_position,
+                        Arc::new(field.try_into_arrow()?),
+                    ));
+                } else if field.nullable {
                     debug!("Inserting missing and nullable field: {}", field.name());
                     reorder_indices.push(ReorderIndex::missing(
                         requested_position,
@@ -521,10 +611,13 @@ fn ordering_needs_transform(requested_ordering: &[ReorderIndex]) -> bool {
 type FieldArrayOpt = Option<(Arc<ArrowField>, Arc<dyn ArrowArray>)>;
 
 /// Reorder a RecordBatch to match `requested_ordering`. For each non-zero value in
-/// `requested_ordering`, the column at that index will be added in order to returned batch
+/// `requested_ordering`, the column at that index will be added in order to returned batch. The
+/// next `input_data.len()` values of `row_indexes` fill in the row index column, if requested (only
+/// a top-level row index column is supported).
 pub(crate) fn reorder_struct_array(
     input_data: StructArray,
     requested_ordering: &[ReorderIndex],
+    mut row_indexes: Option<&mut RowIndexIterator>,
 ) -> DeltaResult<StructArray> {
     debug!("Reordering {input_data:?} with ordering: {requested_ordering:?}");
     if !ordering_needs_transform(requested_ordering) {
@@ -538,6 +631,7 @@ pub(crate) fn reorder_struct_array(
         let num_cols = requested_ordering.len();
         let (input_fields, input_cols, null_buffer) = input_data.into_parts();
         let mut final_fields_cols: Vec<FieldArrayOpt> = vec![None; num_cols];
+        let mut row_index_array: Option<Arc<dyn ArrowArray>> = None;
         for (parquet_position, reorder_index) in requested_ordering.iter().enumerate() {
             // for each item, reorder_index.index() tells us where to put it, and its position in
             // requested_ordering tells us where it is in the parquet data
@@ -559,7 +653,7 @@ pub(crate) fn reorder_struct_array(
                         ArrowDataType::Struct(_) => {
                             let struct_array = input_cols[parquet_position].as_struct().clone();
                             let result_array =
-                                Arc::new(reorder_struct_array(struct_array, children)?);
+                                Arc::new(reorder_struct_array(struct_array, children, None)?);
                             // create the new field specifying the correct order for the struct
                             let new_field = Arc::new(ArrowField::new_struct(
                                 input_field_name,
@@ -602,6 +696,28 @@ pub(crate) fn reorder_struct_array(
                     let field = field.clone(); // cheap Arc clone
                     final_fields_cols[reorder_index.index] = Some((field, null_array));
                 }
+                ReorderIndexTransform::RowIndex(field) => {
+                    // all row index columns of the batch share the same row indexes
+                    let array = match row_index_array {
+                        Some(ref array) => Arc::clone(array),
+                        None => {
+                            let Some(ref mut row_indexes) = row_indexes else {
+                                return Err(Error::internal_error(
+                                    "Row indexes are required to fill in a row index column",
+                                ));
+                            };
+                            let array: Int64Array = row_indexes.by_ref().take(num_rows).collect();
+                            require!(
+                                array.len() == num_rows,
+                                Error::internal_error("Ran out of row indexes for the rows read")
+                            );
+                            let array: Arc<dyn ArrowArray> = Arc::new(array);
+                            row_index_array = Some(Arc::clone(&array));
+                            array
+                        }
+                    };
+                    final_fields_cols[reorder_index.index] = Some((field.clone(), array));
+                }
             }
         }
         let num_cols = final_fields_cols.len();
@@ -627,7 +743,7 @@ fn reorder_list<O: OffsetSizeTrait>(
     let (list_field, offset_buffer, maybe_sa, null_buf) = list_array.into_parts();
     if let Some(struct_array) = maybe_sa.as_struct_opt() {
         let struct_array = struct_array.clone();
-        let result_array = Arc::new(reorder_struct_array(struct_array, children)?);
+        let result_array = Arc::new(reorder_struct_array(struct_array, children, None)?);
         let new_list_field = Arc::new(ArrowField::new_struct(
             list_field.name(),
             result_array.fields().clone(),
@@ -658,7 +774,7 @@ fn reorder_map(
     children: &[ReorderIndex],
 ) -> DeltaResult<FieldArrayOpt> {
     let (map_field, offset_buffer, struct_array, null_buf, ordered) = map_array.into_parts();
-    let result_array = reorder_struct_array(struct_array, children)?;
+    let result_array = reorder_struct_array(struct_array, children, None)?;
     let result_fields = result_array.fields();
     let new_map_field = Arc::new(ArrowField::new_struct(
         map_field.name(),
@@ -809,7 +925,7 @@ mod tests {
         MapArray, MapBuilder, StructArray, StructBuilder,
     };
     use crate::arrow::datatypes::{
-        DataType as ArrowDataType, Field as ArrowField, Fields, Schema as ArrowSchema,
+        DataType as ArrowDataType, Field as ArrowField, Fields, Int64Type, Schema as ArrowSchema,
         SchemaRef as ArrowSchemaRef,
     };
     use crate::arrow::{
@@ -910,6 +1026,46 @@ mod tests {
         assert_eq!(reorder_indices, expect_reorder);
     }
 
+    #[test]
+    fn row_index_mask_indices() {
+        let requested_schema = Arc::new(StructType::new([
+            StructField::not_null("i", DataType::INTEGER),
+            StructField::create_metadata_column("row_index", MetadataColumnSpec::RowIndex),
+        ]));
+        // a metadata column is never read from the file, even if the file has a column of that name
+        let parquet_schema = Arc::new(ArrowSchema::new(vec![
+            ArrowField::new("i", ArrowDataType::Int32, false),
+            ArrowField::new("row_index", ArrowDataType::Int64, false),
+        ]));
+        let (mask_indices, reorder_indices) =
+            get_requested_indices(&requested_schema, &parquet_schema).unwrap();
+        let expect_mask = vec![0];
+        let expect_reorder = vec![
+            ReorderIndex::identity(0),
+            ReorderIndex::row_index(
+                1,
+                Arc::new(
+                    requested_schema
+                        .field("row_index")
+                        .unwrap()
+                        .try_into_arrow()
+                        .unwrap(),
+                ),
+            ),
+        ];
+        assert_eq!(mask_indices, expect_mask);
+        assert_eq!(reorder_indices, expect_reorder);
+        assert!(ordering_needs_row_indexes(&reorder_indices));
+
+        // other metadata columns can't be read from a parquet file
+        let requested_schema = Arc::new(StructType::new([
+            StructField::not_null("i", DataType::INTEGER),
+            StructField::create_metadata_column("row_id", MetadataColumnSpec::RowId),
+        ]));
+        let res = get_requested_indices(&requested_schema, &parquet_schema);
+        assert!(matches!(res, Err(Error::Unsupported(_))));
+    }
+
     #[test]
     fn ensure_data_types_fails_correctly() {
         let requested_schema = Arc::new(StructType::new([
@@ -1466,10 +1622,40 @@ mod tests {
     fn simple_reorder_struct() {
         let arry = make_struct_array();
         let reorder = vec![ReorderIndex::identity(1), ReorderIndex::identity(0)];
-        let ordered = reorder_struct_array(arry, &reorder).unwrap();
+        let ordered = reorder_struct_array(arry, &reorder, None).unwrap();
         assert_eq!(ordered.column_names(), vec!["c", "b"]);
     }
 
+    #[test]
+    fn reorder_struct_with_row_index() {
+        let arry = make_struct_array();
+        let row_index_field = Arc::new(ArrowField::new("row_index", ArrowDataType::Int64, false));
+        let reorder = vec![
+            ReorderIndex::identity(0),
+            ReorderIndex::identity(1),
+            ReorderIndex::row_index(2, row_index_field),
+        ];
+        // row group 1 (rows 2..5) is skipped
+        let mut row_indexes = RowIndexBuilder {
+            row_group_row_index_ranges: vec![0..2, 2..5, 5..9],
+            row_group_ordinals: None,
+        };
+        row_indexes.select_row_groups(&[0, 2]);
+        let mut row_indexes = row_indexes.build().unwrap();
+        let ordered = reorder_struct_array(arry, &reorder, Some(&mut row_indexes)).unwrap();
+        assert_eq!(ordered.column_names(), vec!["b", "c", "row_index"]);
+        let row_index = ordered.column(2).as_primitive::<Int64Type>();
+        assert_eq!(row_index.values(), &[0, 1, 5, 6]);
+
+        // the next batch continues where the previous one left off, and runs out of row indexes
+        let res = reorder_struct_array(make_struct_array(), &reorder, Some(&mut row_indexes));
+        assert!(res.is_err());
+
+        // row indexes are required to fill in a row index column
+        let res = reorder_struct_array(make_struct_array(), &reorder, None);
+        assert!(res.is_err());
+    }
+
     #[test]
     fn nested_reorder_struct() {
         let arry1 = Arc::new(make_struct_array());
@@ -1514,7 +1700,7 @@ mod tests {
                 ],
             ),
         ];
-        let ordered = reorder_struct_array(nested, &reorder).unwrap();
+        let ordered = reorder_struct_array(nested, &reorder, None).unwrap();
         assert_eq!(ordered.column_names(), vec!["struct2", "struct1"]);
         let ordered_s2 = ordered.column(0).as_struct();
         assert_eq!(ordered_s2.column_names(), vec!["b", "c", "s"]);
@@ -1561,7 +1747,7 @@ mod tests {
             0,
             vec![ReorderIndex::identity(1), ReorderIndex::identity(0)],
         )];
-        let ordered = reorder_struct_array(struct_array, &reorder).unwrap();
+        let ordered = reorder_struct_array(struct_array, &reorder, None).unwrap();
         let ordered_list_col = ordered.column(0).as_list::<i32>();
         for i in 0..ordered_list_col.len() {
             let array_item = ordered_list_col.value(i);
@@ -1627,7 +1813,7 @@ mod tests {
                 ],
             ),
         ];
-        let ordered = reorder_struct_array(struct_array, &reorder).unwrap();
+        let ordered = reorder_struct_array(struct_array, &reorder, None).unwrap();
         assert_eq!(ordered.column_names(), vec!["map", "i"]);
         if let ArrowDataType::Map(field, _) = ordered.column(0).data_type() {
             if let ArrowDataType::Struct(fields) = field.data_type() {
@@ -7,7 +7,7 @@ use itertools::Itertools;
 use url::Url;
 
 use crate::object_store::path::Path;
-use crate::object_store::{DynObjectStore, ObjectStore};
+use crate::object_store::{self, DynObjectStore, ObjectStore, PutMode};
 
 use super::UrlExt;
 use crate::engine::default::executor::TaskExecutor;
@@ -183,6 +183,37 @@ impl<E: TaskExecutor> StorageHandler for ObjectStoreStorageHandler<E> {
 
         Ok(Box::new(receiver.into_iter()))
     }
+
+    fn write_file(&self, path: &Url, data: Bytes, overwrite: bool) -> DeltaResult<()> {
+        let put_mode = if overwrite {
+            PutMode::Overwrite
+        } else {
+            PutMode::Create
+        };
+
+        let store = self.inner.clone();
+        let path = Path::from_url_path(path.path())?;
+        let path_str = path.to_string();
+        self.task_executor
+            .block_on(async move { store.put_opts(&path, data.into(), put_mode.into()).await })
+            .map_err(|e| match e {
+                object_store::Error::AlreadyExists { .. } => Error::FileAlreadyExists(path_str),
+                e => e.into(),
+            })?;
+        Ok(())
+    }
+
+    fn delete_file(&self, path: &Url) -> DeltaResult<()> {
+        let store = self.inner.clone();
+        let path = Path::from_url_path(path.path())?;
+        match self
+            .task_executor
+            .block_on(async move { store.delete(&path).await })
+        {
+            Ok(()) | Err(object_store::Error::NotFound { .. }) => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -308,4 +339,26 @@ mod tests {
         }
         assert_eq!(len, 10, "list_from should have returned 10 files");
     }
+
+    #[test]
+    fn test_delete_file() {
+        let store = Arc::new(InMemory::new());
+        let storage =
+            ObjectStoreStorageHandler::new(store.clone(), Arc::new(TokioBackgroundExecutor::new()));
+        let url = Url::parse("memory:///_delta_log/00000000000000000000.json").unwrap();
+
+        storage
+            .write_file(&url, Bytes::from("kernel-data"), false)
+            .unwrap();
+        storage.delete_file(&url).unwrap();
+        let files: Vec<_> = storage
+            .list_from(&Url::parse("memory:///_delta_log/").unwrap())
+            .unwrap()
+            .try_collect()
+            .unwrap();
+        assert!(files.is_empty());
+
+        // deleting a missing file succeeds
+        storage.delete_file(&url).unwrap();
+    }
 }
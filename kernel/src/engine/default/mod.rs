@@ -31,7 +31,9 @@ pub mod file_stream;
 pub mod filesystem;
 pub mod json;
 pub mod parquet;
+mod stats;
 pub mod storage;
+mod vacuum;
 
 #[derive(Debug)]
 pub struct DefaultEngine<E: TaskExecutor> {
@@ -102,19 +104,28 @@ impl<E: TaskExecutor> DefaultEngine<E> {
     ) -> DeltaResult<Box<dyn EngineData>> {
         let transform = write_context.logical_to_physical();
         let input_schema = Schema::try_from_arrow(data.record_batch().schema())?;
-        let output_schema = write_context.schema();
+        let output_schema = write_context.physical_schema();
         let logical_to_physical_expr = self.evaluation_handler().new_expression_evaluator(
             input_schema.into(),
             transform.clone(),
             output_schema.clone().into(),
         );
         let physical_data = logical_to_physical_expr.evaluate(data)?;
+        // partition values are keyed by the physical names of the partition columns
+        let partition_values = partition_values
+            .into_iter()
+            .map(|(name, value)| match write_context.schema().field(&name) {
+                Some(field) => (field.physical_name().to_string(), value),
+                None => (name, value),
+            })
+            .collect();
         self.parquet
-            .write_parquet_file(
+            .write_parquet_file_with_stats(
                 write_context.target_dir(),
                 physical_data,
                 partition_values,
                 data_change,
+                write_context.stats_columns(),
             )
             .await
     }
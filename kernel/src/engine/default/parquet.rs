@@ -17,16 +17,20 @@ use futures::StreamExt;
 use uuid::Uuid;
 
 use super::file_stream::{FileOpenFuture, FileOpener, FileStream};
+use super::stats::collect_stats;
 use super::UrlExt;
-use crate::engine::arrow_conversion::TryIntoArrow as _;
+use crate::engine::arrow_conversion::{TryFromArrow as _, TryIntoArrow as _};
 use crate::engine::arrow_data::ArrowEngineData;
-use crate::engine::arrow_utils::{fixup_parquet_read, generate_mask, get_requested_indices};
+use crate::engine::arrow_utils::{
+    fixup_parquet_read, generate_mask, get_requested_indices, ordering_needs_row_indexes,
+    RowIndexBuilder,
+};
 use crate::engine::default::executor::TaskExecutor;
 use crate::engine::parquet_row_group_skipping::ParquetRowGroupSkipping;
-use crate::schema::SchemaRef;
+use crate::schema::{ColumnName, SchemaRef, StructType};
 use crate::{
-    DeltaResult, EngineData, Error, FileDataReadResultIterator, FileMeta, ParquetHandler,
-    PredicateRef,
+    DeltaResult, EngineData, Error, FileDataReadResultIterator, FileMeta, ParquetFooter,
+    ParquetHandler, PredicateRef,
 };
 
 #[derive(Debug)]
@@ -36,16 +40,26 @@ pub struct DefaultParquetHandler<E: TaskExecutor> {
     readahead: usize,
 }
 
-/// Metadata of a data file (typically a parquet file), currently just includes the file metadata
-/// but will expand to include file statistics and other metadata in the future.
+/// Metadata of a data file (typically a parquet file): the file metadata and the file statistics
+/// (serialized as the JSON `stats` of an `add` action).
 #[derive(Debug)]
 pub struct DataFileMetadata {
     file_meta: FileMeta,
+    stats: Option<String>,
 }
 
 impl DataFileMetadata {
     pub fn new(file_meta: FileMeta) -> Self {
-        Self { file_meta }
+        Self {
+            file_meta,
+            stats: None,
+        }
+    }
+
+    /// Set the file statistics, serialized as the JSON `stats` of an `add` action.
+    pub fn with_stats(mut self, stats: String) -> Self {
+        self.stats = Some(stats);
+        self
     }
 
     // convert DataFileMetadata into a record batch which matches the 'add_files_schema' schema
@@ -61,6 +75,7 @@ impl DataFileMetadata {
                     last_modified,
                     size,
                 },
+            stats,
         } = self;
         let add_files_schema = crate::transaction::add_files_schema();
 
@@ -87,9 +102,17 @@ impl DataFileMetadata {
         let size = Arc::new(Int64Array::from(vec![size]));
         let data_change = Arc::new(BooleanArray::from(vec![data_change]));
         let modification_time = Arc::new(Int64Array::from(vec![*last_modified]));
+        let stats = Arc::new(StringArray::from(vec![stats.clone()]));
         Ok(Box::new(ArrowEngineData::new(RecordBatch::try_new(
             Arc::new(add_files_schema.as_ref().try_into_arrow()?),
-            vec![path, partitions, size, modification_time, data_change],
+            vec![
+                path,
+                partitions,
+                size,
+                modification_time,
+                data_change,
+                stats,
+            ],
         )?)))
     }
 }
@@ -112,7 +135,8 @@ impl<E: TaskExecutor> DefaultParquetHandler<E> {
     }
 
     // Write `data` to `{path}/<uuid>.parquet` as parquet using ArrowWriter and return the parquet
-    // metadata (where `<uuid>` is a generated UUIDv4).
+    // metadata, including the statistics of the `stats_columns` (where `<uuid>` is a generated
+    // UUIDv4).
     //
     // Note: after encoding the data as parquet, this issues a PUT followed by a HEAD to storage in
     // order to obtain metadata about the object just written.
@@ -120,9 +144,11 @@ impl<E: TaskExecutor> DefaultParquetHandler<E> {
         &self,
         path: &url::Url,
         data: Box<dyn EngineData>,
+        stats_columns: &[ColumnName],
     ) -> DeltaResult<DataFileMetadata> {
         let batch: Box<_> = ArrowEngineData::try_from_engine_data(data)?;
         let record_batch = batch.record_batch();
+        let stats = collect_stats(record_batch, stats_columns)?;
 
         let mut buffer = vec![];
         let mut writer = ArrowWriter::try_new(&mut buffer, record_batch.schema(), None)?;
@@ -161,7 +187,7 @@ impl<E: TaskExecutor> DefaultParquetHandler<E> {
         }
 
         let file_meta = FileMeta::new(path, modification_time, size);
-        Ok(DataFileMetadata::new(file_meta))
+        Ok(DataFileMetadata::new(file_meta).with_stats(stats))
     }
 
     /// Write `data` to `{path}/<uuid>.parquet` as parquet using ArrowWriter and return the parquet
@@ -176,7 +202,24 @@ impl<E: TaskExecutor> DefaultParquetHandler<E> {
         partition_values: HashMap<String, String>,
         data_change: bool,
     ) -> DeltaResult<Box<dyn EngineData>> {
-        let parquet_metadata = self.write_parquet(path, data).await?;
+        self.write_parquet_file_with_stats(path, data, partition_values, data_change, &[])
+            .await
+    }
+
+    /// Like [`write_parquet_file`], but the file statistics also cover the given (physical)
+    /// `stats_columns`, see [`WriteContext::stats_columns`].
+    ///
+    /// [`write_parquet_file`]: Self::write_parquet_file
+    /// [`WriteContext::stats_columns`]: crate::transaction::WriteContext::stats_columns
+    pub async fn write_parquet_file_with_stats(
+        &self,
+        path: &url::Url,
+        data: Box<dyn EngineData>,
+        partition_values: HashMap<String, String>,
+        data_change: bool,
+        stats_columns: &[ColumnName],
+    ) -> DeltaResult<Box<dyn EngineData>> {
+        let parquet_metadata = self.write_parquet(path, data, stats_columns).await?;
         parquet_metadata.as_record_batch(&partition_values, data_change)
     }
 }
@@ -222,6 +265,68 @@ impl<E: TaskExecutor> ParquetHandler for DefaultParquetHandler<E> {
             self.readahead,
         )
     }
+
+    fn read_parquet_footer(&self, file: &FileMeta) -> DeltaResult<ParquetFooter> {
+        let location = file.location.clone();
+        let arrow_schema = if location.is_presigned() {
+            self.task_executor.block_on(async move {
+                let client = reqwest::Client::new();
+                let reader = client.get(location).send().await?.bytes().await?;
+                let metadata = ArrowReaderMetadata::load(&reader, Default::default())?;
+                Ok::<_, Error>(metadata.schema().clone())
+            })?
+        } else {
+            let store = self.store.clone();
+            self.task_executor.block_on(async move {
+                let path = Path::from_url_path(location.path())?;
+                let mut reader = parquet_object_reader(store, path, &location).await?;
+                let metadata =
+                    ArrowReaderMetadata::load_async(&mut reader, Default::default()).await?;
+                Ok::<_, Error>(metadata.schema().clone())
+            })?
+        };
+        Ok(ParquetFooter {
+            schema: Arc::new(StructType::try_from_arrow(arrow_schema)?),
+        })
+    }
+}
+
+/// Creates a reader for the parquet file at `path` in `store`, whose URL is `location`.
+async fn parquet_object_reader(
+    store: Arc<DynObjectStore>,
+    path: Path,
+    location: &url::Url,
+) -> DeltaResult<ParquetObjectReader> {
+    #[cfg(feature = "arrow-55")]
+    let reader = {
+        use crate::object_store::ObjectStoreScheme;
+        // HACK: unfortunately, `ParquetObjectReader` under the hood does a suffix range
+        // request which isn't supported by Azure. For now we just detect if the URL is
+        // pointing to azure and if so, do a HEAD request so we can pass in file size to the
+        // reader which will cause the reader to avoid a suffix range request.
+        // see also: https://github.com/delta-io/delta-kernel-rs/issues/968
+        //
+        // TODO(#1010): Note that we don't need this at all and can actually just _always_
+        // do the `with_file_size` but need to (1) update our unit tests which often
+        // hardcode size=0 and (2) update CDF execute which also hardcodes size=0.
+        if let Ok((ObjectStoreScheme::MicrosoftAzure, _)) = ObjectStoreScheme::parse(location) {
+            // also note doing HEAD then actual GET isn't atomic, and leaves us vulnerable
+            // to file changing between the two calls.
+            let meta = store.head(&path).await?;
+            ParquetObjectReader::new(store, path).with_file_size(meta.size)
+        } else {
+            ParquetObjectReader::new(store, path)
+        }
+    };
+    #[cfg(all(feature = "arrow-54", not(feature = "arrow-55")))]
+    let reader = {
+        let _ = location;
+        // TODO avoid IO by converting passed file meta to ObjectMeta (no longer an issue
+        // in arrow 55)
+        let meta = store.head(&path).await?;
+        ParquetObjectReader::new(store, meta)
+    };
+    Ok(reader)
 }
 
 /// Implements [`FileOpener`] for a parquet file
@@ -263,36 +368,7 @@ impl FileOpener for ParquetOpener {
         let limit = self.limit;
 
         Ok(Box::pin(async move {
-            #[cfg(feature = "arrow-55")]
-            let mut reader = {
-                use crate::object_store::ObjectStoreScheme;
-                // HACK: unfortunately, `ParquetObjectReader` under the hood does a suffix range
-                // request which isn't supported by Azure. For now we just detect if the URL is
-                // pointing to azure and if so, do a HEAD request so we can pass in file size to the
-                // reader which will cause the reader to avoid a suffix range request.
-                // see also: https://github.com/delta-io/delta-kernel-rs/issues/968
-                //
-                // TODO(#1010): Note that we don't need this at all and can actually just _always_
-                // do the `with_file_size` but need to (1) update our unit tests which often
-                // hardcode size=0 and (2) update CDF execute which also hardcodes size=0.
-                if let Ok((ObjectStoreScheme::MicrosoftAzure, _)) =
-                    ObjectStoreScheme::parse(&file_meta.location)
-                {
-                    // also note doing HEAD then actual GET isn't atomic, and leaves us vulnerable
-                    // to file changing between the two calls.
-                    let meta = store.head(&path).await?;
-                    ParquetObjectReader::new(store, path).with_file_size(meta.size)
-                } else {
-                    ParquetObjectReader::new(store, path)
-                }
-            };
-            #[cfg(all(feature = "arrow-54", not(feature = "arrow-55")))]
-            let mut reader = {
-                // TODO avoid IO by converting passed file meta to ObjectMeta (no longer an issue
-                // in arrow 55)
-                let meta = store.head(&path).await?;
-                ParquetObjectReader::new(store, meta)
-            };
+            let mut reader = parquet_object_reader(store, path, &file_meta.location).await?;
             let metadata = ArrowReaderMetadata::load_async(&mut reader, Default::default()).await?;
             let parquet_schema = metadata.schema();
             let (indices, requested_ordering) =
@@ -309,16 +385,21 @@ impl FileOpener for ParquetOpener {
                 builder = builder.with_projection(mask)
             }
 
+            let mut row_indexes = ordering_needs_row_indexes(&requested_ordering)
+                .then(|| RowIndexBuilder::new(builder.metadata().row_groups()));
             if let Some(ref predicate) = predicate {
-                builder = builder.with_row_group_filter(predicate);
+                builder = builder.with_row_group_filter(predicate, row_indexes.as_mut());
             }
+            let mut row_indexes = row_indexes.map(|rb| rb.build()).transpose()?;
             if let Some(limit) = limit {
                 builder = builder.with_limit(limit)
             }
 
             let stream = builder.with_batch_size(batch_size).build()?;
 
-            let stream = stream.map(move |rbr| fixup_parquet_read(rbr?, &requested_ordering));
+            let stream = stream.map(move |rbr| {
+                fixup_parquet_read(rbr?, &requested_ordering, row_indexes.as_mut())
+            });
             Ok(stream.boxed())
         }))
     }
@@ -377,9 +458,12 @@ impl FileOpener for PresignedUrlOpener {
                 builder = builder.with_projection(mask)
             }
 
+            let mut row_indexes = ordering_needs_row_indexes(&requested_ordering)
+                .then(|| RowIndexBuilder::new(builder.metadata().row_groups()));
             if let Some(ref predicate) = predicate {
-                builder = builder.with_row_group_filter(predicate);
+                builder = builder.with_row_group_filter(predicate, row_indexes.as_mut());
             }
+            let mut row_indexes = row_indexes.map(|rb| rb.build()).transpose()?;
             if let Some(limit) = limit {
                 builder = builder.with_limit(limit)
             }
@@ -387,7 +471,9 @@ impl FileOpener for PresignedUrlOpener {
             let reader = builder.with_batch_size(batch_size).build()?;
 
             let stream = futures::stream::iter(reader);
-            let stream = stream.map(move |rbr| fixup_parquet_read(rbr?, &requested_ordering));
+            let stream = stream.map(move |rbr| {
+                fixup_parquet_read(rbr?, &requested_ordering, row_indexes.as_mut())
+            });
             Ok(stream.boxed())
         }))
     }
@@ -405,6 +491,7 @@ mod tests {
     use crate::engine::arrow_conversion::TryIntoKernel as _;
     use crate::engine::arrow_data::ArrowEngineData;
     use crate::engine::default::executor::tokio::TokioBackgroundExecutor;
+    use crate::schema::{column_name, DataType};
     use crate::EngineData;
 
     use itertools::Itertools;
@@ -462,13 +549,37 @@ mod tests {
         assert_eq!(data[0].num_rows(), 10);
     }
 
+    #[test]
+    fn test_read_parquet_footer() {
+        let store = Arc::new(LocalFileSystem::new());
+        let path = std::fs::canonicalize(PathBuf::from(
+            "./tests/data/app-txn-checkpoint/_delta_log/00000000000000000001.checkpoint.parquet",
+        ))
+        .unwrap();
+        let file = FileMeta {
+            location: url::Url::from_file_path(path).unwrap(),
+            last_modified: 0,
+            size: 0,
+        };
+
+        let handler = DefaultParquetHandler::new(store, Arc::new(TokioBackgroundExecutor::new()));
+        let footer = handler.read_parquet_footer(&file).unwrap();
+
+        let Some(DataType::Struct(add)) = footer.schema.field("add").map(|f| f.data_type()) else {
+            panic!("checkpoint footer has no add struct: {:?}", footer.schema);
+        };
+        assert!(add.field("stats_parsed").is_some());
+        assert!(add.field("partitionValues_parsed").is_some());
+    }
+
     #[test]
     fn test_as_record_batch() {
         let location = Url::parse("file:///test_url").unwrap();
         let size = 1_000_000;
         let last_modified = 10000000000;
         let file_metadata = FileMeta::new(location.clone(), last_modified, size);
-        let data_file_metadata = DataFileMetadata::new(file_metadata);
+        let stats = r#"{"numRecords":10}"#.to_string();
+        let data_file_metadata = DataFileMetadata::new(file_metadata).with_stats(stats.clone());
         let partition_values = HashMap::from([("partition1".to_string(), "a".to_string())]);
         let data_change = true;
         let actual = data_file_metadata
@@ -505,6 +616,7 @@ mod tests {
                 Arc::new(Int64Array::from(vec![size as i64])),
                 Arc::new(Int64Array::from(vec![last_modified])),
                 Arc::new(BooleanArray::from(vec![data_change])),
+                Arc::new(StringArray::from(vec![stats])),
             ],
         )
         .unwrap();
@@ -527,7 +639,11 @@ mod tests {
         ));
 
         let write_metadata = parquet_handler
-            .write_parquet(&Url::parse("memory:///data/").unwrap(), data)
+            .write_parquet(
+                &Url::parse("memory:///data/").unwrap(),
+                data,
+                &[column_name!("a")],
+            )
             .await
             .unwrap();
 
@@ -538,6 +654,7 @@ mod tests {
                     last_modified,
                     size,
                 },
+            ref stats,
         } = write_metadata;
         let expected_location = Url::parse("memory:///data/").unwrap();
 
@@ -560,6 +677,9 @@ mod tests {
         assert_eq!(&expected_location.join(filename).unwrap(), location);
         assert_eq!(expected_size, size);
         assert!(now - last_modified < 10_000);
+        let expected_stats =
+            r#"{"maxValues":{"a":3},"minValues":{"a":1},"nullCount":{"a":0},"numRecords":3}"#;
+        assert_eq!(stats.as_deref(), Some(expected_stats));
 
         // check we can read back
         let path = Path::from_url_path(location.path()).unwrap();
@@ -600,7 +720,7 @@ mod tests {
         ));
 
         assert!(parquet_handler
-            .write_parquet(&Url::parse("memory:///data").unwrap(), data)
+            .write_parquet(&Url::parse("memory:///data").unwrap(), data, &[])
             .await
             .is_err());
     }
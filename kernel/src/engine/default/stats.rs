@@ -0,0 +1,394 @@
+//! Collection of the file statistics that are written to the `stats` field of an `add` action.
+//!
+//! Statistics are collected for every leaf column covered by the requested stats columns:
+//! - `nullCount`: the number of null values (a value whose parent struct is null counts as null)
+//! - `minValues`/`maxValues`: the bounds of the non-null values, for the types that support them
+//!
+//! String bounds are truncated to [`STRING_PREFIX_LENGTH`] characters. A truncated max value is
+//! followed by the largest unicode code point so that it remains an upper bound.
+
+use chrono::{DateTime, NaiveDate};
+use serde_json::{json, Map, Value};
+
+use crate::arrow::array::{make_array, Array, ArrayRef, AsArray as _, PrimitiveArray, RecordBatch};
+use crate::arrow::buffer::NullBuffer;
+use crate::arrow::compute::{max, max_string, min, min_string};
+use crate::arrow::datatypes::{
+    ArrowPrimitiveType, DataType as ArrowDataType, Date32Type, Fields, Float32Type, Float64Type,
+    Int16Type, Int32Type, Int64Type, Int8Type, TimeUnit, TimestampMicrosecondType,
+};
+use crate::schema::ColumnName;
+use crate::DeltaResult;
+
+/// The number of characters that string min/max values are truncated to.
+const STRING_PREFIX_LENGTH: usize = 32;
+
+/// The character appended to a truncated string max value, so that it is larger than every string
+/// sharing the truncated prefix.
+const STRING_MAX_SUFFIX: char = '\u{10FFFF}';
+
+/// Collect the statistics of `batch` for the given (physical) `stats_columns` and serialize them as
+/// the JSON `stats` string of an `add` action.
+pub(crate) fn collect_stats(
+    batch: &RecordBatch,
+    stats_columns: &[ColumnName],
+) -> DeltaResult<String> {
+    let mut stats = ColumnStats::default();
+    let mut path = vec![];
+    collect_fields_stats(
+        batch.schema().fields(),
+        batch.columns(),
+        None,
+        stats_columns,
+        &mut path,
+        &mut stats,
+    )?;
+    let stats = json!({
+        "numRecords": batch.num_rows(),
+        "minValues": stats.min_values,
+        "maxValues": stats.max_values,
+        "nullCount": stats.null_count,
+    });
+    Ok(stats.to_string())
+}
+
+/// The (nested) statistics of a set of columns.
+#[derive(Default)]
+struct ColumnStats {
+    min_values: Map<String, Value>,
+    max_values: Map<String, Value>,
+    null_count: Map<String, Value>,
+}
+
+fn collect_fields_stats(
+    fields: &Fields,
+    columns: &[ArrayRef],
+    parent_nulls: Option<&NullBuffer>,
+    stats_columns: &[ColumnName],
+    path: &mut Vec<String>,
+    stats: &mut ColumnStats,
+) -> DeltaResult<()> {
+    for (field, column) in fields.iter().zip(columns) {
+        path.push(field.name().clone());
+        // a value is null if it or any of its ancestors is null
+        let nulls = NullBuffer::union(parent_nulls, column.logical_nulls().as_ref());
+        match column.data_type() {
+            ArrowDataType::Struct(_) if may_contain_stats_column(path, stats_columns) => {
+                let column = column.as_struct();
+                let mut nested = ColumnStats::default();
+                collect_fields_stats(
+                    column.fields(),
+                    column.columns(),
+                    nulls.as_ref(),
+                    stats_columns,
+                    path,
+                    &mut nested,
+                )?;
+                stats.insert_nested(field.name(), nested);
+            }
+            ArrowDataType::Struct(_) => {}
+            _ if is_stats_column(path, stats_columns) => {
+                let column = with_nulls(column, nulls)?;
+                let name = field.name();
+                stats
+                    .null_count
+                    .insert(name.clone(), column.null_count().into());
+                if let Some((min_value, max_value)) = min_max(column.as_ref()) {
+                    stats.min_values.insert(name.clone(), min_value);
+                    stats.max_values.insert(name.clone(), max_value);
+                }
+            }
+            _ => {}
+        }
+        path.pop();
+    }
+    Ok(())
+}
+
+impl ColumnStats {
+    /// Insert the statistics of a struct column's children, omitting empty structs.
+    fn insert_nested(&mut self, name: &str, nested: ColumnStats) {
+        let maps = [
+            (&mut self.min_values, nested.min_values),
+            (&mut self.max_values, nested.max_values),
+            (&mut self.null_count, nested.null_count),
+        ];
+        for (map, nested) in maps {
+            if !nested.is_empty() {
+                map.insert(name.to_string(), Value::Object(nested));
+            }
+        }
+    }
+}
+
+/// Whether the leaf column at `path` is (nested in) one of the stats columns.
+fn is_stats_column(path: &[String], stats_columns: &[ColumnName]) -> bool {
+    stats_columns
+        .iter()
+        .any(|column| path.starts_with(column.path()))
+}
+
+/// Whether the struct column at `path` is, is nested in, or contains one of the stats columns.
+fn may_contain_stats_column(path: &[String], stats_columns: &[ColumnName]) -> bool {
+    stats_columns
+        .iter()
+        .any(|column| path.starts_with(column.path()) || column.path().starts_with(path))
+}
+
+/// Replace the null buffer of `column`, so that values whose parent is null are null too.
+fn with_nulls(column: &ArrayRef, nulls: Option<NullBuffer>) -> DeltaResult<ArrayRef> {
+    if nulls.as_ref() == column.logical_nulls().as_ref() {
+        return Ok(column.clone());
+    }
+    let data = column.to_data().into_builder().nulls(nulls).build()?;
+    Ok(make_array(data))
+}
+
+/// The JSON min and max values of the non-null values of `column`, or `None` if the column has no
+/// non-null values or its type doesn't support min/max statistics.
+fn min_max(column: &dyn Array) -> Option<(Value, Value)> {
+    match column.data_type() {
+        ArrowDataType::Int8 => primitive_min_max::<Int8Type>(column, Value::from),
+        ArrowDataType::Int16 => primitive_min_max::<Int16Type>(column, Value::from),
+        ArrowDataType::Int32 => primitive_min_max::<Int32Type>(column, Value::from),
+        ArrowDataType::Int64 => primitive_min_max::<Int64Type>(column, Value::from),
+        ArrowDataType::Float32 => float_min_max(column.as_primitive::<Float32Type>()),
+        ArrowDataType::Float64 => float_min_max(column.as_primitive::<Float64Type>()),
+        ArrowDataType::Date32 => primitive_min_max::<Date32Type>(column, |days| {
+            let date = NaiveDate::default().checked_add_signed(chrono::Duration::days(days.into()));
+            date.map(|date| date.format("%Y-%m-%d").to_string()).into()
+        }),
+        ArrowDataType::Timestamp(TimeUnit::Microsecond, timezone) => {
+            // timestamps are written with millisecond precision
+            let format = match timezone {
+                Some(_) => "%Y-%m-%dT%H:%M:%S%.3fZ",
+                None => "%Y-%m-%dT%H:%M:%S%.3f",
+            };
+            primitive_min_max::<TimestampMicrosecondType>(column, |micros| {
+                let timestamp = DateTime::from_timestamp_micros(micros);
+                timestamp
+                    .map(|timestamp| timestamp.format(format).to_string())
+                    .into()
+            })
+        }
+        ArrowDataType::Utf8 => {
+            let column = column.as_string::<i32>();
+            string_min_max(min_string(column)?, max_string(column)?)
+        }
+        ArrowDataType::LargeUtf8 => {
+            let column = column.as_string::<i64>();
+            string_min_max(min_string(column)?, max_string(column)?)
+        }
+        ArrowDataType::Utf8View => {
+            let column = column.as_string_view();
+            let min_value = column.iter().flatten().min()?;
+            let max_value = column.iter().flatten().max()?;
+            string_min_max(min_value, max_value)
+        }
+        // booleans, binary, decimals, and nested types have no min/max statistics
+        _ => None,
+    }
+}
+
+fn primitive_min_max<T: ArrowPrimitiveType>(
+    column: &dyn Array,
+    to_json: impl Fn(T::Native) -> Value,
+) -> Option<(Value, Value)> {
+    let column = column.as_primitive::<T>();
+    Some((to_json(min(column)?), to_json(max(column)?)))
+}
+
+/// Floating point bounds are only written if all values are finite, since NaN and infinity cannot
+/// be represented in JSON.
+fn float_min_max<T: ArrowPrimitiveType>(column: &PrimitiveArray<T>) -> Option<(Value, Value)>
+where
+    T::Native: Into<f64>,
+{
+    let mut values = column.iter().flatten().map(Into::<f64>::into);
+    let first = values.next()?;
+    let (min_value, max_value) = values.try_fold((first, first), |(min_value, max_value), v| {
+        v.is_finite().then(|| (min_value.min(v), max_value.max(v)))
+    })?;
+    first
+        .is_finite()
+        .then(|| (min_value.into(), max_value.into()))
+}
+
+fn string_min_max(min_value: &str, max_value: &str) -> Option<(Value, Value)> {
+    let min_value: String = min_value.chars().take(STRING_PREFIX_LENGTH).collect();
+    let mut truncated_max: String = max_value.chars().take(STRING_PREFIX_LENGTH).collect();
+    if truncated_max.len() < max_value.len() {
+        truncated_max.push(STRING_MAX_SUFFIX);
+    }
+    Some((min_value.into(), truncated_max.into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+
+    use crate::arrow::array::{
+        BooleanArray, Date32Array, Float64Array, Int32Array, Int64Array, StringArray, StructArray,
+        TimestampMicrosecondArray,
+    };
+    use crate::arrow::datatypes::Field;
+    use crate::schema::column_name;
+
+    /// Collect the statistics of a single-column batch.
+    fn collect_column_stats(
+        name: &str,
+        column: ArrayRef,
+        stats_columns: &[ColumnName],
+    ) -> DeltaResult<Value> {
+        let batch = RecordBatch::try_from_iter([(name, column)])?;
+        let stats = collect_stats(&batch, stats_columns)?;
+        Ok(serde_json::from_str(&stats).unwrap())
+    }
+
+    #[test]
+    fn test_primitive_stats() {
+        let stats = collect_column_stats(
+            "a",
+            Arc::new(Int64Array::from(vec![Some(3), None, Some(-1), Some(7)])),
+            &[column_name!("a")],
+        )
+        .unwrap();
+        assert_eq!(
+            stats,
+            json!({
+                "numRecords": 4,
+                "minValues": { "a": -1 },
+                "maxValues": { "a": 7 },
+                "nullCount": { "a": 1 },
+            })
+        );
+    }
+
+    #[test]
+    fn test_all_null_column() {
+        let stats = collect_column_stats(
+            "a",
+            Arc::new(Int32Array::from(vec![None, None])),
+            &[column_name!("a")],
+        )
+        .unwrap();
+        assert_eq!(
+            stats,
+            json!({
+                "numRecords": 2,
+                "minValues": {},
+                "maxValues": {},
+                "nullCount": { "a": 2 },
+            })
+        );
+    }
+
+    #[test]
+    fn test_unsupported_min_max_types() {
+        let stats = collect_column_stats(
+            "b",
+            Arc::new(BooleanArray::from(vec![Some(true), None])),
+            &[column_name!("b")],
+        )
+        .unwrap();
+        assert_eq!(stats["minValues"], json!({}));
+        assert_eq!(stats["nullCount"], json!({ "b": 1 }));
+    }
+
+    #[test]
+    fn test_non_finite_floats() {
+        let column = Arc::new(Float64Array::from(vec![1.5, -2.0]));
+        let stats = collect_column_stats("f", column, &[column_name!("f")]).unwrap();
+        assert_eq!(stats["minValues"], json!({ "f": -2.0 }));
+        assert_eq!(stats["maxValues"], json!({ "f": 1.5 }));
+
+        for value in [f64::NAN, f64::INFINITY] {
+            let column = Arc::new(Float64Array::from(vec![value, 1.0]));
+            let stats = collect_column_stats("f", column, &[column_name!("f")]).unwrap();
+            assert_eq!(stats["minValues"], json!({}));
+            assert_eq!(stats["nullCount"], json!({ "f": 0 }));
+        }
+    }
+
+    #[test]
+    fn test_date_and_timestamp_stats() {
+        let column = Arc::new(Date32Array::from(vec![19000, 0]));
+        let stats = collect_column_stats("d", column, &[column_name!("d")]).unwrap();
+        assert_eq!(stats["minValues"], json!({ "d": "1970-01-01" }));
+        assert_eq!(stats["maxValues"], json!({ "d": "2022-01-08" }));
+
+        let micros = vec![1_000_001_999, 0];
+        let column = Arc::new(TimestampMicrosecondArray::from(micros.clone()).with_timezone("UTC"));
+        let stats = collect_column_stats("ts", column, &[column_name!("ts")]).unwrap();
+        assert_eq!(
+            stats["minValues"],
+            json!({ "ts": "1970-01-01T00:00:00.000Z" })
+        );
+        assert_eq!(
+            stats["maxValues"],
+            json!({ "ts": "1970-01-01T00:16:40.001Z" })
+        );
+
+        let column = Arc::new(TimestampMicrosecondArray::from(micros));
+        let stats = collect_column_stats("ts", column, &[column_name!("ts")]).unwrap();
+        assert_eq!(
+            stats["maxValues"],
+            json!({ "ts": "1970-01-01T00:16:40.001" })
+        );
+    }
+
+    #[test]
+    fn test_string_truncation() {
+        let long = "a".repeat(40);
+        let column = Arc::new(StringArray::from(vec![long.as_str(), "b", "0"]));
+        let stats = collect_column_stats("s", column, &[column_name!("s")]).unwrap();
+        assert_eq!(stats["minValues"], json!({ "s": "0" }));
+        assert_eq!(stats["maxValues"], json!({ "s": "b" }));
+
+        let column = Arc::new(StringArray::from(vec![long.as_str()]));
+        let stats = collect_column_stats("s", column, &[column_name!("s")]).unwrap();
+        let expected_max = format!("{}{STRING_MAX_SUFFIX}", &long[..STRING_PREFIX_LENGTH]);
+        assert_eq!(
+            stats["minValues"],
+            json!({ "s": &long[..STRING_PREFIX_LENGTH] })
+        );
+        assert_eq!(stats["maxValues"], json!({ "s": expected_max }));
+    }
+
+    #[test]
+    fn test_nested_stats() {
+        // {"x": 1, "y": "a"}, null, {"x": null, "y": "c"}
+        let struct_array = StructArray::new(
+            vec![
+                Field::new("x", ArrowDataType::Int32, true),
+                Field::new("y", ArrowDataType::Utf8, true),
+            ]
+            .into(),
+            vec![
+                Arc::new(Int32Array::from(vec![Some(1), Some(100), None])),
+                Arc::new(StringArray::from(vec!["a", "z", "c"])),
+            ],
+            Some(NullBuffer::from(vec![true, false, true])),
+        );
+        let column: ArrayRef = Arc::new(struct_array);
+
+        let stats = collect_column_stats("s", column.clone(), &[column_name!("s")]).unwrap();
+        assert_eq!(
+            stats,
+            json!({
+                "numRecords": 3,
+                "minValues": { "s": { "x": 1, "y": "a" } },
+                "maxValues": { "s": { "x": 1, "y": "c" } },
+                "nullCount": { "s": { "x": 2, "y": 1 } },
+            })
+        );
+
+        let stats = collect_column_stats("s", column.clone(), &[column_name!("s.y")]).unwrap();
+        assert_eq!(stats["nullCount"], json!({ "s": { "y": 1 } }));
+
+        let stats = collect_column_stats("s", column, &[column_name!("other")]).unwrap();
+        assert_eq!(stats["nullCount"], json!({}));
+    }
+}
@@ -0,0 +1,53 @@
+//! Execution of a [`VacuumPlan`] by the default engine.
+use futures::{StreamExt as _, TryStreamExt as _};
+use itertools::Itertools as _;
+use url::Url;
+
+use super::executor::TaskExecutor;
+use super::DefaultEngine;
+use crate::object_store::path::Path;
+use crate::vacuum::VacuumPlan;
+use crate::{DeltaResult, FileMeta};
+
+impl<E: TaskExecutor> DefaultEngine<E> {
+    /// Vacuums a table: lists all the files under the table root of the `plan` and deletes the
+    /// ones eligible for deletion. With `dry_run`, the files are not deleted. Returns the paths of
+    /// the deleted (or, with `dry_run`, deletable) files.
+    pub async fn vacuum(&self, plan: &VacuumPlan, dry_run: bool) -> DeltaResult<Vec<Url>> {
+        let table_root = plan.table_root();
+        let prefix = Path::from_url_path(table_root.path())?;
+        let files: Vec<_> = self
+            .object_store
+            .list(Some(&prefix))
+            .map_ok(|meta| {
+                let mut location = table_root.clone();
+                location.set_path(&format!("/{}", meta.location.as_ref()));
+                let size = meta.size;
+                #[cfg(not(feature = "arrow-55"))]
+                let size = size.try_into().expect("convert file size to u64");
+                FileMeta {
+                    location,
+                    last_modified: meta.last_modified.timestamp_millis(),
+                    size,
+                }
+            })
+            .try_collect()
+            .await?;
+        let deletable_files: Vec<Url> = plan
+            .deletable_files(files.into_iter().map(Ok))
+            .try_collect()?;
+        if dry_run {
+            return Ok(deletable_files);
+        }
+
+        let paths = deletable_files
+            .iter()
+            .map(|location| Path::from_url_path(location.path()))
+            .collect::<Result<Vec<_>, _>>()?;
+        self.object_store
+            .delete_stream(futures::stream::iter(paths.into_iter().map(Ok)).boxed())
+            .try_for_each(|_| futures::future::ready(Ok(())))
+            .await?;
+        Ok(deletable_files)
+    }
+}
@@ -1,4 +1,5 @@
 //! An implementation of parquet row group skipping using data skipping predicates over footer stats.
+use crate::engine::arrow_utils::RowIndexBuilder;
 use crate::expressions::{ColumnName, DecimalData, Predicate, Scalar};
 use crate::kernel_predicates::parquet_stats_skipping::ParquetStatsProvider;
 use crate::parquet::arrow::arrow_reader::ArrowReaderBuilder;
@@ -16,12 +17,21 @@ mod tests;
 /// An extension trait for [`ArrowReaderBuilder`] that injects row group skipping capability.
 pub(crate) trait ParquetRowGroupSkipping {
     /// Instructs the parquet reader to perform row group skipping, eliminating any row group whose
-    /// stats prove that none of the group's rows can satisfy the given `predicate`.
-    fn with_row_group_filter(self, predicate: &Predicate) -> Self;
+    /// stats prove that none of the group's rows can satisfy the given `predicate`. If the reader
+    /// computes row indexes, `row_indexes` is told which row groups are read.
+    fn with_row_group_filter(
+        self,
+        predicate: &Predicate,
+        row_indexes: Option<&mut RowIndexBuilder>,
+    ) -> Self;
 }
 impl<T> ParquetRowGroupSkipping for ArrowReaderBuilder<T> {
-    fn with_row_group_filter(self, predicate: &Predicate) -> Self {
-        let indices = self
+    fn with_row_group_filter(
+        self,
+        predicate: &Predicate,
+        row_indexes: Option<&mut RowIndexBuilder>,
+    ) -> Self {
+        let indices: Vec<usize> = self
             .metadata()
             .row_groups()
             .iter()
@@ -32,6 +42,9 @@ impl<T> ParquetRowGroupSkipping for ArrowReaderBuilder<T> {
             })
             .collect();
         debug!("with_row_group_filter({predicate:#?}) = {indices:?})");
+        if let Some(row_indexes) = row_indexes {
+            row_indexes.select_row_groups(&indices);
+        }
         self.with_row_groups(indices)
     }
 }
@@ -1,14 +1,22 @@
 use std::fs::File;
+use std::sync::Arc;
 
 use crate::arrow::datatypes::SchemaRef as ArrowSchemaRef;
 use crate::parquet::arrow::arrow_reader::{ArrowReaderMetadata, ParquetRecordBatchReaderBuilder};
 
 use super::read_files;
+use crate::engine::arrow_conversion::TryFromArrow as _;
 use crate::engine::arrow_data::ArrowEngineData;
-use crate::engine::arrow_utils::{fixup_parquet_read, generate_mask, get_requested_indices};
+use crate::engine::arrow_utils::{
+    fixup_parquet_read, generate_mask, get_requested_indices, ordering_needs_row_indexes,
+    RowIndexBuilder,
+};
 use crate::engine::parquet_row_group_skipping::ParquetRowGroupSkipping;
-use crate::schema::SchemaRef;
-use crate::{DeltaResult, FileDataReadResultIterator, FileMeta, ParquetHandler, PredicateRef};
+use crate::schema::{SchemaRef, StructType};
+use crate::{
+    DeltaResult, Error, FileDataReadResultIterator, FileMeta, ParquetFooter, ParquetHandler,
+    PredicateRef,
+};
 
 pub(crate) struct SyncParquetHandler;
 
@@ -25,11 +33,14 @@ fn try_create_from_parquet(
     if let Some(mask) = generate_mask(&schema, parquet_schema, builder.parquet_schema(), &indices) {
         builder = builder.with_projection(mask);
     }
+    let mut row_indexes = ordering_needs_row_indexes(&requested_ordering)
+        .then(|| RowIndexBuilder::new(builder.metadata().row_groups()));
     if let Some(predicate) = predicate {
-        builder = builder.with_row_group_filter(predicate.as_ref());
+        builder = builder.with_row_group_filter(predicate.as_ref(), row_indexes.as_mut());
     }
+    let mut row_indexes = row_indexes.map(|rb| rb.build()).transpose()?;
     let stream = builder.build()?;
-    Ok(stream.map(move |rbr| fixup_parquet_read(rbr?, &requested_ordering)))
+    Ok(stream.map(move |rbr| fixup_parquet_read(rbr?, &requested_ordering, row_indexes.as_mut())))
 }
 
 impl ParquetHandler for SyncParquetHandler {
@@ -41,4 +52,16 @@ impl ParquetHandler for SyncParquetHandler {
     ) -> DeltaResult<FileDataReadResultIterator> {
         read_files(files, schema, predicate, try_create_from_parquet)
     }
+
+    fn read_parquet_footer(&self, file: &FileMeta) -> DeltaResult<ParquetFooter> {
+        let path = file
+            .location
+            .to_file_path()
+            .map_err(|_| Error::generic("can only read local files"))?;
+        let metadata = ArrowReaderMetadata::load(&File::open(path)?, Default::default())?;
+        let schema = StructType::try_from_arrow(metadata.schema().as_ref())?;
+        Ok(ParquetFooter {
+            schema: Arc::new(schema),
+        })
+    }
 }
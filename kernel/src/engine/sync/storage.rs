@@ -1,5 +1,8 @@
+use std::io::Write as _;
+
 use bytes::Bytes;
 use itertools::Itertools;
+use tempfile::NamedTempFile;
 use url::Url;
 
 use crate::{DeltaResult, Error, FileMeta, FileSlice, StorageHandler};
@@ -70,6 +73,47 @@ impl StorageHandler for SyncStorageHandler {
         });
         Ok(Box::new(iter))
     }
+
+    /// Write data to a file on the local filesystem, creating any missing parent directories.
+    fn write_file(&self, path: &Url, data: Bytes, overwrite: bool) -> DeltaResult<()> {
+        let path = path
+            .to_file_path()
+            .map_err(|_| Error::generic("sync client can only write local files"))?;
+        let Some(parent) = path.parent() else {
+            return Err(Error::generic(format!("no parent found for {path:?}")));
+        };
+        std::fs::create_dir_all(parent)?;
+
+        // write data to tmp file, then atomically move it into place
+        let mut tmp_file = NamedTempFile::new_in(parent)?;
+        tmp_file.write_all(&data)?;
+        tmp_file.flush()?;
+
+        let persist_result = if overwrite {
+            tmp_file.persist(path.clone())
+        } else {
+            tmp_file.persist_noclobber(path.clone())
+        };
+        persist_result.map_err(|e| {
+            if !overwrite && e.error.kind() == std::io::ErrorKind::AlreadyExists {
+                Error::FileAlreadyExists(path.to_string_lossy().to_string())
+            } else {
+                Error::IOError(e.into())
+            }
+        })?;
+        Ok(())
+    }
+
+    /// Delete a file on the local filesystem.
+    fn delete_file(&self, path: &Url) -> DeltaResult<()> {
+        let path = path
+            .to_file_path()
+            .map_err(|_| Error::generic("sync client can only delete local files"))?;
+        match std::fs::remove_file(path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -78,7 +122,7 @@ mod tests {
     use std::time::{Duration, SystemTime};
     use std::{fs::File, time::UNIX_EPOCH};
 
-    use bytes::{BufMut, BytesMut};
+    use bytes::{BufMut, Bytes, BytesMut};
     use itertools::Itertools;
     use url::Url;
 
@@ -175,4 +219,42 @@ mod tests {
         assert_eq!(file_count, 1);
         Ok(())
     }
+
+    #[test]
+    fn test_write_file() -> Result<(), Box<dyn std::error::Error>> {
+        let storage = SyncStorageHandler;
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let path = tmp_dir.path().join("nested").join("file.bin");
+        let url = Url::from_file_path(&path).unwrap();
+
+        storage.write_file(&url, Bytes::from_static(b"first"), false)?;
+        assert_eq!(std::fs::read(&path)?, b"first");
+
+        // without overwrite, writing to an existing file fails
+        assert!(matches!(
+            storage.write_file(&url, Bytes::from_static(b"second"), false),
+            Err(crate::Error::FileAlreadyExists(_))
+        ));
+        assert_eq!(std::fs::read(&path)?, b"first");
+
+        storage.write_file(&url, Bytes::from_static(b"second"), true)?;
+        assert_eq!(std::fs::read(&path)?, b"second");
+        Ok(())
+    }
+
+    #[test]
+    fn test_delete_file() -> Result<(), Box<dyn std::error::Error>> {
+        let storage = SyncStorageHandler;
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let path = tmp_dir.path().join("file.bin");
+        let url = Url::from_file_path(&path).unwrap();
+
+        storage.write_file(&url, Bytes::from_static(b"data"), false)?;
+        storage.delete_file(&url)?;
+        assert!(!path.exists());
+
+        // deleting a missing file succeeds
+        storage.delete_file(&url)?;
+        Ok(())
+    }
 }
@@ -64,6 +64,13 @@ pub enum BinaryExpressionOp {
     Divide,
 }
 
+/// A variadic expression operator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum VariadicExpressionOp {
+    /// Collapse multiple values into one by taking the first non-null value
+    Coalesce,
+}
+
 /// A junction (AND/OR) predicate operator.
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum JunctionPredicateOp {
@@ -203,6 +210,14 @@ pub struct BinaryExpression {
     pub right: Box<Expression>,
 }
 
+#[derive(Clone, Debug, PartialEq)]
+pub struct VariadicExpression {
+    /// The operator.
+    pub op: VariadicExpressionOp,
+    /// The input expressions.
+    pub exprs: Vec<Expression>,
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct JunctionPredicate {
     /// The operator.
@@ -260,6 +275,8 @@ pub enum Expression {
     Struct(Vec<Expression>),
     /// An expression that takes two expressions as input.
     Binary(BinaryExpression),
+    /// An expression that takes a variable number of expressions as input.
+    Variadic(VariadicExpression),
     /// An expression that the engine defines and implements. Kernel interacts with the expression
     /// only through methods provided by the [`OpaqueExpressionOp`] trait.
     Opaque(OpaqueExpression),
@@ -366,6 +383,12 @@ impl BinaryPredicate {
     }
 }
 
+impl VariadicExpression {
+    fn new(op: VariadicExpressionOp, exprs: Vec<Expression>) -> Self {
+        Self { op, exprs }
+    }
+}
+
 impl JunctionPredicate {
     fn new(op: JunctionPredicateOp, preds: Vec<Predicate>) -> Self {
         Self { op, preds }
@@ -469,6 +492,20 @@ impl Expression {
         })
     }
 
+    /// Creates a new variadic expression OP(exprs...)
+    pub fn variadic(
+        op: VariadicExpressionOp,
+        exprs: impl IntoIterator<Item = impl Into<Expression>>,
+    ) -> Self {
+        let exprs = exprs.into_iter().map(Into::into).collect();
+        Self::Variadic(VariadicExpression::new(op, exprs))
+    }
+
+    /// Creates a new COALESCE(exprs...) expression, which evaluates to the first non-null value
+    pub fn coalesce(exprs: impl IntoIterator<Item = impl Into<Expression>>) -> Self {
+        Self::variadic(VariadicExpressionOp::Coalesce, exprs)
+    }
+
     /// Creates a new opaque expression
     pub fn opaque(
         op: impl OpaqueExpressionOp,
@@ -651,6 +688,15 @@ impl Display for BinaryExpressionOp {
     }
 }
 
+impl Display for VariadicExpressionOp {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        use VariadicExpressionOp::*;
+        match self {
+            Coalesce => write!(f, "COALESCE"),
+        }
+    }
+}
+
 impl Display for BinaryPredicateOp {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         use BinaryPredicateOp::*;
@@ -681,6 +727,9 @@ impl Display for Expression {
             Predicate(p) => write!(f, "{p}"),
             Struct(exprs) => write!(f, "Struct({})", format_child_list(exprs)),
             Binary(BinaryExpression { op, left, right }) => write!(f, "{left} {op} {right}"),
+            Variadic(VariadicExpression { op, exprs }) => {
+                write!(f, "{op}({})", format_child_list(exprs))
+            }
             Opaque(OpaqueExpression { op, exprs }) => {
                 write!(f, "{op:?}({})", format_child_list(exprs))
             }
@@ -3,7 +3,7 @@ use std::collections::HashSet;
 
 use crate::expressions::{
     BinaryExpression, BinaryPredicate, ColumnName, Expression, JunctionPredicate, OpaqueExpression,
-    OpaquePredicate, Predicate, Scalar, UnaryPredicate,
+    OpaquePredicate, Predicate, Scalar, UnaryPredicate, VariadicExpression,
 };
 use crate::utils::CowExt as _;
 
@@ -89,6 +89,15 @@ pub trait ExpressionTransform<'a> {
         self.recurse_into_expr_binary(expr)
     }
 
+    /// Called for each [`VariadicExpression`] encountered during the traversal. Implementations can
+    /// call [`Self::recurse_into_expr_variadic`] if they wish to recursively transform the children.
+    fn transform_expr_variadic(
+        &mut self,
+        expr: &'a VariadicExpression,
+    ) -> Option<Cow<'a, VariadicExpression>> {
+        self.recurse_into_expr_variadic(expr)
+    }
+
     /// Called for each [`BinaryPredicate`] encountered during the traversal. Implementations can
     /// call [`Self::recurse_into_pred_binary`] if they wish to recursively transform the children.
     fn transform_pred_binary(
@@ -141,6 +150,9 @@ pub trait ExpressionTransform<'a> {
             Expression::Binary(b) => self
                 .transform_expr_binary(b)?
                 .map_owned_or_else(expr, Expression::Binary),
+            Expression::Variadic(v) => self
+                .transform_expr_variadic(v)?
+                .map_owned_or_else(expr, Expression::Variadic),
             Expression::Opaque(o) => self
                 .transform_expr_opaque(o)?
                 .map_owned_or_else(expr, Expression::Opaque),
@@ -250,6 +262,17 @@ pub trait ExpressionTransform<'a> {
         Some((left, right).map_owned_or_else(b, f))
     }
 
+    /// Recursively transforms a variadic expression's children. Returns `None` if all children were
+    /// removed, `Some(Cow::Owned)` if at least one child was changed or removed, and
+    /// `Some(Cow::Borrowed)` otherwise.
+    fn recurse_into_expr_variadic(
+        &mut self,
+        v: &'a VariadicExpression,
+    ) -> Option<Cow<'a, VariadicExpression>> {
+        let nested_result = recurse_into_children(&v.exprs, |e| self.transform_expr(e))?;
+        Some(nested_result.map_owned_or_else(v, |exprs| VariadicExpression::new(v.op, exprs)))
+    }
+
     /// Recursively transforms a junction predicate's children. Returns `None` if all children were
     /// removed, `Some(Cow::Owned)` if at least one child was changed or removed, and
     /// `Some(Cow::Borrowed)` otherwise.
@@ -419,6 +442,13 @@ impl<'a> ExpressionTransform<'a> for ExpressionDepthChecker {
         self.depth_limited(Self::recurse_into_expr_binary, expr)
     }
 
+    fn transform_expr_variadic(
+        &mut self,
+        expr: &'a VariadicExpression,
+    ) -> Option<Cow<'a, VariadicExpression>> {
+        self.depth_limited(Self::recurse_into_expr_variadic, expr)
+    }
+
     fn transform_pred_binary(
         &mut self,
         pred: &'a BinaryPredicate,
@@ -1 +1,533 @@
+//! The history manager provides utilities to convert between timestamps and table versions.
+//!
+//! A commit's timestamp is its in-commit timestamp (ICT) if in-commit timestamps were enabled at
+//! the time of the commit. Otherwise the commit's timestamp is the modification time of the commit
+//! file. Since in-commit timestamps may be enabled part way through the history of a table, the
+//! log is split into the range of commits before ICT enablement (which use file modification
+//! times) and the range of commits after ICT enablement (which use in-commit timestamps).
+//!
+//! Note that file modification times are not guaranteed to be monotonic. The timestamp to version
+//! conversion assumes that they are, and results are unspecified if this does not hold.
+
+use delta_kernel_derive::internal_api;
+use itertools::Itertools;
+use tracing::debug;
+use url::Url;
+
+use crate::actions::visitors::{CommitInfoVisitor, InCommitTimestampVisitor};
+use crate::actions::{get_log_commit_info_schema, CommitInfo};
+use crate::engine_data::RowVisitor as _;
+use crate::log_segment::list_log_files;
+use crate::path::ParsedLogPath;
+use crate::snapshot::Snapshot;
+use crate::{DeltaResult, Engine, Error, Version};
+
+use search::{binary_search_by_key_with_bounds, Bound, SearchError};
+
 pub(crate) mod search;
+
+/// A single entry in the history of a table, as returned by [`Snapshot::history`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HistoryEntry {
+    version: Version,
+    timestamp: i64,
+    commit_info: CommitInfo,
+}
+
+impl HistoryEntry {
+    /// The version of the commit.
+    pub fn version(&self) -> Version {
+        self.version
+    }
+
+    /// The timestamp of the commit in milliseconds since the epoch. This is the in-commit timestamp
+    /// if in-commit timestamps were enabled for the commit, and the commit file's modification time
+    /// otherwise.
+    pub fn timestamp(&self) -> i64 {
+        self.timestamp
+    }
+
+    /// The commitInfo action of the commit. If the commit has no commitInfo action, all of its
+    /// fields are `None`.
+    pub fn commit_info(&self) -> &CommitInfo {
+        &self.commit_info
+    }
+}
+
+/// Converts timestamps to table versions for the history of a table up to a given [`Snapshot`].
+///
+/// The history manager lists all commits that are still present in the log up to the snapshot's
+/// version. Commits that were cleaned up by log retention cannot be found.
+#[internal_api]
+#[derive(Debug)]
+pub(crate) struct LogHistoryManager {
+    log_root: Url,
+    /// All the commit files in the log that precede the snapshot version, with no gaps. The
+    /// last commit is at the snapshot version.
+    ascending_commit_files: Vec<ParsedLogPath>,
+    /// The in-commit timestamp enablement version and timestamp, if ICT is enabled.
+    ict_enablement: Option<(Version, i64)>,
+}
+
+impl LogHistoryManager {
+    /// Create a new [`LogHistoryManager`] for the history of the table up to the version of the
+    /// given `snapshot`.
+    #[internal_api]
+    pub(crate) fn try_new(engine: &dyn Engine, snapshot: &Snapshot) -> DeltaResult<Self> {
+        let log_root = snapshot.log_segment().log_root.clone();
+        let end_version = snapshot.version();
+        let storage = engine.storage_handler();
+
+        let mut ascending_commit_files: Vec<ParsedLogPath> =
+            list_log_files(storage.as_ref(), &log_root, None, end_version)?
+                .filter_ok(|path| path.is_commit())
+                .try_collect()?;
+
+        // Only the trailing run of contiguous commits is usable. Older commits may have a gap
+        // if the log was cleaned up.
+        let contiguous_start = ascending_commit_files
+            .windows(2)
+            .rposition(|pair| pair[0].version + 1 != pair[1].version)
+            .map_or(0, |idx| idx + 1);
+        ascending_commit_files.drain(..contiguous_start);
+
+        match ascending_commit_files.last() {
+            Some(last) if last.version == end_version => {}
+            _ => {
+                return Err(Error::generic(format!(
+                    "Failed to find the commit file for snapshot version {end_version}"
+                )))
+            }
+        }
+
+        let ict_enablement = snapshot
+            .table_configuration()
+            .in_commit_timestamp_enablement()?;
+
+        Ok(Self {
+            log_root,
+            ascending_commit_files,
+            ict_enablement,
+        })
+    }
+
+    /// The latest (greatest) version of the table whose commit timestamp is less than or equal to
+    /// `timestamp`.
+    ///
+    /// Returns an error if `timestamp` is earlier than the earliest available commit.
+    #[internal_api]
+    pub(crate) fn latest_version_as_of(
+        &self,
+        engine: &dyn Engine,
+        timestamp: i64,
+    ) -> DeltaResult<Version> {
+        self.timestamp_to_version(engine, timestamp, Bound::GreatestLower)
+    }
+
+    /// The earliest (least) version of the table whose commit timestamp is greater than or equal
+    /// to `timestamp`.
+    ///
+    /// Returns an error if `timestamp` is later than the latest commit.
+    #[internal_api]
+    pub(crate) fn first_version_after(
+        &self,
+        engine: &dyn Engine,
+        timestamp: i64,
+    ) -> DeltaResult<Version> {
+        self.timestamp_to_version(engine, timestamp, Bound::LeastUpper)
+    }
+
+    /// Returns an iterator over the [`HistoryEntry`] of each commit, from newest to oldest. At most
+    /// `limit` entries are returned if a limit is given. The iterator ends at the earliest commit
+    /// that is still available in the log.
+    pub(crate) fn into_history(
+        self,
+        engine: &dyn Engine,
+        limit: Option<usize>,
+    ) -> impl Iterator<Item = DeltaResult<HistoryEntry>> + '_ {
+        let limit = limit.unwrap_or(usize::MAX);
+        (0..self.ascending_commit_files.len())
+            .rev()
+            .take(limit)
+            .map(move |idx| self.history_entry(engine, &self.ascending_commit_files[idx]))
+    }
+
+    fn history_entry(
+        &self,
+        engine: &dyn Engine,
+        commit: &ParsedLogPath,
+    ) -> DeltaResult<HistoryEntry> {
+        let mut batches = engine.json_handler().read_json_files(
+            &[commit.location.clone()],
+            get_log_commit_info_schema().clone(),
+            None,
+        )?;
+        let mut visitor = CommitInfoVisitor::default();
+        batches.try_for_each(|batch| visitor.visit_rows_of(batch?.as_ref()))?;
+        let commit_info = visitor.commit_info.unwrap_or_default();
+
+        let timestamp = match self.ict_enablement {
+            Some((enablement_version, _)) if commit.version >= enablement_version => {
+                commit_info.in_commit_timestamp.ok_or_else(|| {
+                    Error::generic(format!(
+                        "In-commit timestamp is missing from commit at version {}",
+                        commit.version
+                    ))
+                })?
+            }
+            _ => commit.location.last_modified,
+        };
+        Ok(HistoryEntry {
+            version: commit.version,
+            timestamp,
+            commit_info,
+        })
+    }
+
+    /// Get the timestamp of the given commit. This is the in-commit timestamp if the commit was
+    /// written with in-commit timestamps enabled, and the file modification time otherwise.
+    pub(crate) fn commit_timestamp(
+        &self,
+        engine: &dyn Engine,
+        commit: &ParsedLogPath,
+    ) -> DeltaResult<i64> {
+        match self.ict_enablement {
+            Some((enablement_version, _)) if commit.version >= enablement_version => {
+                read_in_commit_timestamp(engine, commit)
+            }
+            _ => Ok(commit.location.last_modified),
+        }
+    }
+
+    fn timestamp_to_version(
+        &self,
+        engine: &dyn Engine,
+        timestamp: i64,
+        bound: Bound,
+    ) -> DeltaResult<Version> {
+        debug!(
+            "Converting timestamp {timestamp} to version with bound {bound:?} for table {}",
+            self.log_root
+        );
+        let commits = self.ascending_commit_files.as_slice();
+        let (non_ict_commits, ict_commits) = match self.ict_enablement {
+            Some((enablement_version, enablement_timestamp)) => {
+                let split = commits.partition_point(|commit| commit.version < enablement_version);
+                let (non_ict, ict) = commits.split_at(split);
+                // Decide which range of the log the timestamp falls into. The enablement timestamp
+                // is the in-commit timestamp of the enablement commit.
+                let search_ict = match bound {
+                    Bound::GreatestLower => timestamp >= enablement_timestamp,
+                    Bound::LeastUpper => timestamp > enablement_timestamp,
+                };
+                if search_ict {
+                    (&[][..], ict)
+                } else {
+                    (non_ict, &ict[..ict.len().min(1)])
+                }
+            }
+            None => (commits, &[][..]),
+        };
+
+        // Search the file modification time range first. For a least upper bound, fall back to
+        // the first ICT commit if no file modification time is large enough.
+        let key_fn = |commit: &ParsedLogPath| self.commit_timestamp(engine, commit);
+        let result =
+            match binary_search_by_key_with_bounds(non_ict_commits, timestamp, key_fn, bound) {
+                Ok(idx) => Ok(&non_ict_commits[idx]),
+                Err(SearchError::OutOfRange) if !ict_commits.is_empty() => {
+                    binary_search_by_key_with_bounds(ict_commits, timestamp, key_fn, bound)
+                        .map(|idx| &ict_commits[idx])
+                }
+                Err(err) => Err(err),
+            };
+
+        match result {
+            Ok(commit) => Ok(commit.version),
+            Err(SearchError::OutOfRange) => Err(Error::generic(format!(
+                "No commit found for timestamp {timestamp} with bound {bound:?}. Available \
+                 versions are {} to {}",
+                commits.first().map_or(0, |c| c.version),
+                commits.last().map_or(0, |c| c.version),
+            ))),
+            Err(SearchError::KeyFunctionError(err)) => Err(err),
+        }
+    }
+}
+
+/// Read the in-commit timestamp from the `commitInfo` action of the given commit file. The
+/// `commitInfo` action must be the first action in the commit.
+pub(crate) fn read_in_commit_timestamp(
+    engine: &dyn Engine,
+    commit: &ParsedLogPath,
+) -> DeltaResult<i64> {
+    let mut batches = engine.json_handler().read_json_files(
+        &[commit.location.clone()],
+        InCommitTimestampVisitor::schema(),
+        None,
+    )?;
+    let mut visitor = InCommitTimestampVisitor::default();
+    if let Some(batch) = batches.next() {
+        visitor.visit_rows_of(batch?.as_ref())?;
+    }
+    visitor.in_commit_timestamp.ok_or_else(|| {
+        Error::generic(format!(
+            "In-commit timestamp is missing from commit at version {}",
+            commit.version
+        ))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::File;
+    use std::io::Write as _;
+    use std::path::Path;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use serde_json::{json, Value};
+    use url::Url;
+
+    use super::*;
+    use crate::engine::sync::SyncEngine;
+
+    fn protocol_and_metadata(configuration: Value) -> [Value; 2] {
+        [
+            json!({
+                "protocol": {
+                    "minReaderVersion": 1,
+                    "minWriterVersion": 7,
+                    "writerFeatures": ["inCommitTimestamp"]
+                }
+            }),
+            json!({
+                "metaData": {
+                    "id": "5fba94ed-9794-4965-ba6e-6ee3c0d22af9",
+                    "format": { "provider": "parquet", "options": {} },
+                    "schemaString": "{\"type\":\"struct\",\"fields\":[{\"name\":\"id\",\"type\":\"integer\",\"nullable\":true,\"metadata\":{}}]}",
+                    "partitionColumns": [],
+                    "configuration": configuration,
+                    "createdTime": 1587968585495i64
+                }
+            }),
+        ]
+    }
+
+    fn commit_info(in_commit_timestamp: Option<i64>) -> Value {
+        match in_commit_timestamp {
+            Some(ict) => {
+                json!({ "commitInfo": { "inCommitTimestamp": ict, "operation": "WRITE" } })
+            }
+            None => json!({ "commitInfo": { "operation": "WRITE" } }),
+        }
+    }
+
+    /// Write a commit file for `version` and set its modification time to `modification_time`.
+    fn write_commit(
+        table_root: &Path,
+        version: Version,
+        actions: &[Value],
+        modification_time: i64,
+    ) {
+        let log_root = table_root.join("_delta_log");
+        std::fs::create_dir_all(&log_root).unwrap();
+        let mut file = File::create(log_root.join(format!("{version:020}.json"))).unwrap();
+        for action in actions {
+            writeln!(file, "{action}").unwrap();
+        }
+        let mtime = UNIX_EPOCH + Duration::from_millis(modification_time.try_into().unwrap());
+        file.set_modified(mtime).unwrap();
+    }
+
+    fn history_manager(table_root: &Path, engine: &dyn Engine) -> LogHistoryManager {
+        let url = Url::from_directory_path(table_root).unwrap();
+        let snapshot = Snapshot::try_new(url, engine, None).unwrap();
+        LogHistoryManager::try_new(engine, &snapshot).unwrap()
+    }
+
+    /// Creates a table with commits 0..=2 using file modification times 100, 200, 300 and
+    /// commits 3..=4 using in-commit timestamps 400, 500. In-commit timestamps are enabled at
+    /// version 3. The file modification times of the ICT commits are deliberately out of order
+    /// to make sure they are not used.
+    fn write_ict_table(table_root: &Path) {
+        let [protocol, metadata] = protocol_and_metadata(json!({}));
+        write_commit(table_root, 0, &[commit_info(None), protocol, metadata], 100);
+        write_commit(table_root, 1, &[commit_info(None)], 200);
+        write_commit(table_root, 2, &[commit_info(None)], 300);
+        let [protocol, metadata] = protocol_and_metadata(json!({
+            "delta.enableInCommitTimestamps": "true",
+            "delta.inCommitTimestampEnablementVersion": "3",
+            "delta.inCommitTimestampEnablementTimestamp": "400",
+        }));
+        write_commit(
+            table_root,
+            3,
+            &[commit_info(Some(400)), protocol, metadata],
+            10_000,
+        );
+        write_commit(table_root, 4, &[commit_info(Some(500))], 50);
+    }
+
+    #[test]
+    fn test_timestamp_to_version_file_modification_time() {
+        let dir = tempfile::tempdir().unwrap();
+        let [protocol, metadata] = protocol_and_metadata(json!({}));
+        write_commit(dir.path(), 0, &[commit_info(None), protocol, metadata], 100);
+        write_commit(dir.path(), 1, &[commit_info(None)], 200);
+        write_commit(dir.path(), 2, &[commit_info(None)], 300);
+
+        let engine = SyncEngine::new();
+        let manager = history_manager(dir.path(), &engine);
+
+        let as_of = |ts| manager.latest_version_as_of(&engine, ts);
+        assert!(as_of(99).is_err());
+        assert_eq!(as_of(100).unwrap(), 0);
+        assert_eq!(as_of(250).unwrap(), 1);
+        assert_eq!(as_of(300).unwrap(), 2);
+        assert_eq!(as_of(1000).unwrap(), 2);
+
+        let after = |ts| manager.first_version_after(&engine, ts);
+        assert_eq!(after(0).unwrap(), 0);
+        assert_eq!(after(100).unwrap(), 0);
+        assert_eq!(after(150).unwrap(), 1);
+        assert_eq!(after(300).unwrap(), 2);
+        assert!(after(301).is_err());
+    }
+
+    #[test]
+    fn test_timestamp_to_version_in_commit_timestamps() {
+        let dir = tempfile::tempdir().unwrap();
+        write_ict_table(dir.path());
+
+        let engine = SyncEngine::new();
+        let manager = history_manager(dir.path(), &engine);
+
+        let as_of = |ts| manager.latest_version_as_of(&engine, ts);
+        assert_eq!(as_of(350).unwrap(), 2);
+        assert_eq!(as_of(400).unwrap(), 3);
+        assert_eq!(as_of(499).unwrap(), 3);
+        assert_eq!(as_of(500).unwrap(), 4);
+        assert_eq!(as_of(20_000).unwrap(), 4);
+
+        let after = |ts| manager.first_version_after(&engine, ts);
+        assert_eq!(after(300).unwrap(), 2);
+        assert_eq!(after(301).unwrap(), 3);
+        assert_eq!(after(400).unwrap(), 3);
+        assert_eq!(after(401).unwrap(), 4);
+        assert!(after(501).is_err());
+    }
+
+    #[test]
+    fn test_missing_in_commit_timestamp_fails() {
+        let dir = tempfile::tempdir().unwrap();
+        write_ict_table(dir.path());
+        write_commit(dir.path(), 5, &[commit_info(None)], 600);
+
+        let engine = SyncEngine::new();
+        let manager = history_manager(dir.path(), &engine);
+        assert!(manager.latest_version_as_of(&engine, 600).is_err());
+    }
+
+    #[test]
+    fn test_history() {
+        let dir = tempfile::tempdir().unwrap();
+        write_ict_table(dir.path());
+        let commit_info = json!({
+            "commitInfo": {
+                "inCommitTimestamp": 600,
+                "timestamp": 599,
+                "operation": "DELETE",
+                "operationParameters": { "predicate": "[\"(id > 1)\"]" },
+                "engineInfo": "Apache-Spark/3.5.0 Delta-Lake/3.2.0",
+                "txnId": "3f9fc3a4-5b7e-4b47-a5e4-9d4c23d4b1a2",
+                "operationMetrics": { "numRemovedFiles": "1", "numDeletedRows": "10" },
+            }
+        });
+        write_commit(dir.path(), 5, &[commit_info], 20);
+
+        let engine = SyncEngine::new();
+        let url = Url::from_directory_path(dir.path()).unwrap();
+        let snapshot = Snapshot::try_new(url.clone(), &engine, None).unwrap();
+
+        let history: Vec<_> = snapshot
+            .history(&engine, None)
+            .unwrap()
+            .try_collect()
+            .unwrap();
+        let versions_and_timestamps: Vec<_> = history
+            .iter()
+            .map(|entry| (entry.version(), entry.timestamp()))
+            .collect();
+        assert_eq!(
+            versions_and_timestamps,
+            vec![(5, 600), (4, 500), (3, 400), (2, 300), (1, 200), (0, 100)]
+        );
+
+        let latest = history[0].commit_info();
+        assert_eq!(latest.timestamp(), Some(599));
+        assert_eq!(latest.in_commit_timestamp(), Some(600));
+        assert_eq!(latest.operation(), Some("DELETE"));
+        assert_eq!(
+            latest.operation_parameters().unwrap()["predicate"],
+            "[\"(id > 1)\"]"
+        );
+        assert_eq!(
+            latest.engine_info(),
+            Some("Apache-Spark/3.5.0 Delta-Lake/3.2.0")
+        );
+        assert_eq!(
+            latest.txn_id(),
+            Some("3f9fc3a4-5b7e-4b47-a5e4-9d4c23d4b1a2")
+        );
+        let metrics = latest.operation_metrics().unwrap();
+        assert_eq!(metrics["numRemovedFiles"], "1");
+        assert_eq!(metrics["numDeletedRows"], "10");
+        assert_eq!(history[1].commit_info().operation(), Some("WRITE"));
+
+        // The limit returns the newest entries
+        let history: Vec<_> = snapshot
+            .history(&engine, Some(2))
+            .unwrap()
+            .map_ok(|entry| entry.version())
+            .try_collect()
+            .unwrap();
+        assert_eq!(history, vec![5, 4]);
+
+        // History of an older snapshot ends at that snapshot's version
+        let snapshot = Snapshot::try_new(url, &engine, Some(2)).unwrap();
+        let history: Vec<_> = snapshot
+            .history(&engine, None)
+            .unwrap()
+            .map_ok(|entry| entry.version())
+            .try_collect()
+            .unwrap();
+        assert_eq!(history, vec![2, 1, 0]);
+    }
+
+    #[test]
+    fn test_history_stops_at_earliest_available_commit() {
+        // Copy the log without commits 0 and 1, as if they had been removed by log cleanup
+        let source_log = Path::new("./tests/data/with_checkpoint_no_last_checkpoint/_delta_log");
+        let dir = tempfile::tempdir().unwrap();
+        let log_root = dir.path().join("_delta_log");
+        std::fs::create_dir(&log_root).unwrap();
+        for file_name in [
+            "00000000000000000002.checkpoint.parquet",
+            "00000000000000000002.json",
+            "00000000000000000003.json",
+        ] {
+            std::fs::copy(source_log.join(file_name), log_root.join(file_name)).unwrap();
+        }
+
+        let engine = SyncEngine::new();
+        let url = Url::from_directory_path(dir.path()).unwrap();
+        let snapshot = Snapshot::try_new(url, &engine, None).unwrap();
+        let history: Vec<_> = snapshot
+            .history(&engine, None)
+            .unwrap()
+            .map_ok(|entry| entry.version())
+            .try_collect()
+            .unwrap();
+        assert_eq!(history, vec![3, 2]);
+    }
+}
@@ -0,0 +1,353 @@
+//! A conservative checker for predicate implication, used to decide whether results filtered by
+//! one predicate can be reused for another predicate.
+//!
+//! `pred` implies `implied` if every row for which `pred` evaluates to TRUE also makes `implied`
+//! evaluate to TRUE. The check is sound but incomplete: `true` is returned only if the implication
+//! could be proven, and `false` means the implication may or may not hold.
+use std::cmp::Ordering;
+
+use crate::expressions::{
+    BinaryPredicate, BinaryPredicateOp, ColumnName, Expression as Expr, JunctionPredicate,
+    JunctionPredicateOp, Predicate as Pred, Scalar, UnaryPredicate, UnaryPredicateOp,
+};
+
+/// Returns `true` if `pred` provably implies `implied`.
+pub(crate) fn predicate_implies(pred: &Pred, implied: &Pred) -> bool {
+    implies(
+        &negation_normal_form(pred, false),
+        &negation_normal_form(implied, false),
+    )
+}
+
+// Both inputs are expected in negation normal form, so that `Not` only wraps leaf predicates.
+fn implies(pred: &Pred, implied: &Pred) -> bool {
+    use JunctionPredicateOp::*;
+    // The junction cases are ordered so that conjunctions in `implied` and disjunctions in `pred`
+    // are split first, since each of their children must be proven.
+    match (pred, implied) {
+        (_, Pred::Junction(JunctionPredicate { op: And, preds })) => {
+            preds.iter().all(|implied| implies(pred, implied))
+        }
+        (Pred::Junction(JunctionPredicate { op: Or, preds }), _) => {
+            preds.iter().all(|pred| implies(pred, implied))
+        }
+        (Pred::Junction(JunctionPredicate { op: And, preds }), _)
+            if preds.iter().any(|pred| implies(pred, implied)) =>
+        {
+            true
+        }
+        (_, Pred::Junction(JunctionPredicate { op: Or, preds }))
+            if preds.iter().any(|implied| implies(pred, implied)) =>
+        {
+            true
+        }
+        _ => implies_leaf(pred, implied),
+    }
+}
+
+fn implies_leaf(pred: &Pred, implied: &Pred) -> bool {
+    if pred == implied
+        || *pred == Pred::literal(false)
+        || *implied == Pred::literal(true)
+        || matches!(
+            pred,
+            Pred::BooleanExpression(Expr::Literal(Scalar::Null(_)))
+        )
+    {
+        return true;
+    }
+    let Some(pred) = Comparison::try_new(pred) else {
+        return false;
+    };
+    // Comparisons only evaluate to TRUE over non-NULL values
+    if let Some(column) = is_not_null_column(implied) {
+        return pred.column == column;
+    }
+    match Comparison::try_new(implied) {
+        Some(implied) if pred.column == implied.column => pred.implies(&implied),
+        _ => false,
+    }
+}
+
+/// Pushes all `NOT` down to the leaves of the predicate, inverting the junctions along the way.
+fn negation_normal_form(pred: &Pred, inverted: bool) -> Pred {
+    match pred {
+        Pred::Not(pred) => negation_normal_form(pred, !inverted),
+        Pred::Junction(JunctionPredicate { op, preds }) => {
+            let op = match (op, inverted) {
+                (JunctionPredicateOp::And, false) | (JunctionPredicateOp::Or, true) => {
+                    JunctionPredicateOp::And
+                }
+                (JunctionPredicateOp::Or, false) | (JunctionPredicateOp::And, true) => {
+                    JunctionPredicateOp::Or
+                }
+            };
+            let preds = preds
+                .iter()
+                .map(|pred| negation_normal_form(pred, inverted));
+            Pred::junction(op, preds)
+        }
+        Pred::BooleanExpression(Expr::Literal(Scalar::Boolean(value))) => {
+            Pred::literal(*value != inverted)
+        }
+        pred if inverted => Pred::not(pred.clone()),
+        pred => pred.clone(),
+    }
+}
+
+fn is_not_null_column(pred: &Pred) -> Option<&ColumnName> {
+    match pred {
+        Pred::Not(pred) => match pred.as_ref() {
+            Pred::Unary(UnaryPredicate {
+                op: UnaryPredicateOp::IsNull,
+                expr,
+            }) => match expr.as_ref() {
+                Expr::Column(column) => Some(column),
+                _ => None,
+            },
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum ComparisonOp {
+    LessThan,
+    LessThanOrEqual,
+    GreaterThan,
+    GreaterThanOrEqual,
+    Equal,
+    NotEqual,
+}
+
+impl ComparisonOp {
+    fn invert(self) -> Self {
+        use ComparisonOp::*;
+        match self {
+            LessThan => GreaterThanOrEqual,
+            LessThanOrEqual => GreaterThan,
+            GreaterThan => LessThanOrEqual,
+            GreaterThanOrEqual => LessThan,
+            Equal => NotEqual,
+            NotEqual => Equal,
+        }
+    }
+
+    // `a OP b` is equivalent to `b OP' a`
+    fn commute(self) -> Self {
+        use ComparisonOp::*;
+        match self {
+            LessThan => GreaterThan,
+            LessThanOrEqual => GreaterThanOrEqual,
+            GreaterThan => LessThan,
+            GreaterThanOrEqual => LessThanOrEqual,
+            Equal => Equal,
+            NotEqual => NotEqual,
+        }
+    }
+
+    // Whether `a OP b` holds, given the ordering of `a` relative to `b`
+    fn holds_for(self, ordering: Ordering) -> bool {
+        use ComparisonOp::*;
+        match self {
+            LessThan => ordering.is_lt(),
+            LessThanOrEqual => ordering.is_le(),
+            GreaterThan => ordering.is_gt(),
+            GreaterThanOrEqual => ordering.is_ge(),
+            Equal => ordering.is_eq(),
+            NotEqual => ordering.is_ne(),
+        }
+    }
+}
+
+/// A comparison `column OP value` between a column and a non-NULL literal.
+struct Comparison<'a> {
+    column: &'a ColumnName,
+    op: ComparisonOp,
+    value: &'a Scalar,
+}
+
+impl<'a> Comparison<'a> {
+    fn try_new(pred: &'a Pred) -> Option<Self> {
+        let (pred, inverted) = match pred {
+            Pred::Not(pred) => (pred.as_ref(), true),
+            pred => (pred, false),
+        };
+        let Pred::Binary(BinaryPredicate { op, left, right }) = pred else {
+            return None;
+        };
+        let op = match op {
+            BinaryPredicateOp::LessThan => ComparisonOp::LessThan,
+            BinaryPredicateOp::GreaterThan => ComparisonOp::GreaterThan,
+            BinaryPredicateOp::Equal => ComparisonOp::Equal,
+            BinaryPredicateOp::Distinct | BinaryPredicateOp::In => return None,
+        };
+        let op = if inverted { op.invert() } else { op };
+        let (column, op, value) = match (left.as_ref(), right.as_ref()) {
+            (Expr::Column(column), Expr::Literal(value)) => (column, op, value),
+            (Expr::Literal(value), Expr::Column(column)) => (column, op.commute(), value),
+            _ => return None,
+        };
+        (!value.is_null()).then_some(Self { column, op, value })
+    }
+
+    /// Whether every value satisfying `self` also satisfies `implied` (on the same column).
+    fn implies(&self, implied: &Comparison<'_>) -> bool {
+        use ComparisonOp::*;
+        let Some(ordering) = self.value.partial_cmp(implied.value) else {
+            return false;
+        };
+        match self.op {
+            // the only value satisfying `self` is `self.value`
+            Equal => implied.op.holds_for(ordering),
+            LessThan => {
+                matches!(implied.op, LessThan | LessThanOrEqual | NotEqual) && ordering.is_le()
+            }
+            LessThanOrEqual => match implied.op {
+                LessThan | NotEqual => ordering.is_lt(),
+                LessThanOrEqual => ordering.is_le(),
+                _ => false,
+            },
+            GreaterThan => {
+                matches!(implied.op, GreaterThan | GreaterThanOrEqual | NotEqual)
+                    && ordering.is_ge()
+            }
+            GreaterThanOrEqual => match implied.op {
+                GreaterThan | NotEqual => ordering.is_gt(),
+                GreaterThanOrEqual => ordering.is_ge(),
+                _ => false,
+            },
+            NotEqual => implied.op == NotEqual && ordering.is_eq(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expressions::column_expr;
+    use crate::schema::DataType;
+
+    #[test]
+    fn test_comparisons() {
+        let a = || column_expr!("a");
+        let lit = |value: i32| Expr::literal(value);
+        let cases = [
+            // (pred, implied, expected)
+            (Pred::eq(a(), lit(30)), Pred::lt(a(), lit(42)), true),
+            (Pred::eq(a(), lit(50)), Pred::lt(a(), lit(42)), false),
+            (Pred::eq(a(), lit(30)), Pred::ne(a(), lit(42)), true),
+            (Pred::eq(a(), lit(42)), Pred::ne(a(), lit(42)), false),
+            (Pred::lt(a(), lit(10)), Pred::lt(a(), lit(42)), true),
+            (Pred::lt(a(), lit(42)), Pred::lt(a(), lit(42)), true),
+            (Pred::lt(a(), lit(42)), Pred::le(a(), lit(42)), true),
+            (Pred::le(a(), lit(42)), Pred::lt(a(), lit(42)), false),
+            (Pred::le(a(), lit(41)), Pred::lt(a(), lit(42)), true),
+            (Pred::lt(a(), lit(42)), Pred::lt(a(), lit(10)), false),
+            (Pred::lt(a(), lit(42)), Pred::gt(a(), lit(10)), false),
+            (Pred::lt(a(), lit(42)), Pred::ne(a(), lit(42)), true),
+            (Pred::le(a(), lit(42)), Pred::ne(a(), lit(42)), false),
+            (Pred::gt(a(), lit(42)), Pred::ge(a(), lit(42)), true),
+            (Pred::ge(a(), lit(42)), Pred::gt(a(), lit(42)), false),
+            (Pred::ge(a(), lit(43)), Pred::gt(a(), lit(42)), true),
+            (Pred::ne(a(), lit(42)), Pred::ne(a(), lit(42)), true),
+            (Pred::ne(a(), lit(42)), Pred::ne(a(), lit(43)), false),
+            // literal on the left
+            (Pred::gt(lit(10), a()), Pred::lt(a(), lit(42)), true),
+            (Pred::lt(a(), lit(10)), Pred::gt(lit(42), a()), true),
+            // NOT is pushed down into the comparison
+            (
+                Pred::not(Pred::ge(a(), lit(10))),
+                Pred::lt(a(), lit(42)),
+                true,
+            ),
+            // different columns or types
+            (
+                Pred::lt(a(), lit(10)),
+                Pred::lt(column_expr!("b"), lit(42)),
+                false,
+            ),
+            (
+                Pred::lt(a(), lit(10)),
+                Pred::lt(a(), Expr::literal(42i64)),
+                false,
+            ),
+            // NULL literals
+            (
+                Pred::lt(a(), Expr::null_literal(DataType::INTEGER)),
+                Pred::lt(a(), lit(42)),
+                false,
+            ),
+            // comparisons only hold for non-NULL values
+            (Pred::lt(a(), lit(10)), Pred::is_not_null(a()), true),
+            (
+                Pred::lt(a(), lit(10)),
+                Pred::is_not_null(column_expr!("b")),
+                false,
+            ),
+            (Pred::is_null(a()), Pred::lt(a(), lit(42)), false),
+        ];
+        for (pred, implied, expected) in cases {
+            assert_eq!(
+                predicate_implies(&pred, &implied),
+                expected,
+                "{pred:?} implies {implied:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_junctions() {
+        let a_lt = |value: i32| Pred::lt(column_expr!("a"), Expr::literal(value));
+        let b_eq = |value: i32| Pred::eq(column_expr!("b"), Expr::literal(value));
+        let c_eq = |value: i32| Pred::eq(column_expr!("c"), Expr::literal(value));
+        let old = Pred::and(a_lt(42), b_eq(10));
+        let cases = [
+            // examples from `Scan::scan_metadata_from`
+            (
+                Pred::and(Pred::eq(column_expr!("a"), Expr::literal(30)), b_eq(10)),
+                true,
+            ),
+            (Pred::and(a_lt(10), b_eq(10)), true),
+            (Pred::and_from([a_lt(42), b_eq(10), c_eq(20)]), true),
+            (a_lt(42), false),
+            (
+                Pred::and(Pred::eq(column_expr!("a"), Expr::literal(50)), b_eq(10)),
+                false,
+            ),
+            (
+                Pred::and(a_lt(42), Pred::le(column_expr!("b"), Expr::literal(10))),
+                false,
+            ),
+            (Pred::or(a_lt(42), b_eq(10)), false),
+            // disjunctions must imply the old predicate in every branch
+            (
+                Pred::or(Pred::and(a_lt(10), b_eq(10)), Pred::and(a_lt(20), b_eq(10))),
+                true,
+            ),
+            (Pred::or(Pred::and(a_lt(10), b_eq(10)), a_lt(20)), false),
+            // NOT is pushed through junctions
+            (
+                Pred::not(Pred::or(
+                    Pred::ge(column_expr!("a"), Expr::literal(42)),
+                    Pred::ne(column_expr!("b"), Expr::literal(10)),
+                )),
+                true,
+            ),
+            (Pred::literal(false), true),
+            (Pred::literal(true), false),
+        ];
+        for (pred, expected) in cases {
+            assert_eq!(predicate_implies(&pred, &old), expected, "{pred:?}");
+        }
+
+        // implying a disjunction only requires implying one of its branches
+        let old = Pred::or(a_lt(42), b_eq(10));
+        assert!(predicate_implies(&a_lt(10), &old));
+        assert!(predicate_implies(&Pred::and(c_eq(1), b_eq(10)), &old));
+        assert!(!predicate_implies(&c_eq(1), &old));
+        assert!(predicate_implies(&c_eq(1), &Pred::literal(true)));
+        assert!(predicate_implies(&c_eq(1), &Pred::and_from([])));
+    }
+}
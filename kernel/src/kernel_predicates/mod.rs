@@ -7,13 +7,14 @@ use crate::expressions::{
     BinaryExpression, BinaryExpressionOp, BinaryPredicate, BinaryPredicateOp, ColumnName,
     Expression as Expr, JunctionPredicate, JunctionPredicateOp, OpaqueExpression,
     OpaqueExpressionOpRef, OpaquePredicate, OpaquePredicateOpRef, Predicate as Pred, Scalar,
-    UnaryPredicate, UnaryPredicateOp,
+    UnaryPredicate, UnaryPredicateOp, VariadicExpression, VariadicExpressionOp,
 };
 use crate::schema::DataType;
 
 use std::cmp::Ordering;
 use tracing::{debug, warn};
 
+pub(crate) mod implication;
 pub(crate) mod parquet_stats_skipping;
 
 #[cfg(test)]
@@ -177,7 +178,7 @@ pub trait KernelPredicateEvaluator {
             Expr::Opaque(OpaqueExpression { op, exprs }) => {
                 self.eval_pred_expr_opaque(op, exprs, inverted)
             }
-            Expr::Struct(_) | Expr::Binary(_) | Expr::Unknown(_) => None,
+            Expr::Struct(_) | Expr::Binary(_) | Expr::Variadic(_) | Expr::Unknown(_) => None,
         }
     }
 
@@ -200,6 +201,7 @@ pub trait KernelPredicateEvaluator {
                 Expr::Predicate(_)
                 | Expr::Struct(_)
                 | Expr::Binary(_)
+                | Expr::Variadic(_)
                 | Expr::Opaque(_)
                 | Expr::Unknown(_) => {
                     debug!("Unsupported operand: IS [NOT] NULL: {expr:?}");
@@ -618,6 +620,20 @@ impl<R: ResolveColumnAsScalar> DefaultKernelPredicateEvaluator<R> {
                 };
                 op_fn(&self.eval_expr(left)?, &self.eval_expr(right)?)
             }
+            Expr::Variadic(VariadicExpression { op, exprs }) => match op {
+                // the first non-null value, or the last (null) value if all are null
+                VariadicExpressionOp::Coalesce => {
+                    let mut result = None;
+                    for expr in exprs {
+                        let value = self.eval_expr(expr)?;
+                        if !value.is_null() {
+                            return Some(value);
+                        }
+                        result = Some(value);
+                    }
+                    result
+                }
+            },
             Expr::Opaque(OpaqueExpression { op, exprs }) => op
                 .eval_expr_scalar(&|expr| self.eval_expr(expr), exprs)
                 .inspect_err(|err| {
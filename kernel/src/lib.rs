@@ -78,6 +78,7 @@ pub mod checkpoint;
 pub mod engine_data;
 pub mod error;
 pub mod expressions;
+pub mod log_compaction;
 pub mod scan;
 pub mod schema;
 pub mod snapshot;
@@ -86,12 +87,14 @@ pub mod table_configuration;
 pub mod table_features;
 pub mod table_properties;
 pub mod transaction;
+pub mod vacuum;
 
 mod arrow_compat;
 #[cfg(any(feature = "arrow-54", feature = "arrow-55"))]
 pub use arrow_compat::*;
 
 pub mod kernel_predicates;
+pub(crate) mod log_cleanup;
 pub(crate) mod utils;
 
 #[cfg(feature = "internal-api")]
@@ -125,6 +128,7 @@ pub use delta_kernel_derive;
 pub use engine_data::{EngineData, RowVisitor};
 pub use error::{DeltaResult, Error};
 pub use expressions::{Expression, ExpressionRef, Predicate, PredicateRef};
+pub use history_manager::HistoryEntry;
 pub use snapshot::Snapshot;
 
 use expressions::literal_expression_transform::LiteralExpressionTransform;
@@ -503,6 +507,30 @@ pub trait StorageHandler: AsAny {
         &self,
         files: Vec<FileSlice>,
     ) -> DeltaResult<Box<dyn Iterator<Item = DeltaResult<Bytes>>>>;
+
+    /// Write `data` to the file at `path`. The write must be atomic: readers should never observe
+    /// a partially written file.
+    ///
+    /// If `overwrite` is false and the file already exists, the call must fail with
+    /// [`Error::FileAlreadyExists`].
+    ///
+    /// The default implementation fails with [`Error::Unsupported`], so kernel operations that
+    /// write files (e.g. writing deletion vectors) are not available.
+    fn write_file(&self, path: &Url, _data: Bytes, _overwrite: bool) -> DeltaResult<()> {
+        Err(Error::unsupported(format!(
+            "Writing files is not supported by this storage handler (writing {path})"
+        )))
+    }
+
+    /// Delete the file at `path`. Deleting a file that does not exist is not an error.
+    ///
+    /// The default implementation fails with [`Error::Unsupported`], so kernel operations that
+    /// delete files (e.g. cleaning up expired log files) are not available.
+    fn delete_file(&self, path: &Url) -> DeltaResult<()> {
+        Err(Error::unsupported(format!(
+            "Deleting files is not supported by this storage handler (deleting {path})"
+        )))
+    }
 }
 
 /// Provides JSON handling functionality to Delta Kernel.
@@ -585,17 +613,49 @@ pub trait ParquetHandler: AsAny {
     /// the columns requested by physical schema . The ParquetHandler _must_ return exactly the
     /// columns specified in `physical_schema`, and they _must_ be in schema order.
     ///
+    /// A field of `physical_schema` that is a [`MetadataColumnSpec::RowIndex`] metadata column is
+    /// not read from the file. Instead, the ParquetHandler _must_ fill it in with the index of each
+    /// returned row in its file. Kernel requests row indexes when it needs to match the returned
+    /// rows to a deletion vector after the `predicate` may have caused rows to be skipped.
+    ///
     /// # Parameters
     ///
     /// - `files` - File metadata for files to be read.
     /// - `physical_schema` - Select list and order of columns to read from the Parquet file.
-    /// - `predicate` - Optional push-down predicate hint (engine is free to ignore it).
+    /// - `predicate` - Optional push-down predicate hint (engine is free to ignore it). The engine
+    ///   may skip row groups or rows that cannot satisfy it.
+    ///
+    /// [`MetadataColumnSpec::RowIndex`]: crate::schema::MetadataColumnSpec::RowIndex
     fn read_parquet_files(
         &self,
         files: &[FileMeta],
         physical_schema: SchemaRef,
         predicate: Option<PredicateRef>,
     ) -> DeltaResult<FileDataReadResultIterator>;
+
+    /// Read the footer of the Parquet file at the given location, without reading any of its data.
+    /// Kernel uses the footer to learn which columns the file provides, e.g. whether a checkpoint
+    /// has the optional `add.stats_parsed` and `add.partitionValues_parsed` columns.
+    ///
+    /// # Parameters
+    ///
+    /// - `file` - File metadata for the file whose footer should be read.
+    ///
+    /// The default implementation fails with [`Error::Unsupported`], in which case kernel does not
+    /// use the typed stats of checkpoints whose schema is not known otherwise.
+    fn read_parquet_footer(&self, file: &FileMeta) -> DeltaResult<ParquetFooter> {
+        Err(Error::unsupported(format!(
+            "Reading parquet footers is not supported by this parquet handler (reading {})",
+            file.location
+        )))
+    }
+}
+
+/// The metadata in the footer of a Parquet file, see [`ParquetHandler::read_parquet_footer`].
+#[derive(Debug, Clone)]
+pub struct ParquetFooter {
+    /// The schema of the file.
+    pub schema: SchemaRef,
 }
 
 /// The `Engine` trait encapsulates all the functionality an engine or connector needs to provide
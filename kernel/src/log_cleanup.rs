@@ -0,0 +1,389 @@
+//! Cleanup of expired log files, i.e. the commit, checkpoint, CRC and log compaction files that are
+//! older than the table's log retention period and no longer needed to reconstruct any retained
+//! version of the table.
+//!
+//! The log retention period is set by the `delta.logRetentionDuration` table property (default 30
+//! days). A version is retained if its commit is newer than the retention period. The retained
+//! versions are reconstructed from the newest complete checkpoint written no later than the
+//! oldest retained version, so every log file of an older version is expired. The files of that
+//! checkpoint's version (and any newer version) are never deleted.
+//!
+//! The sidecar files (in `_delta_log/_sidecars`) of expired V2 checkpoints are deleted along with
+//! them, unless a retained checkpoint references them too. Sidecars that no checkpoint references
+//! (e.g. left behind by a failed checkpoint write) are not deleted.
+//!
+//! Writers clean up expired log files after each checkpoint (see [`CheckpointWriter::finalize`]),
+//! unless the `delta.enableExpiredLogCleanup` table property is `false`. Like vacuum, log cleanup
+//! is only allowed if kernel supports the table's protocol for vacuum (which, with the
+//! `vacuumProtocolCheck` reader feature, includes reading the table).
+//!
+//! [`CheckpointWriter::finalize`]: crate::checkpoint::CheckpointWriter::finalize
+use std::collections::HashSet;
+use std::time::Duration;
+
+use crate::actions::visitors::SidecarVisitor;
+use crate::actions::{get_log_schema, SIDECAR_NAME};
+use crate::engine_data::RowVisitor as _;
+use crate::log_segment::{group_checkpoint_parts, list_log_files};
+use crate::path::{LogPathFileType, ParsedLogPath};
+use crate::table_properties::TableProperties;
+use crate::utils::current_time_ms;
+use crate::{DeltaResult, Engine, Error, Version};
+
+use itertools::Itertools as _;
+use tracing::debug;
+use url::Url;
+
+/// The default log retention period of 30 days
+const DEFAULT_LOG_RETENTION: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+/// Returns the timestamp (in milliseconds since the unix epoch) before which log files are expired,
+/// according to the `delta.logRetentionDuration` table property.
+pub(crate) fn log_retention_timestamp(table_properties: &TableProperties) -> DeltaResult<i64> {
+    let retention = table_properties
+        .log_retention_duration
+        .unwrap_or(DEFAULT_LOG_RETENTION);
+    let retention_ms = i64::try_from(retention.as_millis())
+        .map_err(|_| Error::generic("Log retention duration exceeds i64 millisecond range"))?;
+    Ok(current_time_ms()? - retention_ms)
+}
+
+/// Deletes the expired log files of the table with the given log root, among the files of versions
+/// up to `end_version`, along with the sidecar files only the expired checkpoints reference.
+/// Commits written at or before `cutoff_timestamp` are expired. Returns the number of deleted files.
+pub(crate) fn cleanup_expired_logs(
+    engine: &dyn Engine,
+    log_root: &Url,
+    end_version: Version,
+    cutoff_timestamp: i64,
+) -> DeltaResult<usize> {
+    let storage = engine.storage_handler();
+    let log_files: Vec<_> =
+        list_log_files(storage.as_ref(), log_root, None, end_version)?.try_collect()?;
+    let expired_files = expired_log_files(log_files.clone(), cutoff_timestamp);
+    let expired_sidecars = expired_sidecar_files(engine, log_root, &log_files, &expired_files)?;
+    // Delete the oldest files first, so that an interrupted cleanup never leaves a gap in the log,
+    // and the sidecars last, so that no remaining checkpoint references a deleted sidecar
+    for file in &expired_files {
+        storage.delete_file(&file.location.location)?;
+    }
+    for sidecar in &expired_sidecars {
+        storage.delete_file(sidecar)?;
+    }
+    debug!(
+        "Deleted {} expired log files and {} expired sidecar files in {log_root}",
+        expired_files.len(),
+        expired_sidecars.len()
+    );
+    Ok(expired_files.len() + expired_sidecars.len())
+}
+
+/// Returns the sidecar files (in the `_sidecars` directory of the log) that are referenced by the
+/// expired checkpoints among `expired_files`, but by none of the retained checkpoints among
+/// `log_files`.
+fn expired_sidecar_files(
+    engine: &dyn Engine,
+    log_root: &Url,
+    log_files: &[ParsedLogPath],
+    expired_files: &[ParsedLogPath],
+) -> DeltaResult<Vec<Url>> {
+    let expired_sidecars = checkpoint_sidecar_files(engine, log_root, expired_files)?;
+    if expired_sidecars.is_empty() {
+        return Ok(vec![]);
+    }
+    // Every log file of the expired versions is expired
+    let last_expired_version = expired_files.iter().map(|file| file.version).max();
+    let retained_files = log_files
+        .iter()
+        .filter(|file| Some(file.version) > last_expired_version)
+        .cloned()
+        .collect_vec();
+    let retained_sidecars = checkpoint_sidecar_files(engine, log_root, &retained_files)?;
+    let sidecars_dir = log_root.join("_sidecars/")?;
+    Ok(expired_sidecars
+        .into_iter()
+        .filter(|sidecar| !retained_sidecars.contains(sidecar))
+        .filter(|sidecar| sidecar.as_str().starts_with(sidecars_dir.as_str()))
+        .sorted()
+        .collect())
+}
+
+/// Returns the locations of the sidecar files referenced by the single-file checkpoints among the
+/// log files. Multi-part checkpoints never reference sidecars.
+fn checkpoint_sidecar_files(
+    engine: &dyn Engine,
+    log_root: &Url,
+    log_files: &[ParsedLogPath],
+) -> DeltaResult<HashSet<Url>> {
+    let schema = get_log_schema().project(&[SIDECAR_NAME])?;
+    let mut sidecars = HashSet::new();
+    for file in log_files {
+        if !matches!(
+            file.file_type,
+            LogPathFileType::SinglePartCheckpoint | LogPathFileType::UuidCheckpoint(_)
+        ) {
+            continue;
+        }
+        let files = [file.location.clone()];
+        let batches = match file.extension.as_str() {
+            "json" => engine
+                .json_handler()
+                .read_json_files(&files, schema.clone(), None)?,
+            _ => engine
+                .parquet_handler()
+                .read_parquet_files(&files, schema.clone(), None)?,
+        };
+        for batch in batches {
+            let mut visitor = SidecarVisitor::default();
+            visitor.visit_rows_of(batch?.as_ref())?;
+            for sidecar in visitor.sidecars {
+                sidecars.insert(sidecar.to_filemeta(log_root)?.location);
+            }
+        }
+    }
+    Ok(sidecars)
+}
+
+/// Returns the expired files among the (ascending) log files: the known log files of all versions
+/// before the newest complete checkpoint whose version is expired.
+///
+/// The modification times of the commits are adjusted to be strictly increasing with the version,
+/// so that a commit with a skewed timestamp never expires before the commits preceding it.
+fn expired_log_files(log_files: Vec<ParsedLogPath>, cutoff_timestamp: i64) -> Vec<ParsedLogPath> {
+    let mut commit_timestamp = i64::MIN;
+    let last_expired_version = log_files
+        .iter()
+        .filter(|file| file.is_commit())
+        .take_while(|commit| {
+            commit_timestamp = commit
+                .location
+                .last_modified
+                .max(commit_timestamp.saturating_add(1));
+            commit_timestamp <= cutoff_timestamp
+        })
+        .last()
+        .map(|commit| commit.version);
+    let Some(last_expired_version) = last_expired_version else {
+        return vec![];
+    };
+
+    let checkpoint_version = log_files
+        .iter()
+        .filter(|file| file.is_checkpoint() && file.version <= last_expired_version)
+        .chunk_by(|file| file.version)
+        .into_iter()
+        .filter_map(|(version, parts)| {
+            let checkpoints = group_checkpoint_parts(parts.cloned().collect());
+            let is_complete = checkpoints
+                .iter()
+                // `num_parts` is guaranteed to be non-negative and within `usize` range
+                .any(|(num_parts, part_files)| part_files.len() == *num_parts as usize);
+            is_complete.then_some(version)
+        })
+        .last();
+    let Some(checkpoint_version) = checkpoint_version else {
+        return vec![];
+    };
+
+    log_files
+        .into_iter()
+        .filter(|file| file.version < checkpoint_version && !file.is_unknown())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::object_store::{path::Path, ObjectStore as _};
+    use crate::utils::test_utils::{
+        block_on, in_memory_engine, metadata_json, protocol_json, write_json_commit,
+    };
+    use crate::{FileMeta, Snapshot};
+
+    use serde_json::json;
+
+    fn log_file(name: &str, last_modified: i64) -> ParsedLogPath {
+        let location = Url::parse("memory:///_delta_log/")
+            .unwrap()
+            .join(name)
+            .unwrap();
+        let file = FileMeta {
+            location,
+            last_modified,
+            size: 1,
+        };
+        ParsedLogPath::try_from(file).unwrap().unwrap()
+    }
+
+    fn commit(version: Version, last_modified: i64) -> ParsedLogPath {
+        log_file(&format!("{version:020}.json"), last_modified)
+    }
+
+    fn file_names(files: &[ParsedLogPath]) -> Vec<&str> {
+        files.iter().map(|file| file.filename.as_str()).collect()
+    }
+
+    /// A log with commits 0 to 6 (written at `1000 * version`), a checkpoint at version 2, a
+    /// 2-part checkpoint at version 4, an incomplete 2-part checkpoint at version 5, CRC files,
+    /// a compaction file and an unknown file.
+    fn test_log_files() -> Vec<ParsedLogPath> {
+        let mut files = vec![
+            commit(0, 0),
+            log_file(
+                "00000000000000000000.00000000000000000001.compacted.json",
+                0,
+            ),
+            commit(1, 1000),
+            log_file("00000000000000000001.crc", 1000),
+            log_file("00000000000000000001.unknown", 1000),
+            log_file("00000000000000000002.checkpoint.parquet", 2000),
+            commit(2, 2000),
+            commit(3, 3000),
+            log_file("00000000000000000003.crc", 3000),
+            log_file(
+                "00000000000000000004.checkpoint.0000000001.0000000002.parquet",
+                4000,
+            ),
+            log_file(
+                "00000000000000000004.checkpoint.0000000002.0000000002.parquet",
+                4000,
+            ),
+            commit(4, 4000),
+            log_file(
+                "00000000000000000005.checkpoint.0000000001.0000000002.parquet",
+                5000,
+            ),
+            commit(5, 5000),
+            commit(6, 6000),
+        ];
+        files.sort_by(|a, b| a.filename.cmp(&b.filename));
+        files
+    }
+
+    #[test]
+    fn test_expired_log_files() {
+        // Versions up to 5 are expired, and 4 is the newest complete checkpoint among them
+        let expired = expired_log_files(test_log_files(), 5000);
+        assert_eq!(
+            file_names(&expired),
+            [
+                "00000000000000000000.00000000000000000001.compacted.json",
+                "00000000000000000000.json",
+                "00000000000000000001.crc",
+                "00000000000000000001.json",
+                "00000000000000000002.checkpoint.parquet",
+                "00000000000000000002.json",
+                "00000000000000000003.crc",
+                "00000000000000000003.json",
+            ]
+        );
+
+        // Versions up to 3 are expired, and 2 is the newest complete checkpoint among them
+        let expired = expired_log_files(test_log_files(), 3500);
+        assert_eq!(
+            file_names(&expired),
+            [
+                "00000000000000000000.00000000000000000001.compacted.json",
+                "00000000000000000000.json",
+                "00000000000000000001.crc",
+                "00000000000000000001.json",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_no_expired_log_files() {
+        // No expired commit
+        assert!(expired_log_files(test_log_files(), -1).is_empty());
+        // No complete checkpoint among the expired versions
+        assert!(expired_log_files(test_log_files(), 1500).is_empty());
+        // No checkpoint at all
+        let commits = (0..5).map(|version| commit(version, 0)).collect();
+        assert!(expired_log_files(commits, 1000).is_empty());
+    }
+
+    #[test]
+    fn test_expired_log_files_adjusts_commit_timestamps() {
+        // Commit 3 was written with a clock ahead of the others, so commits 3 and 4 are retained
+        // even though commit 4 is older than the cutoff
+        let mut files = test_log_files();
+        for file in files.iter_mut() {
+            if file.filename == "00000000000000000003.json" {
+                file.location.last_modified = 10_000;
+            }
+        }
+        let expired = expired_log_files(files, 5000);
+        assert_eq!(
+            file_names(&expired),
+            [
+                "00000000000000000000.00000000000000000001.compacted.json",
+                "00000000000000000000.json",
+                "00000000000000000001.crc",
+                "00000000000000000001.json",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_cleanup_expired_sidecars() -> DeltaResult<()> {
+        // V2 checkpoints at versions 1 (sidecars a and b) and 2 (sidecars b and c), and sidecar d
+        // that no checkpoint references. All commits are expired, so 2 is the oldest checkpoint.
+        let (store, engine) = in_memory_engine();
+        for version in 0..=3 {
+            write_json_commit(&store, "", version, &[json!({"commitInfo": {}})])?;
+        }
+        let sidecar = |path: &str| json!({"sidecar": {"path": path, "sizeInBytes": 1, "modificationTime": 1}});
+        for (version, sidecars) in [
+            (1, ["a.parquet", "b.parquet"]),
+            (2, ["b.parquet", "c.parquet"]),
+        ] {
+            let actions = std::iter::once(json!({"checkpointMetadata": {"version": version}}))
+                .chain(sidecars.map(sidecar))
+                .join("\n");
+            let path = format!(
+                "_delta_log/{version:020}.checkpoint.3a0d65cd-4056-49b8-937b-95f9e3ee90e5.json"
+            );
+            block_on(store.put(&Path::from(path), actions.into()))?;
+        }
+        for name in ["a", "b", "c", "d"] {
+            let path = Path::from(format!("_delta_log/_sidecars/{name}.parquet"));
+            block_on(store.put(&path, "data".into()))?;
+        }
+
+        let log_root = Url::parse("memory:///_delta_log/")?;
+        assert_eq!(cleanup_expired_logs(&engine, &log_root, 3, i64::MAX)?, 4);
+        let files: Vec<_> = block_on(futures::TryStreamExt::try_collect(store.list(None)))?;
+        let files = files
+            .into_iter()
+            .map(|meta| meta.location.to_string())
+            .sorted()
+            .collect_vec();
+        assert_eq!(
+            files,
+            [
+                "_delta_log/00000000000000000002.checkpoint.3a0d65cd-4056-49b8-937b-95f9e3ee90e5.json",
+                "_delta_log/00000000000000000002.json",
+                "_delta_log/00000000000000000003.json",
+                "_delta_log/_sidecars/b.parquet",
+                "_delta_log/_sidecars/c.parquet",
+                "_delta_log/_sidecars/d.parquet",
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_cleanup_expired_logs_unsupported_protocol() -> DeltaResult<()> {
+        // The table can't be vacuumed, so its log isn't cleaned up either
+        let (store, engine) = in_memory_engine();
+        let protocol = protocol_json(
+            &["vacuumProtocolCheck"],
+            &["vacuumProtocolCheck", "checkConstraints"],
+        );
+        let metadata = metadata_json(&[], json!({}));
+        write_json_commit(&store, "", 0, &[protocol, metadata])?;
+        let snapshot = Snapshot::try_new(Url::parse("memory:///")?, &engine, None)?;
+        assert!(snapshot.cleanup_expired_logs(&engine).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,96 @@
+//! The [`LogCompactionProcessor`] implements the log replay logic for writing log compaction
+//! files. It selects the same actions as checkpoint log replay without expiring any tombstones or
+//! txns, and additionally keeps the latest `domainMetadata` action of each domain.
+use std::collections::HashSet;
+use std::sync::LazyLock;
+
+use crate::checkpoint::log_replay::{CheckpointBatch, CheckpointLogReplayProcessor};
+use crate::engine_data::{FilteredEngineData, GetData, RowVisitor, TypedGetData as _};
+use crate::log_replay::{ActionsBatch, LogReplayProcessor};
+use crate::scan::data_skipping::DataSkippingFilter;
+use crate::schema::{column_name, ColumnName, ColumnNamesAndTypes, DataType};
+use crate::utils::require;
+use crate::{DeltaResult, Error};
+
+/// A [`LogReplayProcessor`] that filters the actions of a range of commits for inclusion in a log
+/// compaction file. Batches must be processed in reverse chronological order.
+pub(crate) struct LogCompactionProcessor {
+    /// Selects the file, protocol, metadata and txn actions
+    checkpoint_processor: CheckpointLogReplayProcessor,
+    /// Domains whose latest `domainMetadata` action has been seen
+    seen_domains: HashSet<String>,
+}
+
+impl LogCompactionProcessor {
+    pub(crate) fn new() -> Self {
+        Self {
+            // Nothing is old enough to expire: the compaction file must be equivalent to the
+            // commits it replaces
+            checkpoint_processor: CheckpointLogReplayProcessor::new(i64::MIN, None),
+            seen_domains: Default::default(),
+        }
+    }
+}
+
+impl LogReplayProcessor for LogCompactionProcessor {
+    type Output = FilteredEngineData;
+
+    fn process_actions_batch(&mut self, actions_batch: ActionsBatch) -> DeltaResult<Self::Output> {
+        let CheckpointBatch { filtered_data, .. } = self
+            .checkpoint_processor
+            .process_actions_batch(actions_batch)?;
+        let mut visitor = DomainMetadataCompactionVisitor {
+            seen_domains: &mut self.seen_domains,
+            selection_vector: filtered_data.selection_vector,
+        };
+        visitor.visit_rows_of(filtered_data.data.as_ref())?;
+        Ok(FilteredEngineData {
+            data: filtered_data.data,
+            selection_vector: visitor.selection_vector,
+        })
+    }
+
+    /// We never do data skipping for log compaction (all actions of the commits are reconciled)
+    fn data_skipping_filter(&self) -> Option<&DataSkippingFilter> {
+        None
+    }
+}
+
+/// Selects the first (newest) `domainMetadata` action of each domain, in addition to the rows
+/// already selected. Removed domains are kept, since their tombstones still hide the domain's
+/// configuration in the commits before the compacted range.
+struct DomainMetadataCompactionVisitor<'seen> {
+    seen_domains: &'seen mut HashSet<String>,
+    selection_vector: Vec<bool>,
+}
+
+impl RowVisitor for DomainMetadataCompactionVisitor<'_> {
+    fn selected_column_names_and_types(&self) -> (&'static [ColumnName], &'static [DataType]) {
+        static NAMES_AND_TYPES: LazyLock<ColumnNamesAndTypes> = LazyLock::new(|| {
+            (
+                vec![column_name!("domainMetadata.domain")],
+                vec![DataType::STRING],
+            )
+                .into()
+        });
+        NAMES_AND_TYPES.as_ref()
+    }
+
+    fn visit<'a>(&mut self, row_count: usize, getters: &[&'a dyn GetData<'a>]) -> DeltaResult<()> {
+        require!(
+            getters.len() == 1,
+            Error::InternalError(format!(
+                "Wrong number of visitor getters: {}",
+                getters.len()
+            ))
+        );
+
+        for i in 0..row_count {
+            let domain: Option<String> = getters[0].get_opt(i, "domainMetadata.domain")?;
+            if let Some(domain) = domain {
+                self.selection_vector[i] = self.seen_domains.insert(domain);
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,171 @@
+//! This module implements the API for writing log compaction files.
+//!
+//! The entry point for this API is [`Snapshot::compact_log`].
+//!
+//! A log compaction file `<start>.<end>.compacted.json` holds the reconciled actions of the commits
+//! `start` through `end` (inclusive), so that readers can replay one file instead of all the
+//! commits it covers. Readers pick up compaction files automatically when loading a snapshot whose
+//! log segment includes the covered commits.
+//!
+//! The actions are reconciled the same way as for a checkpoint (see [`crate::checkpoint`]), with a
+//! few differences that keep the compaction file equivalent to the commits it replaces:
+//! - Remove actions (tombstones) are always kept, since they still shadow add actions of the
+//!   commits before `start`. Expired tombstones are only dropped when writing a checkpoint.
+//! - Txn actions are always kept, regardless of the `delta.setTransactionRetentionDuration`
+//!   table property.
+//! - The latest `domainMetadata` action of each domain is kept, including removed domains.
+//! - CommitInfo and CDC actions are not included.
+//!
+//! ## Usage
+//!
+//! 1. Create a [`LogCompactionWriter`] using [`Snapshot::compact_log`]
+//! 2. Get the compaction file path from [`LogCompactionWriter::compaction_path`]
+//! 3. Get the compaction data from [`LogCompactionWriter::compaction_data`]
+//! 4. Write the selected rows of the data to the path as newline-delimited JSON
+//!    (engine-specific)
+//!
+//! ```no_run
+//! # use std::sync::Arc;
+//! # use delta_kernel::engine_data::FilteredEngineData;
+//! # use delta_kernel::Engine;
+//! # use delta_kernel::Snapshot;
+//! # use delta_kernel::DeltaResult;
+//! # use delta_kernel::Error;
+//! # use url::Url;
+//! fn write_json_file(
+//!     path: Url,
+//!     data: impl Iterator<Item = DeltaResult<FilteredEngineData>>,
+//! ) -> DeltaResult<()> {
+//!     todo!() /* engine-specific logic to write data to object storage */
+//! }
+//!
+//! let engine: &dyn Engine = todo!(); /* create engine instance */
+//!
+//! // Create a snapshot that includes the commits to compact
+//! let snapshot = Arc::new(Snapshot::try_from_uri("./tests/data/basic_partitioned", engine, None)?);
+//!
+//! // Compact the commits 0 through 1
+//! let writer = snapshot.compact_log(0, 1)?;
+//! write_json_file(writer.compaction_path()?, writer.compaction_data(engine)?)?;
+//!
+//! # Ok::<_, Error>(())
+//! ```
+use std::sync::{Arc, LazyLock};
+
+use crate::actions::{
+    Add, DomainMetadata, Metadata, Protocol, Remove, SetTransaction, Sidecar, ADD_NAME,
+    DOMAIN_METADATA_NAME, METADATA_NAME, PROTOCOL_NAME, REMOVE_NAME, SET_TRANSACTION_NAME,
+    SIDECAR_NAME,
+};
+use crate::engine_data::FilteredEngineData;
+use crate::log_replay::LogReplayProcessor as _;
+use crate::log_segment::LogSegment;
+use crate::path::ParsedLogPath;
+use crate::schema::{SchemaRef, StructField, StructType, ToSchema as _};
+use crate::utils::require;
+use crate::{DeltaResult, Engine, Error, Snapshot, Version};
+use log_replay::LogCompactionProcessor;
+
+use url::Url;
+
+mod log_replay;
+#[cfg(test)]
+mod tests;
+
+/// Schema for extracting relevant actions from log files for log compaction
+static COMPACTION_ACTIONS_SCHEMA: LazyLock<SchemaRef> = LazyLock::new(|| {
+    Arc::new(StructType::new([
+        StructField::nullable(ADD_NAME, Add::to_schema()),
+        StructField::nullable(REMOVE_NAME, Remove::to_schema()),
+        StructField::nullable(METADATA_NAME, Metadata::to_schema()),
+        StructField::nullable(PROTOCOL_NAME, Protocol::to_schema()),
+        StructField::nullable(SET_TRANSACTION_NAME, SetTransaction::to_schema()),
+        StructField::nullable(DOMAIN_METADATA_NAME, DomainMetadata::to_schema()),
+    ]))
+});
+
+/// The compacted range never includes a checkpoint, but log replay requires the checkpoint read
+/// schema to include the sidecar column whenever it includes file actions.
+static COMPACTION_CHECKPOINT_READ_SCHEMA: LazyLock<SchemaRef> = LazyLock::new(|| {
+    let fields = COMPACTION_ACTIONS_SCHEMA.fields().cloned();
+    let sidecar = StructField::nullable(SIDECAR_NAME, Sidecar::to_schema());
+    Arc::new(StructType::new(fields.chain([sidecar])))
+});
+
+/// Produces the data of a log compaction file for a range of commits of a table.
+///
+/// See the module-level documentation for more details.
+#[derive(Debug)]
+pub struct LogCompactionWriter {
+    snapshot: Arc<Snapshot>,
+    start_version: Version,
+    end_version: Version,
+}
+
+impl LogCompactionWriter {
+    /// Creates a writer compacting the commits `start_version..=end_version` of the snapshot's
+    /// table. The range must cover at least two commits and end at or before the snapshot version.
+    pub(crate) fn try_new(
+        snapshot: Arc<Snapshot>,
+        start_version: Version,
+        end_version: Version,
+    ) -> DeltaResult<Self> {
+        require!(
+            start_version < end_version,
+            Error::generic(format!(
+                "Invalid log compaction range: start version {start_version} must be less than \
+                 end version {end_version}"
+            ))
+        );
+        require!(
+            end_version <= snapshot.version(),
+            Error::generic(format!(
+                "Invalid log compaction range: end version {end_version} is greater than the \
+                 snapshot version {}",
+                snapshot.version()
+            ))
+        );
+        Ok(Self {
+            snapshot,
+            start_version,
+            end_version,
+        })
+    }
+
+    /// Returns the URL where the log compaction file should be written:
+    ///
+    /// `<table_root>/_delta_log/<start_version>.<end_version>.compacted.json`
+    ///
+    /// where both versions are zero-padded to 20 digits.
+    pub fn compaction_path(&self) -> DeltaResult<Url> {
+        ParsedLogPath::new_log_compaction(
+            self.snapshot.table_root(),
+            self.start_version,
+            self.end_version,
+        )
+        .map(|parsed| parsed.location)
+    }
+
+    /// Returns the data to be written to the log compaction file. Only the selected rows of each
+    /// batch belong in the file.
+    ///
+    /// The commits are read from storage, so all commits of the range must still exist.
+    pub fn compaction_data(
+        &self,
+        engine: &dyn Engine,
+    ) -> DeltaResult<impl Iterator<Item = DeltaResult<FilteredEngineData>> + Send> {
+        let log_segment = LogSegment::for_table_changes(
+            engine.storage_handler().as_ref(),
+            self.snapshot.log_segment().log_root.clone(),
+            self.start_version,
+            self.end_version,
+        )?;
+        let actions = log_segment.read_actions(
+            engine,
+            COMPACTION_ACTIONS_SCHEMA.clone(),
+            COMPACTION_CHECKPOINT_READ_SCHEMA.clone(),
+            None,
+        )?;
+        Ok(LogCompactionProcessor::new().process_actions_iter(actions))
+    }
+}
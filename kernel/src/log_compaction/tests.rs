@@ -0,0 +1,277 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::arrow::array::RecordBatch;
+use crate::arrow::compute::filter_record_batch;
+use crate::engine::arrow_data::ArrowEngineData;
+use crate::engine::default::{executor::tokio::TokioBackgroundExecutor, DefaultEngine};
+use crate::engine_data::FilteredEngineData;
+use crate::object_store::{memory::InMemory, ObjectStore};
+use crate::scan::state::{DvInfo, Stats};
+use crate::utils::test_utils::{
+    add_json, block_on, in_memory_engine, metadata_json, protocol_json, remove_json,
+    write_json_commit,
+};
+use crate::{DeltaResult, Engine as _, EngineData, ExpressionRef, Snapshot};
+
+use itertools::Itertools as _;
+use serde_json::{json, Value};
+use test_utils::delta_path_for_version;
+use url::Url;
+
+type TestEngine = DefaultEngine<TokioBackgroundExecutor>;
+
+fn add(path: &str) -> Value {
+    add_json(path, json!({}))
+}
+
+fn remove(path: &str) -> Value {
+    remove_json(path, 1)
+}
+
+fn txn(app_id: &str, version: i64) -> Value {
+    json!({"txn": {"appId": app_id, "version": version}})
+}
+
+fn domain_metadata(domain: &str, configuration: &str, removed: bool) -> Value {
+    json!({"domainMetadata": {
+        "domain": domain,
+        "configuration": configuration,
+        "removed": removed,
+    }})
+}
+
+fn commit_info() -> Value {
+    json!({"commitInfo": {"operation": "WRITE"}})
+}
+
+/// Writes a table with the following commits:
+/// - 0: protocol and metadata
+/// - 1: adds file1..file3, txn app1 at version 1, domain d1
+/// - 2: removes file1, updates domain d1, adds domain d2
+/// - 3: adds file4, txn app1 at version 2, txn app2, removes domain d2
+/// - 4: adds file5
+fn write_test_table() -> DeltaResult<(Arc<InMemory>, TestEngine)> {
+    let (store, engine) = in_memory_engine();
+    let protocol = protocol_json(&[], &["domainMetadata"]);
+    let metadata = metadata_json(&[], json!({}));
+    write_json_commit(&store, "", 0, &[commit_info(), protocol, metadata])?;
+    write_json_commit(
+        &store,
+        "",
+        1,
+        &[
+            commit_info(),
+            add("file1"),
+            add("file2"),
+            add("file3"),
+            txn("app1", 1),
+            domain_metadata("d1", "old", false),
+        ],
+    )?;
+    write_json_commit(
+        &store,
+        "",
+        2,
+        &[
+            commit_info(),
+            remove("file1"),
+            domain_metadata("d1", "new", false),
+            domain_metadata("d2", "config", false),
+        ],
+    )?;
+    write_json_commit(
+        &store,
+        "",
+        3,
+        &[
+            commit_info(),
+            add("file4"),
+            txn("app1", 2),
+            txn("app2", 1),
+            domain_metadata("d2", "config", true),
+        ],
+    )?;
+    write_json_commit(&store, "", 4, &[commit_info(), add("file5")])?;
+    Ok((store, engine))
+}
+
+/// Returns the selected rows of the data as a single record batch.
+fn collect_selected_rows(
+    data: impl Iterator<Item = DeltaResult<FilteredEngineData>>,
+) -> DeltaResult<RecordBatch> {
+    let batches: Vec<RecordBatch> = data
+        .map(|data| -> DeltaResult<_> {
+            let data = data?;
+            let batch: RecordBatch = ArrowEngineData::try_from_engine_data(data.data)?.into();
+            Ok(filter_record_batch(&batch, &data.selection_vector.into())?)
+        })
+        .try_collect()?;
+    let schema = batches[0].schema();
+    Ok(crate::arrow::compute::concat_batches(&schema, &batches)?)
+}
+
+/// Returns the names of the (non-null) actions of each row, e.g. `add:file4` or `txn:app1`.
+fn action_names(batch: &RecordBatch) -> Vec<String> {
+    let mut buffer = vec![];
+    let mut writer = crate::arrow::json::LineDelimitedWriter::new(&mut buffer);
+    writer.write(batch).unwrap();
+    writer.finish().unwrap();
+    let mut names: Vec<_> = buffer
+        .split(|byte| *byte == b'\n')
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            let action: Value = serde_json::from_slice(line).unwrap();
+            let (name, fields) = action.as_object().unwrap().iter().exactly_one().unwrap();
+            let key = match name.as_str() {
+                "add" | "remove" => &fields["path"],
+                "txn" => &fields["appId"],
+                "domainMetadata" => &fields["domain"],
+                _ => &Value::Null,
+            };
+            match key {
+                Value::String(key) => format!("{name}:{key}"),
+                _ => name.clone(),
+            }
+        })
+        .collect();
+    names.sort();
+    names
+}
+
+fn scan_file_paths(snapshot: Arc<Snapshot>, engine: &TestEngine) -> DeltaResult<Vec<String>> {
+    fn visit_path(
+        paths: &mut Vec<String>,
+        path: &str,
+        _: i64,
+        _: Option<Stats>,
+        _: DvInfo,
+        _: Option<ExpressionRef>,
+        _: HashMap<String, String>,
+    ) {
+        paths.push(path.to_string());
+    }
+    let scan = snapshot.scan_builder().build()?;
+    let mut paths = vec![];
+    for scan_metadata in scan.scan_metadata(engine)? {
+        paths = scan_metadata?.visit_scan_files(paths, visit_path)?;
+    }
+    paths.sort();
+    Ok(paths)
+}
+
+#[test]
+fn test_compaction_data() -> DeltaResult<()> {
+    let (_, engine) = write_test_table()?;
+    let table_root = Url::parse("memory:///")?;
+    let snapshot = Arc::new(Snapshot::try_new(table_root, &engine, None)?);
+    let writer = snapshot.compact_log(1, 3)?;
+
+    assert_eq!(
+        writer.compaction_path()?.as_str(),
+        "memory:///_delta_log/00000000000000000001.00000000000000000003.compacted.json"
+    );
+
+    // Tombstones of files and domains are kept, only the latest txn of each app is kept, and
+    // commitInfo actions are dropped
+    let batch = collect_selected_rows(writer.compaction_data(&engine)?)?;
+    assert_eq!(
+        action_names(&batch),
+        [
+            "add:file2",
+            "add:file3",
+            "add:file4",
+            "domainMetadata:d1",
+            "domainMetadata:d2",
+            "remove:file1",
+            "txn:app1",
+            "txn:app2",
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_compaction_with_protocol_and_metadata() -> DeltaResult<()> {
+    let (_, engine) = write_test_table()?;
+    let table_root = Url::parse("memory:///")?;
+    let snapshot = Arc::new(Snapshot::try_new(table_root, &engine, Some(2))?);
+    let writer = snapshot.compact_log(0, 2)?;
+
+    let batch = collect_selected_rows(writer.compaction_data(&engine)?)?;
+    assert_eq!(
+        action_names(&batch),
+        [
+            "add:file2",
+            "add:file3",
+            "domainMetadata:d1",
+            "domainMetadata:d2",
+            "metaData",
+            "protocol",
+            "remove:file1",
+            "txn:app1",
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_read_table_with_compaction() -> DeltaResult<()> {
+    let (store, engine) = write_test_table()?;
+    let table_root = Url::parse("memory:///")?;
+    let snapshot = Arc::new(Snapshot::try_new(table_root.clone(), &engine, None)?);
+    let expected_paths = scan_file_paths(snapshot.clone(), &engine)?;
+    let writer = snapshot.compact_log(1, 3)?;
+
+    let path = writer.compaction_path()?;
+    let data = writer.compaction_data(&engine)?.map(|data| {
+        let data = data?;
+        let batch: RecordBatch = ArrowEngineData::try_from_engine_data(data.data)?.into();
+        let batch = filter_record_batch(&batch, &data.selection_vector.into())?;
+        Ok(Box::new(ArrowEngineData::new(batch)) as Box<dyn EngineData>)
+    });
+    engine
+        .json_handler()
+        .write_json_file(&path, Box::new(data), false)?;
+
+    // Make the compacted commits unreadable, so that reading the table relies on the compaction
+    for version in 1..=3 {
+        block_on(store.put(&delta_path_for_version(version, "json"), "not json".into()))?;
+    }
+
+    let snapshot = Arc::new(Snapshot::try_new(table_root, &engine, None)?);
+    assert_eq!(snapshot.log_segment().ascending_compaction_files.len(), 1);
+    assert_eq!(scan_file_paths(snapshot.clone(), &engine)?, expected_paths);
+    assert_eq!(
+        expected_paths,
+        ["file2", "file3", "file4", "file5"].map(String::from)
+    );
+    assert_eq!(
+        snapshot.get_domain_metadata("d1", &engine)?.as_deref(),
+        Some("new")
+    );
+    assert_eq!(snapshot.get_domain_metadata("d2", &engine)?, None);
+    assert_eq!(
+        snapshot.clone().get_app_id_version("app1", &engine)?,
+        Some(2)
+    );
+    assert_eq!(snapshot.get_app_id_version("app2", &engine)?, Some(1));
+    Ok(())
+}
+
+#[test]
+fn test_compact_log_invalid_range() -> DeltaResult<()> {
+    let (_, engine) = write_test_table()?;
+    let table_root = Url::parse("memory:///")?;
+    let snapshot = Arc::new(Snapshot::try_new(table_root, &engine, Some(3))?);
+
+    let err = snapshot.clone().compact_log(2, 2).unwrap_err();
+    assert!(err.to_string().contains("must be less than end version 2"));
+    let err = snapshot.clone().compact_log(3, 1).unwrap_err();
+    assert!(err.to_string().contains("must be less than end version 1"));
+    let err = snapshot.compact_log(1, 4).unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("end version 4 is greater than the snapshot version 3"));
+    Ok(())
+}
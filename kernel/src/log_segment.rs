@@ -54,6 +54,8 @@ pub(crate) struct LogSegment {
     pub checkpoint_parts: Vec<ParsedLogPath>,
     /// Latest CRC (checksum) file
     pub latest_crc_file: Option<ParsedLogPath>,
+    /// The schema of the checkpoint, if known from the `_last_checkpoint` file
+    pub checkpoint_schema: Option<SchemaRef>,
 }
 
 impl LogSegment {
@@ -123,6 +125,7 @@ impl LogSegment {
             ascending_compaction_files,
             checkpoint_parts,
             latest_crc_file,
+            checkpoint_schema: None,
         })
     }
 
@@ -144,16 +147,24 @@ impl LogSegment {
         time_travel_version: impl Into<Option<Version>>,
     ) -> DeltaResult<Self> {
         let time_travel_version = time_travel_version.into();
+        let checkpoint_hint = checkpoint_hint.into();
 
-        let listed_files = match (checkpoint_hint.into(), time_travel_version) {
-            (Some(cp), None) => list_log_files_with_checkpoint(&cp, storage, &log_root, None)?,
+        let listed_files = match (&checkpoint_hint, time_travel_version) {
+            (Some(cp), None) => list_log_files_with_checkpoint(cp, storage, &log_root, None)?,
             (Some(cp), Some(end_version)) if cp.version <= end_version => {
-                list_log_files_with_checkpoint(&cp, storage, &log_root, Some(end_version))?
+                list_log_files_with_checkpoint(cp, storage, &log_root, Some(end_version))?
             }
             _ => list_log_files_with_version(storage, &log_root, None, time_travel_version)?,
         };
 
-        LogSegment::try_new(listed_files, log_root, time_travel_version)
+        let mut log_segment = LogSegment::try_new(listed_files, log_root, time_travel_version)?;
+        // The schema in the hint only describes the checkpoint of the hint's version
+        if let Some(cp) = checkpoint_hint {
+            if log_segment.checkpoint_version == Some(cp.version) {
+                log_segment.checkpoint_schema = cp.checkpoint_schema.map(Arc::new);
+            }
+        }
+        Ok(log_segment)
     }
 
     /// Constructs a [`LogSegment`] to be used for `TableChanges`. For a TableChanges between versions
@@ -477,7 +488,7 @@ impl LogSegment {
 /// `end_version` is not specified, files up to the most recent version will be included.
 ///
 /// Note: this calls [`StorageHandler::list_from`] to get the list of log files.
-fn list_log_files(
+pub(crate) fn list_log_files(
     storage: &dyn StorageHandler,
     log_root: &Url,
     start_version: impl Into<Option<Version>>,
@@ -640,7 +651,9 @@ pub(crate) fn list_log_files_with_version(
 ///
 /// NOTE: There could be a single-part and/or any number of uuid-based checkpoints. They
 /// are all equivalent, and this routine keeps only one of them (arbitrarily chosen).
-fn group_checkpoint_parts(parts: Vec<ParsedLogPath>) -> HashMap<u32, Vec<ParsedLogPath>> {
+pub(crate) fn group_checkpoint_parts(
+    parts: Vec<ParsedLogPath>,
+) -> HashMap<u32, Vec<ParsedLogPath>> {
     let mut checkpoints: HashMap<u32, Vec<ParsedLogPath>> = HashMap::new();
     for part_file in parts {
         use LogPathFileType::*;
@@ -276,6 +276,8 @@ fn build_snapshot_with_correct_last_uuid_checkpoint() {
         num_of_add_files: None,
         checkpoint_schema: None,
         checksum: None,
+        tags: None,
+        v2_checkpoint: None,
     };
 
     let (storage, log_root) = build_log_with_paths_and_checkpoint(
@@ -354,6 +356,8 @@ fn build_snapshot_with_out_of_date_last_checkpoint() {
         num_of_add_files: None,
         checkpoint_schema: None,
         checksum: None,
+        tags: None,
+        v2_checkpoint: None,
     };
 
     let (storage, log_root) = build_log_with_paths_and_checkpoint(
@@ -391,6 +395,8 @@ fn build_snapshot_with_correct_last_multipart_checkpoint() {
         num_of_add_files: None,
         checkpoint_schema: None,
         checksum: None,
+        tags: None,
+        v2_checkpoint: None,
     };
 
     let (storage, log_root) = build_log_with_paths_and_checkpoint(
@@ -434,6 +440,8 @@ fn build_snapshot_with_missing_checkpoint_part_from_hint_fails() {
         num_of_add_files: None,
         checkpoint_schema: None,
         checksum: None,
+        tags: None,
+        v2_checkpoint: None,
     };
 
     let (storage, log_root) = build_log_with_paths_and_checkpoint(
@@ -469,6 +477,8 @@ fn build_snapshot_with_bad_checkpoint_hint_fails() {
         num_of_add_files: None,
         checkpoint_schema: None,
         checksum: None,
+        tags: None,
+        v2_checkpoint: None,
     };
 
     let (storage, log_root) = build_log_with_paths_and_checkpoint(
@@ -543,6 +553,8 @@ fn build_snapshot_with_out_of_date_last_checkpoint_and_incomplete_recent_checkpo
         num_of_add_files: None,
         checkpoint_schema: None,
         checksum: None,
+        tags: None,
+        v2_checkpoint: None,
     };
 
     let (storage, log_root) = build_log_with_paths_and_checkpoint(
@@ -632,6 +644,8 @@ fn build_snapshot_with_checkpoint_greater_than_time_travel_version() {
         num_of_add_files: None,
         checkpoint_schema: None,
         checksum: None,
+        tags: None,
+        v2_checkpoint: None,
     };
     let (storage, log_root) = build_log_with_paths_and_checkpoint(
         &[
@@ -672,6 +686,8 @@ fn build_snapshot_with_start_checkpoint_and_time_travel_version() {
         num_of_add_files: None,
         checkpoint_schema: None,
         checksum: None,
+        tags: None,
+        v2_checkpoint: None,
     };
 
     let (storage, log_root) = build_log_with_paths_and_checkpoint(
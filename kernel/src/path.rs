@@ -216,6 +216,24 @@ impl ParsedLogPath<Url> {
         Ok(path)
     }
 
+    /// Create a new ParsedCheckpointPath<Url> for one part (numbered from 1) of a multi-part
+    /// parquet checkpoint
+    pub(crate) fn new_multi_part_parquet_checkpoint(
+        table_root: &Url,
+        version: Version,
+        part_num: u32,
+        num_parts: u32,
+    ) -> DeltaResult<Self> {
+        let filename = format!("{version:020}.checkpoint.{part_num:010}.{num_parts:010}.parquet");
+        let path = Self::create_path(table_root, filename)?;
+        if !path.is_checkpoint() {
+            return Err(Error::internal_error(
+                "ParsedLogPath::new_multi_part_parquet_checkpoint created a non-checkpoint path",
+            ));
+        }
+        Ok(path)
+    }
+
     /// Create a new ParsedCheckpointPath<Url> for a UUID-based parquet checkpoint file
     #[allow(dead_code)] // TODO: Remove this once we have a use case for it
     pub(crate) fn new_uuid_parquet_checkpoint(
@@ -232,6 +250,23 @@ impl ParsedLogPath<Url> {
         Ok(path)
     }
 
+    /// Create a new ParsedLogPath<Url> for a log compaction file covering the commits
+    /// `start_version..=end_version`
+    pub(crate) fn new_log_compaction(
+        table_root: &Url,
+        start_version: Version,
+        end_version: Version,
+    ) -> DeltaResult<Self> {
+        let filename = format!("{start_version:020}.{end_version:020}.compacted.json");
+        let path = Self::create_path(table_root, filename)?;
+        if !matches!(path.file_type, LogPathFileType::CompactedCommit { .. }) {
+            return Err(Error::internal_error(
+                "ParsedLogPath::new_log_compaction created a non-compaction path",
+            ));
+        }
+        Ok(path)
+    }
+
     // TODO: remove after support for writing CRC files
     #[allow(unused)]
     /// Create a new ParsedCommitPath<Url> for a new CRC file
@@ -671,4 +706,48 @@ mod tests {
         ));
         assert_eq!(log_path.filename, "00000000000000000010.checkpoint.parquet");
     }
+
+    #[test]
+    fn test_new_multi_part_parquet_checkpoint() {
+        let table_log_dir = table_log_dir_url();
+        let log_path =
+            ParsedLogPath::new_multi_part_parquet_checkpoint(&table_log_dir, 10, 2, 3).unwrap();
+
+        assert_eq!(log_path.version, 10);
+        assert!(log_path.is_checkpoint());
+        assert_eq!(log_path.extension, "parquet");
+        assert_eq!(
+            log_path.file_type,
+            LogPathFileType::MultiPartCheckpoint {
+                part_num: 2,
+                num_parts: 3
+            }
+        );
+        assert_eq!(
+            log_path.filename,
+            "00000000000000000010.checkpoint.0000000002.0000000003.parquet"
+        );
+
+        ParsedLogPath::new_multi_part_parquet_checkpoint(&table_log_dir, 10, 4, 3)
+            .expect_err("part number out of range");
+    }
+
+    #[test]
+    fn test_new_log_compaction() {
+        let table_log_dir = table_log_dir_url();
+        let log_path = ParsedLogPath::new_log_compaction(&table_log_dir, 3, 7).unwrap();
+
+        assert_eq!(log_path.version, 3);
+        assert_eq!(log_path.extension, "json");
+        assert_eq!(
+            log_path.file_type,
+            LogPathFileType::CompactedCommit { hi: 7 }
+        );
+        assert_eq!(
+            log_path.filename,
+            "00000000000000000003.00000000000000000007.compacted.json"
+        );
+        assert!(!log_path.is_commit());
+        assert!(!log_path.is_checkpoint());
+    }
 }
@@ -1,15 +1,16 @@
 use std::borrow::Cow;
 use std::cmp::Ordering;
+use std::collections::{BTreeSet, HashSet};
 use std::sync::{Arc, LazyLock};
 
 use tracing::debug;
 
-use crate::actions::get_log_add_schema;
 use crate::actions::visitors::SelectionVectorVisitor;
+use crate::actions::{get_log_add_schema, ADD_NAME};
 use crate::error::DeltaResult;
 use crate::expressions::{
-    column_expr, joined_column_expr, BinaryPredicateOp, ColumnName, Expression as Expr,
-    JunctionPredicateOp, OpaquePredicateOpRef, Predicate as Pred, PredicateRef, Scalar,
+    column_expr, BinaryPredicateOp, ColumnName, Expression as Expr, JunctionPredicateOp,
+    OpaquePredicateOpRef, Predicate as Pred, PredicateRef, Scalar,
 };
 use crate::kernel_predicates::{
     DataSkippingPredicateEvaluator, KernelPredicateEvaluator, KernelPredicateEvaluatorDefaults,
@@ -22,6 +23,11 @@ use crate::{
 #[cfg(test)]
 mod tests;
 
+/// The name of the optional typed stats column of add actions in checkpoints.
+pub(crate) const STATS_PARSED_NAME: &str = "stats_parsed";
+/// The name of the optional typed partition values column of add actions in checkpoints.
+pub(crate) const PARTITION_VALUES_PARSED_NAME: &str = "partitionValues_parsed";
+
 /// Rewrites a predicate to a predicate that can be used to skip files based on their stats.
 /// Returns `None` if the predicate is not eligible for data skipping.
 ///
@@ -40,32 +46,35 @@ mod tests;
 ///   predicate is dropped.
 #[cfg(test)]
 pub(crate) fn as_data_skipping_predicate(pred: &Pred) -> Option<Pred> {
-    DataSkippingPredicateCreator.eval(pred)
+    DataSkippingPredicateCreator::default().eval(pred)
 }
 
 /// Like `as_data_skipping_predicate`, but invokes [`KernelPredicateEvaluator::eval_sql_where`]
 /// instead of [`KernelPredicateEvaluator::eval`].
 fn as_sql_data_skipping_predicate(pred: &Pred) -> Option<Pred> {
-    DataSkippingPredicateCreator.eval_sql_where(pred)
+    DataSkippingPredicateCreator::default().eval_sql_where(pred)
 }
 
 pub(crate) struct DataSkippingFilter {
     stats_schema: SchemaRef,
     select_stats_evaluator: Arc<dyn ExpressionEvaluator>,
     skipping_evaluator: Arc<dyn PredicateEvaluator>,
+    checkpoint_skipping_evaluator: Option<Arc<dyn PredicateEvaluator>>,
     filter_evaluator: Arc<dyn PredicateEvaluator>,
     json_handler: Arc<dyn JsonHandler>,
 }
 
 impl DataSkippingFilter {
     /// Creates a new data skipping filter. Returns None if there is no predicate, or the predicate
-    /// is ineligible for data skipping.
+    /// is ineligible for data skipping. If `checkpoint_columns` is given, checkpoint batches are
+    /// filtered using their typed stats and partition values instead of parsing `add.stats`.
     ///
     /// NOTE: None is equivalent to a trivial filter that always returns TRUE (= keeps all files),
     /// but using an Option lets the engine easily avoid the overhead of applying trivial filters.
     pub(crate) fn new(
         engine: &dyn Engine,
         physical_predicate: Option<(PredicateRef, SchemaRef)>,
+        checkpoint_columns: Option<&CheckpointStatsColumns>,
     ) -> Option<Self> {
         static STATS_EXPR: LazyLock<Expr> = LazyLock::new(|| column_expr!("add.stats"));
         static FILTER_PRED: LazyLock<Pred> =
@@ -74,52 +83,7 @@ impl DataSkippingFilter {
         let (predicate, referenced_schema) = physical_predicate?;
         debug!("Creating a data skipping filter for {:#?}", predicate);
 
-        // Convert all fields into nullable, as stats may not be available for all columns
-        // (and usually aren't for partition columns).
-        struct NullableStatsTransform;
-        impl<'a> SchemaTransform<'a> for NullableStatsTransform {
-            fn transform_struct_field(
-                &mut self,
-                field: &'a StructField,
-            ) -> Option<Cow<'a, StructField>> {
-                use Cow::*;
-                let field = match self.transform(&field.data_type)? {
-                    Borrowed(_) if field.is_nullable() => Borrowed(field),
-                    data_type => Owned(StructField {
-                        name: field.name.clone(),
-                        data_type: data_type.into_owned(),
-                        nullable: true,
-                        metadata: field.metadata.clone(),
-                    }),
-                };
-                Some(field)
-            }
-        }
-
-        // Convert a min/max stats schema into a nullcount schema (all leaf fields are LONG)
-        struct NullCountStatsTransform;
-        impl<'a> SchemaTransform<'a> for NullCountStatsTransform {
-            fn transform_primitive(
-                &mut self,
-                _ptype: &'a PrimitiveType,
-            ) -> Option<Cow<'a, PrimitiveType>> {
-                Some(Cow::Owned(PrimitiveType::Long))
-            }
-        }
-
-        let stats_schema = NullableStatsTransform
-            .transform_struct(&referenced_schema)?
-            .into_owned();
-
-        let nullcount_schema = NullCountStatsTransform
-            .transform_struct(&stats_schema)?
-            .into_owned();
-        let stats_schema = Arc::new(StructType::new([
-            StructField::nullable("numRecords", DataType::LONG),
-            StructField::nullable("nullCount", nullcount_schema),
-            StructField::nullable("minValues", stats_schema.clone()),
-            StructField::nullable("maxValues", stats_schema),
-        ]));
+        let stats_schema = Arc::new(stats_schema(&referenced_schema)?);
 
         // Skipping happens in several steps:
         //
@@ -132,6 +96,9 @@ impl DataSkippingFilter {
         //
         // 3. The selection evaluator does DISTINCT(col(predicate), 'false') to produce true (= keep) when
         //    the predicate is true/null and false (= skip) when the predicate is false.
+        //
+        // Checkpoint batches read with typed stats skip step 1, and evaluate the predicate directly
+        // on the `add.stats_parsed` and `add.partitionValues_parsed` columns of the actions.
         let select_stats_evaluator = engine.evaluation_handler().new_expression_evaluator(
             // safety: kernel is very broken if we don't have the schema for Add actions
             get_log_add_schema().clone(),
@@ -144,6 +111,18 @@ impl DataSkippingFilter {
             as_sql_data_skipping_predicate(&predicate)?,
         );
 
+        // Columns that only provide the number of records can't be used for data skipping
+        let checkpoint_columns = checkpoint_columns.filter(|columns| {
+            columns.stats_parsed.is_some() || columns.partition_values_parsed.is_some()
+        });
+        let checkpoint_skipping_evaluator = checkpoint_columns.and_then(|columns| {
+            let predicate = columns.predicate_creator().eval_sql_where(&predicate)?;
+            let evaluator = engine
+                .evaluation_handler()
+                .new_predicate_evaluator(columns.read_schema(), predicate);
+            Some(evaluator)
+        });
+
         let filter_evaluator = engine
             .evaluation_handler()
             .new_predicate_evaluator(stats_schema.clone(), FILTER_PRED.clone());
@@ -152,6 +131,7 @@ impl DataSkippingFilter {
             stats_schema,
             select_stats_evaluator,
             skipping_evaluator,
+            checkpoint_skipping_evaluator,
             filter_evaluator,
             json_handler: engine.json_handler(),
         })
@@ -171,15 +151,7 @@ impl DataSkippingFilter {
         // evaluate the predicate on the parsed stats, then convert to selection vector
         let skipping_predicate = self.skipping_evaluator.evaluate(&*parsed_stats)?;
         assert_eq!(skipping_predicate.len(), actions.len());
-        let selection_vector = self
-            .filter_evaluator
-            .evaluate(skipping_predicate.as_ref())?;
-        assert_eq!(selection_vector.len(), actions.len());
-
-        // visit the engine's selection vector to produce a Vec<bool>
-        let mut visitor = SelectionVectorVisitor::default();
-        visitor.visit_rows_of(selection_vector.as_ref())?;
-        Ok(visitor.selection_vector)
+        self.selection_vector(skipping_predicate.as_ref())
 
         // TODO(zach): add some debug info about data skipping that occurred
         // let before_count = actions.length();
@@ -188,17 +160,316 @@ impl DataSkippingFilter {
         //     filtered_actions.num_rows()
         // );
     }
+
+    /// Like [`DataSkippingFilter::apply`], but for a batch of actions read from a checkpoint. If the
+    /// checkpoint was read with typed stats (see [`CheckpointStatsColumns`]), those are used
+    /// instead of parsing the JSON stats of each add action.
+    pub(crate) fn apply_to_checkpoint_batch(
+        &self,
+        actions: &dyn EngineData,
+    ) -> DeltaResult<Vec<bool>> {
+        let Some(ref checkpoint_skipping_evaluator) = self.checkpoint_skipping_evaluator else {
+            return self.apply(actions);
+        };
+        let skipping_predicate = checkpoint_skipping_evaluator.evaluate(actions)?;
+        assert_eq!(skipping_predicate.len(), actions.len());
+        self.selection_vector(skipping_predicate.as_ref())
+    }
+
+    /// Converts the output of a skipping evaluator to a selection vector.
+    fn selection_vector(&self, skipping_predicate: &dyn EngineData) -> DeltaResult<Vec<bool>> {
+        let selection_vector = self.filter_evaluator.evaluate(skipping_predicate)?;
+        assert_eq!(selection_vector.len(), skipping_predicate.len());
+
+        // visit the engine's selection vector to produce a Vec<bool>
+        let mut visitor = SelectionVectorVisitor::default();
+        visitor.visit_rows_of(selection_vector.as_ref())?;
+        Ok(visitor.selection_vector)
+    }
 }
 
-struct DataSkippingPredicateCreator;
+/// Builds the schema of the stats of a file, for the columns of `referenced_schema`. Returns `None`
+/// if no stats schema can be built for the referenced columns.
+fn stats_schema(referenced_schema: &StructType) -> Option<StructType> {
+    // Convert all fields into nullable, as stats may not be available for all columns
+    // (and usually aren't for partition columns).
+    struct NullableStatsTransform;
+    impl<'a> SchemaTransform<'a> for NullableStatsTransform {
+        fn transform_struct_field(
+            &mut self,
+            field: &'a StructField,
+        ) -> Option<Cow<'a, StructField>> {
+            use Cow::*;
+            let field = match self.transform(&field.data_type)? {
+                Borrowed(_) if field.is_nullable() => Borrowed(field),
+                data_type => Owned(StructField {
+                    name: field.name.clone(),
+                    data_type: data_type.into_owned(),
+                    nullable: true,
+                    metadata: field.metadata.clone(),
+                }),
+            };
+            Some(field)
+        }
+    }
+
+    // Convert a min/max stats schema into a nullcount schema (all leaf fields are LONG)
+    struct NullCountStatsTransform;
+    impl<'a> SchemaTransform<'a> for NullCountStatsTransform {
+        fn transform_primitive(
+            &mut self,
+            _ptype: &'a PrimitiveType,
+        ) -> Option<Cow<'a, PrimitiveType>> {
+            Some(Cow::Owned(PrimitiveType::Long))
+        }
+    }
+
+    let stats_schema = NullableStatsTransform
+        .transform_struct(referenced_schema)?
+        .into_owned();
+
+    let nullcount_schema = NullCountStatsTransform
+        .transform_struct(&stats_schema)?
+        .into_owned();
+    Some(StructType::new([
+        StructField::nullable("numRecords", DataType::LONG),
+        StructField::nullable("nullCount", nullcount_schema),
+        StructField::nullable("minValues", stats_schema.clone()),
+        StructField::nullable("maxValues", stats_schema),
+    ]))
+}
 
-impl DataSkippingPredicateEvaluator for DataSkippingPredicateCreator {
+/// The typed stats columns of the add actions in a checkpoint, which can be used for data skipping
+/// instead of the JSON `add.stats`:
+///
+/// - `add.stats_parsed` holds the stats of the data columns, using the same schema as the stats
+///   (see [`stats_schema`]).
+/// - `add.partitionValues_parsed` holds the partition values, using the types of the partition
+///   columns. Each partition value is both the min and max value of its column in the file.
+///
+/// Both columns are optional in checkpoints, so they are only used if the checkpoint provides them
+/// with the types the predicate needs. Even if the predicate needs no typed stats, the number of
+/// records of each file is read from `add.stats_parsed.numRecords`, because checkpoints written
+/// with `delta.checkpoint.writeStatsAsJson = false` have no JSON stats to get it from.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct CheckpointStatsColumns {
+    /// The schema of `add.stats_parsed` to read, if the checkpoint provides it.
+    stats_parsed: Option<SchemaRef>,
+    /// Whether the checkpoint provides `add.stats_parsed.numRecords`.
+    num_records: bool,
+    /// The schema of `add.partitionValues_parsed` to read, if the checkpoint provides it.
+    partition_values_parsed: Option<SchemaRef>,
+    /// The physical names of the table's partition columns.
+    partition_columns: HashSet<String>,
+}
+
+impl CheckpointStatsColumns {
+    /// Determines which typed stats columns of a checkpoint with the given `checkpoint_schema` can
+    /// be used to evaluate a predicate that references the columns of `referenced_schema` (which is
+    /// empty if there is no predicate). Returns `None` if the checkpoint provides none of them.
+    pub(crate) fn try_new(
+        referenced_schema: &StructType,
+        partition_columns: HashSet<String>,
+        checkpoint_schema: &StructType,
+    ) -> Option<Self> {
+        let DataType::Struct(add) = checkpoint_schema.field(ADD_NAME)?.data_type() else {
+            return None;
+        };
+        let (partition_fields, data_fields): (Vec<_>, Vec<_>) = referenced_schema
+            .fields()
+            .partition(|field| partition_columns.contains(field.name()));
+
+        // Only use a column if all requested fields the checkpoint provides have the right type.
+        let provided = |name: &str, schema: StructType| {
+            let field = add.field(name)?;
+            is_readable_as(field.data_type(), &DataType::from(schema.clone()))
+                .then(|| Arc::new(schema))
+        };
+        let stats_parsed = match data_fields.is_empty() {
+            true => None,
+            false => stats_schema(&StructType::new(data_fields.into_iter().cloned()))
+                .and_then(|schema| provided(STATS_PARSED_NAME, schema)),
+        };
+        let partition_values_parsed = match partition_fields.is_empty() {
+            true => None,
+            false => {
+                let fields = partition_fields
+                    .into_iter()
+                    .map(|field| StructField::nullable(field.name(), field.data_type().clone()));
+                provided(PARTITION_VALUES_PARSED_NAME, StructType::new(fields))
+            }
+        };
+        let num_records = match add.field(STATS_PARSED_NAME).map(|field| field.data_type()) {
+            Some(DataType::Struct(stats)) => stats
+                .field("numRecords")
+                .is_some_and(|field| field.data_type() == &DataType::LONG),
+            _ => false,
+        };
+        if stats_parsed.is_none() && partition_values_parsed.is_none() && !num_records {
+            return None;
+        }
+        Some(Self {
+            stats_parsed,
+            num_records,
+            partition_values_parsed,
+            partition_columns,
+        })
+    }
+
+    /// The typed stats columns of actions that only provide `add.stats_parsed.numRecords`, such as
+    /// the add actions restored from existing scan metadata.
+    pub(crate) fn num_records_only() -> Self {
+        Self {
+            stats_parsed: None,
+            num_records: true,
+            partition_values_parsed: None,
+            partition_columns: HashSet::new(),
+        }
+    }
+
+    /// The fields to add to the `add` struct of a checkpoint read schema, in order to read the
+    /// typed stats columns.
+    fn add_fields(&self) -> impl Iterator<Item = StructField> + '_ {
+        let stats_parsed = match self.stats_parsed {
+            Some(ref schema) => Some(schema.as_ref().clone()),
+            None if self.num_records => Some(StructType::new([StructField::nullable(
+                "numRecords",
+                DataType::LONG,
+            )])),
+            None => None,
+        };
+        let stats_parsed =
+            stats_parsed.map(|schema| StructField::nullable(STATS_PARSED_NAME, schema));
+        let partition_values_parsed = self.partition_values_parsed.as_ref().map(|schema| {
+            StructField::nullable(PARTITION_VALUES_PARSED_NAME, schema.as_ref().clone())
+        });
+        stats_parsed.into_iter().chain(partition_values_parsed)
+    }
+
+    /// The schema of the typed stats columns, nested in the `add` struct.
+    fn read_schema(&self) -> SchemaRef {
+        let add = StructType::new(self.add_fields());
+        Arc::new(StructType::new([StructField::nullable(ADD_NAME, add)]))
+    }
+
+    /// Extends the `add` struct of the given checkpoint read schema with the typed stats columns.
+    pub(crate) fn extend_read_schema(&self, checkpoint_read_schema: &StructType) -> SchemaRef {
+        let fields = checkpoint_read_schema
+            .fields()
+            .map(|field| match field.data_type() {
+                DataType::Struct(add) if field.name() == ADD_NAME => {
+                    let add = StructType::new(add.fields().cloned().chain(self.add_fields()));
+                    StructField::new(field.name(), add, field.is_nullable())
+                }
+                _ => field.clone(),
+            });
+        Arc::new(StructType::new(fields))
+    }
+
+    /// Rewrites a (physical) predicate to a predicate over the typed stats columns of a checkpoint,
+    /// which the engine can use to skip checkpoint row groups whose add actions all fail data
+    /// skipping. Returns `None` if the predicate is not eligible for data skipping.
+    ///
+    /// Unlike data skipping, which keeps any file whose skipping predicate evaluates to null, row
+    /// group skipping drops rows whose predicate evaluates to null. The rewritten predicate thus
+    /// also keeps every row where any of the referenced stats is null, which includes all rows that
+    /// are not add actions (e.g. sidecars).
+    pub(crate) fn meta_predicate(&self, predicate: &Pred) -> Option<PredicateRef> {
+        let skipping_predicate = self.predicate_creator().eval_sql_where(predicate)?;
+        let references: BTreeSet<_> = skipping_predicate.references().into_iter().collect();
+        let null_stats: Vec<_> = references
+            .into_iter()
+            .map(|column| Pred::is_null(column.clone()))
+            .collect();
+        if null_stats.is_empty() {
+            return None;
+        }
+        let preds = std::iter::once(skipping_predicate).chain(null_stats);
+        Some(Arc::new(Pred::or_from(preds)))
+    }
+
+    fn predicate_creator(&self) -> DataSkippingPredicateCreator<'_> {
+        DataSkippingPredicateCreator {
+            checkpoint_columns: Some(self),
+        }
+    }
+
+    /// The column holding the given stat (`minValues`, `maxValues` or `nullCount`) for the given
+    /// column, if the checkpoint provides it.
+    fn stat_column(&self, stat: &str, col: &ColumnName) -> Option<Expr> {
+        let is_partition_column = col
+            .first()
+            .is_some_and(|name| self.partition_columns.contains(name));
+        let stat_column = match is_partition_column {
+            true if stat == "nullCount" => return None,
+            true => {
+                self.partition_values_parsed.as_ref()?;
+                ColumnName::new([ADD_NAME, PARTITION_VALUES_PARSED_NAME]).join(col)
+            }
+            false => {
+                self.stats_parsed.as_ref()?;
+                ColumnName::new([ADD_NAME, STATS_PARSED_NAME, stat]).join(col)
+            }
+        };
+        Some(Expr::column(stat_column))
+    }
+
+    /// The column holding the number of records of each file, if the checkpoint provides it.
+    fn rowcount_column(&self) -> Option<Expr> {
+        self.stats_parsed.as_ref()?;
+        Some(Expr::column([ADD_NAME, STATS_PARSED_NAME, "numRecords"]))
+    }
+
+    /// The column holding the number of records of each file, if the checkpoint provides it, to use
+    /// when the JSON stats of a file are missing. Unlike [`Self::rowcount_column`], this does not
+    /// depend on the predicate.
+    pub(crate) fn num_records_column(&self) -> Option<Expr> {
+        (self.stats_parsed.is_some() || self.num_records)
+            .then(|| Expr::column([ADD_NAME, STATS_PARSED_NAME, "numRecords"]))
+    }
+}
+
+/// Whether a column of the given type in a file can be read with the requested type. Nested fields
+/// missing from the file are allowed (they are read as null), and nullability is ignored.
+fn is_readable_as(file_type: &DataType, read_type: &DataType) -> bool {
+    match (file_type, read_type) {
+        (DataType::Struct(file_struct), DataType::Struct(read_struct)) => {
+            read_struct.fields().all(|read_field| {
+                file_struct
+                    .field(read_field.name())
+                    .is_none_or(|file_field| {
+                        is_readable_as(file_field.data_type(), read_field.data_type())
+                    })
+            })
+        }
+        _ => file_type == read_type,
+    }
+}
+
+/// Creates data skipping predicates. By default, the predicates reference the columns of a stats
+/// struct (see [`stats_schema`]); with checkpoint columns, they reference the typed stats columns
+/// of checkpoint add actions instead.
+#[derive(Default)]
+struct DataSkippingPredicateCreator<'a> {
+    checkpoint_columns: Option<&'a CheckpointStatsColumns>,
+}
+
+impl DataSkippingPredicateCreator<'_> {
+    fn stat_column(&self, stat: &str, col: &ColumnName) -> Option<Expr> {
+        match self.checkpoint_columns {
+            Some(checkpoint_columns) => checkpoint_columns.stat_column(stat, col),
+            None => Some(Expr::column(ColumnName::new([stat]).join(col))),
+        }
+    }
+}
+
+impl DataSkippingPredicateEvaluator for DataSkippingPredicateCreator<'_> {
     type Output = Pred;
     type ColumnStat = Expr;
 
     /// Retrieves the minimum value of a column, if it exists and has the requested type.
     fn get_min_stat(&self, col: &ColumnName, _data_type: &DataType) -> Option<Expr> {
-        Some(joined_column_expr!("minValues", col))
+        self.stat_column("minValues", col)
     }
 
     /// Retrieves the maximum value of a column, if it exists and has the requested type.
@@ -207,18 +478,21 @@ impl DataSkippingPredicateEvaluator for DataSkippingPredicateCreator {
     fn get_max_stat(&self, col: &ColumnName, data_type: &DataType) -> Option<Expr> {
         match data_type {
             &DataType::TIMESTAMP | &DataType::TIMESTAMP_NTZ => None,
-            _ => Some(joined_column_expr!("maxValues", col)),
+            _ => self.stat_column("maxValues", col),
         }
     }
 
     /// Retrieves the null count of a column, if it exists.
     fn get_nullcount_stat(&self, col: &ColumnName) -> Option<Expr> {
-        Some(joined_column_expr!("nullCount", col))
+        self.stat_column("nullCount", col)
     }
 
     /// Retrieves the row count of a column (parquet footers always include this stat).
     fn get_rowcount_stat(&self) -> Option<Expr> {
-        Some(column_expr!("numRecords"))
+        match self.checkpoint_columns {
+            Some(checkpoint_columns) => checkpoint_columns.rowcount_column(),
+            None => Some(column_expr!("numRecords")),
+        }
     }
 
     fn eval_partial_cmp(
@@ -1,7 +1,11 @@
 use super::*;
 
+use crate::actions::get_log_schema;
+use crate::arrow::array::StringArray;
+use crate::engine::sync::SyncEngine;
 use crate::expressions::column_name;
 use crate::kernel_predicates::{DefaultKernelPredicateEvaluator, UnimplementedColumnResolver};
+use crate::utils::test_utils::string_array_to_engine_data;
 use std::collections::HashMap;
 
 const TRUE: Option<bool> = Some(true);
@@ -344,7 +348,7 @@ fn test_sql_where() {
 // are truncated to milliseconds in add.stats.
 #[test]
 fn test_timestamp_skipping_disabled() {
-    let creator = DataSkippingPredicateCreator;
+    let creator = DataSkippingPredicateCreator::default();
     let col = &column_name!("timestamp_col");
 
     assert!(
@@ -406,3 +410,181 @@ fn test_timestamp_predicates_dont_data_skip() {
         );
     }
 }
+
+fn checkpoint_schema(
+    stats_parsed: Option<StructType>,
+    partition_values_parsed: Option<StructType>,
+) -> StructType {
+    let parsed_fields = [
+        stats_parsed.map(|schema| StructField::nullable(STATS_PARSED_NAME, schema)),
+        partition_values_parsed
+            .map(|schema| StructField::nullable(PARTITION_VALUES_PARSED_NAME, schema)),
+    ];
+    let add = StructType::new(
+        get_log_add_schema()
+            .field(ADD_NAME)
+            .map(|field| match field.data_type() {
+                DataType::Struct(add) => add.fields().cloned().collect(),
+                _ => vec![],
+            })
+            .unwrap()
+            .into_iter()
+            .chain(parsed_fields.into_iter().flatten()),
+    );
+    StructType::new([StructField::nullable(ADD_NAME, add)])
+}
+
+#[test]
+fn test_checkpoint_stats_columns() {
+    let referenced_schema = StructType::new([
+        StructField::nullable("x", DataType::INTEGER),
+        StructField::nullable("p", DataType::STRING),
+    ]);
+    let partition_columns = || HashSet::from(["p".to_string()]);
+    let x_stats = |data_type: DataType| {
+        let x = StructType::new([StructField::nullable("x", data_type)]);
+        StructType::new([
+            StructField::nullable("numRecords", DataType::LONG),
+            StructField::nullable("minValues", x.clone()),
+            StructField::nullable("maxValues", x),
+        ])
+    };
+    let p_values = StructType::new([StructField::nullable("p", DataType::STRING)]);
+
+    // the checkpoint has no typed stats columns
+    let schema = checkpoint_schema(None, None);
+    assert_eq!(
+        CheckpointStatsColumns::try_new(&referenced_schema, partition_columns(), &schema),
+        None
+    );
+
+    // the stats have the wrong type, so only their number of records is read
+    let schema = checkpoint_schema(Some(x_stats(DataType::STRING)), None);
+    let columns =
+        CheckpointStatsColumns::try_new(&referenced_schema, partition_columns(), &schema).unwrap();
+    assert!(columns.stats_parsed.is_none());
+    assert!(columns.partition_values_parsed.is_none());
+    let num_records_field = StructField::nullable(
+        STATS_PARSED_NAME,
+        StructType::new([StructField::nullable("numRecords", DataType::LONG)]),
+    );
+    assert_eq!(
+        columns.add_fields().collect::<Vec<_>>(),
+        [num_records_field]
+    );
+    assert!(columns.num_records_column().is_some());
+
+    // without a predicate, only the number of records is read
+    let columns =
+        CheckpointStatsColumns::try_new(&StructType::new([]), partition_columns(), &schema)
+            .unwrap();
+    assert_eq!(
+        columns,
+        CheckpointStatsColumns {
+            partition_columns: partition_columns(),
+            ..CheckpointStatsColumns::num_records_only()
+        }
+    );
+
+    // the stats are usable even though they lack the null counts, which are read as null
+    let schema = checkpoint_schema(Some(x_stats(DataType::INTEGER)), None);
+    let columns =
+        CheckpointStatsColumns::try_new(&referenced_schema, partition_columns(), &schema).unwrap();
+    assert!(columns.stats_parsed.is_some());
+    assert!(columns.partition_values_parsed.is_none());
+    let stats_field = StructField::nullable(
+        STATS_PARSED_NAME,
+        stats_schema(&StructType::new([StructField::nullable(
+            "x",
+            DataType::INTEGER,
+        )]))
+        .unwrap(),
+    );
+    assert_eq!(columns.add_fields().collect::<Vec<_>>(), [stats_field]);
+
+    let schema = checkpoint_schema(Some(x_stats(DataType::INTEGER)), Some(p_values.clone()));
+    let columns =
+        CheckpointStatsColumns::try_new(&referenced_schema, partition_columns(), &schema).unwrap();
+    assert!(columns.stats_parsed.is_some());
+    assert_eq!(columns.partition_values_parsed.as_deref(), Some(&p_values));
+
+    // the read schema extends the add struct with the typed stats columns
+    let read_schema = columns.extend_read_schema(&get_log_schema().project(&[ADD_NAME]).unwrap());
+    let Some(DataType::Struct(add)) = read_schema.field(ADD_NAME).map(|field| field.data_type())
+    else {
+        panic!("missing add struct in {read_schema:?}");
+    };
+    assert!(add.field("path").is_some());
+    assert!(add.field(STATS_PARSED_NAME).is_some());
+    assert!(add.field(PARTITION_VALUES_PARSED_NAME).is_some());
+
+    // the meta predicate keeps all rows with null stats
+    let pred = Pred::and(
+        Pred::gt(column_expr!("x"), Expr::literal(5)),
+        Pred::eq(column_expr!("p"), Expr::literal("a")),
+    );
+    let Some(Pred::Junction(meta_predicate)) = columns.meta_predicate(&pred).as_deref().cloned()
+    else {
+        panic!("expected a junction");
+    };
+    assert_eq!(meta_predicate.op, JunctionPredicateOp::Or);
+    let null_stats = [
+        Pred::is_null(column_expr!("add.partitionValues_parsed.p")),
+        Pred::is_null(column_expr!("add.stats_parsed.maxValues.x")),
+        Pred::is_null(column_expr!("add.stats_parsed.nullCount.x")),
+        Pred::is_null(column_expr!("add.stats_parsed.numRecords")),
+    ];
+    assert_eq!(meta_predicate.preds[1..], null_stats);
+}
+
+#[test]
+fn test_apply_to_checkpoint_batch() {
+    let engine = SyncEngine::new();
+    let referenced_schema = Arc::new(StructType::new([StructField::nullable(
+        "x",
+        DataType::INTEGER,
+    )]));
+    let stats_parsed = stats_schema(&referenced_schema).unwrap();
+    let schema = checkpoint_schema(Some(stats_parsed), None);
+    let columns =
+        CheckpointStatsColumns::try_new(&referenced_schema, HashSet::new(), &schema).unwrap();
+
+    // The JSON stats disagree with the typed stats, to show which of them are used
+    let add = |name: &str, stats_parsed: &str| {
+        format!(
+            r#"{{"add":{{"path":"{name}.parquet","partitionValues":{{}},"size":635,"modificationTime":1677811178336,"dataChange":true,"stats":"{{\"numRecords\":3,\"minValues\":{{\"x\":100}},\"maxValues\":{{\"x\":100}}}}"{stats_parsed}}}}}"#
+        )
+    };
+    let json_strings: StringArray = vec![
+        add(
+            "a",
+            r#","stats_parsed":{"numRecords":3,"nullCount":{"x":0},"minValues":{"x":1},"maxValues":{"x":3}}"#,
+        ),
+        add(
+            "b",
+            r#","stats_parsed":{"numRecords":3,"nullCount":{"x":0},"minValues":{"x":4},"maxValues":{"x":10}}"#,
+        ),
+        add("c", ""),
+        r#"{"remove":{"path":"d.parquet","deletionTimestamp":1677811178336,"dataChange":true}}"#
+            .to_string(),
+    ]
+    .into();
+    let actions = engine
+        .json_handler()
+        .parse_json(
+            string_array_to_engine_data(json_strings),
+            columns.extend_read_schema(&get_log_schema().project(&[ADD_NAME, "remove"]).unwrap()),
+        )
+        .unwrap();
+
+    let predicate = Arc::new(Pred::gt(column_expr!("x"), Expr::literal(5)));
+    let physical_predicate = Some((predicate, referenced_schema));
+    let filter = DataSkippingFilter::new(&engine, physical_predicate.clone(), Some(&columns));
+    let selection_vector = filter.unwrap().apply_to_checkpoint_batch(actions.as_ref());
+    assert_eq!(selection_vector.unwrap(), [false, true, true, true]);
+
+    // Without typed stats, the JSON stats are used
+    let filter = DataSkippingFilter::new(&engine, physical_predicate, None);
+    let selection_vector = filter.unwrap().apply_to_checkpoint_batch(actions.as_ref());
+    assert_eq!(selection_vector.unwrap(), [true, true, true, true]);
+}
@@ -0,0 +1,241 @@
+//! Limit pushdown for scans, see [`ScanBuilder::with_limit`].
+//!
+//! [`ScanBuilder::with_limit`]: super::ScanBuilder::with_limit
+
+use std::sync::LazyLock;
+
+use tracing::{debug, warn};
+
+use super::state::Stats;
+use super::{ScanMetadata, ScanResult};
+use crate::engine_data::{GetData, RowVisitor, TypedGetData as _};
+use crate::schema::{ColumnName, ColumnNamesAndTypes, DataType};
+use crate::utils::require;
+use crate::{DeltaResult, Error};
+
+/// Stops emitting scan files once the files already emitted are known to contain `limit` rows.
+/// Files without a `numRecords` statistic are always emitted, as they may contain no rows at all.
+/// If `limit` is `None`, all scan files are emitted.
+pub(crate) fn limit_scan_metadata(
+    scan_metadata_iter: impl Iterator<Item = DeltaResult<ScanMetadata>>,
+    limit: Option<usize>,
+) -> impl Iterator<Item = DeltaResult<ScanMetadata>> {
+    let mut remaining = limit.map(|limit| limit as u64);
+    scan_metadata_iter.map_while(move |scan_metadata| {
+        let Some(ref mut remaining) = remaining else {
+            return Some(scan_metadata);
+        };
+        if *remaining == 0 {
+            debug!("Scan limit reached, skipping the remaining scan files");
+            return None;
+        }
+        Some(scan_metadata.and_then(|mut scan_metadata| {
+            let scan_files = &mut scan_metadata.scan_files;
+            let mut visitor = RowCountVisitor::default();
+            visitor.visit_rows_of(scan_files.data.as_ref())?;
+            let selection_vector = &mut scan_files.selection_vector;
+            selection_vector.resize(visitor.row_counts.len(), true);
+            for (selected, row_count) in selection_vector.iter_mut().zip(visitor.row_counts) {
+                if !*selected {
+                    continue;
+                }
+                if *remaining == 0 {
+                    *selected = false;
+                } else if let Some(row_count) = row_count {
+                    *remaining = remaining.saturating_sub(row_count);
+                }
+            }
+            Ok(scan_metadata)
+        }))
+    })
+}
+
+/// Stops returning scan results once `limit` selected rows have been returned. The mask of the
+/// last result is trimmed so that exactly `limit` rows are selected in total. If `limit` is
+/// `None`, all scan results are returned unchanged.
+pub(crate) fn limit_scan_results(
+    scan_results: impl Iterator<Item = DeltaResult<ScanResult>>,
+    limit: Option<usize>,
+) -> impl Iterator<Item = DeltaResult<ScanResult>> {
+    let mut remaining = limit;
+    scan_results.map_while(move |scan_result| {
+        let Some(ref mut remaining) = remaining else {
+            return Some(scan_result);
+        };
+        if *remaining == 0 {
+            return None;
+        }
+        Some(scan_result.map(|mut scan_result| {
+            let Ok(ref data) = scan_result.raw_data else {
+                return scan_result;
+            };
+            let mut mask = scan_result.raw_mask.take().unwrap_or_default();
+            mask.resize(data.len(), true);
+            for selected in mask.iter_mut().filter(|selected| **selected) {
+                if *remaining == 0 {
+                    *selected = false;
+                } else {
+                    *remaining -= 1;
+                }
+            }
+            scan_result.raw_mask = Some(mask);
+            scan_result
+        }))
+    })
+}
+
+/// Computes the number of (undeleted) rows of each scan file, if known.
+#[derive(Default)]
+struct RowCountVisitor {
+    row_counts: Vec<Option<u64>>,
+}
+
+impl RowVisitor for RowCountVisitor {
+    fn selected_column_names_and_types(&self) -> (&'static [ColumnName], &'static [DataType]) {
+        static NAMES_AND_TYPES: LazyLock<ColumnNamesAndTypes> = LazyLock::new(|| {
+            (
+                vec![
+                    ColumnName::new(["stats"]),
+                    ColumnName::new(["deletionVector", "cardinality"]),
+                    ColumnName::new(["numRecords"]),
+                ],
+                vec![DataType::STRING, DataType::LONG, DataType::LONG],
+            )
+                .into()
+        });
+        NAMES_AND_TYPES.as_ref()
+    }
+
+    fn visit<'a>(&mut self, row_count: usize, getters: &[&'a dyn GetData<'a>]) -> DeltaResult<()> {
+        require!(
+            getters.len() == 3,
+            Error::InternalError(format!(
+                "Wrong number of RowCountVisitor getters: {}",
+                getters.len()
+            ))
+        );
+        for i in 0..row_count {
+            let stats: Option<String> = getters[0].get_opt(i, "scanFile.stats")?;
+            let num_records = stats.and_then(|json| match serde_json::from_str::<Stats>(&json) {
+                Ok(stats) => Some(stats.num_records),
+                Err(e) => {
+                    warn!("Invalid stats string in Add file {json}: {e}");
+                    None
+                }
+            });
+            // Files without JSON stats may still have a typed number of records
+            let num_records = match num_records {
+                Some(num_records) => Some(num_records),
+                None => getters[2]
+                    .get_opt(i, "scanFile.numRecords")?
+                    .and_then(|num_records: i64| num_records.try_into().ok()),
+            };
+            let deleted: Option<i64> =
+                getters[1].get_opt(i, "scanFile.deletionVector.cardinality")?;
+            let deleted = deleted.unwrap_or(0).try_into().unwrap_or(0);
+            self.row_counts
+                .push(num_records.map(|num_records| num_records.saturating_sub(deleted)));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::actions::get_log_schema;
+    use crate::arrow::array::{Int32Array, RecordBatch, StringArray};
+    use crate::arrow::datatypes::{DataType as ArrowDataType, Field, Schema as ArrowSchema};
+    use crate::engine::arrow_data::ArrowEngineData;
+    use crate::engine::sync::SyncEngine;
+    use crate::log_replay::ActionsBatch;
+    use crate::scan::{log_replay::scan_action_iter, ScanResult};
+    use crate::schema::{DataType, StructField, StructType};
+    use crate::utils::test_utils::string_array_to_engine_data;
+    use crate::Engine as _;
+
+    use super::*;
+
+    fn scan_result(values: Vec<i32>, raw_mask: Option<Vec<bool>>) -> DeltaResult<ScanResult> {
+        let batch = RecordBatch::try_new(
+            Arc::new(ArrowSchema::new(vec![Field::new(
+                "value",
+                ArrowDataType::Int32,
+                true,
+            )])),
+            vec![Arc::new(Int32Array::from(values))],
+        )
+        .unwrap();
+        Ok(ScanResult {
+            raw_data: Ok(Box::new(ArrowEngineData::new(batch))),
+            raw_mask,
+        })
+    }
+
+    #[test]
+    fn test_limit_scan_results() {
+        let results = vec![
+            scan_result(vec![1, 2, 3], Some(vec![true, false])),
+            scan_result(vec![4, 5, 6], None),
+            scan_result(vec![7, 8], None),
+        ];
+        let masks: Vec<_> = limit_scan_results(results.into_iter(), Some(4))
+            .map(|result| result.unwrap().raw_mask.unwrap())
+            .collect();
+        assert_eq!(masks, [vec![true, false, true], vec![true, true, false]]);
+    }
+
+    #[test]
+    fn test_limit_scan_metadata() {
+        // a: 3 records, 2 of which are deleted; b: no stats; c and d: 5 records each
+        let add = |name: &str, stats: &str, dv: &str| {
+            format!(
+                r#"{{"add":{{"path":"{name}.parquet","partitionValues":{{}},"size":635,"modificationTime":1677811178336,"dataChange":true{stats}{dv}}}}}"#
+            )
+        };
+        let json_strings: StringArray = vec![
+            add(
+                "a",
+                r#","stats":"{\"numRecords\":3}""#,
+                r#","deletionVector":{"storageType":"u","pathOrInlineDv":"vBn[lx{q8@P<9BNH/isA","offset":1,"sizeInBytes":36,"cardinality":2}"#,
+            ),
+            add("b", "", ""),
+            add("c", r#","stats":"{\"numRecords\":5}""#, ""),
+            add("d", r#","stats":"{\"numRecords\":5}""#, ""),
+        ]
+        .into();
+        let engine = SyncEngine::new();
+        let logical_schema = Arc::new(StructType::new([StructField::nullable(
+            "value",
+            DataType::INTEGER,
+        )]));
+        let selected = |limit| -> Vec<bool> {
+            let batch = engine
+                .json_handler()
+                .parse_json(
+                    string_array_to_engine_data(json_strings.clone()),
+                    get_log_schema().clone(),
+                )
+                .unwrap();
+            let scan_metadata = scan_action_iter(
+                &engine,
+                [Ok(ActionsBatch::new(batch, true))].into_iter(),
+                logical_schema.clone(),
+                None,
+                None,
+                None,
+            );
+            limit_scan_metadata(scan_metadata, limit)
+                .flat_map(|scan_metadata| scan_metadata.unwrap().scan_files.selection_vector)
+                .collect()
+        };
+        assert_eq!(selected(None), [true, true, true, true]);
+        assert_eq!(selected(Some(1)), [true, false, false, false]);
+        // files without stats don't count towards the limit
+        assert_eq!(selected(Some(2)), [true, true, true, false]);
+        assert_eq!(selected(Some(6)), [true, true, true, false]);
+        assert_eq!(selected(Some(7)), [true, true, true, true]);
+        assert_eq!(selected(Some(0)), Vec::<bool>::new());
+    }
+}
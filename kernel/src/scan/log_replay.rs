@@ -4,7 +4,7 @@ use std::sync::{Arc, LazyLock};
 
 use itertools::Itertools;
 
-use super::data_skipping::DataSkippingFilter;
+use super::data_skipping::{CheckpointStatsColumns, DataSkippingFilter};
 use super::{ScanMetadata, Transform};
 use crate::actions::deletion_vector::DeletionVectorDescriptor;
 use crate::actions::get_log_add_schema;
@@ -46,6 +46,9 @@ pub(crate) struct ScanLogReplayProcessor {
     partition_filter: Option<PredicateRef>,
     data_skipping_filter: Option<DataSkippingFilter>,
     add_transform: Arc<dyn ExpressionEvaluator>,
+    /// Transforms the add actions of checkpoint batches that were read with the typed number of
+    /// records (`add.stats_parsed.numRecords`), if any.
+    checkpoint_add_transform: Option<Arc<dyn ExpressionEvaluator>>,
     logical_schema: SchemaRef,
     transform: Option<Arc<Transform>>,
     /// A set of (data file path, dv_unique_id) pairs that have been seen thus
@@ -59,17 +62,28 @@ impl ScanLogReplayProcessor {
     fn new(
         engine: &dyn Engine,
         physical_predicate: Option<(PredicateRef, SchemaRef)>,
+        checkpoint_stats_columns: Option<&CheckpointStatsColumns>,
         logical_schema: SchemaRef,
         transform: Option<Arc<Transform>>,
     ) -> Self {
-        Self {
-            partition_filter: physical_predicate.as_ref().map(|(e, _)| e.clone()),
-            data_skipping_filter: DataSkippingFilter::new(engine, physical_predicate),
-            add_transform: engine.evaluation_handler().new_expression_evaluator(
+        let new_add_transform = |num_records| {
+            engine.evaluation_handler().new_expression_evaluator(
                 get_log_add_schema().clone(),
-                get_add_transform_expr(),
+                get_add_transform_expr(num_records),
                 SCAN_ROW_DATATYPE.clone(),
+            )
+        };
+        Self {
+            partition_filter: physical_predicate.as_ref().map(|(e, _)| e.clone()),
+            data_skipping_filter: DataSkippingFilter::new(
+                engine,
+                physical_predicate,
+                checkpoint_stats_columns,
             ),
+            add_transform: new_add_transform(Expression::null_literal(DataType::LONG)),
+            checkpoint_add_transform: checkpoint_stats_columns
+                .and_then(CheckpointStatsColumns::num_records_column)
+                .map(new_add_transform),
             seen_file_keys: Default::default(),
             logical_schema,
             transform,
@@ -96,8 +110,10 @@ impl AddRemoveDedupVisitor<'_> {
     const ADD_PATH_INDEX: usize = 0; // Position of "add.path" in getters
     const ADD_PARTITION_VALUES_INDEX: usize = 1; // Position of "add.partitionValues" in getters
     const ADD_DV_START_INDEX: usize = 2; // Start position of add deletion vector columns
-    const REMOVE_PATH_INDEX: usize = 5; // Position of "remove.path" in getters
-    const REMOVE_DV_START_INDEX: usize = 6; // Start position of remove deletion vector columns
+    const ADD_BASE_ROW_ID_INDEX: usize = 5; // Position of "add.baseRowId" in getters
+    const ADD_DEFAULT_ROW_COMMIT_VERSION_INDEX: usize = 6; // Position of "add.defaultRowCommitVersion" in getters
+    const REMOVE_PATH_INDEX: usize = 7; // Position of "remove.path" in getters
+    const REMOVE_DV_START_INDEX: usize = 8; // Start position of remove deletion vector columns
 
     fn new(
         seen: &mut HashSet<FileActionKey>,
@@ -152,14 +168,18 @@ impl AddRemoveDedupVisitor<'_> {
                 TransformExpr::Partition(field_idx) => {
                     Some(self.parse_partition_value(*field_idx, partition_values))
                 }
-                TransformExpr::Static(_) => None,
+                TransformExpr::Static(_)
+                | TransformExpr::RowId { .. }
+                | TransformExpr::RowCommitVersion { .. } => None,
             })
             .try_collect()
     }
 
     /// Compute an expression that will transform from physical to logical for a given Add file action
-    fn get_transform_expr(
+    fn get_transform_expr<'a>(
         &self,
+        i: usize,
+        getters: &[&'a dyn GetData<'a>],
         transform: &Transform,
         mut partition_values: HashMap<usize, (String, Scalar)>,
     ) -> DeltaResult<ExpressionRef> {
@@ -175,6 +195,38 @@ impl AddRemoveDedupVisitor<'_> {
                     Ok(partition_value.into())
                 }
                 TransformExpr::Static(field_expr) => Ok(field_expr.clone()),
+                TransformExpr::RowId {
+                    materialized_column,
+                    row_index_column,
+                } => {
+                    let base_row_id: i64 = getters[Self::ADD_BASE_ROW_ID_INDEX]
+                        .get_opt(i, "add.baseRowId")?
+                        .ok_or_else(|| {
+                            Error::generic("Row tracking file is missing a baseRowId")
+                        })?;
+                    // A row's id is materialized if the row was copied from another file, and is
+                    // otherwise the file's base row id plus the row's index in the file.
+                    let row_id = Expression::literal(base_row_id)
+                        + Expression::column([row_index_column.as_str()]);
+                    Ok(Expression::coalesce([
+                        Expression::column([materialized_column.as_str()]),
+                        row_id,
+                    ]))
+                }
+                TransformExpr::RowCommitVersion {
+                    materialized_column,
+                } => {
+                    let default_row_commit_version: i64 = getters
+                        [Self::ADD_DEFAULT_ROW_COMMIT_VERSION_INDEX]
+                        .get_opt(i, "add.defaultRowCommitVersion")?
+                        .ok_or_else(|| {
+                            Error::generic("Row tracking file is missing a defaultRowCommitVersion")
+                        })?;
+                    Ok(Expression::coalesce([
+                        Expression::column([materialized_column.as_str()]),
+                        Expression::literal(default_row_commit_version),
+                    ]))
+                }
             })
             .try_collect()?;
         Ok(Arc::new(Expression::Struct(transforms)))
@@ -203,7 +255,7 @@ impl AddRemoveDedupVisitor<'_> {
     fn is_valid_add<'a>(&mut self, i: usize, getters: &[&'a dyn GetData<'a>]) -> DeltaResult<bool> {
         // When processing file actions, we extract path and deletion vector information based on action type:
         // - For Add actions: path is at index 0, followed by DV fields at indexes 2-4
-        // - For Remove actions (in log batches only): path is at index 5, followed by DV fields at indexes 6-8
+        // - For Remove actions (in log batches only): path is at index 7, followed by DV fields at indexes 8-10
         // The file extraction logic selects the appropriate indexes based on whether we found a valid path.
         // Remove getters are not included when visiting a non-log batch (checkpoint batch), so do
         // not try to extract remove actions in that case.
@@ -242,7 +294,7 @@ impl AddRemoveDedupVisitor<'_> {
         let transform = self
             .transform
             .as_ref()
-            .map(|transform| self.get_transform_expr(transform, partition_values))
+            .map(|transform| self.get_transform_expr(i, getters, transform, partition_values))
             .transpose()?;
         if transform.is_some() {
             // fill in any needed `None`s for previous rows
@@ -259,6 +311,7 @@ impl RowVisitor for AddRemoveDedupVisitor<'_> {
         static NAMES_AND_TYPES: LazyLock<ColumnNamesAndTypes> = LazyLock::new(|| {
             const STRING: DataType = DataType::STRING;
             const INTEGER: DataType = DataType::INTEGER;
+            const LONG: DataType = DataType::LONG;
             let ss_map: DataType = MapType::new(STRING, STRING, true).into();
             let types_and_names = vec![
                 (STRING, column_name!("add.path")),
@@ -266,6 +319,8 @@ impl RowVisitor for AddRemoveDedupVisitor<'_> {
                 (STRING, column_name!("add.deletionVector.storageType")),
                 (STRING, column_name!("add.deletionVector.pathOrInlineDv")),
                 (INTEGER, column_name!("add.deletionVector.offset")),
+                (LONG, column_name!("add.baseRowId")),
+                (LONG, column_name!("add.defaultRowCommitVersion")),
                 (STRING, column_name!("remove.path")),
                 (STRING, column_name!("remove.deletionVector.storageType")),
                 (STRING, column_name!("remove.deletionVector.pathOrInlineDv")),
@@ -280,13 +335,13 @@ impl RowVisitor for AddRemoveDedupVisitor<'_> {
         } else {
             // All checkpoint actions are already reconciled and Remove actions in checkpoint files
             // only serve as tombstones for vacuum jobs. So we only need to examine the adds here.
-            (&names[..5], &types[..5])
+            (&names[..7], &types[..7])
         }
     }
 
     fn visit<'a>(&mut self, row_count: usize, getters: &[&'a dyn GetData<'a>]) -> DeltaResult<()> {
         let is_log_batch = self.deduplicator.is_log_batch();
-        let expected_getters = if is_log_batch { 9 } else { 5 };
+        let expected_getters = if is_log_batch { 11 } else { 7 };
         require!(
             getters.len() == expected_getters,
             Error::InternalError(format!(
@@ -310,8 +365,11 @@ impl RowVisitor for AddRemoveDedupVisitor<'_> {
 pub(crate) static SCAN_ROW_SCHEMA: LazyLock<Arc<StructType>> = LazyLock::new(|| {
     // Note that fields projected out of a nullable struct must be nullable
     let partition_values = MapType::new(DataType::STRING, DataType::STRING, true);
-    let file_constant_values =
-        StructType::new([StructField::nullable("partitionValues", partition_values)]);
+    let file_constant_values = StructType::new([
+        StructField::nullable("partitionValues", partition_values),
+        StructField::nullable("baseRowId", DataType::LONG),
+        StructField::nullable("defaultRowCommitVersion", DataType::LONG),
+    ]);
     Arc::new(StructType::new([
         StructField::nullable("path", DataType::STRING),
         StructField::nullable("size", DataType::LONG),
@@ -319,25 +377,38 @@ pub(crate) static SCAN_ROW_SCHEMA: LazyLock<Arc<StructType>> = LazyLock::new(||
         StructField::nullable("stats", DataType::STRING),
         StructField::nullable("deletionVector", DeletionVectorDescriptor::to_schema()),
         StructField::nullable("fileConstantValues", file_constant_values),
+        StructField::nullable("numRecords", DataType::LONG),
+        StructField::nullable(
+            "tags",
+            MapType::new(DataType::STRING, DataType::STRING, false),
+        ),
+        StructField::nullable("clusteringProvider", DataType::STRING),
     ]))
 });
 
 pub(crate) static SCAN_ROW_DATATYPE: LazyLock<DataType> =
     LazyLock::new(|| SCAN_ROW_SCHEMA.clone().into());
 
-fn get_add_transform_expr() -> Expression {
+/// The expression that transforms add actions into scan rows. `num_records` is the typed number of
+/// records of the file, if the actions provide it.
+fn get_add_transform_expr(num_records: Expression) -> Expression {
     Expression::Struct(vec![
         column_expr!("add.path"),
         column_expr!("add.size"),
         column_expr!("add.modificationTime"),
         column_expr!("add.stats"),
         column_expr!("add.deletionVector"),
-        Expression::Struct(vec![column_expr!("add.partitionValues")]),
+        Expression::Struct(vec![
+            column_expr!("add.partitionValues"),
+            column_expr!("add.baseRowId"),
+            column_expr!("add.defaultRowCommitVersion"),
+        ]),
+        num_records,
+        column_expr!("add.tags"),
+        column_expr!("add.clusteringProvider"),
     ])
 }
 
-// TODO: remove once `scan_metadata_from` is pub.
-#[allow(unused)]
 pub(crate) fn get_scan_metadata_transform_expr() -> Expression {
     Expression::Struct(vec![Expression::Struct(vec![
         column_expr!("path"),
@@ -346,6 +417,11 @@ pub(crate) fn get_scan_metadata_transform_expr() -> Expression {
         column_expr!("modificationTime"),
         column_expr!("stats"),
         column_expr!("deletionVector"),
+        column_expr!("fileConstantValues.baseRowId"),
+        column_expr!("fileConstantValues.defaultRowCommitVersion"),
+        Expression::Struct(vec![column_expr!("numRecords")]),
+        column_expr!("tags"),
+        column_expr!("clusteringProvider"),
     ])])
 }
 
@@ -360,7 +436,12 @@ impl LogReplayProcessor for ScanLogReplayProcessor {
         // Build an initial selection vector for the batch which has had the data skipping filter
         // applied. The selection vector is further updated by the deduplication visitor to remove
         // rows that are not valid adds.
-        let selection_vector = self.build_selection_vector(actions.as_ref())?;
+        let selection_vector = match self.data_skipping_filter {
+            Some(ref filter) if !is_log_batch => {
+                filter.apply_to_checkpoint_batch(actions.as_ref())?
+            }
+            _ => self.build_selection_vector(actions.as_ref())?,
+        };
         assert_eq!(selection_vector.len(), actions.len());
 
         let mut visitor = AddRemoveDedupVisitor::new(
@@ -374,7 +455,11 @@ impl LogReplayProcessor for ScanLogReplayProcessor {
         visitor.visit_rows_of(actions.as_ref())?;
 
         // TODO: Teach expression eval to respect the selection vector we just computed so carefully!
-        let result = self.add_transform.evaluate(actions.as_ref())?;
+        let add_transform = match self.checkpoint_add_transform {
+            Some(ref checkpoint_add_transform) if !is_log_batch => checkpoint_add_transform,
+            _ => &self.add_transform,
+        };
+        let result = add_transform.evaluate(actions.as_ref())?;
         Ok(ScanMetadata::new(
             result,
             visitor.selection_vector,
@@ -395,15 +480,25 @@ impl LogReplayProcessor for ScanLogReplayProcessor {
 ///
 /// Note: The iterator of [`ActionsBatch`]s ('action_iter' parameter) must be sorted by the order of
 /// the actions in the log from most recent to least recent.
+///
+/// If the checkpoint batches were read with typed stats columns, `checkpoint_stats_columns`
+/// describes them, so that data skipping can use them instead of parsing the JSON stats.
 pub(crate) fn scan_action_iter(
     engine: &dyn Engine,
     action_iter: impl Iterator<Item = DeltaResult<ActionsBatch>>,
     logical_schema: SchemaRef,
     transform: Option<Arc<Transform>>,
     physical_predicate: Option<(PredicateRef, SchemaRef)>,
+    checkpoint_stats_columns: Option<&CheckpointStatsColumns>,
 ) -> impl Iterator<Item = DeltaResult<ScanMetadata>> {
-    ScanLogReplayProcessor::new(engine, physical_predicate, logical_schema, transform)
-        .process_actions_iter(action_iter)
+    ScanLogReplayProcessor::new(
+        engine,
+        physical_predicate,
+        checkpoint_stats_columns,
+        logical_schema,
+        transform,
+    )
+    .process_actions_iter(action_iter)
 }
 
 #[cfg(test)]
@@ -486,6 +581,7 @@ mod tests {
             logical_schema,
             None,
             None,
+            None,
         );
         for res in iter {
             let scan_metadata = res.unwrap();
@@ -503,7 +599,7 @@ mod tests {
             StructField::new("date", DataType::DATE, true),
         ]));
         let partition_cols = ["date".to_string()];
-        let state_info = get_state_info(schema.as_ref(), &partition_cols).unwrap();
+        let state_info = get_state_info(schema.as_ref(), &partition_cols, &HashMap::new()).unwrap();
         let static_transform = Some(Arc::new(Scan::get_static_transform(&state_info.all_fields)));
         let batch = vec![add_batch_with_partition_col()];
         let iter = scan_action_iter(
@@ -514,6 +610,7 @@ mod tests {
             schema,
             static_transform,
             None,
+            None,
         );
 
         fn validate_transform(transform: Option<&ExpressionRef>, expected_date_offset: i32) {
@@ -4,35 +4,44 @@ use std::borrow::Cow;
 use std::collections::{HashMap, HashSet};
 use std::sync::{Arc, LazyLock};
 
-use delta_kernel_derive::internal_api;
 use itertools::Itertools;
-use tracing::debug;
+use tracing::{debug, info};
 use url::Url;
 
+use self::data_skipping::{CheckpointStatsColumns, STATS_PARSED_NAME};
 use self::log_replay::get_scan_metadata_transform_expr;
 use crate::actions::deletion_vector::{
     deletion_treemap_to_bools, split_vector, DeletionVectorDescriptor,
 };
+use crate::actions::visitors::SelectionVectorVisitor;
 use crate::actions::{get_log_schema, ADD_NAME, REMOVE_NAME, SIDECAR_NAME};
-use crate::engine_data::FilteredEngineData;
+use crate::engine_data::{FilteredEngineData, RowVisitor as _};
 use crate::expressions::transforms::ExpressionTransform;
-use crate::expressions::{ColumnName, Expression, ExpressionRef, Predicate, PredicateRef, Scalar};
+use crate::expressions::{
+    column_expr, ColumnName, Expression, ExpressionRef, Predicate, PredicateRef, Scalar,
+};
+use crate::kernel_predicates::implication::predicate_implies;
 use crate::kernel_predicates::{DefaultKernelPredicateEvaluator, EmptyColumnResolver};
 use crate::log_replay::{ActionsBatch, HasSelectionVector};
 use crate::log_segment::{ListedLogFiles, LogSegment};
 use crate::scan::state::{DvInfo, Stats};
 use crate::schema::ToSchema as _;
 use crate::schema::{
-    ArrayType, DataType, MapType, PrimitiveType, Schema, SchemaRef, SchemaTransform, StructField,
-    StructType,
+    ArrayType, DataType, MapType, MetadataColumnSpec, PrimitiveType, Schema, SchemaRef,
+    SchemaTransform, StructField, StructType,
 };
 use crate::snapshot::Snapshot;
 use crate::table_features::ColumnMappingMode;
-use crate::{DeltaResult, Engine, EngineData, Error, FileMeta, Version};
+use crate::transaction::row_tracking::{
+    MATERIALIZED_ROW_COMMIT_VERSION_COLUMN_PROPERTY, MATERIALIZED_ROW_ID_COLUMN_PROPERTY,
+};
+use crate::utils::require;
+use crate::{DeltaResult, Engine, EngineData, Error, FileMeta, PredicateEvaluator, Version};
 
 use self::log_replay::scan_action_iter;
 
 pub(crate) mod data_skipping;
+mod limit;
 pub mod log_replay;
 pub mod state;
 
@@ -46,6 +55,8 @@ pub struct ScanBuilder {
     snapshot: Arc<Snapshot>,
     schema: Option<SchemaRef>,
     predicate: Option<PredicateRef>,
+    exact_filtering: bool,
+    limit: Option<usize>,
 }
 
 impl std::fmt::Debug for ScanBuilder {
@@ -53,6 +64,8 @@ impl std::fmt::Debug for ScanBuilder {
         f.debug_struct("ScanBuilder")
             .field("schema", &self.schema)
             .field("predicate", &self.predicate)
+            .field("exact_filtering", &self.exact_filtering)
+            .field("limit", &self.limit)
             .finish()
     }
 }
@@ -64,6 +77,8 @@ impl ScanBuilder {
             snapshot: snapshot.into(),
             schema: None,
             predicate: None,
+            exact_filtering: false,
+            limit: None,
         }
     }
 
@@ -99,6 +114,30 @@ impl ScanBuilder {
         self
     }
 
+    /// Whether [`Scan::execute`] should filter out every row that doesn't satisfy the predicate
+    /// (see [`ScanBuilder::with_predicate`]). If `true`, the kernel evaluates the predicate over the
+    /// data of each [`ScanResult`] and removes the rows that don't satisfy it from its mask, so the
+    /// results are exact. Otherwise (the default), the engine must filter the rows itself.
+    ///
+    /// NOTE: This doesn't affect [`Scan::scan_metadata`], whose filtering is always best-effort.
+    pub fn with_exact_filtering(mut self, exact_filtering: bool) -> Self {
+        self.exact_filtering = exact_filtering;
+        self
+    }
+
+    /// Limit the scan to (at least) `limit` rows. [`Scan::scan_metadata`] stops returning files
+    /// once the `numRecords` statistics of the files already returned (minus the rows deleted by
+    /// their deletion vectors) prove that they contain `limit` rows, and [`Scan::execute`] stops
+    /// reading once `limit` selected rows have been returned.
+    ///
+    /// NOTE: Rows filtered out by a predicate can't be known in advance, so the limit is ignored
+    /// if the scan has a predicate, unless [`ScanBuilder::with_exact_filtering`] is enabled. In
+    /// that case only [`Scan::execute`] applies the limit.
+    pub fn with_limit(mut self, limit: impl Into<Option<usize>>) -> Self {
+        self.limit = limit.into();
+        self
+    }
+
     /// Build the [`Scan`].
     ///
     /// This does not scan the table at this point, but does do some work to ensure that the
@@ -108,15 +147,35 @@ impl ScanBuilder {
     pub fn build(self) -> DeltaResult<Scan> {
         // if no schema is provided, use snapshot's entire schema (e.g. SELECT *)
         let logical_schema = self.schema.unwrap_or_else(|| self.snapshot.schema());
+        let requests_row_tracking_columns = logical_schema.fields().any(|field| {
+            matches!(
+                field.get_metadata_column_spec(),
+                Some(MetadataColumnSpec::RowId | MetadataColumnSpec::RowCommitVersion)
+            )
+        });
+        require!(
+            !requests_row_tracking_columns
+                || self
+                    .snapshot
+                    .table_configuration()
+                    .is_row_tracking_enabled(),
+            Error::unsupported(
+                "Row ids and row commit versions can only be read from tables with row tracking enabled"
+            )
+        );
         let state_info = get_state_info(
             logical_schema.as_ref(),
             &self.snapshot.metadata().partition_columns,
+            self.snapshot.metadata().configuration(),
         )?;
 
         let physical_predicate = match self.predicate {
-            Some(predicate) => PhysicalPredicate::try_new(&predicate, &logical_schema)?,
+            Some(ref predicate) => PhysicalPredicate::try_new(predicate, &logical_schema)?,
             None => PhysicalPredicate::None,
         };
+        let limit = self
+            .limit
+            .filter(|_| self.predicate.is_none() || self.exact_filtering);
 
         Ok(Scan {
             snapshot: self.snapshot,
@@ -125,6 +184,10 @@ impl ScanBuilder {
             physical_predicate,
             all_fields: Arc::new(state_info.all_fields),
             have_partition_cols: state_info.have_partition_cols,
+            have_row_tracking_cols: state_info.have_row_tracking_cols,
+            predicate: self.predicate,
+            exact_filtering: self.exact_filtering,
+            limit,
         })
     }
 }
@@ -299,16 +362,31 @@ impl ScanResult {
     }
 }
 
+/// The name under which a scan reads the row index column it needs to compute row ids. See
+/// [`MetadataColumnSpec::RowIndex`].
+pub(crate) const ROW_INDEX_COLUMN_NAME: &str = "_metadata.row_index";
+
 /// Scan uses this to set up what kinds of top-level columns it is scanning. For `Selected` we just
 /// store the name of the column, as that's all that's needed during the actual query. For
 /// `Partition` we store an index into the logical schema for this query since later we need the
-/// data type as well to materialize the partition column.
+/// data type as well to materialize the partition column. For the row tracking metadata columns we
+/// store the names of the physical columns they are computed from.
 #[derive(PartialEq, Debug)]
 pub enum ColumnType {
     // A column, selected from the data, as is
     Selected(String),
     // A partition column that needs to be added back in
     Partition(usize),
+    // The row id metadata column, computed from the materialized row id column and the row index
+    RowId {
+        materialized_column: String,
+        row_index_column: String,
+    },
+    // The row commit version metadata column, computed from the materialized row commit version
+    // column
+    RowCommitVersion {
+        materialized_column: String,
+    },
 }
 
 /// A transform is ultimately a `Struct` expr. This holds the set of expressions that make that struct expr up
@@ -324,12 +402,71 @@ pub fn get_transform_for_row(
     transforms.get(row).cloned().flatten()
 }
 
+/// Filters the rows of a [`ScanResult`] by evaluating the scan's logical predicate over its data.
+/// See [`ScanBuilder::with_exact_filtering`].
+struct ExactFilter {
+    predicate_evaluator: Arc<dyn PredicateEvaluator>,
+    selection_evaluator: Arc<dyn PredicateEvaluator>,
+}
+
+impl ExactFilter {
+    fn new(engine: &dyn Engine, logical_schema: SchemaRef, predicate: Predicate) -> Self {
+        // Only rows for which the predicate is TRUE are selected, so turn NULL into FALSE
+        static SELECTION_PRED: LazyLock<Predicate> = LazyLock::new(|| {
+            Predicate::not(Predicate::distinct(
+                column_expr!("output"),
+                Expression::literal(true),
+            ))
+        });
+        static OUTPUT_SCHEMA: LazyLock<SchemaRef> = LazyLock::new(|| {
+            Arc::new(StructType::new([StructField::nullable(
+                "output",
+                DataType::BOOLEAN,
+            )]))
+        });
+        let evaluation_handler = engine.evaluation_handler();
+        Self {
+            predicate_evaluator: evaluation_handler
+                .new_predicate_evaluator(logical_schema, predicate),
+            selection_evaluator: evaluation_handler
+                .new_predicate_evaluator(OUTPUT_SCHEMA.clone(), SELECTION_PRED.clone()),
+        }
+    }
+
+    /// Removes the rows of `result` that don't satisfy the predicate from its mask.
+    fn apply(&self, mut result: ScanResult) -> DeltaResult<ScanResult> {
+        let Ok(ref data) = result.raw_data else {
+            return Ok(result);
+        };
+        let output = self.predicate_evaluator.evaluate(data.as_ref())?;
+        let selection = self.selection_evaluator.evaluate(output.as_ref())?;
+        let mut visitor = SelectionVectorVisitor::default();
+        visitor.visit_rows_of(selection.as_ref())?;
+        let mut mask = visitor.selection_vector;
+        if let Some(raw_mask) = result.raw_mask {
+            // rows past the end of the raw mask are valid
+            mask.iter_mut()
+                .zip(raw_mask)
+                .for_each(|(selected, valid)| *selected &= valid);
+        }
+        result.raw_mask = Some(mask);
+        Ok(result)
+    }
+}
+
 /// Transforms aren't computed all at once. So static ones can just go straight to `Expression`, but
 /// things like partition columns need to filled in. This enum holds an expression that's part of a
 /// `Transform`.
 pub(crate) enum TransformExpr {
     Static(Expression),
     Partition(usize),
+    RowId {
+        materialized_column: String,
+        row_index_column: String,
+    },
+    RowCommitVersion {
+        materialized_column: String,
+    },
 }
 
 /// [`ScanMetadata`] contains (1) a batch of [`FilteredEngineData`] specifying data files to be scanned
@@ -385,6 +522,13 @@ pub struct Scan {
     physical_predicate: PhysicalPredicate,
     all_fields: Arc<Vec<ColumnType>>,
     have_partition_cols: bool,
+    have_row_tracking_cols: bool,
+    // The logical predicate, as provided to the `ScanBuilder`
+    predicate: Option<PredicateRef>,
+    // Whether `Scan::execute` must filter out the rows that don't satisfy the predicate
+    exact_filtering: bool,
+    // The maximum number of rows `Scan::execute` must return, if any
+    limit: Option<usize>,
 }
 
 impl std::fmt::Debug for Scan {
@@ -392,6 +536,7 @@ impl std::fmt::Debug for Scan {
         f.debug_struct("Scan")
             .field("schema", &self.logical_schema)
             .field("predicate", &self.physical_predicate)
+            .field("limit", &self.limit)
             .finish()
     }
 }
@@ -449,6 +594,18 @@ impl Scan {
                     TransformExpr::Static(ColumnName::new([col_name]).into())
                 }
                 ColumnType::Partition(idx) => TransformExpr::Partition(*idx),
+                ColumnType::RowId {
+                    materialized_column,
+                    row_index_column,
+                } => TransformExpr::RowId {
+                    materialized_column: materialized_column.clone(),
+                    row_index_column: row_index_column.clone(),
+                },
+                ColumnType::RowCommitVersion {
+                    materialized_column,
+                } => TransformExpr::RowCommitVersion {
+                    materialized_column: materialized_column.clone(),
+                },
             })
             .collect()
     }
@@ -476,7 +633,10 @@ impl Scan {
         &self,
         engine: &dyn Engine,
     ) -> DeltaResult<impl Iterator<Item = DeltaResult<ScanMetadata>>> {
-        self.scan_metadata_inner(engine, self.replay_for_scan_metadata(engine)?)
+        let checkpoint_stats_columns = self.checkpoint_stats_columns(engine)?;
+        let action_batch_iter =
+            self.replay_for_scan_metadata(engine, checkpoint_stats_columns.as_ref())?;
+        self.scan_metadata_inner(engine, action_batch_iter, checkpoint_stats_columns.as_ref())
     }
 
     /// Get an updated iterator of [`ScanMetadata`]s based on an existing iterator of [`EngineData`]s.
@@ -506,28 +666,22 @@ impl Scan {
     /// WHERE a < 42 OR b = 10
     /// ```
     ///
-    /// <div class="warning">
-    ///
-    /// The current implementation does not yet validate the existing
-    /// predicate against the current predicate. Until this is implemented,
-    /// the caller must ensure that the existing predicate is compatible with
-    /// the current predicate.
-    ///
-    /// </div>
+    /// The kernel verifies this by proving that the new scan's predicate implies the previous
+    /// scan's predicate. The check is conservative, so compatible predicates may not be recognized
+    /// as such. Whenever the implication cannot be proven, the existing data is ignored and the log
+    /// is replayed in full, as if by [`Scan::scan_metadata`].
     ///
     /// # Parameters
     ///
     /// * `existing_version` - Table version the provided data was read from.
     /// * `existing_data` - Existing processed scan metadata with all selection vectors applied.
-    /// * `existing_predicate` - The predicate used by the previous scan.
-    #[allow(unused)]
-    #[internal_api]
-    pub(crate) fn scan_metadata_from(
+    /// * `existing_predicate` - The (logical) predicate used by the previous scan.
+    pub fn scan_metadata_from(
         &self,
         engine: &dyn Engine,
         existing_version: Version,
         existing_data: impl IntoIterator<Item = Box<dyn EngineData>> + 'static,
-        _existing_predicate: Option<PredicateRef>,
+        existing_predicate: Option<PredicateRef>,
     ) -> DeltaResult<Box<dyn Iterator<Item = DeltaResult<ScanMetadata>>>> {
         static RESTORED_ADD_SCHEMA: LazyLock<DataType> = LazyLock::new(|| {
             let partition_values = MapType::new(DataType::STRING, DataType::STRING, true);
@@ -540,12 +694,24 @@ impl Scan {
                     StructField::nullable("modificationTime", DataType::LONG),
                     StructField::nullable("stats", DataType::STRING),
                     StructField::nullable("deletionVector", DeletionVectorDescriptor::to_schema()),
+                    StructField::nullable("baseRowId", DataType::LONG),
+                    StructField::nullable("defaultRowCommitVersion", DataType::LONG),
+                    StructField::nullable(
+                        STATS_PARSED_NAME,
+                        DataType::struct_type(vec![StructField::nullable(
+                            "numRecords",
+                            DataType::LONG,
+                        )]),
+                    ),
+                    StructField::nullable(
+                        "tags",
+                        MapType::new(DataType::STRING, DataType::STRING, false),
+                    ),
+                    StructField::nullable("clusteringProvider", DataType::STRING),
                 ]),
             )])
         });
 
-        // TODO(#966): validate that the current predicate is compatible with the hint predicate.
-
         if existing_version > self.snapshot.version() {
             return Err(Error::Generic(format!(
                 "existing_version {} is greater than current version {}",
@@ -554,6 +720,24 @@ impl Scan {
             )));
         }
 
+        // The existing data only contains the files the previous predicate kept, so it can only be
+        // reused if the current predicate skips at least the same files.
+        let compatible = match (&self.predicate, &existing_predicate) {
+            (_, None) => true,
+            (Some(predicate), Some(existing_predicate)) => {
+                predicate_implies(predicate, existing_predicate)
+            }
+            (None, Some(_)) => false,
+        };
+        if !compatible {
+            info!(
+                "Scan predicate {:?} does not provably imply the existing predicate {:?}, \
+                 ignoring the existing data",
+                self.predicate, existing_predicate
+            );
+            return Ok(Box::new(self.scan_metadata(engine)?));
+        }
+
         // in order to be processed by our log replay, we must re-shape the existing scan metadata
         // back into shape as we read it from the log. Since it is already reconciled data,
         // we treat it as if it originated from a checkpoint.
@@ -566,38 +750,60 @@ impl Scan {
             Ok(ActionsBatch::new(transform.evaluate(data.as_ref())?, false))
         };
 
+        // The restored add actions keep the typed number of records of the existing scan rows
+        let restored_stats_columns = CheckpointStatsColumns::num_records_only();
+
         // If the snapshot version corresponds to the hint version, we process the existing data
         // to apply file skipping and provide the required transformations.
         if existing_version == self.snapshot.version() {
             let scan = existing_data.into_iter().map(apply_transform);
-            return Ok(Box::new(self.scan_metadata_inner(engine, scan)?));
+            return Ok(Box::new(self.scan_metadata_inner(
+                engine,
+                scan,
+                Some(&restored_stats_columns),
+            )?));
         }
 
         let log_segment = self.snapshot.log_segment();
 
-        // If the current log segment contains a checkpoint newer than the hint version
-        // we disregard the existing data hint, and perform a full scan. The current log segment
-        // only has deltas after the checkpoint, so we cannot update from prior versions.
-        // TODO: we may be able to apply heuristics or other logic to try and fetch missing deltas
-        // from the log.
-        if matches!(log_segment.checkpoint_version, Some(v) if v > existing_version) {
-            return Ok(Box::new(self.scan_metadata(engine)?));
-        }
-
         // create a new log segment containing only the commits added after the version hint.
-        let mut ascending_commit_files = log_segment.ascending_commit_files.clone();
-        ascending_commit_files.retain(|f| f.version > existing_version);
-        let listed_log_files = ListedLogFiles {
-            ascending_commit_files,
-            ascending_compaction_files: vec![],
-            checkpoint_parts: vec![],
-            latest_crc_file: None,
+        let has_newer_checkpoint =
+            matches!(log_segment.checkpoint_version, Some(v) if v > existing_version);
+        let new_log_segment = if has_newer_checkpoint {
+            // The current log segment only has deltas after its checkpoint, so we list the
+            // commits since the hint version from the log. If some of them are no longer retained,
+            // we disregard the existing data hint and perform a full scan.
+            let new_log_segment = LogSegment::for_table_changes(
+                engine.storage_handler().as_ref(),
+                log_segment.log_root.clone(),
+                existing_version + 1,
+                log_segment.end_version,
+            );
+            match new_log_segment {
+                Ok(new_log_segment) => new_log_segment,
+                Err(err) => {
+                    info!(
+                        "Unable to read the commits since version {existing_version}, ignoring \
+                         the existing data: {err}"
+                    );
+                    return Ok(Box::new(self.scan_metadata(engine)?));
+                }
+            }
+        } else {
+            let mut ascending_commit_files = log_segment.ascending_commit_files.clone();
+            ascending_commit_files.retain(|f| f.version > existing_version);
+            let listed_log_files = ListedLogFiles {
+                ascending_commit_files,
+                ascending_compaction_files: vec![],
+                checkpoint_parts: vec![],
+                latest_crc_file: None,
+            };
+            LogSegment::try_new(
+                listed_log_files,
+                log_segment.log_root.clone(),
+                Some(log_segment.end_version),
+            )?
         };
-        let new_log_segment = LogSegment::try_new(
-            listed_log_files,
-            log_segment.log_root.clone(),
-            Some(log_segment.end_version),
-        )?;
 
         let it = new_log_segment
             .read_actions(
@@ -608,18 +814,24 @@ impl Scan {
             )?
             .chain(existing_data.into_iter().map(apply_transform));
 
-        Ok(Box::new(self.scan_metadata_inner(engine, it)?))
+        Ok(Box::new(self.scan_metadata_inner(
+            engine,
+            it,
+            Some(&restored_stats_columns),
+        )?))
     }
 
     fn scan_metadata_inner(
         &self,
         engine: &dyn Engine,
         action_batch_iter: impl Iterator<Item = DeltaResult<ActionsBatch>>,
+        checkpoint_stats_columns: Option<&CheckpointStatsColumns>,
     ) -> DeltaResult<impl Iterator<Item = DeltaResult<ScanMetadata>>> {
         // Compute the static part of the transformation. This is `None` if no transformation is
-        // needed (currently just means no partition cols AND no column mapping but will be extended
-        // for other transforms as we support them)
+        // needed (currently just means no partition cols AND no row tracking cols AND no column
+        // mapping but will be extended for other transforms as we support them)
         let static_transform = (self.have_partition_cols
+            || self.have_row_tracking_cols
             || self.snapshot.column_mapping_mode() != ColumnMappingMode::None)
             .then(|| Arc::new(Scan::get_static_transform(&self.all_fields)));
         let physical_predicate = match self.physical_predicate.clone() {
@@ -633,7 +845,11 @@ impl Scan {
             self.logical_schema.clone(),
             static_transform,
             physical_predicate,
+            checkpoint_stats_columns,
         );
+        // Files are only known to contribute their `numRecords` rows if no predicate filters them
+        let limit = self.limit.filter(|_| self.predicate.is_none());
+        let it = limit::limit_scan_metadata(it, limit);
         Ok(Some(it).into_iter().flatten())
     }
 
@@ -641,17 +857,80 @@ impl Scan {
     fn replay_for_scan_metadata(
         &self,
         engine: &dyn Engine,
+        checkpoint_stats_columns: Option<&CheckpointStatsColumns>,
     ) -> DeltaResult<impl Iterator<Item = DeltaResult<ActionsBatch>> + Send> {
-        // NOTE: We don't pass any meta-predicate because we expect no meaningful row group skipping
-        // when ~every checkpoint file will contain the adds and removes we are looking for.
+        // The typed stats columns of the checkpoint let the engine skip checkpoint row groups whose
+        // adds are all skipped by data skipping. Without them, we don't pass any meta-predicate
+        // because the checkpoint row groups provide no stats about the files they reference.
+        let (checkpoint_read_schema, meta_predicate) =
+            match (checkpoint_stats_columns, &self.physical_predicate) {
+                (Some(columns), PhysicalPredicate::Some(predicate, _)) => (
+                    columns.extend_read_schema(&CHECKPOINT_READ_SCHEMA),
+                    columns.meta_predicate(predicate),
+                ),
+                (Some(columns), _) => (columns.extend_read_schema(&CHECKPOINT_READ_SCHEMA), None),
+                _ => (CHECKPOINT_READ_SCHEMA.clone(), None),
+            };
         self.snapshot.log_segment().read_actions(
             engine,
             COMMIT_READ_SCHEMA.clone(),
-            CHECKPOINT_READ_SCHEMA.clone(),
-            None,
+            checkpoint_read_schema,
+            meta_predicate,
         )
     }
 
+    /// Determines which typed stats columns (`add.stats_parsed` and `add.partitionValues_parsed`)
+    /// the snapshot's parquet checkpoint provides for the scan's predicate, using the checkpoint
+    /// schema from the `_last_checkpoint` file, or else by reading the footer of the checkpoint.
+    /// Without a predicate, only a limit needs the typed `numRecords` of the files. Returns `None`
+    /// if the scan has neither or the checkpoint provides none of the columns.
+    fn checkpoint_stats_columns(
+        &self,
+        engine: &dyn Engine,
+    ) -> DeltaResult<Option<CheckpointStatsColumns>> {
+        let referenced_schema = match self.physical_predicate {
+            PhysicalPredicate::Some(_, ref referenced_schema) => referenced_schema.clone(),
+            PhysicalPredicate::None if self.limit.is_some() => Arc::new(StructType::new([])),
+            _ => return Ok(None),
+        };
+        let checkpoint = match self.snapshot.log_segment().checkpoint_parts.first() {
+            Some(checkpoint) if checkpoint.extension == "parquet" => checkpoint,
+            _ => return Ok(None),
+        };
+        // The checkpoint schema from the `_last_checkpoint` file saves reading the footer
+        let checkpoint_schema = match self.snapshot.log_segment().checkpoint_schema {
+            Some(ref checkpoint_schema) => checkpoint_schema.clone(),
+            None => match engine
+                .parquet_handler()
+                .read_parquet_footer(&checkpoint.location)
+            {
+                Ok(footer) => footer.schema,
+                // Without the checkpoint schema, data skipping falls back to the JSON stats
+                Err(Error::Unsupported(_)) => return Ok(None),
+                Err(e) => return Err(e),
+            },
+        };
+        let schema = self.snapshot.schema();
+        let partition_columns = self
+            .snapshot
+            .metadata()
+            .partition_columns
+            .iter()
+            .filter_map(|column| schema.field(column))
+            .map(|field| field.physical_name().to_string())
+            .collect();
+        let columns = CheckpointStatsColumns::try_new(
+            &referenced_schema,
+            partition_columns,
+            &checkpoint_schema,
+        );
+        debug!(
+            "Typed stats columns of checkpoint {}: {columns:?}",
+            checkpoint.location.location
+        );
+        Ok(columns)
+    }
+
     /// Perform an "all in one" scan. This will use the provided `engine` to read and process all
     /// the data for the query. Each [`ScanResult`] in the resultant iterator encapsulates the raw
     /// data and an optional boolean vector built from the deletion vector if it was present. See
@@ -693,6 +972,15 @@ impl Scan {
         );
 
         let table_root = self.snapshot.table_root().clone();
+        let predicate = state::parquet_predicate(self.physical_predicate(), &self.physical_schema);
+        let exact_filter = self.predicate.as_ref().filter(|_| self.exact_filtering);
+        let exact_filter = exact_filter.map(|predicate| {
+            Arc::new(ExactFilter::new(
+                engine.as_ref(),
+                self.logical_schema.clone(),
+                predicate.as_ref().clone(),
+            ))
+        });
 
         let scan_metadata_iter = self.scan_metadata(engine.as_ref())?;
         let scan_files_iter = scan_metadata_iter
@@ -711,6 +999,26 @@ impl Scan {
                 let mut selection_vector = scan_file
                     .dv_info
                     .get_selection_vector(engine.as_ref(), &table_root)?;
+                // The parquet reader may skip rows that don't satisfy the predicate, so the
+                // deletion vector must be applied by row index rather than by position.
+                let read_row_indexes = selection_vector.is_some() && predicate.is_some();
+                let (read_schema, added_row_index_column) = if read_row_indexes {
+                    state::with_row_index_column(self.physical_schema())
+                } else {
+                    (self.physical_schema().clone(), false)
+                };
+                // The transform drops the added row index column, so we need one even if the
+                // physical data is otherwise already logical.
+                let transform = match scan_file.transform {
+                    None if added_row_index_column => {
+                        let columns = self
+                            .physical_schema()
+                            .fields()
+                            .map(|field| Expression::column([field.name()]));
+                        Some(Arc::new(Expression::struct_from(columns)))
+                    }
+                    transform => transform,
+                };
                 let meta = FileMeta {
                     last_modified: 0,
                     size: scan_file.size.try_into().map_err(|_| {
@@ -719,50 +1027,59 @@ impl Scan {
                     location: file_path,
                 };
 
-                // WARNING: We validated the physical predicate against a schema that includes
-                // partition columns, but the read schema we use here does _NOT_ include partition
-                // columns. So we cannot safely assume that all column references are valid. See
-                // https://github.com/delta-io/delta-kernel-rs/issues/434 for more details.
-                //
-                // TODO(#860): we disable predicate pushdown until we support row indexes.
                 let read_result_iter = engine.parquet_handler().read_parquet_files(
                     &[meta],
-                    self.physical_schema().clone(),
-                    None,
+                    read_schema.clone(),
+                    predicate.clone(),
                 )?;
 
                 // Arc clones
                 let engine = engine.clone();
+                let exact_filter = exact_filter.clone();
                 Ok(read_result_iter.map(move |read_result| -> DeltaResult<_> {
                     let read_result = read_result?;
+                    let row_index_mask = match selection_vector {
+                        Some(ref sv) if read_row_indexes => Some(
+                            state::row_index_selection_vector(read_result.as_ref(), sv, true)?,
+                        ),
+                        _ => None,
+                    };
                     // transform the physical data into the correct logical form
                     let logical = state::transform_to_logical(
                         engine.as_ref(),
                         read_result,
-                        self.physical_schema(),
+                        &read_schema,
                         self.logical_schema(),
-                        &scan_file.transform,
+                        &transform,
                     );
-                    let len = logical.as_ref().map_or(0, |res| res.len());
-                    // need to split the dv_mask. what's left in dv_mask covers this result, and rest
-                    // will cover the following results. we `take()` out of `selection_vector` to avoid
-                    // trying to return a captured variable. We're going to reassign `selection_vector`
-                    // to `rest` in a moment anyway
-                    let mut sv = selection_vector.take();
-                    let rest = split_vector(sv.as_mut(), len, None);
+                    let raw_mask = if read_row_indexes {
+                        row_index_mask
+                    } else {
+                        let len = logical.as_ref().map_or(0, |res| res.len());
+                        // need to split the dv_mask. what's left in dv_mask covers this result, and
+                        // rest will cover the following results. we `take()` out of
+                        // `selection_vector` to avoid trying to return a captured variable. We're
+                        // going to reassign `selection_vector` to `rest` in a moment anyway
+                        let mut sv = selection_vector.take();
+                        let rest = split_vector(sv.as_mut(), len, None);
+                        selection_vector = rest;
+                        sv
+                    };
                     let result = ScanResult {
                         raw_data: logical,
-                        raw_mask: sv,
+                        raw_mask,
                     };
-                    selection_vector = rest;
-                    Ok(result)
+                    match exact_filter {
+                        Some(ref exact_filter) => exact_filter.apply(result),
+                        None => Ok(result),
+                    }
                 }))
             })
             // Iterator<DeltaResult<Iterator<DeltaResult<ScanResult>>>> to Iterator<DeltaResult<DeltaResult<ScanResult>>>
             .flatten_ok()
             // Iterator<DeltaResult<DeltaResult<ScanResult>>> to Iterator<DeltaResult<ScanResult>>
             .map(|x| x?);
-        Ok(result)
+        Ok(limit::limit_scan_results(result, self.limit))
     }
 }
 
@@ -783,10 +1100,20 @@ impl Scan {
 ///      cardinality: long,
 ///    },
 ///    fileConstantValues: {
-///      partitionValues: map<string, string>
-///    }
+///      partitionValues: map<string, string>,
+///      baseRowId: long,
+///      defaultRowCommitVersion: long,
+///    },
+///    numRecords: long,
+///    tags: map<string, string>,
+///    clusteringProvider: string,
 /// }
 /// ```
+///
+/// `numRecords` is the number of records of the file if the log provides it as a typed statistic
+/// (`add.stats_parsed.numRecords` in checkpoints), which is the only source of it for files whose
+/// `stats` are null. `tags` and `clusteringProvider` are copied from the file's `add` action, so
+/// that writers replacing the action (e.g. to add a deletion vector) can preserve them.
 pub fn scan_row_schema() -> SchemaRef {
     log_replay::SCAN_ROW_SCHEMA.clone()
 }
@@ -812,20 +1139,81 @@ struct StateInfo {
     read_fields: Vec<StructField>,
     /// True if this query references any partition columns.
     have_partition_cols: bool,
+    /// True if this query references the row id or row commit version metadata columns.
+    have_row_tracking_cols: bool,
 }
 
-/// Get the state needed to process a scan, see [`StateInfo`] for details.
-fn get_state_info(logical_schema: &Schema, partition_columns: &[String]) -> DeltaResult<StateInfo> {
+/// Get the state needed to process a scan, see [`StateInfo`] for details. The table's metadata
+/// `configuration` names the materialized row tracking columns, if the scan needs them.
+fn get_state_info(
+    logical_schema: &Schema,
+    partition_columns: &[String],
+    configuration: &HashMap<String, String>,
+) -> DeltaResult<StateInfo> {
     let mut have_partition_cols = false;
-    let mut read_fields = Vec::with_capacity(logical_schema.fields.len());
+    let mut have_row_tracking_cols = false;
+    let mut read_fields: Vec<StructField> = Vec::with_capacity(logical_schema.fields.len());
+    // Adds a field to the read schema, unless another logical field already needs it
+    let mut add_read_field = |field: StructField| {
+        if !read_fields
+            .iter()
+            .any(|read_field| read_field.name == field.name)
+        {
+            read_fields.push(field);
+        }
+    };
+    let get_materialized_column = |property: &str| {
+        configuration.get(property).cloned().ok_or_else(|| {
+            Error::generic(format!(
+                "Table with row tracking is missing the {property} table property"
+            ))
+        })
+    };
     // Loop over all selected fields and note if they are columns that will be read from the
-    // parquet file ([`ColumnType::Selected`]) or if they are partition columns and will need to
-    // be filled in by evaluating an expression ([`ColumnType::Partition`])
+    // parquet file ([`ColumnType::Selected`]), if they are partition columns and will need to
+    // be filled in by evaluating an expression ([`ColumnType::Partition`]), or if they are row
+    // tracking metadata columns computed from other columns ([`ColumnType::RowId`] and
+    // [`ColumnType::RowCommitVersion`])
     let all_fields = logical_schema
         .fields()
         .enumerate()
         .map(|(index, logical_field)| -> DeltaResult<_> {
-            if partition_columns.contains(logical_field.name()) {
+            if let Some(spec) = logical_field.get_metadata_column_spec() {
+                let materialized_column = |property| -> DeltaResult<_> {
+                    let name = get_materialized_column(property)?;
+                    Ok((StructField::nullable(&name, DataType::LONG), name))
+                };
+                match spec {
+                    MetadataColumnSpec::RowIndex => {
+                        // The parquet reader fills in row indexes, so just select the column
+                        add_read_field(logical_field.clone());
+                        Ok(ColumnType::Selected(logical_field.name().clone()))
+                    }
+                    MetadataColumnSpec::RowId => {
+                        have_row_tracking_cols = true;
+                        let (field, materialized_column) =
+                            materialized_column(MATERIALIZED_ROW_ID_COLUMN_PROPERTY)?;
+                        add_read_field(field);
+                        add_read_field(StructField::create_metadata_column(
+                            ROW_INDEX_COLUMN_NAME,
+                            MetadataColumnSpec::RowIndex,
+                        ));
+                        Ok(ColumnType::RowId {
+                            materialized_column,
+                            row_index_column: ROW_INDEX_COLUMN_NAME.to_string(),
+                        })
+                    }
+                    MetadataColumnSpec::RowCommitVersion => {
+                        have_row_tracking_cols = true;
+                        let (field, materialized_column) =
+                            materialized_column(MATERIALIZED_ROW_COMMIT_VERSION_COLUMN_PROPERTY)?;
+                        add_read_field(field);
+                        Ok(ColumnType::RowCommitVersion {
+                            materialized_column,
+                        })
+                    }
+                }
+            } else if partition_columns.contains(logical_field.name()) {
                 // Store the index into the schema for this field. When we turn it into an
                 // expression in the inner loop, we will index into the schema and get the name and
                 // data type, which we need to properly materialize the column.
@@ -837,7 +1225,7 @@ fn get_state_info(logical_schema: &Schema, partition_columns: &[String]) -> Delt
                 let physical_field = logical_field.make_physical();
                 debug!("\n\n{logical_field:#?}\nAfter mapping: {physical_field:#?}\n\n");
                 let physical_name = physical_field.name.clone();
-                read_fields.push(physical_field);
+                add_read_field(physical_field);
                 Ok(ColumnType::Selected(physical_name))
             }
         })
@@ -846,6 +1234,7 @@ fn get_state_info(logical_schema: &Schema, partition_columns: &[String]) -> Delt
         all_fields,
         read_fields,
         have_partition_cols,
+        have_row_tracking_cols,
     })
 }
 
@@ -981,6 +1370,7 @@ pub(crate) mod test_utils {
             logical_schema,
             transform,
             None,
+            None,
         );
         let mut batch_count = 0;
         for res in iter {
@@ -1006,7 +1396,10 @@ mod tests {
     use crate::engine::sync::SyncEngine;
     use crate::expressions::{column_expr, column_pred, Expression as Expr, Predicate as Pred};
     use crate::schema::{ColumnMetadataKey, PrimitiveType};
-    use crate::Snapshot;
+    use crate::{
+        EvaluationHandler, FileDataReadResultIterator, JsonHandler, ParquetHandler, Snapshot,
+        StorageHandler,
+    };
 
     use super::*;
 
@@ -1311,6 +1704,137 @@ mod tests {
         assert_eq!(new_files[1].num_rows(), 3);
     }
 
+    // with_checkpoint_no_last_checkpoint overwrites its single file in each of the commits 1 to 3,
+    // and has a checkpoint at v2. The table is copied so that log files can be deleted after the
+    // snapshot is created.
+    fn scan_metadata_from_across_checkpoint(deleted_log_file: &str) -> Vec<String> {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let log_dir = tmp_dir.path().join("_delta_log");
+        std::fs::create_dir(&log_dir).unwrap();
+        for entry in
+            std::fs::read_dir("./tests/data/with_checkpoint_no_last_checkpoint/_delta_log").unwrap()
+        {
+            let entry = entry.unwrap();
+            std::fs::copy(entry.path(), log_dir.join(entry.file_name())).unwrap();
+        }
+        let url = url::Url::from_directory_path(tmp_dir.path()).unwrap();
+        let engine = Arc::new(SyncEngine::new());
+
+        let scan = Snapshot::try_new(url.clone(), engine.as_ref(), Some(1))
+            .unwrap()
+            .into_scan_builder()
+            .build()
+            .unwrap();
+        let files: Vec<_> = scan
+            .scan_metadata(engine.as_ref())
+            .unwrap()
+            .map_ok(|ScanMetadata { scan_files, .. }| {
+                let batch: RecordBatch = ArrowEngineData::try_from_engine_data(scan_files.data)
+                    .unwrap()
+                    .into();
+                let selection_vector = BooleanArray::from(scan_files.selection_vector);
+                let batch = filter_record_batch(&batch, &selection_vector).unwrap();
+                Box::new(ArrowEngineData::from(batch)) as Box<dyn EngineData>
+            })
+            .try_collect()
+            .unwrap();
+
+        let snapshot = Snapshot::try_new(url, engine.as_ref(), None).unwrap();
+        assert_eq!(snapshot.log_segment().checkpoint_version, Some(2));
+        std::fs::remove_file(log_dir.join(deleted_log_file)).unwrap();
+        let scan = snapshot.into_scan_builder().build().unwrap();
+        let mut paths = vec![];
+        for scan_metadata in scan
+            .scan_metadata_from(engine.as_ref(), 1, files, None)
+            .unwrap()
+        {
+            paths = scan_metadata
+                .unwrap()
+                .visit_scan_files(paths, |paths: &mut Vec<String>, path, _, _, _, _, _| {
+                    paths.push(path.to_string())
+                })
+                .unwrap();
+        }
+        paths
+    }
+
+    #[test_log::test]
+    fn test_scan_metadata_from_across_checkpoint() {
+        // the commits since the existing version are still retained, so the checkpoint isn't read
+        let paths = scan_metadata_from_across_checkpoint("00000000000000000002.checkpoint.parquet");
+        assert_eq!(
+            paths,
+            ["part-00000-70b1dcdf-0236-4f63-a072-124cdbafd8a0-c000.snappy.parquet"]
+        );
+    }
+
+    #[test_log::test]
+    fn test_scan_metadata_from_across_checkpoint_with_missing_commits() {
+        // commit 2 is missing, so the log is replayed in full from the checkpoint
+        let paths = scan_metadata_from_across_checkpoint("00000000000000000002.json");
+        assert_eq!(
+            paths,
+            ["part-00000-70b1dcdf-0236-4f63-a072-124cdbafd8a0-c000.snappy.parquet"]
+        );
+    }
+
+    // basic_partitioned has 6 files at v1 with `number` values 1 through 6
+    #[test_log::test]
+    fn test_scan_metadata_from_validates_predicate() {
+        let path = std::fs::canonicalize(PathBuf::from("./tests/data/basic_partitioned/")).unwrap();
+        let url = url::Url::from_directory_path(path).unwrap();
+        let engine = Arc::new(SyncEngine::new());
+        let snapshot = Arc::new(Snapshot::try_new(url, engine.as_ref(), Some(1)).unwrap());
+        let number_gt =
+            |value: i64| Arc::new(Pred::gt(column_expr!("number"), Expr::literal(value)));
+        let scan_with = |predicate: Option<PredicateRef>| {
+            snapshot
+                .clone()
+                .scan_builder()
+                .with_predicate(predicate)
+                .build()
+                .unwrap()
+        };
+        let existing_files = |predicate| -> Vec<Box<dyn EngineData>> {
+            scan_with(predicate)
+                .scan_metadata(engine.as_ref())
+                .unwrap()
+                .map_ok(|ScanMetadata { scan_files, .. }| {
+                    let batch: RecordBatch = ArrowEngineData::try_from_engine_data(scan_files.data)
+                        .unwrap()
+                        .into();
+                    let selection_vector = BooleanArray::from(scan_files.selection_vector);
+                    let batch = filter_record_batch(&batch, &selection_vector).unwrap();
+                    Box::new(ArrowEngineData::from(batch)) as Box<dyn EngineData>
+                })
+                .try_collect()
+                .unwrap()
+        };
+        let num_files_from = |predicate, existing_predicate: Option<PredicateRef>| -> usize {
+            let existing = existing_files(existing_predicate.clone());
+            scan_with(predicate)
+                .scan_metadata_from(engine.as_ref(), 1, existing, existing_predicate)
+                .unwrap()
+                .map_ok(|scan_metadata| {
+                    let selection_vector = scan_metadata.scan_files.selection_vector;
+                    selection_vector
+                        .into_iter()
+                        .filter(|selected| *selected)
+                        .count()
+                })
+                .sum::<DeltaResult<_>>()
+                .unwrap()
+        };
+
+        // the existing data is reused, so skipping is as precise as the stricter predicate
+        assert_eq!(num_files_from(Some(number_gt(5)), Some(number_gt(4))), 1);
+        assert_eq!(num_files_from(Some(number_gt(4)), Some(number_gt(4))), 2);
+        assert_eq!(num_files_from(Some(number_gt(4)), None), 2);
+        // the existing data lacks files the current predicate needs, so the log is replayed in full
+        assert_eq!(num_files_from(Some(number_gt(3)), Some(number_gt(4))), 3);
+        assert_eq!(num_files_from(None, Some(number_gt(4))), 6);
+    }
+
     #[test]
     fn test_get_partition_value() {
         let cases = [
@@ -1364,7 +1888,7 @@ mod tests {
         let snapshot = Snapshot::try_new(url, &engine, None).unwrap();
         let scan = snapshot.into_scan_builder().build().unwrap();
         let data: Vec<_> = scan
-            .replay_for_scan_metadata(&engine)
+            .replay_for_scan_metadata(&engine, None)
             .unwrap()
             .try_collect()
             .unwrap();
@@ -1403,11 +1927,7 @@ mod tests {
         let data: Vec<_> = scan.execute(engine.clone()).unwrap().try_collect().unwrap();
         assert_eq!(data.len(), 1);
 
-        // TODO(#860): we disable predicate pushdown until we support row indexes. Update this test
-        // accordingly after support is reintroduced.
-        //
-        // Effective predicate pushdown, so no data files should be returned. BUT since we disabled
-        // predicate pushdown, the one data file is still returned.
+        // Effective predicate pushdown, so no data files should be returned.
         let predicate = Arc::new(int_col.lt(value));
         let scan = snapshot
             .scan_builder()
@@ -1415,7 +1935,7 @@ mod tests {
             .build()
             .unwrap();
         let data: Vec<_> = scan.execute(engine).unwrap().try_collect().unwrap();
-        assert_eq!(data.len(), 1);
+        assert_eq!(data.len(), 0);
     }
 
     #[test]
@@ -1477,4 +1997,106 @@ mod tests {
         );
         Ok(())
     }
+
+    #[test]
+    fn test_checkpoint_stats_parsed_skipping() -> DeltaResult<()> {
+        // The checkpoint has `add.stats_parsed` and `add.partitionValues_parsed`, and the table has
+        // no commits after it.
+        let path = std::fs::canonicalize(PathBuf::from("./tests/data/app-txn-checkpoint/"))?;
+        let url = url::Url::from_directory_path(path).unwrap();
+        let engine = SyncEngine::new();
+        let snapshot = Arc::new(Snapshot::try_new(url, &engine, None)?);
+        let scan_with = |predicate: Pred| {
+            snapshot
+                .clone()
+                .scan_builder()
+                .with_predicate(Arc::new(predicate))
+                .build()
+        };
+
+        let scan = scan_with(column_expr!("value").gt(Expr::literal(5)))?;
+        let columns = scan.checkpoint_stats_columns(&engine)?.unwrap();
+        assert!(columns
+            .meta_predicate(&scan.physical_predicate().unwrap())
+            .is_some());
+        let mut files = get_files_for_scan(scan, &engine)?;
+        files.sort();
+        assert_eq!(
+            files,
+            [
+                "modified=2021-02-01/part-00001-3b6e7f26-8140-4067-8504-47540a363758-c000.snappy.parquet",
+                "modified=2021-02-01/part-00001-7e32952f-35ad-423c-8926-dbd3d264b1ee-c000.snappy.parquet",
+            ]
+        );
+
+        let scan = scan_with(column_expr!("modified").eq(Expr::literal("2021-02-02")))?;
+        assert!(scan.checkpoint_stats_columns(&engine)?.is_some());
+        let mut files = get_files_for_scan(scan, &engine)?;
+        files.sort();
+        assert_eq!(
+            files,
+            [
+                "modified=2021-02-02/part-00001-5113d412-8632-458b-a49a-f34db1069081-c000.snappy.parquet",
+                "modified=2021-02-02/part-00001-f968feb7-7f54-40c5-8aea-6a3b7a406d9c-c000.snappy.parquet",
+            ]
+        );
+
+        let scan = scan_with(column_expr!("value").gt(Expr::literal(100)))?;
+        assert!(get_files_for_scan(scan, &engine)?.is_empty());
+
+        // Without a predicate, the checkpoint footer isn't read
+        let scan = snapshot.clone().scan_builder().build()?;
+        assert!(scan.checkpoint_stats_columns(&engine)?.is_none());
+        assert_eq!(get_files_for_scan(scan, &engine)?.len(), 4);
+        Ok(())
+    }
+    #[test]
+    fn test_checkpoint_stats_without_footer_support() -> DeltaResult<()> {
+        /// A parquet handler that relies on the default `read_parquet_footer`
+        struct NoFooterParquetHandler(Arc<dyn ParquetHandler>);
+
+        impl ParquetHandler for NoFooterParquetHandler {
+            fn read_parquet_files(
+                &self,
+                files: &[FileMeta],
+                physical_schema: SchemaRef,
+                predicate: Option<PredicateRef>,
+            ) -> DeltaResult<FileDataReadResultIterator> {
+                self.0.read_parquet_files(files, physical_schema, predicate)
+            }
+        }
+
+        struct NoFooterEngine(SyncEngine);
+
+        impl Engine for NoFooterEngine {
+            fn evaluation_handler(&self) -> Arc<dyn EvaluationHandler> {
+                self.0.evaluation_handler()
+            }
+
+            fn storage_handler(&self) -> Arc<dyn StorageHandler> {
+                self.0.storage_handler()
+            }
+
+            fn json_handler(&self) -> Arc<dyn JsonHandler> {
+                self.0.json_handler()
+            }
+
+            fn parquet_handler(&self) -> Arc<dyn ParquetHandler> {
+                Arc::new(NoFooterParquetHandler(self.0.parquet_handler()))
+            }
+        }
+
+        // Without the checkpoint footer, data skipping falls back to the JSON stats
+        let path = std::fs::canonicalize(PathBuf::from("./tests/data/app-txn-checkpoint/"))?;
+        let url = url::Url::from_directory_path(path).unwrap();
+        let engine = NoFooterEngine(SyncEngine::new());
+        let snapshot = Arc::new(Snapshot::try_new(url, &engine, None)?);
+        let scan = snapshot
+            .scan_builder()
+            .with_predicate(Arc::new(column_expr!("value").gt(Expr::literal(5))))
+            .build()?;
+        assert!(scan.checkpoint_stats_columns(&engine)?.is_none());
+        assert_eq!(get_files_for_scan(scan, &engine)?.len(), 2);
+        Ok(())
+    }
 }
@@ -1,11 +1,12 @@
 //! This module encapsulates the state of a scan
 
 use std::collections::HashMap;
-use std::sync::LazyLock;
+use std::sync::{Arc, LazyLock};
 
 use crate::actions::deletion_vector::deletion_treemap_to_bools;
-use crate::scan::get_transform_for_row;
-use crate::schema::Schema;
+use crate::expressions::PredicateRef;
+use crate::scan::{get_transform_for_row, ROW_INDEX_COLUMN_NAME};
+use crate::schema::{MetadataColumnSpec, Schema, StructField, StructType};
 use crate::utils::require;
 use crate::ExpressionRef;
 use crate::{
@@ -113,6 +114,93 @@ pub fn transform_to_logical(
     }
 }
 
+/// The predicate to push down into the parquet reader when reading `read_schema` from data files, if
+/// any. A physical predicate can reference partition columns (and change data feed columns), which
+/// are not part of the read schema (see https://github.com/delta-io/delta-kernel-rs/issues/434), so
+/// it is only pushed down if every column it references is read.
+pub(crate) fn parquet_predicate(
+    predicate: Option<PredicateRef>,
+    read_schema: &StructType,
+) -> Option<PredicateRef> {
+    predicate.filter(|predicate| {
+        predicate.references().iter().all(|column| {
+            column
+                .path()
+                .first()
+                .is_some_and(|name| read_schema.field(name).is_some())
+        })
+    })
+}
+
+/// Adds the row index metadata column to `read_schema`, unless it is already present. Returns the
+/// new schema and whether the column was added. A parquet reader that is given a predicate may skip
+/// rows, so a deletion vector (which selects rows by their index in the file) must be applied by
+/// row index rather than by position; see [`row_index_selection_vector`].
+pub(crate) fn with_row_index_column(read_schema: &SchemaRef) -> (SchemaRef, bool) {
+    if read_schema.field(ROW_INDEX_COLUMN_NAME).is_some() {
+        return (read_schema.clone(), false);
+    }
+    let row_index_field =
+        StructField::create_metadata_column(ROW_INDEX_COLUMN_NAME, MetadataColumnSpec::RowIndex);
+    let fields = read_schema.fields().cloned().chain([row_index_field]);
+    (Arc::new(StructType::new(fields)), true)
+}
+
+/// Computes the selection vector of a batch that was read along with its row indexes (see
+/// [`with_row_index_column`]) from a file whose deletion vector produced `selection_vector`. Rows
+/// past the end of `selection_vector` are selected if `extend` is true.
+pub(crate) fn row_index_selection_vector(
+    data: &dyn EngineData,
+    selection_vector: &[bool],
+    extend: bool,
+) -> DeltaResult<Vec<bool>> {
+    let mut visitor = RowIndexVisitor::default();
+    visitor.visit_rows_of(data)?;
+    visitor
+        .row_indexes
+        .into_iter()
+        .map(|row_index| {
+            let row_index = usize::try_from(row_index)
+                .map_err(|_| Error::generic(format!("Invalid row index {row_index}")))?;
+            Ok(selection_vector.get(row_index).copied().unwrap_or(extend))
+        })
+        .collect()
+}
+
+/// Collects the row indexes of a batch read with [`with_row_index_column`].
+#[derive(Default)]
+struct RowIndexVisitor {
+    row_indexes: Vec<i64>,
+}
+
+impl RowVisitor for RowIndexVisitor {
+    fn selected_column_names_and_types(&self) -> (&'static [ColumnName], &'static [DataType]) {
+        static NAMES_AND_TYPES: LazyLock<ColumnNamesAndTypes> = LazyLock::new(|| {
+            (
+                vec![ColumnName::new([ROW_INDEX_COLUMN_NAME])],
+                vec![DataType::LONG],
+            )
+                .into()
+        });
+        NAMES_AND_TYPES.as_ref()
+    }
+
+    fn visit<'a>(&mut self, row_count: usize, getters: &[&'a dyn GetData<'a>]) -> DeltaResult<()> {
+        require!(
+            getters.len() == 1,
+            Error::InternalError(format!(
+                "Wrong number of RowIndexVisitor getters: {}",
+                getters.len()
+            ))
+        );
+        for i in 0..row_count {
+            self.row_indexes
+                .push(getters[0].get(i, ROW_INDEX_COLUMN_NAME)?);
+        }
+        Ok(())
+    }
+}
+
 pub type ScanCallback<T> = fn(
     context: &mut T,
     path: &str,
@@ -179,7 +267,7 @@ impl<T> RowVisitor for ScanFileVisitor<'_, T> {
     }
     fn visit<'a>(&mut self, row_count: usize, getters: &[&'a dyn GetData<'a>]) -> DeltaResult<()> {
         require!(
-            getters.len() == 10,
+            getters.len() == 15,
             Error::InternalError(format!(
                 "Wrong number of ScanFileVisitor getters: {}",
                 getters.len()
@@ -202,6 +290,12 @@ impl<T> RowVisitor for ScanFileVisitor<'_, T> {
                             None
                         }
                     });
+                let num_records: Option<i64> =
+                    getters[12].get_opt(row_index, "scanFile.numRecords")?;
+                let stats = stats.or_else(|| {
+                    let num_records = num_records?.try_into().ok()?;
+                    Some(Stats { num_records })
+                });
 
                 let dv_index = SCAN_ROW_SCHEMA
                     .index_of("deletionVector")
@@ -229,11 +323,21 @@ impl<T> RowVisitor for ScanFileVisitor<'_, T> {
 mod tests {
     use std::collections::HashMap;
 
+    use std::sync::Arc;
+
     use crate::actions::get_log_schema;
+    use crate::arrow::array::{Int32Array, Int64Array, RecordBatch};
+    use crate::arrow::datatypes::{DataType as ArrowDataType, Field, Schema as ArrowSchema};
+    use crate::engine::arrow_data::ArrowEngineData;
+    use crate::expressions::{column_expr, Expression as Expr, Predicate as Pred};
     use crate::scan::test_utils::{add_batch_simple, run_with_validate_callback};
+    use crate::scan::ROW_INDEX_COLUMN_NAME;
+    use crate::schema::{DataType, StructField, StructType};
     use crate::ExpressionRef;
 
-    use super::{DvInfo, Stats};
+    use super::{
+        parquet_predicate, row_index_selection_vector, with_row_index_column, DvInfo, Stats,
+    };
 
     #[derive(Clone)]
     struct TestContext {
@@ -277,4 +381,58 @@ mod tests {
             validate_visit,
         );
     }
+
+    #[test]
+    fn test_parquet_predicate() {
+        let read_schema = StructType::new([StructField::nullable("a", DataType::INTEGER)]);
+        let predicate = Arc::new(Pred::gt(column_expr!("a"), Expr::literal(1)));
+        assert_eq!(
+            parquet_predicate(Some(predicate.clone()), &read_schema),
+            Some(predicate)
+        );
+        // partition columns are not part of the read schema
+        let predicate = Arc::new(Pred::and(
+            Pred::gt(column_expr!("a"), Expr::literal(1)),
+            Pred::eq(column_expr!("part"), Expr::literal(1)),
+        ));
+        assert_eq!(parquet_predicate(Some(predicate), &read_schema), None);
+        assert_eq!(parquet_predicate(None, &read_schema), None);
+    }
+
+    #[test]
+    fn test_row_index_selection_vector() {
+        let read_schema = Arc::new(StructType::new([StructField::nullable(
+            "a",
+            DataType::INTEGER,
+        )]));
+        let (read_schema, added) = with_row_index_column(&read_schema);
+        assert!(added);
+        assert!(read_schema.field(ROW_INDEX_COLUMN_NAME).is_some());
+        let (same_schema, added) = with_row_index_column(&read_schema);
+        assert!(!added);
+        assert_eq!(same_schema, read_schema);
+
+        // rows 1 and 2 were skipped by the reader
+        let batch = RecordBatch::try_new(
+            Arc::new(ArrowSchema::new(vec![
+                Field::new("a", ArrowDataType::Int32, true),
+                Field::new(ROW_INDEX_COLUMN_NAME, ArrowDataType::Int64, false),
+            ])),
+            vec![
+                Arc::new(Int32Array::from(vec![0, 3, 4, 5])),
+                Arc::new(Int64Array::from(vec![0, 3, 4, 5])),
+            ],
+        )
+        .unwrap();
+        let data = ArrowEngineData::new(batch);
+        let selection_vector = [true, true, true, false, true];
+        assert_eq!(
+            row_index_selection_vector(&data, &selection_vector, true).unwrap(),
+            [true, false, true, true]
+        );
+        assert_eq!(
+            row_index_selection_vector(&data, &selection_vector, false).unwrap(),
+            [true, false, true, false]
+        );
+    }
 }
@@ -3,6 +3,7 @@
 use std::borrow::Cow;
 use std::collections::HashMap;
 use std::fmt::{Display, Formatter};
+use std::str::FromStr;
 use std::sync::Arc;
 
 use indexmap::IndexMap;
@@ -91,6 +92,8 @@ pub enum ColumnMetadataKey {
     IdentityHighWaterMark,
     IdentityAllowExplicitInsert,
     Invariants,
+    MetadataSpec,
+    ParquetFieldId,
 }
 
 impl AsRef<str> for ColumnMetadataKey {
@@ -104,6 +107,57 @@ impl AsRef<str> for ColumnMetadataKey {
             Self::IdentityStart => "delta.identity.start",
             Self::IdentityStep => "delta.identity.step",
             Self::Invariants => "delta.invariants",
+            Self::MetadataSpec => "delta.metadataSpec",
+            Self::ParquetFieldId => "PARQUET:field_id",
+        }
+    }
+}
+
+/// The metadata columns that a scan can produce in addition to the columns of the table. A field
+/// of a read schema is a metadata column if its metadata has a [`ColumnMetadataKey::MetadataSpec`]
+/// entry; see [`StructField::create_metadata_column`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MetadataColumnSpec {
+    /// The index of the row in the data file it was read from.
+    RowIndex,
+    /// The row id of the row, in a table with row tracking enabled.
+    RowId,
+    /// The version of the commit that last added or updated the row, in a table with row tracking
+    /// enabled.
+    RowCommitVersion,
+}
+
+impl MetadataColumnSpec {
+    /// The value of the [`ColumnMetadataKey::MetadataSpec`] entry that identifies this metadata
+    /// column.
+    pub fn text_value(&self) -> &'static str {
+        match self {
+            Self::RowIndex => "row_index",
+            Self::RowId => "row_id",
+            Self::RowCommitVersion => "row_commit_version",
+        }
+    }
+
+    /// The data type of this metadata column.
+    pub fn data_type(&self) -> DataType {
+        DataType::LONG
+    }
+
+    /// Whether this metadata column is nullable. Only the row index is never null.
+    pub fn nullable(&self) -> bool {
+        !matches!(self, Self::RowIndex)
+    }
+}
+
+impl FromStr for MetadataColumnSpec {
+    type Err = Error;
+
+    fn from_str(s: &str) -> DeltaResult<Self> {
+        match s {
+            "row_index" => Ok(Self::RowIndex),
+            "row_id" => Ok(Self::RowId),
+            "row_commit_version" => Ok(Self::RowCommitVersion),
+            _ => Err(Error::generic(format!("Unknown metadata column spec: {s}"))),
         }
     }
 }
@@ -153,10 +207,30 @@ impl StructField {
         self
     }
 
+    /// Creates a field for the given metadata column, which a scan materializes instead of reading
+    /// it from the table's data.
+    pub fn create_metadata_column(name: impl Into<String>, spec: MetadataColumnSpec) -> Self {
+        Self::new(name, spec.data_type(), spec.nullable())
+            .with_metadata([(ColumnMetadataKey::MetadataSpec.as_ref(), spec.text_value())])
+    }
+
     pub fn get_config_value(&self, key: &ColumnMetadataKey) -> Option<&MetadataValue> {
         self.metadata.get(key.as_ref())
     }
 
+    /// The metadata column this field represents, or `None` if it isn't a metadata column.
+    pub fn get_metadata_column_spec(&self) -> Option<MetadataColumnSpec> {
+        match self.get_config_value(&ColumnMetadataKey::MetadataSpec) {
+            Some(MetadataValue::String(spec)) => spec.parse().ok(),
+            _ => None,
+        }
+    }
+
+    /// Whether this field is a metadata column. See [`MetadataColumnSpec`].
+    pub fn is_metadata_column(&self) -> bool {
+        self.get_metadata_column_spec().is_some()
+    }
+
     /// Get the physical name for this field as it should be read from parquet.
     ///
     /// NOTE: Caller affirms that the schema was already validated by
@@ -1092,6 +1166,31 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_metadata_column() {
+        for spec in [
+            MetadataColumnSpec::RowIndex,
+            MetadataColumnSpec::RowId,
+            MetadataColumnSpec::RowCommitVersion,
+        ] {
+            let field = StructField::create_metadata_column("meta", spec);
+            assert_eq!(field.data_type(), &DataType::LONG);
+            assert_eq!(field.is_nullable(), spec != MetadataColumnSpec::RowIndex);
+            assert_eq!(field.get_metadata_column_spec(), Some(spec));
+            assert!(field.is_metadata_column());
+            assert_eq!(
+                spec.text_value().parse::<MetadataColumnSpec>().unwrap(),
+                spec
+            );
+        }
+
+        assert!(!StructField::nullable("a", DataType::LONG).is_metadata_column());
+        let unknown = StructField::nullable("a", DataType::LONG)
+            .with_metadata([(ColumnMetadataKey::MetadataSpec.as_ref(), "unknown")]);
+        assert!(!unknown.is_metadata_column());
+        assert!("unknown".parse::<MetadataColumnSpec>().is_err());
+    }
+
     #[test]
     fn test_field_metadata() {
         let data = r#"
@@ -1,26 +1,35 @@
 //! In-memory representation of snapshots of tables (snapshot is a table at given point in time, it
 //! has schema etc.)
 
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use crate::actions::domain_metadata::domain_metadata_configuration;
 use crate::actions::set_transaction::SetTransactionScanner;
-use crate::actions::{Metadata, Protocol, INTERNAL_DOMAIN_PREFIX};
+use crate::actions::{Metadata, Protocol, Sidecar, INTERNAL_DOMAIN_PREFIX};
 use crate::checkpoint::CheckpointWriter;
+use crate::history_manager::{read_in_commit_timestamp, HistoryEntry, LogHistoryManager};
+use crate::log_cleanup::{cleanup_expired_logs, log_retention_timestamp};
+use crate::log_compaction::LogCompactionWriter;
 use crate::log_segment::{self, ListedLogFiles, LogSegment};
+use crate::path::ParsedLogPath;
 use crate::scan::ScanBuilder;
 use crate::schema::{Schema, SchemaRef};
 use crate::table_configuration::TableConfiguration;
 use crate::table_features::ColumnMappingMode;
 use crate::table_properties::TableProperties;
 use crate::transaction::Transaction;
-use crate::utils::{calculate_transaction_expiration_timestamp, try_parse_uri};
-use crate::{DeltaResult, Engine, Error, StorageHandler, Version};
+use crate::utils::{calculate_transaction_expiration_timestamp, require, try_parse_uri};
+use crate::vacuum::VacuumPlan;
+use crate::{DeltaResult, Engine, Error, FileMeta, StorageHandler, Version};
 use delta_kernel_derive::internal_api;
 
+use itertools::Itertools as _;
+use md5::{Digest as _, Md5};
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
 use tracing::{debug, warn};
-use url::Url;
+use url::{form_urlencoded, Url};
 
 /// Name of the _last_checkpoint file that provides metadata about the last checkpoint
 /// created for the table. This file is used as a hint for the engine to quickly locate
@@ -105,6 +114,32 @@ impl Snapshot {
         Self::try_new_from_log_segment(table_root, log_segment, engine)
     }
 
+    /// Create a new [`Snapshot`] instance for the latest version of the table whose commit
+    /// timestamp is less than or equal to the given timestamp.
+    ///
+    /// The commit timestamp is the in-commit timestamp for commits made while in-commit timestamps
+    /// are enabled, and the commit file's modification time otherwise. Only commits that have not
+    /// been cleaned up from the log are considered.
+    ///
+    /// # Parameters
+    ///
+    /// - `table_root`: url pointing at the table root (where `_delta_log` folder is located)
+    /// - `engine`: Implementation of [`Engine`] apis.
+    /// - `timestamp`: the timestamp in milliseconds since the unix epoch.
+    pub fn try_new_at_timestamp(
+        table_root: Url,
+        engine: &dyn Engine,
+        timestamp: i64,
+    ) -> DeltaResult<Self> {
+        let latest_snapshot = Self::try_new(table_root.clone(), engine, None)?;
+        let history_manager = LogHistoryManager::try_new(engine, &latest_snapshot)?;
+        let version = history_manager.latest_version_as_of(engine, timestamp)?;
+        if version == latest_snapshot.version() {
+            return Ok(latest_snapshot);
+        }
+        Self::try_new(table_root, engine, Some(version))
+    }
+
     /// Create a new [`Snapshot`] instance from an existing [`Snapshot`]. This is useful when you
     /// already have a [`Snapshot`] lying around and want to do the minimal work to 'update' the
     /// snapshot to a later version.
@@ -250,7 +285,7 @@ impl Snapshot {
 
         // we can pass in just the old checkpoint parts since by the time we reach this line, we
         // know there are no checkpoints in the new log segment.
-        let combined_log_segment = LogSegment::try_new(
+        let mut combined_log_segment = LogSegment::try_new(
             ListedLogFiles {
                 ascending_commit_files,
                 ascending_compaction_files,
@@ -260,6 +295,7 @@ impl Snapshot {
             log_root,
             new_version,
         )?;
+        combined_log_segment.checkpoint_schema = old_log_segment.checkpoint_schema.clone();
         Ok(Arc::new(Snapshot::new(
             combined_log_segment,
             table_configuration,
@@ -289,6 +325,52 @@ impl Snapshot {
         CheckpointWriter::try_new(self)
     }
 
+    /// Creates a [`LogCompactionWriter`] for compacting the commits `start_version..=end_version`
+    /// of this snapshot's table into a single log compaction file.
+    ///
+    /// See the [`crate::log_compaction`] module documentation for more details.
+    pub fn compact_log(
+        self: Arc<Self>,
+        start_version: Version,
+        end_version: Version,
+    ) -> DeltaResult<LogCompactionWriter> {
+        LogCompactionWriter::try_new(self, start_version, end_version)
+    }
+
+    /// Deletes the log files of this snapshot's table that are older than the log retention period
+    /// (the `delta.logRetentionDuration` table property, 30 days by default) and no longer needed
+    /// to reconstruct any version of the table within that period. These are the commit,
+    /// checkpoint, CRC and log compaction files of all versions before the newest complete
+    /// checkpoint written before the start of the retention period, along with the sidecar files
+    /// that only the deleted V2 checkpoints reference. Only the files of versions up to this
+    /// snapshot's version are considered. Returns the number of deleted files.
+    ///
+    /// Like [`Snapshot::vacuum_plan`], this fails if the table's protocol is not supported for
+    /// vacuum, e.g. if the table has the `vacuumProtocolCheck` reader feature and kernel does not
+    /// support reading the table.
+    ///
+    /// Expired log files are also cleaned up automatically when finalizing a checkpoint, unless
+    /// the `delta.enableExpiredLogCleanup` table property is `false`.
+    pub fn cleanup_expired_logs(&self, engine: &dyn Engine) -> DeltaResult<usize> {
+        self.table_configuration().ensure_vacuum_supported()?;
+        let cutoff_timestamp = log_retention_timestamp(self.table_properties())?;
+        cleanup_expired_logs(
+            engine,
+            &self.log_segment.log_root,
+            self.version(),
+            cutoff_timestamp,
+        )
+    }
+
+    /// Creates a [`VacuumPlan`] to find the files under the table root that this snapshot of the
+    /// table no longer needs, and that are eligible for deletion.
+    ///
+    /// Note that this method performs log replay (fetches and processes metadata from storage).
+    /// See the [`crate::vacuum`] module documentation for more details.
+    pub fn vacuum_plan(&self, engine: &dyn Engine) -> DeltaResult<VacuumPlan> {
+        VacuumPlan::try_new(self, engine)
+    }
+
     /// Log segment this snapshot uses
     #[internal_api]
     pub(crate) fn log_segment(&self) -> &LogSegment {
@@ -375,6 +457,49 @@ impl Snapshot {
         Ok(txn.map(|t| t.version))
     }
 
+    /// Returns the history of the table up to this snapshot's version as an iterator of
+    /// [`HistoryEntry`], ordered from newest to oldest commit. At most `limit` entries are returned
+    /// if a limit is given.
+    ///
+    /// Only commits that are still present in the log are returned. Commits that were removed by
+    /// log cleanup are not part of the history, so the iterator ends at the earliest available
+    /// commit.
+    ///
+    /// Note that each entry reads the corresponding commit file from storage.
+    pub fn history<'a>(
+        &self,
+        engine: &'a dyn Engine,
+        limit: Option<usize>,
+    ) -> DeltaResult<impl Iterator<Item = DeltaResult<HistoryEntry>> + 'a> {
+        let history_manager = LogHistoryManager::try_new(engine, self)?;
+        Ok(history_manager.into_history(engine, limit))
+    }
+
+    /// Returns the in-commit timestamp of the commit at this snapshot's version, or `None` if
+    /// in-commit timestamps are not enabled at this version.
+    ///
+    /// Note that this method reads the commit file from storage.
+    pub fn get_in_commit_timestamp(&self, engine: &dyn Engine) -> DeltaResult<Option<i64>> {
+        if !self.table_configuration().is_in_commit_timestamps_enabled() {
+            return Ok(None);
+        }
+        let commit = match self.log_segment.ascending_commit_files.last() {
+            Some(commit) if commit.version == self.version() => commit.clone(),
+            // the commit isn't part of the log segment if this version was checkpointed
+            _ => {
+                let commit = ParsedLogPath::new_commit(self.table_root(), self.version())?;
+                ParsedLogPath {
+                    location: FileMeta::new(commit.location, 0, 0),
+                    filename: commit.filename,
+                    extension: commit.extension,
+                    version: commit.version,
+                    file_type: commit.file_type,
+                }
+            }
+        };
+        read_in_commit_timestamp(engine, &commit).map(Some)
+    }
+
     /// Fetch the domainMetadata for a specific domain in this snapshot. This returns the latest
     /// configuration for the domain, or None if the domain does not exist.
     ///
@@ -405,23 +530,121 @@ pub(crate) struct LastCheckpointHint {
     /// The number of actions that are stored in the checkpoint.
     pub(crate) size: i64,
     /// The number of fragments if the last checkpoint was written in multiple parts.
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub(crate) parts: Option<usize>,
     /// The number of bytes of the checkpoint.
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub(crate) size_in_bytes: Option<i64>,
     /// The number of AddFile actions in the checkpoint.
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub(crate) num_of_add_files: Option<i64>,
     /// The schema of the checkpoint file.
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub(crate) checkpoint_schema: Option<Schema>,
     /// The checksum of the last checkpoint JSON.
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub(crate) checksum: Option<String>,
+    /// Additional metadata about the last checkpoint.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) tags: Option<HashMap<String, String>>,
+    /// Information about the V2 checkpoint, if the last checkpoint is a V2 checkpoint.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) v2_checkpoint: Option<LastCheckpointV2Info>,
+}
+
+/// The `v2Checkpoint` field of the `_last_checkpoint` file, describing the top-level file of a V2
+/// checkpoint. (The optional `nonFileActions` are neither written nor read.)
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct LastCheckpointV2Info {
+    /// The name of the top-level checkpoint file, relative to the `_delta_log` directory.
+    pub(crate) path: String,
+    /// The size of the top-level checkpoint file in bytes.
+    pub(crate) size_in_bytes: i64,
+    /// The modification time of the top-level checkpoint file, as milliseconds since the epoch.
+    pub(crate) modification_time: i64,
+    /// The sidecar files referenced by the checkpoint.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) sidecar_files: Option<Vec<Sidecar>>,
+}
+
+impl LastCheckpointHint {
+    /// Serializes the hint to the JSON contents of the `_last_checkpoint` file, including the
+    /// checksum of the other fields.
+    pub(crate) fn to_json_with_checksum(&self) -> DeltaResult<Vec<u8>> {
+        let mut json = serde_json::to_value(self)?;
+        if let Value::Object(ref mut fields) = json {
+            fields.remove(CHECKSUM_FIELD);
+            let checksum = last_checkpoint_checksum(fields);
+            fields.insert(CHECKSUM_FIELD.to_string(), Value::String(checksum));
+        }
+        Ok(serde_json::to_vec(&json)?)
+    }
+}
+
+const CHECKSUM_FIELD: &str = "checksum";
+
+/// Computes the checksum of the `_last_checkpoint` JSON fields (excluding the checksum itself), as
+/// specified by the protocol and implemented by delta-spark: the MD5 hash (in hex) of the
+/// canonical form of the JSON, see [`canonicalize_json`].
+fn last_checkpoint_checksum(fields: &Map<String, Value>) -> String {
+    let mut entries = vec![];
+    for (name, value) in fields {
+        canonicalize_json(value, &mut vec![encode_field_name(name)], &mut entries);
+    }
+    entries.sort();
+    let canonical = entries
+        .iter()
+        .map(|(key, value)| format!("{key}={value}"))
+        .join(",");
+    format!("{:x}", Md5::digest(canonical.as_bytes()))
+}
+
+/// Flattens the JSON value at `path` into `(key, value)` entries, where the key is the path of
+/// quoted, URL-encoded field names (or bare array indexes) joined by `+`, and the value is the leaf
+/// value, URL-encoded and quoted if it is a string. For example, `{"k1": {"k3": ["v3"]}}` becomes
+/// the entry `"k1"+"k3"+0="v3"`.
+fn canonicalize_json(value: &Value, path: &mut Vec<String>, entries: &mut Vec<(String, String)>) {
+    match value {
+        Value::Object(fields) => {
+            for (name, value) in fields {
+                path.push(encode_field_name(name));
+                canonicalize_json(value, path, entries);
+                path.pop();
+            }
+        }
+        Value::Array(values) => {
+            for (index, value) in values.iter().enumerate() {
+                path.push(index.to_string());
+                canonicalize_json(value, path, entries);
+                path.pop();
+            }
+        }
+        Value::String(value) => {
+            entries.push((path.join("+"), format!("\"{}\"", encode_json_string(value))))
+        }
+        value => entries.push((path.join("+"), value.to_string())),
+    }
+}
+
+/// Encodes a field name as a path segment of a canonical entry key: URL-encoded and quoted.
+fn encode_field_name(name: &str) -> String {
+    format!("\"{}\"", encode_json_string(name))
+}
+
+/// URL-encodes the string like Java's `URLEncoder`, except that spaces are encoded as `%20`.
+fn encode_json_string(value: &str) -> String {
+    form_urlencoded::byte_serialize(value.as_bytes())
+        .collect::<String>()
+        .replace('+', "%20")
 }
 
 /// Try reading the `_last_checkpoint` file.
 ///
 /// Note that we typically want to ignore a missing/invalid `_last_checkpoint` file without failing
-/// the read. Thus, the semantics of this function are to return `None` if the file is not found or
-/// is invalid JSON. Unexpected/unrecoverable errors are returned as `Err` case and are assumed to
-/// cause failure.
+/// the read. Thus, the semantics of this function are to return `None` if the file is not found,
+/// is invalid JSON, or does not match its checksum (e.g. due to a torn write). Unexpected/
+/// unrecoverable errors are returned as `Err` case and are assumed to cause failure.
 // TODO(#1047): weird that we propagate FileNotFound as part of the iterator instead of top-level
 // result coming from storage.read_files
 fn read_last_checkpoint(
@@ -430,7 +653,7 @@ fn read_last_checkpoint(
 ) -> DeltaResult<Option<LastCheckpointHint>> {
     let file_path = log_root.join(LAST_CHECKPOINT_FILE_NAME)?;
     match storage.read_files(vec![(file_path, None)])?.next() {
-        Some(Ok(data)) => Ok(serde_json::from_slice(&data)
+        Some(Ok(data)) => Ok(parse_last_checkpoint(&data)
             .inspect_err(|e| warn!("invalid _last_checkpoint JSON: {e}"))
             .ok()),
         Some(Err(Error::FileNotFound(_))) => Ok(None),
@@ -442,6 +665,24 @@ fn read_last_checkpoint(
     }
 }
 
+/// Parses the contents of the `_last_checkpoint` file, verifying its checksum if present.
+fn parse_last_checkpoint(data: &[u8]) -> DeltaResult<LastCheckpointHint> {
+    let mut json: Value = serde_json::from_slice(data)?;
+    if let Value::Object(ref mut fields) = json {
+        if let Some(checksum) = fields.remove(CHECKSUM_FIELD) {
+            let expected = last_checkpoint_checksum(fields);
+            require!(
+                checksum.as_str() == Some(expected.as_str()),
+                Error::generic(format!(
+                    "_last_checkpoint checksum mismatch: expected {expected}, got {checksum}"
+                ))
+            );
+            fields.insert(CHECKSUM_FIELD.to_string(), checksum);
+        }
+    }
+    Ok(serde_json::from_value(json)?)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -465,7 +706,7 @@ mod tests {
     use crate::engine::default::DefaultEngine;
     use crate::engine::sync::SyncEngine;
     use crate::path::ParsedLogPath;
-    use crate::utils::test_utils::string_array_to_engine_data;
+    use crate::utils::test_utils::{string_array_to_engine_data, table_with_cdf_commit_timestamps};
     use test_utils::{add_commit, delta_path_for_version};
 
     #[test]
@@ -504,6 +745,21 @@ mod tests {
         assert_eq!(snapshot.schema(), expected);
     }
 
+    #[test]
+    fn test_snapshot_at_timestamp() {
+        let (_dir, url) = table_with_cdf_commit_timestamps();
+        let engine = SyncEngine::new();
+
+        for (timestamp, expected_version) in [(1000, 0), (2999, 1), (3000, 2), (100_000, 4)] {
+            let snapshot = Snapshot::try_new_at_timestamp(url.clone(), &engine, timestamp).unwrap();
+            assert_eq!(snapshot.version(), expected_version);
+        }
+
+        // The timestamp is before the first commit
+        let res = Snapshot::try_new_at_timestamp(url, &engine, 999);
+        assert!(matches!(res, Err(Error::Generic(_))));
+    }
+
     // TODO: unify this and lots of stuff in LogSegment tests and test_utils
     async fn commit(store: &InMemory, version: Version, commit: Vec<serde_json::Value>) {
         let commit_data = commit
@@ -907,11 +1163,100 @@ mod tests {
             num_of_add_files: None,
             checkpoint_schema: None,
             checksum: None,
+            tags: None,
+            v2_checkpoint: None,
         };
         assert_eq!(valid.unwrap(), expected);
         assert!(invalid.is_none());
     }
 
+    #[test]
+    fn test_last_checkpoint_checksum() {
+        // The worked example of the protocol: field names are quoted and URL-encoded, array indexes
+        // are bare, string values are URL-encoded and quoted, and the `checksum` field is excluded
+        let json = json!({
+            "k0": "'v 0'",
+            "checksum": "adsaskfljadfkjadfkj",
+            "k1": {"k2": 2, "k3": ["v3", [1, 2], {"k4": "v4", "k5": ["v5", "v6", "v7"]}]},
+        });
+        let Value::Object(mut fields) = json else {
+            panic!("expected an object")
+        };
+        fields.remove(CHECKSUM_FIELD);
+        let mut entries = vec![];
+        for (name, value) in &fields {
+            canonicalize_json(value, &mut vec![encode_field_name(name)], &mut entries);
+        }
+        entries.sort();
+        let canonical = entries
+            .iter()
+            .map(|(key, value)| format!("{key}={value}"))
+            .join(",");
+        assert_eq!(
+            canonical,
+            r#""k0"="%27v%200%27","k1"+"k2"=2,"k1"+"k3"+0="v3","k1"+"k3"+1+0=1,"k1"+"k3"+1+1=2,"k1"+"k3"+2+"k4"="v4","k1"+"k3"+2+"k5"+0="v5","k1"+"k3"+2+"k5"+1="v6","k1"+"k3"+2+"k5"+2="v7""#
+        );
+        assert_eq!(
+            last_checkpoint_checksum(&fields),
+            "6a92d155a59bf2eecbd4b4ec7fd1f875"
+        );
+    }
+
+    #[test]
+    fn test_last_checkpoint_with_checksum() {
+        let hint = LastCheckpointHint {
+            version: 10,
+            size: 8,
+            parts: None,
+            size_in_bytes: Some(21857),
+            num_of_add_files: Some(3),
+            checkpoint_schema: None,
+            checksum: None,
+            tags: Some(HashMap::from([("key".to_string(), "a value".to_string())])),
+            v2_checkpoint: None,
+        };
+        let data = hint.to_json_with_checksum().unwrap();
+        let parsed = parse_last_checkpoint(&data).unwrap();
+        assert!(parsed.checksum.is_some());
+        assert_eq!(
+            parsed,
+            LastCheckpointHint {
+                checksum: parsed.checksum.clone(),
+                ..hint
+            }
+        );
+
+        // A file whose contents don't match its checksum is ignored
+        let torn = String::from_utf8(data).unwrap().replace("21857", "2185");
+        let err = parse_last_checkpoint(torn.as_bytes()).unwrap_err();
+        assert!(err.to_string().contains("checksum mismatch"));
+
+        let store = Arc::new(InMemory::new());
+        tokio::runtime::Runtime::new()
+            .expect("create tokio runtime")
+            .block_on(store.put(&Path::from("torn/_last_checkpoint"), torn.into()))
+            .expect("put _last_checkpoint");
+        let storage =
+            ObjectStoreStorageHandler::new(store, Arc::new(TokioBackgroundExecutor::new()));
+        let url = Url::parse("memory:///torn/").expect("valid url");
+        assert!(read_last_checkpoint(&storage, &url).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_checkpoint_schema_from_last_checkpoint() {
+        let path = std::fs::canonicalize(PathBuf::from("./tests/data/parquet_row_group_skipping/"));
+        let url = url::Url::from_directory_path(path.unwrap()).unwrap();
+        let engine = SyncEngine::new();
+
+        let snapshot = Snapshot::try_new(url.clone(), &engine, None).unwrap();
+        let checkpoint_schema = snapshot.log_segment.checkpoint_schema.as_ref().unwrap();
+        assert!(checkpoint_schema.field("add").is_some());
+
+        // The hint doesn't describe the checkpoint of older snapshots
+        let snapshot = Snapshot::try_new(url, &engine, Some(0)).unwrap();
+        assert!(snapshot.log_segment.checkpoint_schema.is_none());
+    }
+
     #[test_log::test]
     fn test_read_table_with_checkpoint() {
         let path = std::fs::canonicalize(PathBuf::from(
@@ -37,6 +37,7 @@ use scan::TableChangesScanBuilder;
 use url::Url;
 
 use crate::actions::{ensure_supported_features, Protocol};
+use crate::history_manager::LogHistoryManager;
 use crate::log_segment::LogSegment;
 use crate::path::AsUrl;
 use crate::schema::{DataType, Schema, StructField, StructType};
@@ -202,6 +203,46 @@ impl TableChanges {
         })
     }
 
+    /// Creates a new [`TableChanges`] instance for the given timestamp range. The start version is
+    /// the earliest commit with a timestamp greater than or equal to `start_timestamp`, and the end
+    /// version is the latest commit with a timestamp less than or equal to `end_timestamp`. See
+    /// [`TableChanges::try_new`] for the properties that are checked on the resulting version range.
+    ///
+    /// Commit timestamps are in-commit timestamps for commits made while in-commit timestamps are
+    /// enabled, and the commit file's modification time otherwise.
+    ///
+    /// # Parameters
+    /// - `table_root`: url pointing at the table root (where `_delta_log` folder is located)
+    /// - `engine`: Implementation of [`Engine`] apis.
+    /// - `start_timestamp`: The start timestamp (inclusive) of the change data feed in milliseconds
+    ///   since the unix epoch.
+    /// - `end_timestamp`: The end timestamp (inclusive) of the change data feed in milliseconds since
+    ///   the unix epoch. If this is none, this defaults to the newest table version.
+    pub fn try_new_with_timestamps(
+        table_root: Url,
+        engine: &dyn Engine,
+        start_timestamp: i64,
+        end_timestamp: Option<i64>,
+    ) -> DeltaResult<Self> {
+        let latest_snapshot = Snapshot::try_new(table_root.clone(), engine, None)?;
+        let history_manager = LogHistoryManager::try_new(engine, &latest_snapshot)?;
+        let start_version = history_manager.first_version_after(engine, start_timestamp)?;
+        let end_version = end_timestamp
+            .map(|end_timestamp| history_manager.latest_version_as_of(engine, end_timestamp))
+            .transpose()?;
+        if let Some(end_version) = end_version {
+            require!(
+                start_version <= end_version,
+                Error::generic(format!(
+                    "No commits found in timestamp range [{start_timestamp}, {}]. The range \
+                     resolves to start version {start_version} and end version {end_version}",
+                    end_timestamp.unwrap_or_default()
+                ))
+            );
+        }
+        Self::try_new(table_root, engine, start_version, end_version)
+    }
+
     /// The start version of the `TableChanges`.
     pub fn start_version(&self) -> Version {
         self.start_version
@@ -279,6 +320,7 @@ mod tests {
     use crate::engine::sync::SyncEngine;
     use crate::schema::{DataType, StructField};
     use crate::table_changes::CDF_FIELDS;
+    use crate::utils::test_utils::table_with_cdf_commit_timestamps;
     use crate::Error;
     use itertools::assert_equal;
 
@@ -313,6 +355,31 @@ mod tests {
             assert!(matches!(res, Err(Error::ChangeDataFeedUnsupported(_))))
         }
     }
+
+    #[test]
+    fn table_changes_with_timestamps() {
+        let (_dir, url) = table_with_cdf_commit_timestamps();
+        let engine = SyncEngine::new();
+
+        let table_changes =
+            TableChanges::try_new_with_timestamps(url.clone(), &engine, 500, Some(2500)).unwrap();
+        assert_eq!(table_changes.start_version(), 0);
+        assert_eq!(table_changes.end_version(), 1);
+
+        let table_changes =
+            TableChanges::try_new_with_timestamps(url.clone(), &engine, 4500, None).unwrap();
+        assert_eq!(table_changes.start_version(), 4);
+        assert_eq!(table_changes.end_version(), 4);
+
+        // No commit falls within the range
+        let res = TableChanges::try_new_with_timestamps(url.clone(), &engine, 1500, Some(1999));
+        assert!(matches!(res, Err(Error::Generic(_))));
+
+        // Start timestamp is after the latest commit
+        let res = TableChanges::try_new_with_timestamps(url, &engine, 5001, None);
+        assert!(matches!(res, Err(Error::Generic(_))));
+    }
+
     #[test]
     fn schema_evolution_fails() {
         let path = "./tests/data/table-with-cdf";
@@ -58,6 +58,9 @@ pub(crate) fn physical_to_logical_expr(
                 let generated_column = cdf_columns.remove(field_name.as_str());
                 Ok(generated_column.unwrap_or_else(|| ColumnName::new([field_name]).into()))
             }
+            ColumnType::RowId { .. } | ColumnType::RowCommitVersion { .. } => Err(
+                Error::unsupported("Row tracking columns are not supported in change data feed"),
+            ),
         })
         .try_collect()?;
     Ok(Expression::Struct(all_fields))
@@ -7,6 +7,7 @@ use tracing::debug;
 use url::Url;
 
 use crate::actions::deletion_vector::split_vector;
+use crate::scan::state::{parquet_predicate, row_index_selection_vector, with_row_index_column};
 use crate::scan::{ColumnType, PhysicalPredicate, ScanResult};
 use crate::schema::{SchemaRef, StructType};
 use crate::{DeltaResult, Engine, FileMeta, PredicateRef};
@@ -279,7 +280,7 @@ fn read_scan_file(
     logical_schema: &SchemaRef,
     physical_schema: &SchemaRef,
     all_fields: &[ColumnType],
-    _physical_predicate: Option<PredicateRef>,
+    physical_predicate: Option<PredicateRef>,
 ) -> DeltaResult<impl Iterator<Item = DeltaResult<ScanResult>>> {
     let ResolvedCdfScanFile {
         scan_file,
@@ -289,6 +290,16 @@ fn read_scan_file(
     let physical_to_logical_expr =
         physical_to_logical_expr(&scan_file, logical_schema.as_ref(), all_fields)?;
     let physical_schema = scan_file_physical_schema(&scan_file, physical_schema.as_ref());
+    let predicate = parquet_predicate(physical_predicate, &physical_schema);
+    // The parquet reader may skip rows that don't satisfy the predicate, so the deletion vector
+    // must be applied by row index rather than by position. The physical to logical expression
+    // drops the row index column.
+    let read_row_indexes = selection_vector.is_some() && predicate.is_some();
+    let physical_schema = if read_row_indexes {
+        with_row_index_column(&physical_schema).0
+    } else {
+        physical_schema
+    };
     let phys_to_logical_eval = engine.evaluation_handler().new_expression_evaluator(
         physical_schema.clone(),
         physical_to_logical_expr,
@@ -303,16 +314,26 @@ fn read_scan_file(
         size: 0,
         location,
     };
-    // TODO(#860): we disable predicate pushdown until we support row indexes.
     let read_result_iter =
         engine
             .parquet_handler()
-            .read_parquet_files(&[file], physical_schema, None)?;
+            .read_parquet_files(&[file], physical_schema, predicate)?;
 
     let result = read_result_iter.map(move |batch| -> DeltaResult<_> {
         let batch = batch?;
         // to transform the physical data into the correct logical form
         let logical = phys_to_logical_eval.evaluate(batch.as_ref());
+        if read_row_indexes {
+            // See the comment on `extend` below
+            let raw_mask = selection_vector
+                .as_deref()
+                .map(|sv| row_index_selection_vector(batch.as_ref(), sv, !is_dv_resolved_pair))
+                .transpose()?;
+            return Ok(ScanResult {
+                raw_data: logical,
+                raw_mask,
+            });
+        }
         let len = logical.as_ref().map_or(0, |res| res.len());
         // need to split the dv_mask. what's left in dv_mask covers this result, and rest
         // will cover the following results. we `take()` out of `selection_vector` to avoid
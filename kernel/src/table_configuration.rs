@@ -8,20 +8,28 @@
 //! [`TableProperties`].
 //!
 //! [`Schema`]: crate::schema::Schema
+use std::borrow::Cow;
 use std::sync::{Arc, LazyLock};
 
 use url::Url;
 
 use crate::actions::{ensure_supported_features, Metadata, Protocol};
-use crate::schema::{InvariantChecker, SchemaRef};
+use crate::schema::{
+    ColumnMetadataKey, ColumnName, DataType, InvariantChecker, SchemaRef, SchemaTransform,
+    StructField, StructType,
+};
 use crate::table_features::{
-    column_mapping_mode, validate_schema_column_mapping, validate_timestamp_ntz_feature_support,
-    ColumnMappingMode, ReaderFeature, WriterFeature,
+    column_mapping_mode, physical_write_schema, validate_schema_column_mapping,
+    validate_timestamp_ntz_feature_support, ColumnMappingMode, ReaderFeature, WriterFeature,
 };
-use crate::table_properties::TableProperties;
+use crate::table_properties::{DataSkippingNumIndexedCols, TableProperties};
 use crate::{DeltaResult, Error, Version};
 use delta_kernel_derive::internal_api;
 
+/// The number of leaf columns for which statistics are collected, if the table doesn't specify
+/// `delta.dataSkippingNumIndexedCols` or `delta.dataSkippingStatsColumns`.
+const DEFAULT_NUM_INDEXED_COLS: usize = 32;
+
 /// Holds all the configuration for a table at a specific version. This includes the supported
 /// reader and writer features, table properties, schema, version, and table root. This can be used
 /// to check whether a table supports a feature or has it enabled. For example, deletion vector
@@ -159,12 +167,67 @@ impl TableConfiguration {
         self.version
     }
 
+    /// The physical names of the columns for which writers should collect file statistics. If the
+    /// `delta.dataSkippingStatsColumns` table property is set, these are the listed columns (a
+    /// struct column includes all of its nested columns), ignoring columns that don't exist in the
+    /// schema. Otherwise, these are the first `delta.dataSkippingNumIndexedCols` (default 32) leaf
+    /// columns of the schema. Partition columns never have statistics.
+    #[internal_api]
+    pub(crate) fn stats_columns(&self) -> Vec<ColumnName> {
+        let partition_columns = &self.metadata.partition_columns;
+        let data_schema = StructType::new(
+            self.schema
+                .fields()
+                .filter(|field| !partition_columns.contains(field.name()))
+                .cloned(),
+        );
+        if let Some(ref columns) = self.table_properties.data_skipping_stats_columns {
+            return columns
+                .iter()
+                .filter_map(|column| physical_column_name(&data_schema, column))
+                .collect();
+        }
+
+        let num_indexed_cols = match self.table_properties.data_skipping_num_indexed_cols {
+            Some(DataSkippingNumIndexedCols::AllColumns) => usize::MAX,
+            Some(DataSkippingNumIndexedCols::NumColumns(n)) => n.try_into().unwrap_or(usize::MAX),
+            None => DEFAULT_NUM_INDEXED_COLS,
+        };
+        let mut leaves = vec![];
+        collect_physical_leaves(data_schema.fields(), &mut vec![], &mut leaves);
+        leaves.truncate(num_indexed_cols);
+        leaves
+    }
+
+    /// The schema of the data files written to this table: the non-partition columns of the
+    /// schema, using their physical names and (with column mapping) parquet field ids.
+    #[internal_api]
+    pub(crate) fn physical_write_schema(&self) -> SchemaRef {
+        let partition_columns = &self.metadata.partition_columns;
+        let data_schema = StructType::new(
+            self.schema
+                .fields()
+                .filter(|field| !partition_columns.contains(field.name()))
+                .cloned(),
+        );
+        Arc::new(physical_write_schema(
+            &data_schema,
+            self.column_mapping_mode,
+        ))
+    }
+
     /// Returns `true` if the kernel supports writing to this table. This checks that the
     /// protocol's writer features are all supported.
     #[internal_api]
     pub(crate) fn ensure_write_supported(&self) -> DeltaResult<()> {
         self.protocol.ensure_write_supported()?;
 
+        // legacy writer versions imply features that the kernel can't write (e.g. writer version 5
+        // for column mapping also implies change data feed), so they must not be in use
+        if self.protocol.writer_features().is_none() {
+            self.ensure_legacy_features_not_in_use()?;
+        }
+
         // for now we don't allow invariants so although we support writer version 2 and the
         // ColumnInvariant TableFeature we _must_ check here that they are not actually in use
         if self.is_invariants_supported()
@@ -178,6 +241,47 @@ impl TableConfiguration {
         Ok(())
     }
 
+    /// Returns `Ok` if the kernel supports vacuuming this table, i.e. deleting the files the table
+    /// no longer references. This requires support for the protocol's writer features and, if the
+    /// table has the `vacuumProtocolCheck` feature, for its reader features too.
+    #[internal_api]
+    pub(crate) fn ensure_vacuum_supported(&self) -> DeltaResult<()> {
+        self.protocol.ensure_write_supported()?;
+        if self
+            .protocol
+            .has_reader_feature(&ReaderFeature::VacuumProtocolCheck)
+        {
+            self.protocol.ensure_read_supported()?;
+        }
+        Ok(())
+    }
+
+    fn ensure_legacy_features_not_in_use(&self) -> DeltaResult<()> {
+        let unsupported = |feature: &str| {
+            Err(Error::unsupported(format!(
+                "Writing to tables with {feature} is not yet supported"
+            )))
+        };
+        if self.table_properties.enable_change_data_feed == Some(true) {
+            return unsupported("change data feed");
+        }
+        if self
+            .metadata
+            .configuration
+            .keys()
+            .any(|key| key.starts_with("delta.constraints."))
+        {
+            return unsupported("check constraints");
+        }
+        if schema_has_column_metadata(&self.schema, ColumnMetadataKey::GenerationExpression) {
+            return unsupported("generated columns");
+        }
+        if schema_has_column_metadata(&self.schema, ColumnMetadataKey::IdentityStart) {
+            return unsupported("identity columns");
+        }
+        Ok(())
+    }
+
     /// Returns `true` if kernel supports reading Change Data Feed on this table.
     /// See the documentation of [`TableChanges`] for more details.
     ///
@@ -253,7 +357,6 @@ impl TableConfiguration {
         }
     }
 
-    #[allow(unused)]
     pub(crate) fn is_append_only_enabled(&self) -> bool {
         self.is_append_only_supported() && self.table_properties.append_only.unwrap_or(false)
     }
@@ -287,7 +390,6 @@ impl TableConfiguration {
     /// To support this feature the table must:
     /// - Have a min_writer_version of 7
     /// - Have the [`WriterFeature::InCommitTimestamp`] writer feature.
-    #[allow(unused)]
     pub(crate) fn is_in_commit_timestamps_supported(&self) -> bool {
         self.protocol().min_writer_version() == 7
             && self
@@ -295,9 +397,41 @@ impl TableConfiguration {
                 .has_writer_feature(&WriterFeature::InCommitTimestamp)
     }
 
+    /// Returns `true` if the table supports writing domain metadata.
+    ///
+    /// To support this feature the table must:
+    /// - Have a min_writer_version of 7
+    /// - Have the [`WriterFeature::DomainMetadata`] writer feature.
+    pub(crate) fn is_domain_metadata_supported(&self) -> bool {
+        self.protocol().min_writer_version() == 7
+            && self
+                .protocol()
+                .has_writer_feature(&WriterFeature::DomainMetadata)
+    }
+
+    /// Returns `true` if the table supports row tracking, in which case every file added to the
+    /// table must be assigned a base row id and a default row commit version.
+    ///
+    /// To support this feature the table must:
+    /// - Have a min_writer_version of 7
+    /// - Have the [`WriterFeature::RowTracking`] writer feature.
+    pub(crate) fn is_row_tracking_supported(&self) -> bool {
+        self.protocol().min_writer_version() == 7
+            && self
+                .protocol()
+                .has_writer_feature(&WriterFeature::RowTracking)
+    }
+
+    /// Returns `true` if row tracking is supported and it is enabled, in which case every file in
+    /// the table has a base row id and a default row commit version. Row tracking is enabled when
+    /// the `delta.enableRowTracking` configuration is set to `true`.
+    pub(crate) fn is_row_tracking_enabled(&self) -> bool {
+        self.is_row_tracking_supported()
+            && self.table_properties().enable_row_tracking.unwrap_or(false)
+    }
+
     /// Returns `true` if in-commit timestamps is supported and it is enabled. In-commit timestamps
     /// is enabled when the `delta.enableInCommitTimestamps` configuration is set to `true`.
-    #[allow(unused)]
     pub(crate) fn is_in_commit_timestamps_enabled(&self) -> bool {
         self.is_in_commit_timestamps_supported()
             && self
@@ -312,7 +446,6 @@ impl TableConfiguration {
     /// If in-commit timestamps is not supported, or not enabled, this returns `None`.
     /// If in-commit timestams is enabled, but the enablement version or timestamp is not present,
     /// this returns an error.
-    #[allow(unused)]
     pub(crate) fn in_commit_timestamp_enablement(&self) -> DeltaResult<Option<(Version, i64)>> {
         if !self.is_in_commit_timestamps_enabled() {
             return Ok(None);
@@ -340,6 +473,63 @@ impl TableConfiguration {
     }
 }
 
+/// Whether any field of the schema (including nested fields) has the given metadata key.
+fn schema_has_column_metadata(schema: &StructType, key: ColumnMetadataKey) -> bool {
+    struct MetadataChecker {
+        key: ColumnMetadataKey,
+        found: bool,
+    }
+    impl<'a> SchemaTransform<'a> for MetadataChecker {
+        fn transform_struct_field(
+            &mut self,
+            field: &'a StructField,
+        ) -> Option<Cow<'a, StructField>> {
+            if field.get_config_value(&self.key).is_some() {
+                self.found = true;
+            } else if !self.found {
+                let _ = self.recurse_into_struct_field(field);
+            }
+            Some(Cow::Borrowed(field))
+        }
+    }
+    let mut checker = MetadataChecker { key, found: false };
+    let _ = checker.transform_struct(schema);
+    checker.found
+}
+
+/// Resolve a logical column name to its physical name, or `None` if the column doesn't exist.
+fn physical_column_name(schema: &StructType, column: &ColumnName) -> Option<ColumnName> {
+    let mut fields = Some(schema);
+    let mut physical_path = vec![];
+    for name in column.iter() {
+        let field = fields?.field(name)?;
+        physical_path.push(field.physical_name().to_string());
+        fields = match field.data_type() {
+            DataType::Struct(struct_type) => Some(struct_type),
+            _ => None,
+        };
+    }
+    Some(ColumnName::new(physical_path))
+}
+
+/// Collect the physical names of all leaf columns (in schema order). Arrays and maps are leaves.
+fn collect_physical_leaves<'a>(
+    fields: impl Iterator<Item = &'a StructField>,
+    path: &mut Vec<String>,
+    leaves: &mut Vec<ColumnName>,
+) {
+    for field in fields {
+        path.push(field.physical_name().to_string());
+        match field.data_type() {
+            DataType::Struct(struct_type) => {
+                collect_physical_leaves(struct_type.fields(), path, leaves)
+            }
+            _ => leaves.push(ColumnName::new(path.iter())),
+        }
+        path.pop();
+    }
+}
+
 #[cfg(test)]
 mod test {
     use std::collections::HashMap;
@@ -347,6 +537,7 @@ mod test {
     use url::Url;
 
     use crate::actions::{Metadata, Protocol};
+    use crate::schema::{column_name, ColumnMetadataKey, MetadataValue};
     use crate::table_features::{ReaderFeature, WriterFeature};
     use crate::table_properties::TableProperties;
     use crate::Error;
@@ -628,4 +819,147 @@ mod test {
             "Should succeed when TIMESTAMP_NTZ is used with required features"
         );
     }
+
+    #[test]
+    fn test_stats_columns() {
+        // schema: part (partition column), a, s: struct<x, y>, b -- with column mapping
+        let field = |name: &str, id: u32, data_type: &str| {
+            format!(
+                r#"{{"name":"{name}","type":{data_type},"nullable":true,"metadata":{{"delta.columnMapping.id":{id},"delta.columnMapping.physicalName":"col_{name}"}}}}"#
+            )
+        };
+        let nested = format!(
+            r#"{{"type":"struct","fields":[{},{}]}}"#,
+            field("x", 4, r#""integer""#),
+            field("y", 5, r#""string""#)
+        );
+        let schema_string = format!(
+            r#"{{"type":"struct","fields":[{},{},{},{}]}}"#,
+            field("part", 1, r#""string""#),
+            field("a", 2, r#""long""#),
+            field("s", 3, &nested),
+            field("b", 6, r#""string""#),
+        );
+        let table_config = |properties: &[(&str, &str)]| {
+            let mut configuration = HashMap::from_iter(
+                properties
+                    .iter()
+                    .map(|(k, v)| (k.to_string(), v.to_string())),
+            );
+            configuration.insert("delta.columnMapping.mode".to_string(), "name".to_string());
+            let metadata = Metadata {
+                configuration,
+                schema_string: schema_string.clone(),
+                partition_columns: vec!["part".to_string()],
+                ..Default::default()
+            };
+            let protocol = Protocol::try_new(
+                3,
+                7,
+                Some([ReaderFeature::ColumnMapping]),
+                Some([WriterFeature::ColumnMapping]),
+            )
+            .unwrap();
+            let table_root = Url::try_from("file:///").unwrap();
+            TableConfiguration::try_new(metadata, protocol, table_root, 0).unwrap()
+        };
+
+        let all_columns = vec![
+            column_name!("col_a"),
+            column_name!("col_s.col_x"),
+            column_name!("col_s.col_y"),
+            column_name!("col_b"),
+        ];
+        assert_eq!(table_config(&[]).stats_columns(), all_columns);
+        assert_eq!(
+            table_config(&[("delta.dataSkippingNumIndexedCols", "2")]).stats_columns(),
+            all_columns[..2]
+        );
+        assert_eq!(
+            table_config(&[("delta.dataSkippingNumIndexedCols", "-1")]).stats_columns(),
+            all_columns
+        );
+        // explicit stats columns take precedence, and unknown and partition columns are ignored
+        let table_config = table_config(&[
+            ("delta.dataSkippingNumIndexedCols", "1"),
+            ("delta.dataSkippingStatsColumns", "b,s,missing,part"),
+        ]);
+        assert_eq!(
+            table_config.stats_columns(),
+            vec![column_name!("col_b"), column_name!("col_s")]
+        );
+    }
+
+    #[test]
+    fn test_ensure_write_supported_legacy_writer_versions() {
+        let schema_string = r#"{"type":"struct","fields":[{"name":"value","type":"integer","nullable":true,"metadata":{"delta.columnMapping.id":1,"delta.columnMapping.physicalName":"col_value"}}]}"#;
+        let table_config = |properties: &[(&str, &str)]| {
+            let mut configuration = HashMap::from_iter(
+                properties
+                    .iter()
+                    .map(|(k, v)| (k.to_string(), v.to_string())),
+            );
+            configuration.insert("delta.columnMapping.mode".to_string(), "name".to_string());
+            let metadata = Metadata {
+                configuration,
+                schema_string: schema_string.to_string(),
+                ..Default::default()
+            };
+            let protocol =
+                Protocol::try_new(2, 5, None::<Vec<String>>, None::<Vec<String>>).unwrap();
+            let table_root = Url::try_from("file:///").unwrap();
+            TableConfiguration::try_new(metadata, protocol, table_root, 0).unwrap()
+        };
+
+        // writer version 5 is writable if the implied features the kernel can't write aren't used
+        let writable = table_config(&[]);
+        writable.ensure_write_supported().unwrap();
+        let physical_schema = writable.physical_write_schema();
+        let field = physical_schema.field("col_value").unwrap();
+        assert_eq!(
+            field.get_config_value(&ColumnMetadataKey::ParquetFieldId),
+            Some(&MetadataValue::Number(1))
+        );
+
+        for property in [
+            ("delta.enableChangeDataFeed", "true"),
+            ("delta.constraints.positive", "value > 0"),
+        ] {
+            assert!(matches!(
+                table_config(&[property]).ensure_write_supported(),
+                Err(Error::Unsupported(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_ensure_vacuum_supported() {
+        let table_config = |writer_features: &[WriterFeature]| {
+            let metadata = Metadata {
+                schema_string: r#"{"type":"struct","fields":[{"name":"value","type":"integer","nullable":true,"metadata":{}}]}"#.to_string(),
+                ..Default::default()
+            };
+            let protocol = Protocol::try_new(
+                3,
+                7,
+                Some([ReaderFeature::VacuumProtocolCheck]),
+                Some(writer_features),
+            )
+            .unwrap();
+            let table_root = Url::try_from("file:///").unwrap();
+            TableConfiguration::try_new(metadata, protocol, table_root, 0).unwrap()
+        };
+
+        table_config(&[WriterFeature::VacuumProtocolCheck])
+            .ensure_vacuum_supported()
+            .unwrap();
+        assert!(matches!(
+            table_config(&[
+                WriterFeature::VacuumProtocolCheck,
+                WriterFeature::CheckConstraints
+            ])
+            .ensure_vacuum_supported(),
+            Err(Error::Unsupported(_))
+        ));
+    }
 }
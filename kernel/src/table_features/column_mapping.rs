@@ -1,7 +1,10 @@
 //! Code to handle column mapping, including modes and schema transforms
 use super::ReaderFeature;
 use crate::actions::Protocol;
-use crate::schema::{ColumnName, DataType, MetadataValue, Schema, SchemaTransform, StructField};
+use crate::schema::{
+    ColumnMetadataKey, ColumnName, DataType, MetadataValue, Schema, SchemaTransform, StructField,
+    StructType,
+};
 use crate::table_properties::TableProperties;
 use crate::{DeltaResult, Error};
 
@@ -61,6 +64,39 @@ pub fn validate_schema_column_mapping(schema: &Schema, mode: ColumnMappingMode)
     }
 }
 
+/// The schema of the data files written to a table with the given (validated) schema: each field
+/// is renamed to its physical name. When column mapping is enabled, each field is also annotated
+/// with its column mapping id as its parquet field id, so that writers can embed the field ids in
+/// the data files.
+pub(crate) fn physical_write_schema(schema: &Schema, mode: ColumnMappingMode) -> StructType {
+    struct MakePhysical {
+        mode: ColumnMappingMode,
+    }
+    impl<'a> SchemaTransform<'a> for MakePhysical {
+        fn transform_struct_field(
+            &mut self,
+            field: &'a StructField,
+        ) -> Option<Cow<'a, StructField>> {
+            let field = self.recurse_into_struct_field(field)?;
+            let mut field = field.with_name(field.physical_name());
+            if self.mode != ColumnMappingMode::None {
+                if let Some(id) = field.get_config_value(&ColumnMetadataKey::ColumnMappingId) {
+                    let id = id.clone();
+                    field
+                        .metadata
+                        .insert(ColumnMetadataKey::ParquetFieldId.as_ref().to_string(), id);
+                }
+            }
+            Some(Cow::Owned(field))
+        }
+    }
+    // NOTE: unwrap is safe because the transformer is incapable of returning None
+    MakePhysical { mode }
+        .transform_struct(schema)
+        .unwrap()
+        .into_owned()
+}
+
 struct ValidateColumnMappings<'a> {
     mode: ColumnMappingMode,
     path: Vec<&'a str>,
@@ -356,4 +392,33 @@ mod tests {
         let schema = create_schema(None, None, None, "\"col-5f422f40\"");
         validate_schema_column_mapping(&schema, ColumnMappingMode::None).expect_err("field name");
     }
+
+    #[test]
+    fn test_physical_write_schema() {
+        let schema = create_schema("5", "\"col-a7f4159c\"", "4", "\"col-5f422f40\"");
+        let physical_schema = physical_write_schema(&schema, ColumnMappingMode::Name);
+        let outer = physical_schema.field("col-5f422f40").unwrap();
+        assert_eq!(
+            outer.get_config_value(&ColumnMetadataKey::ParquetFieldId),
+            Some(&MetadataValue::Number(4))
+        );
+        let DataType::Array(array_type) = outer.data_type() else {
+            panic!("expected an array");
+        };
+        let DataType::Struct(element_type) = array_type.element_type() else {
+            panic!("expected a struct");
+        };
+        let inner = element_type.field("col-a7f4159c").unwrap();
+        assert_eq!(
+            inner.get_config_value(&ColumnMetadataKey::ParquetFieldId),
+            Some(&MetadataValue::Number(5))
+        );
+
+        // without column mapping, the schema is unchanged
+        let schema = create_schema(None, None, None, None);
+        assert_eq!(
+            physical_write_schema(&schema, ColumnMappingMode::None),
+            schema
+        );
+    }
 }
@@ -7,9 +7,11 @@ use crate::schema::derive_macro_utils::ToDataType;
 use crate::schema::DataType;
 use delta_kernel_derive::internal_api;
 
-pub(crate) use column_mapping::column_mapping_mode;
+pub(crate) use column_mapping::{column_mapping_mode, physical_write_schema};
 pub use column_mapping::{validate_schema_column_mapping, ColumnMappingMode};
-pub(crate) use timestamp_ntz::validate_timestamp_ntz_feature_support;
+pub(crate) use timestamp_ntz::{
+    schema_contains_timestamp_ntz, validate_timestamp_ntz_feature_support,
+};
 mod column_mapping;
 mod timestamp_ntz;
 
@@ -147,6 +149,38 @@ impl ToDataType for WriterFeature {
     }
 }
 
+impl WriterFeature {
+    /// The reader feature of a reader-writer feature, or `None` if this is a writer-only feature.
+    pub(crate) fn reader_feature(&self) -> Option<ReaderFeature> {
+        match self {
+            WriterFeature::ColumnMapping => Some(ReaderFeature::ColumnMapping),
+            WriterFeature::DeletionVectors => Some(ReaderFeature::DeletionVectors),
+            WriterFeature::TimestampWithoutTimezone => {
+                Some(ReaderFeature::TimestampWithoutTimezone)
+            }
+            WriterFeature::TypeWidening => Some(ReaderFeature::TypeWidening),
+            WriterFeature::TypeWideningPreview => Some(ReaderFeature::TypeWideningPreview),
+            WriterFeature::V2Checkpoint => Some(ReaderFeature::V2Checkpoint),
+            WriterFeature::VacuumProtocolCheck => Some(ReaderFeature::VacuumProtocolCheck),
+            WriterFeature::VariantType => Some(ReaderFeature::VariantType),
+            _ => None,
+        }
+    }
+
+    /// The legacy writer version (below 7) that enables this feature, or `None` if the feature
+    /// can only be enabled with table features (writer version 7).
+    pub(crate) fn legacy_writer_version(&self) -> Option<i32> {
+        match self {
+            WriterFeature::AppendOnly | WriterFeature::Invariants => Some(2),
+            WriterFeature::CheckConstraints => Some(3),
+            WriterFeature::ChangeDataFeed | WriterFeature::GeneratedColumns => Some(4),
+            WriterFeature::ColumnMapping => Some(5),
+            WriterFeature::IdentityColumns => Some(6),
+            _ => None,
+        }
+    }
+}
+
 #[cfg(test)] // currently only used in tests
 impl ReaderFeature {
     pub(crate) fn unknown(s: impl ToString) -> Self {
@@ -179,12 +213,28 @@ pub(crate) static SUPPORTED_READER_FEATURES: LazyLock<Vec<ReaderFeature>> = Lazy
 pub(crate) static SUPPORTED_WRITER_FEATURES: LazyLock<Vec<WriterFeature>> = LazyLock::new(|| {
     vec![
         WriterFeature::AppendOnly,
+        WriterFeature::ColumnMapping,
         WriterFeature::DeletionVectors,
+        WriterFeature::DomainMetadata,
+        WriterFeature::InCommitTimestamp,
         WriterFeature::Invariants,
+        WriterFeature::RowTracking,
         WriterFeature::TimestampWithoutTimezone,
+        WriterFeature::VacuumProtocolCheck,
     ]
 });
 
+/// The writer features that are implied by legacy writer versions (below 7).
+pub(crate) const LEGACY_WRITER_FEATURES: [WriterFeature; 7] = [
+    WriterFeature::AppendOnly,
+    WriterFeature::Invariants,
+    WriterFeature::CheckConstraints,
+    WriterFeature::ChangeDataFeed,
+    WriterFeature::GeneratedColumns,
+    WriterFeature::ColumnMapping,
+    WriterFeature::IdentityColumns,
+];
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -17,10 +17,8 @@ pub(crate) fn validate_timestamp_ntz_feature_support(
     if !protocol.has_reader_feature(&ReaderFeature::TimestampWithoutTimezone)
         || !protocol.has_writer_feature(&WriterFeature::TimestampWithoutTimezone)
     {
-        let mut uses_timestamp_ntz = UsesTimestampNtz(false);
-        let _ = uses_timestamp_ntz.transform_struct(schema);
         require!(
-            !uses_timestamp_ntz.0,
+            !schema_contains_timestamp_ntz(schema),
             Error::unsupported(
                 "Table contains TIMESTAMP_NTZ columns but does not have the required 'timestampNtz' feature in reader and writer features"
             )
@@ -29,6 +27,13 @@ pub(crate) fn validate_timestamp_ntz_feature_support(
     Ok(())
 }
 
+/// Returns `true` if any column in the schema uses the TIMESTAMP_NTZ type.
+pub(crate) fn schema_contains_timestamp_ntz(schema: &Schema) -> bool {
+    let mut uses_timestamp_ntz = UsesTimestampNtz(false);
+    let _ = uses_timestamp_ntz.transform_struct(schema);
+    uses_timestamp_ntz.0
+}
+
 /// Schema visitor that checks if any column in the schema uses TIMESTAMP_NTZ type
 struct UsesTimestampNtz(bool);
 
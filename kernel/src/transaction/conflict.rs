@@ -0,0 +1,479 @@
+//! Detecting logical conflicts between a transaction and the commits that were written after the
+//! transaction's read version (the "winning" commits).
+//!
+//! When a commit fails because its version already exists, the transaction can still be committed
+//! at a later version as long as none of the winning commits logically conflict with it. Which
+//! concurrent changes conflict depends on the [`IsolationLevel`] of the table:
+//! - Changes to the protocol or metadata, `txn` actions for the same app id, and changes to the
+//!   same metadata domain always conflict.
+//! - Removing a file that the transaction also removes always conflicts.
+//! - If the transaction removes files, it read the table. Since the kernel doesn't track which
+//!   files were read, the transaction is assumed to have read the entire table:
+//!   - Under [`Serializable`], winning commits that add or remove data conflict.
+//!   - Under [`WriteSerializable`], winning commits that remove data conflict, as do winning
+//!     commits that add data unless they are blind appends. A winning commit is a blind append if
+//!     its `commitInfo` says so (`isBlindAppend`), or, without `isBlindAppend`, if it only adds
+//!     data.
+//!   - Under [`SnapshotIsolation`], winning commits that add or remove other files don't conflict.
+//!
+//! Files added or removed with `dataChange = false` (e.g. by compaction) only conflict if they are
+//! also removed by the transaction.
+//!
+//! [`Serializable`]: IsolationLevel::Serializable
+//! [`WriteSerializable`]: IsolationLevel::WriteSerializable
+//! [`SnapshotIsolation`]: IsolationLevel::SnapshotIsolation
+
+use std::collections::HashSet;
+use std::sync::{Arc, LazyLock};
+
+use itertools::Itertools as _;
+
+use crate::actions::{
+    get_log_schema, ADD_NAME, COMMIT_INFO_NAME, DOMAIN_METADATA_NAME, METADATA_NAME, PROTOCOL_NAME,
+    REMOVE_NAME, SET_TRANSACTION_NAME,
+};
+use crate::engine_data::{GetData, RowVisitor, TypedGetData as _};
+use crate::log_segment::list_log_files;
+use crate::path::ParsedLogPath;
+use crate::schema::{
+    column_name, ColumnName, ColumnNamesAndTypes, DataType, StructField, StructType,
+};
+use crate::table_properties::IsolationLevel;
+use crate::utils::require;
+use crate::{DeltaResult, Engine, Error, Version};
+
+/// The changes made by a transaction that can conflict with concurrent commits.
+#[derive(Debug, Default)]
+pub(crate) struct TransactionChanges {
+    /// The paths of the files removed by the transaction (including files whose deletion vector
+    /// is replaced).
+    pub(crate) removed_paths: HashSet<String>,
+    /// The app ids of the transaction's `txn` actions.
+    pub(crate) app_ids: HashSet<String>,
+    /// The metadata domains changed (or removed) by the transaction.
+    pub(crate) domains: HashSet<String>,
+}
+
+/// The reason a transaction conflicts with a winning commit.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum Conflict {
+    TableCreated,
+    ProtocolChanged,
+    MetadataChanged,
+    ConcurrentTransaction(String),
+    ConcurrentDomainMetadata(String),
+    ConcurrentDeleteDelete(String),
+    ConcurrentDeleteRead,
+    ConcurrentAppend,
+}
+
+impl std::fmt::Display for Conflict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Conflict::TableCreated => write!(f, "the table was created"),
+            Conflict::ProtocolChanged => write!(f, "the protocol was changed"),
+            Conflict::MetadataChanged => write!(f, "the metadata was changed"),
+            Conflict::ConcurrentTransaction(app_id) => {
+                write!(f, "a transaction for app id {app_id} was committed")
+            }
+            Conflict::ConcurrentDomainMetadata(domain) => {
+                write!(f, "metadata domain {domain} was changed")
+            }
+            Conflict::ConcurrentDeleteDelete(path) => write!(f, "file {path} was removed"),
+            Conflict::ConcurrentDeleteRead => {
+                write!(f, "files read by the transaction were removed")
+            }
+            Conflict::ConcurrentAppend => {
+                write!(f, "files were added that the transaction should have read")
+            }
+        }
+    }
+}
+
+/// The actions of a winning commit that matter for conflict detection.
+#[derive(Debug, Default)]
+pub(crate) struct WinningCommitSummary {
+    changes_protocol: bool,
+    changes_metadata: bool,
+    adds_data: bool,
+    removes_data: bool,
+    /// The `isBlindAppend` of the commit's `commitInfo`, if any.
+    is_blind_append: Option<bool>,
+    removed_paths: HashSet<String>,
+    app_ids: HashSet<String>,
+    domains: HashSet<String>,
+}
+
+impl WinningCommitSummary {
+    /// Read the actions of a winning commit.
+    pub(crate) fn try_new(engine: &dyn Engine, commit: &ParsedLogPath) -> DeltaResult<Self> {
+        let schema = get_log_schema().project(&[
+            ADD_NAME,
+            REMOVE_NAME,
+            METADATA_NAME,
+            PROTOCOL_NAME,
+            SET_TRANSACTION_NAME,
+            DOMAIN_METADATA_NAME,
+        ])?;
+        // the kernel doesn't read `isBlindAppend` anywhere else, so it isn't part of the commitInfo
+        // schema
+        let commit_info =
+            StructType::new([StructField::nullable("isBlindAppend", DataType::BOOLEAN)]);
+        let schema = Arc::new(StructType::new(
+            schema
+                .fields()
+                .cloned()
+                .chain([StructField::nullable(COMMIT_INFO_NAME, commit_info)]),
+        ));
+        let mut summary = Self::default();
+        for actions in
+            engine
+                .json_handler()
+                .read_json_files(&[commit.location.clone()], schema, None)?
+        {
+            summary.visit_rows_of(actions?.as_ref())?;
+        }
+        Ok(summary)
+    }
+
+    /// Check whether this winning commit conflicts with a transaction making `changes`.
+    pub(crate) fn check_conflicts(
+        &self,
+        changes: &TransactionChanges,
+        isolation_level: IsolationLevel,
+    ) -> Result<(), Conflict> {
+        if self.changes_protocol {
+            return Err(Conflict::ProtocolChanged);
+        }
+        if self.changes_metadata {
+            return Err(Conflict::MetadataChanged);
+        }
+        if let Some(app_id) = self.app_ids.intersection(&changes.app_ids).next() {
+            return Err(Conflict::ConcurrentTransaction(app_id.clone()));
+        }
+        if let Some(domain) = self.domains.intersection(&changes.domains).next() {
+            return Err(Conflict::ConcurrentDomainMetadata(domain.clone()));
+        }
+        if let Some(path) = self
+            .removed_paths
+            .intersection(&changes.removed_paths)
+            .next()
+        {
+            return Err(Conflict::ConcurrentDeleteDelete(path.clone()));
+        }
+
+        // a transaction that doesn't remove files is a blind append: it didn't read the table
+        if changes.removed_paths.is_empty() {
+            return Ok(());
+        }
+        match isolation_level {
+            IsolationLevel::Serializable | IsolationLevel::WriteSerializable
+                if self.removes_data =>
+            {
+                Err(Conflict::ConcurrentDeleteRead)
+            }
+            IsolationLevel::Serializable if self.adds_data => Err(Conflict::ConcurrentAppend),
+            // WriteSerializable allows concurrent blind appends. Without `isBlindAppend`, a winning
+            // commit that adds data is a blind append unless it also removes data (caught above)
+            IsolationLevel::WriteSerializable
+                if self.adds_data && self.is_blind_append == Some(false) =>
+            {
+                Err(Conflict::ConcurrentAppend)
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+impl RowVisitor for WinningCommitSummary {
+    fn selected_column_names_and_types(&self) -> (&'static [ColumnName], &'static [DataType]) {
+        static NAMES_AND_TYPES: LazyLock<ColumnNamesAndTypes> = LazyLock::new(|| {
+            let names = vec![
+                column_name!("add.path"),
+                column_name!("add.dataChange"),
+                column_name!("remove.path"),
+                column_name!("remove.dataChange"),
+                column_name!("metaData.id"),
+                column_name!("protocol.minReaderVersion"),
+                column_name!("txn.appId"),
+                column_name!("domainMetadata.domain"),
+                column_name!("commitInfo.isBlindAppend"),
+            ];
+            let types = vec![
+                DataType::STRING,
+                DataType::BOOLEAN,
+                DataType::STRING,
+                DataType::BOOLEAN,
+                DataType::STRING,
+                DataType::INTEGER,
+                DataType::STRING,
+                DataType::STRING,
+                DataType::BOOLEAN,
+            ];
+            (names, types).into()
+        });
+        NAMES_AND_TYPES.as_ref()
+    }
+
+    fn visit<'a>(&mut self, row_count: usize, getters: &[&'a dyn GetData<'a>]) -> DeltaResult<()> {
+        require!(
+            getters.len() == 9,
+            Error::InternalError(format!(
+                "Wrong number of WinningCommitSummary getters: {}",
+                getters.len()
+            ))
+        );
+        for i in 0..row_count {
+            let add_path: Option<String> = getters[0].get_opt(i, "add.path")?;
+            let metadata_id: Option<String> = getters[4].get_opt(i, "metaData.id")?;
+            let min_reader_version: Option<i32> =
+                getters[5].get_opt(i, "protocol.minReaderVersion")?;
+            if add_path.is_some() {
+                let data_change: bool = getters[1].get(i, "add.dataChange")?;
+                self.adds_data |= data_change;
+            } else if let Some(path) = getters[2].get_opt(i, "remove.path")? {
+                let data_change: bool = getters[3].get(i, "remove.dataChange")?;
+                self.removes_data |= data_change;
+                self.removed_paths.insert(path);
+            } else if metadata_id.is_some() {
+                self.changes_metadata = true;
+            } else if min_reader_version.is_some() {
+                self.changes_protocol = true;
+            } else if let Some(app_id) = getters[6].get_opt(i, "txn.appId")? {
+                self.app_ids.insert(app_id);
+            } else if let Some(domain) = getters[7].get_opt(i, "domainMetadata.domain")? {
+                self.domains.insert(domain);
+            } else if let Some(is_blind_append) =
+                getters[8].get_opt(i, "commitInfo.isBlindAppend")?
+            {
+                self.is_blind_append = Some(is_blind_append);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The commits written after `read_version`, in ascending order (empty if there are none).
+pub(crate) fn winning_commits(
+    engine: &dyn Engine,
+    log_root: &url::Url,
+    read_version: Version,
+) -> DeltaResult<Vec<ParsedLogPath>> {
+    let commits: Vec<_> = list_log_files(
+        engine.storage_handler().as_ref(),
+        log_root,
+        read_version + 1,
+        None,
+    )?
+    .filter_ok(|path| path.is_commit())
+    .try_collect()?;
+    // the winning commits must be contiguous, starting right after the read version
+    for (expected_version, commit) in (read_version + 1..).zip(&commits) {
+        require!(
+            commit.version == expected_version,
+            Error::generic(format!(
+                "Expected commit version {expected_version}, found version {}",
+                commit.version
+            ))
+        );
+    }
+    Ok(commits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn changes(removed_paths: &[&str], app_ids: &[&str]) -> TransactionChanges {
+        TransactionChanges {
+            removed_paths: removed_paths.iter().map(|p| p.to_string()).collect(),
+            app_ids: app_ids.iter().map(|a| a.to_string()).collect(),
+            domains: HashSet::new(),
+        }
+    }
+
+    #[test]
+    fn test_blind_append_conflicts() {
+        let append = changes(&[], &["app"]);
+        let winning_append = WinningCommitSummary {
+            adds_data: true,
+            ..Default::default()
+        };
+        let winning_delete = WinningCommitSummary {
+            removes_data: true,
+            removed_paths: HashSet::from(["a".to_string()]),
+            ..Default::default()
+        };
+        for isolation_level in [
+            IsolationLevel::Serializable,
+            IsolationLevel::WriteSerializable,
+        ] {
+            assert_eq!(
+                winning_append.check_conflicts(&append, isolation_level),
+                Ok(())
+            );
+            assert_eq!(
+                winning_delete.check_conflicts(&append, isolation_level),
+                Ok(())
+            );
+        }
+
+        let winning_metadata = WinningCommitSummary {
+            changes_metadata: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            winning_metadata.check_conflicts(&append, IsolationLevel::WriteSerializable),
+            Err(Conflict::MetadataChanged)
+        );
+        let winning_protocol = WinningCommitSummary {
+            changes_protocol: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            winning_protocol.check_conflicts(&append, IsolationLevel::WriteSerializable),
+            Err(Conflict::ProtocolChanged)
+        );
+        let winning_txn = WinningCommitSummary {
+            app_ids: HashSet::from(["app".to_string()]),
+            ..Default::default()
+        };
+        assert_eq!(
+            winning_txn.check_conflicts(&append, IsolationLevel::WriteSerializable),
+            Err(Conflict::ConcurrentTransaction("app".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_domain_metadata_conflicts() {
+        let changes = TransactionChanges {
+            domains: HashSet::from(["domain".to_string()]),
+            ..Default::default()
+        };
+        let winning_same_domain = WinningCommitSummary {
+            domains: HashSet::from(["domain".to_string()]),
+            ..Default::default()
+        };
+        assert_eq!(
+            winning_same_domain.check_conflicts(&changes, IsolationLevel::SnapshotIsolation),
+            Err(Conflict::ConcurrentDomainMetadata("domain".to_string()))
+        );
+        let winning_other_domain = WinningCommitSummary {
+            domains: HashSet::from(["other".to_string()]),
+            ..Default::default()
+        };
+        assert_eq!(
+            winning_other_domain.check_conflicts(&changes, IsolationLevel::Serializable),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_remove_conflicts() {
+        let delete = changes(&["a"], &[]);
+
+        // removing the same file always conflicts, even when compacting (dataChange = false)
+        let winning_compaction = WinningCommitSummary {
+            adds_data: false,
+            removed_paths: HashSet::from(["a".to_string()]),
+            ..Default::default()
+        };
+        assert_eq!(
+            winning_compaction.check_conflicts(&delete, IsolationLevel::SnapshotIsolation),
+            Err(Conflict::ConcurrentDeleteDelete("a".to_string()))
+        );
+        let winning_compaction = WinningCommitSummary {
+            removed_paths: HashSet::from(["b".to_string()]),
+            ..Default::default()
+        };
+        assert_eq!(
+            winning_compaction.check_conflicts(&delete, IsolationLevel::Serializable),
+            Ok(())
+        );
+
+        // blind appends only conflict under Serializable
+        let winning_append = WinningCommitSummary {
+            adds_data: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            winning_append.check_conflicts(&delete, IsolationLevel::Serializable),
+            Err(Conflict::ConcurrentAppend)
+        );
+        assert_eq!(
+            winning_append.check_conflicts(&delete, IsolationLevel::WriteSerializable),
+            Ok(())
+        );
+        let winning_blind_append = WinningCommitSummary {
+            is_blind_append: Some(true),
+            ..winning_append
+        };
+        assert_eq!(
+            winning_blind_append.check_conflicts(&delete, IsolationLevel::WriteSerializable),
+            Ok(())
+        );
+
+        // appends that read the table conflict under WriteSerializable too
+        let winning_read_append = WinningCommitSummary {
+            adds_data: true,
+            is_blind_append: Some(false),
+            ..Default::default()
+        };
+        assert_eq!(
+            winning_read_append.check_conflicts(&delete, IsolationLevel::WriteSerializable),
+            Err(Conflict::ConcurrentAppend)
+        );
+        assert_eq!(
+            winning_read_append.check_conflicts(&delete, IsolationLevel::SnapshotIsolation),
+            Ok(())
+        );
+
+        // removing other data conflicts unless using snapshot isolation
+        let winning_update = WinningCommitSummary {
+            adds_data: true,
+            removes_data: true,
+            removed_paths: HashSet::from(["b".to_string()]),
+            ..Default::default()
+        };
+        assert_eq!(
+            winning_update.check_conflicts(&delete, IsolationLevel::WriteSerializable),
+            Err(Conflict::ConcurrentDeleteRead)
+        );
+        assert_eq!(
+            winning_update.check_conflicts(&delete, IsolationLevel::SnapshotIsolation),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_read_is_blind_append() -> DeltaResult<()> {
+        let engine = crate::engine::sync::SyncEngine::new();
+        let dir = tempfile::tempdir().unwrap();
+        let read_commit = |version: Version, actions: &str| -> DeltaResult<_> {
+            let path = dir.path().join(format!("{version:020}.json"));
+            std::fs::write(&path, actions).unwrap();
+            let location = crate::FileMeta {
+                location: url::Url::from_file_path(path).unwrap(),
+                last_modified: 0,
+                size: actions.len() as u64,
+            };
+            let commit = ParsedLogPath::try_from(location)?.unwrap();
+            WinningCommitSummary::try_new(&engine, &commit)
+        };
+        let add = r#"{"add":{"path":"a","partitionValues":{},"size":1,"modificationTime":1,"dataChange":true}}"#;
+
+        let summary = read_commit(
+            1,
+            &format!("{{\"commitInfo\":{{\"isBlindAppend\":false}}}}\n{add}"),
+        )?;
+        assert!(summary.adds_data);
+        assert_eq!(summary.is_blind_append, Some(false));
+        let summary = read_commit(
+            2,
+            &format!("{{\"commitInfo\":{{\"operation\":\"WRITE\"}}}}\n{add}"),
+        )?;
+        assert!(summary.adds_data);
+        assert_eq!(summary.is_blind_append, None);
+        Ok(())
+    }
+}
@@ -0,0 +1,448 @@
+//! Creating new tables.
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::str::FromStr as _;
+use std::sync::Arc;
+
+use itertools::Itertools as _;
+use url::Url;
+
+use super::row_tracking::{
+    MATERIALIZED_ROW_COMMIT_VERSION_COLUMN_PROPERTY, MATERIALIZED_ROW_ID_COLUMN_PROPERTY,
+};
+use super::Transaction;
+use crate::actions::{Metadata, Protocol};
+use crate::schema::{
+    ColumnMetadataKey, DataType, InvariantChecker, MetadataValue, SchemaRef, SchemaTransform,
+    StructField, StructType,
+};
+use crate::table_configuration::TableConfiguration;
+use crate::table_features::{
+    schema_contains_timestamp_ntz, ColumnMappingMode, ReaderFeature, WriterFeature,
+};
+use crate::table_properties::{CheckpointPolicy, TableProperties};
+use crate::utils::{current_time_ms, require};
+use crate::{DeltaResult, Error};
+
+/// Table properties with this prefix add a table feature to the protocol of the new table (e.g.
+/// `delta.feature.deletionVectors = supported`) instead of being stored in the table metadata.
+const FEATURE_PROPERTY_PREFIX: &str = "delta.feature.";
+
+/// The table property that records the largest column mapping id assigned to a field.
+const MAX_COLUMN_ID_PROPERTY: &str = "delta.columnMapping.maxColumnId";
+
+/// Builds a [`Transaction`] that creates a new table. Committing the transaction writes version 0
+/// of the table, with the table's protocol, metadata, and any files added by the transaction. If
+/// the table already exists, the commit returns [`CommitResult::Conflict`].
+///
+/// The protocol of the new table is the minimum protocol that supports the requested table
+/// features and the features required by the table properties and schema (e.g.
+/// `delta.enableDeletionVectors = true` requires the `deletionVectors` feature). Legacy protocol
+/// versions are used when they are sufficient, otherwise the table uses table features. Creating
+/// the table fails if the kernel can't write to a table with the resulting protocol.
+///
+/// If the table uses column mapping (`delta.columnMapping.mode`) and the schema has no column
+/// mapping annotations, every field is assigned a column mapping id and a random physical name.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// let txn = CreateTableBuilder::new(table_root, schema)
+///     .with_partition_columns(["date"])
+///     .with_table_properties([("delta.enableDeletionVectors", "true")])
+///     .build()?
+///     .with_commit_info(commit_info);
+/// txn.commit(&engine)?;
+/// ```
+///
+/// [`CommitResult::Conflict`]: super::CommitResult::Conflict
+#[derive(Debug)]
+pub struct CreateTableBuilder {
+    table_root: Url,
+    schema: SchemaRef,
+    partition_columns: Vec<String>,
+    table_properties: HashMap<String, String>,
+    table_features: Vec<String>,
+}
+
+impl CreateTableBuilder {
+    /// Create a builder for a new table at `table_root` with the given schema.
+    pub fn new(table_root: Url, schema: SchemaRef) -> Self {
+        Self {
+            table_root,
+            schema,
+            partition_columns: vec![],
+            table_properties: HashMap::new(),
+            table_features: vec![],
+        }
+    }
+
+    /// Partition the table by the given top-level columns of the schema.
+    pub fn with_partition_columns(
+        mut self,
+        partition_columns: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        self.partition_columns = partition_columns.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Set table properties (the `configuration` of the table's metadata). Properties of the form
+    /// `delta.feature.<name> = supported` (or `enabled`) add the table feature `<name>` to the protocol instead.
+    pub fn with_table_properties(
+        mut self,
+        table_properties: impl IntoIterator<Item = (impl Into<String>, impl Into<String>)>,
+    ) -> Self {
+        self.table_properties.extend(
+            table_properties
+                .into_iter()
+                .map(|(key, value)| (key.into(), value.into())),
+        );
+        self
+    }
+
+    /// Add table features (by name, e.g. `deletionVectors`) to the protocol of the new table, in
+    /// addition to the features required by the table properties and schema.
+    pub fn with_table_features(
+        mut self,
+        table_features: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        self.table_features
+            .extend(table_features.into_iter().map(Into::into));
+        self
+    }
+
+    /// Build the transaction that creates the table. This validates the table definition: the
+    /// partition columns, the table features, and the resulting protocol and metadata.
+    pub fn build(self) -> DeltaResult<Transaction> {
+        validate_partition_columns(&self.schema, &self.partition_columns)?;
+
+        let mut feature_names = self.table_features;
+        let mut configuration = HashMap::new();
+        for (key, value) in self.table_properties {
+            match key.strip_prefix(FEATURE_PROPERTY_PREFIX) {
+                Some(feature_name) => {
+                    require!(
+                        value == "supported" || value == "enabled",
+                        Error::generic(format!(
+                            "Invalid value '{value}' for table property {key}: expected 'supported' or 'enabled'"
+                        ))
+                    );
+                    feature_names.push(feature_name.to_string());
+                }
+                None => {
+                    configuration.insert(key, value);
+                }
+            }
+        }
+
+        // assign column mapping ids and physical names, unless the schema already has them
+        let table_properties = TableProperties::from(configuration.iter());
+        let mut schema = self.schema;
+        if table_properties
+            .column_mapping_mode
+            .is_some_and(|mode| mode != ColumnMappingMode::None)
+            && !has_column_mapping_annotations(&schema)
+        {
+            let (annotated_schema, max_column_id) = assign_column_mapping(&schema);
+            schema = Arc::new(annotated_schema);
+            configuration.insert(
+                MAX_COLUMN_ID_PROPERTY.to_string(),
+                max_column_id.to_string(),
+            );
+        }
+
+        let mut features: Vec<WriterFeature> = feature_names
+            .iter()
+            .map(|name| match WriterFeature::from_str(name) {
+                Ok(WriterFeature::Unknown(_)) | Err(_) => {
+                    Err(Error::unsupported(format!("Unknown table feature: {name}")))
+                }
+                Ok(feature) => Ok(feature),
+            })
+            .try_collect()?;
+        features.extend(required_features(&table_properties, &schema));
+
+        // row tracking stores its high-water mark in a metadata domain, and names the columns that
+        // can materialize row ids and row commit versions in the table properties
+        if features.contains(&WriterFeature::RowTracking) {
+            features.push(WriterFeature::DomainMetadata);
+            for (property, prefix) in [
+                (MATERIALIZED_ROW_ID_COLUMN_PROPERTY, "_row-id-col-"),
+                (
+                    MATERIALIZED_ROW_COMMIT_VERSION_COLUMN_PROPERTY,
+                    "_row-commit-version-col-",
+                ),
+            ] {
+                configuration
+                    .entry(property.to_string())
+                    .or_insert_with(|| format!("{prefix}{}", uuid::Uuid::new_v4()));
+            }
+        }
+
+        let commit_timestamp = current_time_ms()?;
+        let metadata = Metadata::try_new(
+            &schema,
+            self.partition_columns,
+            configuration,
+            commit_timestamp,
+        )?;
+        let protocol = minimal_protocol(features)?;
+        let table_configuration =
+            TableConfiguration::try_new(metadata, protocol, self.table_root, 0)?;
+        Transaction::try_new_create_table(table_configuration, commit_timestamp)
+    }
+}
+
+/// Whether any field of the schema has a column mapping physical name.
+fn has_column_mapping_annotations(schema: &StructType) -> bool {
+    struct AnnotationChecker {
+        found: bool,
+    }
+    impl<'a> SchemaTransform<'a> for AnnotationChecker {
+        fn transform_struct_field(
+            &mut self,
+            field: &'a StructField,
+        ) -> Option<Cow<'a, StructField>> {
+            if field
+                .get_config_value(&ColumnMetadataKey::ColumnMappingPhysicalName)
+                .is_some()
+            {
+                self.found = true;
+            } else if !self.found {
+                let _ = self.recurse_into_struct_field(field);
+            }
+            Some(Cow::Borrowed(field))
+        }
+    }
+    let mut checker = AnnotationChecker { found: false };
+    let _ = checker.transform_struct(schema);
+    checker.found
+}
+
+/// Annotate every field of the schema (including nested fields) with a new column mapping id and
+/// a unique physical name. Returns the annotated schema and the largest assigned id.
+fn assign_column_mapping(schema: &StructType) -> (StructType, i64) {
+    struct AssignColumnMapping {
+        max_column_id: i64,
+    }
+    impl<'a> SchemaTransform<'a> for AssignColumnMapping {
+        fn transform_struct_field(
+            &mut self,
+            field: &'a StructField,
+        ) -> Option<Cow<'a, StructField>> {
+            self.max_column_id += 1;
+            let id = self.max_column_id;
+            let mut field = self.recurse_into_struct_field(field)?.into_owned();
+            field.metadata.extend([
+                (
+                    ColumnMetadataKey::ColumnMappingId.as_ref().to_string(),
+                    MetadataValue::Number(id),
+                ),
+                (
+                    ColumnMetadataKey::ColumnMappingPhysicalName
+                        .as_ref()
+                        .to_string(),
+                    MetadataValue::String(format!("col-{}", uuid::Uuid::new_v4())),
+                ),
+            ]);
+            Some(Cow::Owned(field))
+        }
+    }
+    let mut assigner = AssignColumnMapping { max_column_id: 0 };
+    // NOTE: unwrap is safe because the transformer is incapable of returning None
+    let schema = assigner.transform_struct(schema).unwrap().into_owned();
+    (schema, assigner.max_column_id)
+}
+
+/// Partition columns must be distinct, top-level, primitive columns of the schema.
+fn validate_partition_columns(
+    schema: &StructType,
+    partition_columns: &[String],
+) -> DeltaResult<()> {
+    for (i, column) in partition_columns.iter().enumerate() {
+        let field = schema.field(column).ok_or_else(|| {
+            Error::generic(format!(
+                "Partition column {column} is not in the table schema"
+            ))
+        })?;
+        require!(
+            matches!(field.data_type(), DataType::Primitive(_)),
+            Error::generic(format!(
+                "Partition column {column} has non-primitive type {}",
+                field.data_type()
+            ))
+        );
+        require!(
+            !partition_columns[..i].contains(column),
+            Error::generic(format!("Duplicate partition column {column}"))
+        );
+    }
+    Ok(())
+}
+
+/// The table features required by the table properties and schema of a new table.
+fn required_features(
+    table_properties: &TableProperties,
+    schema: &StructType,
+) -> Vec<WriterFeature> {
+    let enabled = |property: Option<bool>| property == Some(true);
+    let mut features = vec![];
+    if enabled(table_properties.append_only) {
+        features.push(WriterFeature::AppendOnly);
+    }
+    if enabled(table_properties.enable_change_data_feed) {
+        features.push(WriterFeature::ChangeDataFeed);
+    }
+    if table_properties
+        .column_mapping_mode
+        .is_some_and(|mode| mode != ColumnMappingMode::None)
+    {
+        features.push(WriterFeature::ColumnMapping);
+    }
+    if enabled(table_properties.enable_deletion_vectors) {
+        features.push(WriterFeature::DeletionVectors);
+    }
+    if enabled(table_properties.enable_in_commit_timestamps) {
+        features.push(WriterFeature::InCommitTimestamp);
+    }
+    if enabled(table_properties.enable_row_tracking) {
+        features.push(WriterFeature::RowTracking);
+        features.push(WriterFeature::DomainMetadata);
+    }
+    if table_properties.checkpoint_policy == Some(CheckpointPolicy::V2) {
+        features.push(WriterFeature::V2Checkpoint);
+    }
+    if InvariantChecker::has_invariants(schema) {
+        features.push(WriterFeature::Invariants);
+    }
+    if schema_contains_timestamp_ntz(schema) {
+        features.push(WriterFeature::TimestampWithoutTimezone);
+    }
+    features
+}
+
+/// The minimum protocol that supports all the given features. Legacy reader and writer versions
+/// are used if all features are legacy features, otherwise the protocol lists table features.
+fn minimal_protocol(features: Vec<WriterFeature>) -> DeltaResult<Protocol> {
+    let features = features.into_iter().unique().collect_vec();
+    let reader_features = features
+        .iter()
+        .filter_map(WriterFeature::reader_feature)
+        .collect_vec();
+
+    // column mapping is the only reader feature with a legacy reader version
+    let min_reader_version = if reader_features.is_empty() {
+        1
+    } else if reader_features == [ReaderFeature::ColumnMapping] {
+        2
+    } else {
+        3
+    };
+    let reader_features = (min_reader_version == 3).then_some(reader_features);
+
+    let legacy_writer_version = features
+        .iter()
+        .map(WriterFeature::legacy_writer_version)
+        .try_fold(1, |version, feature_version| {
+            Some(version.max(feature_version?))
+        });
+    match legacy_writer_version {
+        Some(min_writer_version) => Protocol::try_new(
+            min_reader_version,
+            min_writer_version,
+            reader_features,
+            None::<Vec<WriterFeature>>,
+        ),
+        None => Protocol::try_new(min_reader_version, 7, reader_features, Some(features)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_minimal_protocol() {
+        let protocol = minimal_protocol(vec![]).unwrap();
+        assert_eq!(
+            protocol,
+            Protocol::try_new(1, 1, None::<Vec<String>>, None::<Vec<String>>).unwrap()
+        );
+
+        let protocol =
+            minimal_protocol(vec![WriterFeature::AppendOnly, WriterFeature::AppendOnly]).unwrap();
+        assert_eq!(
+            protocol,
+            Protocol::try_new(1, 2, None::<Vec<String>>, None::<Vec<String>>).unwrap()
+        );
+
+        let protocol = minimal_protocol(vec![
+            WriterFeature::AppendOnly,
+            WriterFeature::ColumnMapping,
+        ])
+        .unwrap();
+        assert_eq!(
+            protocol,
+            Protocol::try_new(2, 5, None::<Vec<String>>, None::<Vec<String>>).unwrap()
+        );
+
+        let protocol = minimal_protocol(vec![
+            WriterFeature::InCommitTimestamp,
+            WriterFeature::AppendOnly,
+        ])
+        .unwrap();
+        let expected = Protocol::try_new(
+            1,
+            7,
+            None::<Vec<String>>,
+            Some([WriterFeature::InCommitTimestamp, WriterFeature::AppendOnly]),
+        )
+        .unwrap();
+        assert_eq!(protocol, expected);
+
+        let protocol = minimal_protocol(vec![
+            WriterFeature::DeletionVectors,
+            WriterFeature::ColumnMapping,
+        ])
+        .unwrap();
+        let expected = Protocol::try_new(
+            3,
+            7,
+            Some([ReaderFeature::DeletionVectors, ReaderFeature::ColumnMapping]),
+            Some([WriterFeature::DeletionVectors, WriterFeature::ColumnMapping]),
+        )
+        .unwrap();
+        assert_eq!(protocol, expected);
+    }
+
+    #[test]
+    fn test_feature_table_properties() {
+        let table_root = Url::parse("memory:///table/").unwrap();
+        let schema = Arc::new(StructType::new(vec![StructField::nullable(
+            "value",
+            DataType::INTEGER,
+        )]));
+        let build = |value: &str| {
+            CreateTableBuilder::new(table_root.clone(), schema.clone())
+                .with_table_properties([("delta.feature.deletionVectors", value)])
+                .build()
+        };
+
+        for value in ["supported", "enabled"] {
+            let txn = build(value).unwrap();
+            let protocol = txn.table_configuration.protocol();
+            assert!(protocol.has_writer_feature(&WriterFeature::DeletionVectors));
+            assert!(!txn
+                .table_configuration
+                .metadata()
+                .configuration()
+                .contains_key("delta.feature.deletionVectors"));
+        }
+
+        let err = build("true").unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Invalid value 'true' for table property delta.feature.deletionVectors"));
+    }
+}
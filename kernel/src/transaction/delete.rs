@@ -0,0 +1,330 @@
+//! Deleting rows from a table by marking them in deletion vectors.
+//!
+//! Instead of rewriting the data files that contain deleted rows, a delete writes a new deletion
+//! vector for each affected file (combining the newly deleted rows with any rows the file's current
+//! deletion vector already removes), then replaces the file's `add` action with a `remove` and
+//! `add` pair that carries the new deletion vector.
+
+use std::collections::HashMap;
+use std::sync::{Arc, LazyLock};
+
+use roaring::RoaringTreemap;
+use tracing::warn;
+
+use crate::actions::deletion_vector::{DeletionVectorDescriptor, DeletionVectorWriter};
+use crate::actions::visitors::visit_deletion_vector_at;
+use crate::engine_data::{GetData, RowVisitor, TypedGetData as _};
+use crate::expressions::{MapData, Scalar};
+use crate::scan::get_transform_for_row;
+use crate::scan::log_replay::SCAN_ROW_SCHEMA;
+use crate::scan::state::transform_to_logical;
+use crate::schema::{column_name, ColumnName, ColumnNamesAndTypes, DataType};
+use crate::snapshot::Snapshot;
+use crate::utils::require;
+use crate::{
+    DeltaResult, Engine, EngineData, Error, EvaluationHandlerExtension as _, ExpressionRef,
+    FileMeta, PredicateRef, Version,
+};
+
+use super::{remove_files_schema, string_map_type, AddAction};
+
+/// The actions a delete needs to commit.
+#[derive(Default)]
+pub(crate) struct DeletePlan {
+    /// One row (in the [`remove_files_schema`]) for each file whose deletion vector is replaced
+    /// or that is removed entirely.
+    pub(crate) removed_files: Vec<Box<dyn EngineData>>,
+    /// The replacement `add` actions for files that still have rows left after the delete.
+    pub(crate) added_files: Vec<AddAction>,
+    /// The number of rows deleted by the delete (not counting rows that were already deleted).
+    pub(crate) num_deleted_rows: u64,
+}
+
+/// Find the rows of `snapshot` matching `predicate`, and write a deletion vector file that deletes
+/// them.
+///
+/// # Errors
+///
+/// Returns an error if a file that may contain matching rows (or its deletion vector) cannot be
+/// read, or if the new deletion vector file cannot be written.
+pub(crate) fn plan_delete(
+    engine: &dyn Engine,
+    snapshot: Arc<Snapshot>,
+    predicate: PredicateRef,
+) -> DeltaResult<DeletePlan> {
+    let table_root = snapshot.table_root().clone();
+    // the partition values of scan files are keyed by the physical names of the partition columns
+    let schema = snapshot.schema();
+    let partition_columns = snapshot
+        .metadata()
+        .partition_columns
+        .iter()
+        .map(|column| {
+            let field = schema
+                .field(column)
+                .ok_or_else(|| Error::missing_column(column))?;
+            Ok(field.physical_name().to_string())
+        })
+        .collect::<DeltaResult<Vec<_>>>()?;
+    let scan = snapshot
+        .scan_builder()
+        .with_predicate(predicate.clone())
+        .build()?;
+    let predicate_evaluator = engine
+        .evaluation_handler()
+        .new_predicate_evaluator(scan.logical_schema().clone(), predicate.as_ref().clone());
+    let storage = engine.storage_handler();
+
+    // all new deletion vectors are written to the same file
+    let mut dv_writer = DeletionVectorWriter::try_new(&table_root)?;
+    let mut plan = DeletePlan::default();
+    for scan_metadata in scan.scan_metadata(engine)? {
+        let scan_metadata = scan_metadata?;
+        let mut visitor = CandidateFileVisitor {
+            selection_vector: &scan_metadata.scan_files.selection_vector,
+            files: vec![],
+        };
+        visitor.visit_rows_of(scan_metadata.scan_files.data.as_ref())?;
+
+        for file in visitor.files {
+            let transform: Option<ExpressionRef> =
+                get_transform_for_row(file.row_index, &scan_metadata.scan_file_transforms);
+            let mut deleted_rows = match file.deletion_vector {
+                Some(ref dv) => dv.read(storage.clone(), &table_root)?,
+                None => RoaringTreemap::new(),
+            };
+            let previously_deleted = deleted_rows.len();
+
+            // find the rows matching the predicate. We read all rows of the file (without any
+            // predicate pushdown) so that the row indexes in the deletion vector are correct.
+            let meta = FileMeta {
+                location: table_root.join(&file.path)?,
+                last_modified: file.modification_time,
+                size: file.size.try_into().map_err(|_| {
+                    Error::generic("Unable to convert scan file size into FileSize")
+                })?,
+            };
+            let mut num_rows = 0;
+            for physical_data in engine.parquet_handler().read_parquet_files(
+                &[meta],
+                scan.physical_schema().clone(),
+                None,
+            )? {
+                let logical_data = transform_to_logical(
+                    engine,
+                    physical_data?,
+                    scan.physical_schema(),
+                    scan.logical_schema(),
+                    &transform,
+                )?;
+                let matches = predicate_evaluator.evaluate(logical_data.as_ref())?;
+                let mut visitor = MatchingRowsVisitor {
+                    row_offset: num_rows,
+                    deleted_rows: &mut deleted_rows,
+                };
+                visitor.visit_rows_of(matches.as_ref())?;
+                num_rows += logical_data.len() as u64;
+            }
+
+            let newly_deleted = deleted_rows.len() - previously_deleted;
+            if newly_deleted == 0 {
+                continue;
+            }
+            plan.num_deleted_rows += newly_deleted;
+
+            // partition values that are null are absent from the scan file's map, but the actions
+            // must include every partition column
+            let partition_values = MapData::try_new(
+                string_map_type(),
+                partition_columns.iter().map(|column| {
+                    let value = file.partition_values.get(column).cloned();
+                    (column.as_str(), Scalar::from(value))
+                }),
+            )?;
+
+            let remove = remove_files_row(engine, &file, partition_values.clone())?;
+            plan.removed_files.push(remove);
+
+            // a file with no rows left is removed without replacement
+            if deleted_rows.len() < num_rows {
+                let deletion_vector = dv_writer.append(&deleted_rows)?;
+                plan.added_files.push(AddAction {
+                    path: file.path,
+                    partition_values,
+                    size: file.size,
+                    modification_time: file.modification_time,
+                    data_change: true,
+                    stats: match file.stats {
+                        Some(stats) => loosen_stats(&stats),
+                        // keep the typed number of records, e.g. to assign row ids to the file
+                        None => file.num_records.map(|num_records| {
+                            serde_json::json!({"numRecords": num_records, "tightBounds": false})
+                                .to_string()
+                        }),
+                    },
+                    tags: file.tags,
+                    deletion_vector: Some(deletion_vector),
+                    base_row_id: file.base_row_id,
+                    default_row_commit_version: file
+                        .default_row_commit_version
+                        .map(Version::try_from)
+                        .transpose()
+                        .map_err(|_| Error::generic("Invalid defaultRowCommitVersion"))?,
+                    clustering_provider: file.clustering_provider,
+                });
+            }
+        }
+    }
+    if !dv_writer.is_empty() {
+        dv_writer.finish(storage.as_ref())?;
+    }
+    Ok(plan)
+}
+
+/// Create a single row of engine data (in the [`remove_files_schema`]) that removes `file` along
+/// with its current deletion vector.
+fn remove_files_row(
+    engine: &dyn Engine,
+    file: &CandidateFile,
+    partition_values: MapData,
+) -> DeltaResult<Box<dyn EngineData>> {
+    let dv = file.deletion_vector.as_ref();
+    let tags = match &file.tags {
+        Some(tags) => Scalar::Map(MapData::try_new(string_map_type(), tags.clone())?),
+        None => Scalar::Null(string_map_type().into()),
+    };
+    let values = [
+        file.path.as_str().into(),
+        Scalar::Map(partition_values),
+        file.size.into(),
+        true.into(), // dataChange
+        tags,
+        dv.map(|dv| dv.storage_type.clone()).into(),
+        dv.map(|dv| dv.path_or_inline_dv.clone()).into(),
+        dv.and_then(|dv| dv.offset).into(),
+        dv.map(|dv| dv.size_in_bytes).into(),
+        dv.map(|dv| dv.cardinality).into(),
+        file.base_row_id.into(),
+        file.default_row_commit_version.into(),
+    ];
+    engine
+        .evaluation_handler()
+        .create_one(remove_files_schema().clone(), &values)
+}
+
+/// Once a file has deleted rows, its min/max stats may no longer be tight: they can describe values
+/// that only appear in deleted rows. Mark the stats as wide by setting `tightBounds` to false.
+fn loosen_stats(stats: &str) -> Option<String> {
+    match serde_json::from_str(stats) {
+        Ok(serde_json::Value::Object(mut stats)) => {
+            stats.insert("tightBounds".to_string(), false.into());
+            Some(serde_json::Value::Object(stats).to_string())
+        }
+        _ => {
+            warn!("Dropping invalid stats string in Add file: {stats}");
+            None
+        }
+    }
+}
+
+/// A file that may contain rows matching the delete predicate.
+struct CandidateFile {
+    // the index of the file's row in the scan metadata, used to look up its transform
+    row_index: usize,
+    path: String,
+    size: i64,
+    modification_time: i64,
+    stats: Option<String>,
+    // the typed number of records of the file, for files without JSON stats
+    num_records: Option<i64>,
+    deletion_vector: Option<DeletionVectorDescriptor>,
+    partition_values: HashMap<String, String>,
+    base_row_id: Option<i64>,
+    default_row_commit_version: Option<i64>,
+    tags: Option<HashMap<String, String>>,
+    clustering_provider: Option<String>,
+}
+
+/// Collects the selected files of a batch of scan metadata.
+struct CandidateFileVisitor<'a> {
+    selection_vector: &'a [bool],
+    files: Vec<CandidateFile>,
+}
+
+impl RowVisitor for CandidateFileVisitor<'_> {
+    fn selected_column_names_and_types(&self) -> (&'static [ColumnName], &'static [DataType]) {
+        static NAMES_AND_TYPES: LazyLock<ColumnNamesAndTypes> =
+            LazyLock::new(|| SCAN_ROW_SCHEMA.leaves(None));
+        NAMES_AND_TYPES.as_ref()
+    }
+
+    fn visit<'a>(&mut self, row_count: usize, getters: &[&'a dyn GetData<'a>]) -> DeltaResult<()> {
+        require!(
+            getters.len() == 15,
+            Error::InternalError(format!(
+                "Wrong number of CandidateFileVisitor getters: {}",
+                getters.len()
+            ))
+        );
+        for row_index in 0..row_count {
+            if !self.selection_vector[row_index] {
+                continue;
+            }
+            if let Some(path) = getters[0].get_opt(row_index, "scanFile.path")? {
+                self.files.push(CandidateFile {
+                    row_index,
+                    path,
+                    size: getters[1].get(row_index, "scanFile.size")?,
+                    modification_time: getters[2].get(row_index, "scanFile.modificationTime")?,
+                    stats: getters[3].get_opt(row_index, "scanFile.stats")?,
+                    num_records: getters[12].get_opt(row_index, "scanFile.numRecords")?,
+                    deletion_vector: visit_deletion_vector_at(row_index, &getters[4..])?,
+                    partition_values: getters[9]
+                        .get(row_index, "scanFile.fileConstantValues.partitionValues")?,
+                    base_row_id: getters[10]
+                        .get_opt(row_index, "scanFile.fileConstantValues.baseRowId")?,
+                    default_row_commit_version: getters[11].get_opt(
+                        row_index,
+                        "scanFile.fileConstantValues.defaultRowCommitVersion",
+                    )?,
+                    tags: getters[13].get_opt(row_index, "scanFile.tags")?,
+                    clustering_provider: getters[14]
+                        .get_opt(row_index, "scanFile.clusteringProvider")?,
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Adds the index of every row for which the delete predicate is true to `deleted_rows`. Rows for
+/// which the predicate is false or null are kept.
+struct MatchingRowsVisitor<'a> {
+    // the index (in the file) of the first row of the visited batch
+    row_offset: u64,
+    deleted_rows: &'a mut RoaringTreemap,
+}
+
+impl RowVisitor for MatchingRowsVisitor<'_> {
+    fn selected_column_names_and_types(&self) -> (&'static [ColumnName], &'static [DataType]) {
+        static NAMES_AND_TYPES: LazyLock<ColumnNamesAndTypes> =
+            LazyLock::new(|| (vec![column_name!("output")], vec![DataType::BOOLEAN]).into());
+        NAMES_AND_TYPES.as_ref()
+    }
+
+    fn visit<'a>(&mut self, row_count: usize, getters: &[&'a dyn GetData<'a>]) -> DeltaResult<()> {
+        require!(
+            getters.len() == 1,
+            Error::InternalError(format!(
+                "Wrong number of MatchingRowsVisitor getters: {}",
+                getters.len()
+            ))
+        );
+        for i in 0..row_count {
+            if getters[0].get_opt(i, "output")? == Some(true) {
+                self.deleted_rows.insert(self.row_offset + i as u64);
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,1636 @@
+use std::collections::{HashMap, HashSet};
+use std::iter;
+use std::sync::{Arc, LazyLock};
+
+use crate::actions::deletion_vector::DeletionVectorDescriptor;
+use crate::actions::domain_metadata::domain_metadata_configuration;
+use crate::actions::COMMIT_INFO_NAME;
+use crate::actions::{
+    get_log_add_schema, get_log_commit_info_schema, get_log_domain_metadata_schema,
+    get_log_metadata_schema, get_log_protocol_schema, get_log_remove_schema, get_log_txn_schema,
+};
+use crate::actions::{DomainMetadata, Metadata, Protocol, Remove, SetTransaction};
+use crate::engine_data::{GetData, RowVisitor, TypedGetData as _};
+use crate::error::Error;
+use crate::expressions::{column_expr, ExpressionRef, MapData, Scalar, StructData};
+use crate::log_replay::FileActionKey;
+use crate::path::ParsedLogPath;
+use crate::scan::state::{DvInfo, Stats};
+use crate::schema::{
+    column_name, ColumnName, ColumnNamesAndTypes, MapType, SchemaRef, StructField, StructType,
+    ToSchema as _,
+};
+use crate::snapshot::Snapshot;
+use crate::table_configuration::TableConfiguration;
+use crate::table_features::WriterFeature;
+use crate::utils::{current_time_ms, require};
+use crate::{
+    DataType, DeltaResult, Engine, EngineData, EvaluationHandlerExtension as _, Expression,
+    IntoEngineData, PredicateRef, Version,
+};
+
+use tracing::info;
+use url::Url;
+
+use conflict::{winning_commits, Conflict, TransactionChanges, WinningCommitSummary};
+use row_tracking::RowIdAssigner;
+
+pub use create_table::CreateTableBuilder;
+
+mod conflict;
+mod create_table;
+mod delete;
+pub(crate) mod row_tracking;
+
+const KERNEL_VERSION: &str = env!("CARGO_PKG_VERSION");
+const UNKNOWN_OPERATION: &str = "UNKNOWN";
+
+pub(crate) static ADD_FILES_SCHEMA: LazyLock<SchemaRef> = LazyLock::new(|| {
+    Arc::new(StructType::new(vec![
+        StructField::not_null("path", DataType::STRING),
+        StructField::not_null(
+            "partitionValues",
+            MapType::new(DataType::STRING, DataType::STRING, true),
+        ),
+        StructField::not_null("size", DataType::LONG),
+        StructField::not_null("modificationTime", DataType::LONG),
+        StructField::not_null("dataChange", DataType::BOOLEAN),
+        StructField::nullable("stats", DataType::STRING),
+    ]))
+});
+
+/// This function specifies the schema for the add_files metadata. Concretely, it is the expected
+/// schema for engine data passed to [`add_files`].
+///
+/// Each row represents metadata about a file to be added to the table.
+///
+/// [`add_files`]: crate::transaction::Transaction::add_files
+pub fn add_files_schema() -> &'static SchemaRef {
+    &ADD_FILES_SCHEMA
+}
+
+pub(crate) static REMOVE_FILES_SCHEMA: LazyLock<SchemaRef> = LazyLock::new(|| {
+    Arc::new(StructType::new(vec![
+        StructField::not_null("path", DataType::STRING),
+        StructField::not_null(
+            "partitionValues",
+            MapType::new(DataType::STRING, DataType::STRING, true),
+        ),
+        StructField::not_null("size", DataType::LONG),
+        StructField::not_null("dataChange", DataType::BOOLEAN),
+        StructField::nullable(
+            "tags",
+            MapType::new(DataType::STRING, DataType::STRING, true),
+        ),
+        StructField::nullable("deletionVector", DeletionVectorDescriptor::to_schema()),
+        StructField::nullable("baseRowId", DataType::LONG),
+        StructField::nullable("defaultRowCommitVersion", DataType::LONG),
+    ]))
+});
+
+/// This function specifies the schema for the remove_files metadata. Concretely, it is the
+/// expected schema for engine data passed to [`remove_files`].
+///
+/// Each row represents metadata about a file to be removed from the table. The `path` and
+/// `deletionVector` of each row must match a file in the transaction's read snapshot. The
+/// `tags`, `baseRowId` and `defaultRowCommitVersion` of the file's add action (if any) should be
+/// carried over to its remove action. The `deletionTimestamp` of the resulting remove actions is
+/// set by the transaction.
+///
+/// [`remove_files`]: crate::transaction::Transaction::remove_files
+pub fn remove_files_schema() -> &'static SchemaRef {
+    &REMOVE_FILES_SCHEMA
+}
+
+/// A transaction represents an in-progress write to a table. After creating a transaction, changes
+/// to the table may be staged via the transaction methods before calling `commit` to commit the
+/// changes to the table.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// // create a transaction
+/// let mut txn = table.new_transaction(&engine)?;
+/// // stage table changes (right now only commit info)
+/// txn.commit_info(Box::new(ArrowEngineData::new(engine_commit_info)));
+/// // commit! (consume the transaction)
+/// txn.commit(&engine)?;
+/// ```
+pub struct Transaction {
+    // the snapshot the transaction reads from, or `None` if the transaction creates the table
+    read_snapshot: Option<Arc<Snapshot>>,
+    // the configuration of the table that the transaction writes: the configuration of the read
+    // snapshot, or of the table being created (boxed, since it is large)
+    table_configuration: Box<TableConfiguration>,
+    operation: Option<String>,
+    commit_info: Option<Arc<dyn EngineData>>,
+    add_files_metadata: Vec<Box<dyn EngineData>>,
+    remove_files_metadata: Vec<Box<dyn EngineData>>,
+    // add actions (with new deletion vectors) for files that had rows deleted by `delete`. The
+    // matching removes are part of `remove_files_metadata`.
+    deletion_vector_adds: Vec<AddAction>,
+    // NB: hashmap would require either duplicating the appid or splitting SetTransaction
+    // key/payload. HashSet requires Borrow<&str> with matching Eq, Ord, and Hash. Plus,
+    // HashSet::insert drops the to-be-inserted value without returning the existing one, which
+    // would make error messaging unnecessarily difficult. Thus, we keep Vec here and deduplicate in
+    // the commit method.
+    set_transactions: Vec<SetTransaction>,
+    // domain metadata actions to commit. Tombstones for removed domains carry an empty
+    // configuration until the commit fills in the domain's latest configuration.
+    domain_metadatas: Vec<DomainMetadata>,
+    // whether the commit enables in-commit timestamps on the table
+    enable_in_commit_timestamps: bool,
+    // commit-wide timestamp (in milliseconds since epoch) - used in the `txn` action, removes,
+    // etc. to keep all timestamps within the same commit consistent. The in-commit timestamp is
+    // instead taken when each commit attempt is written.
+    commit_timestamp: i64,
+}
+
+impl std::fmt::Debug for Transaction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&format!(
+            "Transaction {{ read_snapshot version: {:?}, commit_info: {} }}",
+            self.read_snapshot
+                .as_ref()
+                .map(|snapshot| snapshot.version()),
+            self.commit_info.is_some()
+        ))
+    }
+}
+
+impl Transaction {
+    /// Create a new transaction from a snapshot. The snapshot will be used to read the current
+    /// state of the table (e.g. to read the current version).
+    ///
+    /// Instead of using this API, the more typical (user-facing) API is
+    /// [Snapshot::transaction](crate::snapshot::Snapshot::transaction) to create a transaction from
+    /// a snapshot.
+    pub(crate) fn try_new(snapshot: impl Into<Arc<Snapshot>>) -> DeltaResult<Self> {
+        let read_snapshot = snapshot.into();
+        let table_configuration = read_snapshot.table_configuration().clone();
+        Self::try_new_with(Some(read_snapshot), table_configuration, current_time_ms()?)
+    }
+
+    /// Create a new transaction that creates a table with the given configuration (at version 0).
+    /// The protocol and metadata of the table configuration are committed along with the rest of
+    /// the transaction. See [`CreateTableBuilder`].
+    pub(crate) fn try_new_create_table(
+        table_configuration: TableConfiguration,
+        commit_timestamp: i64,
+    ) -> DeltaResult<Self> {
+        Self::try_new_with(None, table_configuration, commit_timestamp)
+    }
+
+    fn try_new_with(
+        read_snapshot: Option<Arc<Snapshot>>,
+        table_configuration: TableConfiguration,
+        commit_timestamp: i64,
+    ) -> DeltaResult<Self> {
+        // important! before a read/write to the table we must check it is supported
+        table_configuration.ensure_write_supported()?;
+
+        Ok(Transaction {
+            read_snapshot,
+            table_configuration: Box::new(table_configuration),
+            operation: None,
+            commit_info: None,
+            add_files_metadata: vec![],
+            remove_files_metadata: vec![],
+            deletion_vector_adds: vec![],
+            set_transactions: vec![],
+            domain_metadatas: vec![],
+            enable_in_commit_timestamps: false,
+            commit_timestamp,
+        })
+    }
+
+    /// Consume the transaction and commit it to the table. The result is a [CommitResult] which
+    /// will include the failed transaction in case of a conflict so the user can retry.
+    ///
+    /// If another writer committed the version this transaction tries to commit, the commits
+    /// written since the transaction's read version are checked for logical conflicts with this
+    /// transaction, according to the table's isolation level (`delta.isolationLevel`). If there
+    /// are none, the transaction is rebased onto the latest version of the table and the commit is
+    /// retried automatically. Otherwise, the result is [`CommitResult::Conflict`].
+    pub fn commit(mut self, engine: &dyn Engine) -> DeltaResult<CommitResult> {
+        // step 0: if there are txn(app_id, version) actions being committed, ensure that every
+        // `app_id` is unique.
+        let mut app_ids = HashSet::new();
+        if let Some(dup) = self
+            .set_transactions
+            .iter()
+            .find(|t| !app_ids.insert(t.app_id.clone()))
+        {
+            return Err(Error::generic(format!(
+                "app_id {} already exists in transaction",
+                dup.app_id
+            )));
+        }
+
+        // step 0.25: ensure that every domain is changed at most once, that no system-controlled
+        // domain is changed, and that every removed domain exists
+        let mut domains = HashSet::new();
+        for domain_metadata in &self.domain_metadatas {
+            if domain_metadata.is_internal() {
+                return Err(Error::generic(
+                    "User DomainMetadata are not allowed to use system-controlled 'delta.*' domain",
+                ));
+            }
+            if !domains.insert(domain_metadata.domain().to_string()) {
+                return Err(Error::generic(format!(
+                    "domain {} already exists in transaction",
+                    domain_metadata.domain()
+                )));
+            }
+        }
+        self.resolve_removed_domains(engine)?;
+
+        // step 0.5: ensure that every file being removed is part of the read snapshot
+        let removed_files = self.removed_files()?;
+        if !removed_files.is_empty() {
+            self.validate_removed_files(engine, &removed_files)?;
+        }
+        require!(self.commit_info.is_some(), Error::MissingCommitInfo);
+
+        let changes = TransactionChanges {
+            removed_paths: removed_files.into_iter().map(|key| key.path).collect(),
+            app_ids,
+            domains,
+        };
+        loop {
+            // step one: set new commit version (current_version + 1, or 0 for a new table)
+            let commit_version = self
+                .read_snapshot
+                .as_ref()
+                .map_or(0, |snapshot| snapshot.version() + 1);
+
+            // step two: commit the actions as a json file in the log, and rebase onto the winning
+            // commits if another writer committed the version first
+            match self.write_commit(engine, commit_version) {
+                Ok(()) => return Ok(CommitResult::Committed(commit_version)),
+                Err(Error::FileAlreadyExists(_)) => {
+                    if let Some(conflict) = self.rebase(engine, &changes)? {
+                        info!("Transaction conflicts with a concurrent commit: {conflict}");
+                        return Ok(CommitResult::Conflict(self, commit_version));
+                    }
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    // Write the actions of this transaction as the commit at `commit_version`.
+    fn write_commit(&self, engine: &dyn Engine, commit_version: Version) -> DeltaResult<()> {
+        let set_transaction_actions = self
+            .set_transactions
+            .clone()
+            .into_iter()
+            .map(|txn| txn.into_engine_data(get_log_txn_schema().clone(), engine));
+
+        // construct the iterator of commit info + file actions we want to commit
+        let engine_commit_info = self
+            .commit_info
+            .as_ref()
+            .ok_or_else(|| Error::MissingCommitInfo)?;
+        let in_commit_timestamp = self.in_commit_timestamp(engine)?;
+        let commit_info_actions = generate_commit_info(
+            engine,
+            self.operation.as_deref(),
+            self.commit_timestamp,
+            in_commit_timestamp,
+            engine_commit_info.as_ref(),
+        );
+        let (add_actions, row_tracking_domain_metadata) =
+            self.add_actions(engine, commit_version)?;
+        let remove_actions = generate_removes(
+            engine,
+            self.commit_timestamp,
+            self.remove_files_metadata.iter().map(|r| r.as_ref()),
+        );
+
+        let (protocol, metadata) =
+            self.protocol_and_metadata(commit_version, in_commit_timestamp)?;
+        let protocol_action = protocol
+            .map(|protocol| protocol.into_engine_data(get_log_protocol_schema().clone(), engine));
+        let metadata_action = metadata
+            .map(|metadata| metadata.into_engine_data(get_log_metadata_schema().clone(), engine));
+        let table_actions = protocol_action.into_iter().chain(metadata_action);
+        let domain_metadata_actions = self
+            .domain_metadatas
+            .clone()
+            .into_iter()
+            .chain(row_tracking_domain_metadata)
+            .map(|dm| dm.into_engine_data(get_log_domain_metadata_schema().clone(), engine));
+
+        let actions = iter::once(commit_info_actions)
+            .chain(table_actions)
+            .chain(add_actions)
+            .chain(remove_actions)
+            .chain(set_transaction_actions)
+            .chain(domain_metadata_actions);
+
+        let commit_path =
+            ParsedLogPath::new_commit(self.table_configuration.table_root(), commit_version)?;
+        engine
+            .json_handler()
+            .write_json_file(&commit_path.location, Box::new(actions), false)
+    }
+
+    // The add actions of the commit at `commit_version`, for both new files and files whose
+    // deletion vector was replaced. If the table supports row tracking, the files are assigned row
+    // ids, and the domain metadata action recording the new row id high-water mark is returned
+    // along with the add actions.
+    #[allow(clippy::type_complexity)]
+    fn add_actions<'a>(
+        &'a self,
+        engine: &'a dyn Engine,
+        commit_version: Version,
+    ) -> DeltaResult<(
+        Box<dyn Iterator<Item = DeltaResult<Box<dyn EngineData>>> + Send + 'a>,
+        Option<DomainMetadata>,
+    )> {
+        if !self.table_configuration.is_row_tracking_supported() {
+            let add_actions =
+                generate_adds(engine, self.add_files_metadata.iter().map(|a| a.as_ref()));
+            let deletion_vector_add_actions = self
+                .deletion_vector_adds
+                .iter()
+                .map(|add| add.to_engine_data(engine));
+            return Ok((
+                Box::new(add_actions.chain(deletion_vector_add_actions)),
+                None,
+            ));
+        }
+
+        let mut assigner =
+            RowIdAssigner::try_new(engine, self.read_snapshot.as_deref(), commit_version)?;
+        let mut add_actions = vec![];
+        for add_files_metadata in &self.add_files_metadata {
+            for add in assigner.assign_to_new_files(add_files_metadata.as_ref())? {
+                add_actions.push(add.to_engine_data(engine));
+            }
+        }
+        for add in &self.deletion_vector_adds {
+            let add = assigner.assign_to_deletion_vector_add(add)?;
+            add_actions.push(add.to_engine_data(engine));
+        }
+        Ok((
+            Box::new(add_actions.into_iter()),
+            assigner.high_water_mark_domain_metadata()?,
+        ))
+    }
+
+    // The in-commit timestamp of the commit, if in-commit timestamps are (or are being) enabled.
+    // In-commit timestamps must be monotonic, so this is the current time (taken anew on each
+    // commit attempt) unless that isn't later than the timestamp of the previous commit.
+    fn in_commit_timestamp(&self, engine: &dyn Engine) -> DeltaResult<Option<i64>> {
+        if !self.enable_in_commit_timestamps
+            && !self.table_configuration.is_in_commit_timestamps_enabled()
+        {
+            return Ok(None);
+        }
+        let previous_timestamp = match &self.read_snapshot {
+            None => None,
+            Some(snapshot) => match snapshot.get_in_commit_timestamp(engine)? {
+                Some(timestamp) => Some(timestamp),
+                // when enabling in-commit timestamps, the previous commit's timestamp is the
+                // modification time of its commit file
+                None => snapshot
+                    .log_segment()
+                    .ascending_commit_files
+                    .last()
+                    .filter(|commit| commit.version == snapshot.version())
+                    .map(|commit| commit.location.last_modified),
+            },
+        };
+        let now = current_time_ms()?;
+        Ok(Some(
+            previous_timestamp.map_or(now, |previous| now.max(previous + 1)),
+        ))
+    }
+
+    // The protocol and metadata actions to commit, if any. A new table's protocol and metadata are
+    // committed along with the first commit, writing domain metadata adds the `domainMetadata`
+    // table feature if needed, and enabling in-commit timestamps updates the table's metadata (and
+    // protocol, if the feature isn't supported yet).
+    fn protocol_and_metadata(
+        &self,
+        commit_version: Version,
+        in_commit_timestamp: Option<i64>,
+    ) -> DeltaResult<(Option<Protocol>, Option<Metadata>)> {
+        let creates_table = self.read_snapshot.is_none();
+        let mut protocol = creates_table.then(|| self.table_configuration.protocol().clone());
+        let mut metadata = creates_table.then(|| self.table_configuration.metadata().clone());
+        let with_writer_feature = |protocol: Option<Protocol>, feature| {
+            protocol
+                .as_ref()
+                .unwrap_or(self.table_configuration.protocol())
+                .with_writer_feature(feature)
+        };
+
+        if !self.domain_metadatas.is_empty()
+            && !self.table_configuration.is_domain_metadata_supported()
+        {
+            protocol = Some(with_writer_feature(
+                protocol,
+                WriterFeature::DomainMetadata,
+            )?);
+        }
+
+        // the enablement version and timestamp are recorded when in-commit timestamps are enabled
+        let Some(timestamp) = in_commit_timestamp else {
+            return Ok((protocol, metadata));
+        };
+        let enables_in_commit_timestamps = if creates_table {
+            self.table_configuration
+                .table_properties()
+                .in_commit_timestamp_enablement_version
+                .is_none()
+        } else {
+            !self.table_configuration.is_in_commit_timestamps_enabled()
+        };
+        if enables_in_commit_timestamps {
+            if !self.table_configuration.is_in_commit_timestamps_supported() {
+                protocol = Some(with_writer_feature(
+                    protocol,
+                    WriterFeature::InCommitTimestamp,
+                )?);
+            }
+            let mut new_metadata =
+                metadata.unwrap_or_else(|| self.table_configuration.metadata().clone());
+            new_metadata.configuration.extend(
+                [
+                    ("delta.enableInCommitTimestamps", "true".to_string()),
+                    (
+                        "delta.inCommitTimestampEnablementVersion",
+                        commit_version.to_string(),
+                    ),
+                    (
+                        "delta.inCommitTimestampEnablementTimestamp",
+                        timestamp.to_string(),
+                    ),
+                ]
+                .map(|(key, value)| (key.to_string(), value)),
+            );
+            metadata = Some(new_metadata);
+        }
+        Ok((protocol, metadata))
+    }
+
+    // Check the commits written since the read version for conflicts with this transaction. If
+    // there are none, rebase the transaction onto the latest version of the table. Returns the
+    // conflict, if any.
+    fn rebase(
+        &mut self,
+        engine: &dyn Engine,
+        changes: &TransactionChanges,
+    ) -> DeltaResult<Option<Conflict>> {
+        // a transaction that creates the table conflicts with any other writer creating it
+        let Some(read_snapshot) = self.read_snapshot.clone() else {
+            return Ok(Some(Conflict::TableCreated));
+        };
+        let isolation_level = self
+            .table_configuration
+            .table_properties()
+            .isolation_level
+            .unwrap_or_default();
+        let winning_commits = winning_commits(
+            engine,
+            &read_snapshot.log_segment().log_root,
+            read_snapshot.version(),
+        )?;
+        for commit in &winning_commits {
+            let summary = WinningCommitSummary::try_new(engine, commit)?;
+            if let Err(conflict) = summary.check_conflicts(changes, isolation_level) {
+                return Ok(Some(conflict));
+            }
+        }
+
+        // the commit failed because its version exists, so there must be a winning commit. Without
+        // one, the rebased transaction would retry the same version forever
+        let Some(latest_version) = winning_commits.last().map(|commit| commit.version) else {
+            return Err(Error::generic(format!(
+                "Commit version {} already exists, but no commits after version {} were found",
+                read_snapshot.version() + 1,
+                read_snapshot.version()
+            )));
+        };
+        let snapshot = Snapshot::try_new_from(read_snapshot, engine, Some(latest_version))?;
+        *self.table_configuration = snapshot.table_configuration().clone();
+        self.read_snapshot = Some(snapshot);
+        Ok(None)
+    }
+
+    /// Set the operation that this transaction is performing. This string will be persisted in the
+    /// commit and visible to anyone who describes the table history.
+    pub fn with_operation(mut self, operation: String) -> Self {
+        self.operation = Some(operation);
+        self
+    }
+
+    /// Include a SetTransaction (app_id and version) action for this transaction (with an optional
+    /// `last_updated` timestamp).
+    /// Note that each app_id can only appear once per transaction. That is, multiple app_ids with
+    /// different versions are disallowed in a single transaction. If a duplicate app_id is
+    /// included, the `commit` will fail (that is, we don't eagerly check app_id validity here).
+    pub fn with_transaction_id(mut self, app_id: String, version: i64) -> Self {
+        let set_transaction = SetTransaction::new(app_id, version, Some(self.commit_timestamp));
+        self.set_transactions.push(set_transaction);
+        self
+    }
+
+    /// Set the configuration of a metadata domain with this transaction. Domain metadata lets
+    /// writers store arbitrary (string) configuration for a named domain in the table, e.g. the
+    /// progress of a pipeline writing to the table. Writing domain metadata adds the
+    /// `domainMetadata` table feature to the protocol if needed.
+    ///
+    /// Note that each domain can only be changed once per transaction, and that the `delta.*`
+    /// domains are reserved for system-controlled metadata. If a domain is changed more than once
+    /// or is system-controlled, the `commit` will fail. Concurrent transactions changing the same
+    /// domain conflict.
+    pub fn with_domain_metadata(mut self, domain: String, configuration: String) -> Self {
+        self.domain_metadatas
+            .push(DomainMetadata::new(domain, configuration));
+        self
+    }
+
+    /// Remove a metadata domain with this transaction. The same restrictions as for
+    /// [`with_domain_metadata`] apply. Additionally, the `commit` will fail if the domain doesn't
+    /// exist in the table.
+    ///
+    /// [`with_domain_metadata`]: Self::with_domain_metadata
+    pub fn remove_domain_metadata(mut self, domain: String) -> Self {
+        self.domain_metadatas
+            .push(DomainMetadata::remove(domain, String::new()));
+        self
+    }
+
+    /// Enable in-commit timestamps on the table with this commit. This adds the
+    /// `inCommitTimestamp` table feature to the protocol if needed, and sets the
+    /// `delta.enableInCommitTimestamps` table property along with the enablement version and
+    /// timestamp of in-commit timestamps. Has no effect if in-commit timestamps are already
+    /// enabled.
+    ///
+    /// Once enabled, every commit records an in-commit timestamp (`commitInfo.inCommitTimestamp`)
+    /// which is strictly greater than the timestamp of the previous commit.
+    pub fn with_in_commit_timestamps_enabled(mut self) -> Self {
+        self.enable_in_commit_timestamps = true;
+        self
+    }
+
+    /// WARNING: This is an unstable API and will likely change in the future.
+    ///
+    /// Add commit info to the transaction. This is commit-wide metadata that is written as the
+    /// first action in the commit. The engine data passed here must have exactly one row, and we
+    /// only read one column: `engineCommitInfo` which must be a map<string, string> encoding the
+    /// metadata.
+    ///
+    /// The engine is required to provide commit info before committing the transaction. If the
+    /// engine would like to omit engine-specific commit info, it can do so by passing pass a
+    /// commit_info engine data chunk with one row and one column of type `Map<string, string>`
+    /// that can either be `null` or contain an empty map.
+    ///
+    /// Any other columns in the data chunk are ignored.
+    pub fn with_commit_info(mut self, commit_info: Box<dyn EngineData>) -> Self {
+        self.commit_info = Some(commit_info.into());
+        self
+    }
+
+    // Generate the logical-to-physical transform expression which must be evaluated on every data
+    // chunk before writing. At the moment, this is a transaction-wide expression. Evaluating it
+    // with the physical write schema as the output schema renames the columns to their physical
+    // names (with column mapping).
+    fn generate_logical_to_physical(&self) -> Expression {
+        // for now, we just pass through all the columns except partition columns.
+        // note this is _incorrect_ if table config deems we need partition columns.
+        let partition_columns = &self.table_configuration.metadata().partition_columns;
+        let schema = self.table_configuration.schema();
+        let fields = schema
+            .fields()
+            .filter(|f| !partition_columns.contains(f.name()))
+            .map(|f| Expression::column([f.name()]));
+        Expression::struct_from(fields)
+    }
+
+    /// Get the write context for this transaction. At the moment, this is constant for the whole
+    /// transaction.
+    // Note: after we introduce metadata updates (modify table schema, etc.), we need to make sure
+    // that engines cannot call this method after a metadata change, since the write context could
+    // have invalid metadata.
+    pub fn get_write_context(&self) -> WriteContext {
+        let target_dir = self.table_configuration.table_root();
+        let snapshot_schema = self.table_configuration.schema();
+        let physical_schema = self.table_configuration.physical_write_schema();
+        let logical_to_physical = self.generate_logical_to_physical();
+        let stats_columns = self.table_configuration.stats_columns();
+        WriteContext::new(
+            target_dir.clone(),
+            snapshot_schema,
+            physical_schema,
+            logical_to_physical,
+            stats_columns,
+        )
+    }
+
+    /// Add files to include in this transaction. This API generally enables the engine to
+    /// add/append/insert data (files) to the table. Note that this API can be called multiple times
+    /// to add multiple batches.
+    ///
+    /// The expected schema for `add_metadata` is given by [`add_files_schema`].
+    pub fn add_files(&mut self, add_metadata: Box<dyn EngineData>) {
+        self.add_files_metadata.push(add_metadata);
+    }
+
+    /// Remove files from the table in this transaction. This API generally enables the engine to
+    /// delete/overwrite/compact data (files) in the table. Note that this API can be called
+    /// multiple times to remove multiple batches.
+    ///
+    /// Every removed file must be present in the transaction's read snapshot with the same
+    /// deletion vector. This is checked when the transaction is committed.
+    ///
+    /// The expected schema for `remove_metadata` is given by [`remove_files_schema`].
+    ///
+    /// Returns an error if the table is append-only.
+    pub fn remove_files(&mut self, remove_metadata: Box<dyn EngineData>) -> DeltaResult<()> {
+        self.read_snapshot("remove files")?;
+        require!(
+            !self.table_configuration.is_append_only_enabled(),
+            Error::unsupported("Cannot remove files from an append-only table")
+        );
+        self.remove_files_metadata.push(remove_metadata);
+        Ok(())
+    }
+
+    /// Delete the rows of the table that match `predicate` in this transaction, without rewriting
+    /// any data files. The table must have deletion vectors enabled.
+    ///
+    /// Every file that may contain matching rows (according to data skipping) is read, and the
+    /// predicate is evaluated on each of its rows. For each file with newly deleted rows, a new
+    /// deletion vector file is written immediately, and the file's current `add` action is
+    /// replaced by a `remove` and `add` pair carrying the new deletion vector when the transaction
+    /// is committed. Files with no remaining rows are removed entirely. Rows for which the
+    /// predicate evaluates to null are not deleted.
+    ///
+    /// Files affected by the delete must not also be removed with [`remove_files`] (or by another
+    /// call to `delete`) in the same transaction.
+    ///
+    /// Returns the number of deleted rows.
+    ///
+    /// [`remove_files`]: Self::remove_files
+    pub fn delete(&mut self, engine: &dyn Engine, predicate: PredicateRef) -> DeltaResult<u64> {
+        let read_snapshot = self.read_snapshot("delete rows")?.clone();
+        let table_configuration = &self.table_configuration;
+        require!(
+            table_configuration.is_deletion_vector_enabled(),
+            Error::unsupported(
+                "Deleting rows requires deletion vectors to be enabled on the table"
+            )
+        );
+        require!(
+            !table_configuration.is_append_only_enabled(),
+            Error::unsupported("Cannot delete rows from an append-only table")
+        );
+        let plan = delete::plan_delete(engine, read_snapshot, predicate)?;
+        self.remove_files_metadata.extend(plan.removed_files);
+        self.deletion_vector_adds.extend(plan.added_files);
+        Ok(plan.num_deleted_rows)
+    }
+
+    // The snapshot the transaction reads from. Fails if the transaction creates the table, since
+    // there is nothing to read (e.g. no files to remove).
+    fn read_snapshot(&self, operation: &str) -> DeltaResult<&Arc<Snapshot>> {
+        self.read_snapshot.as_ref().ok_or_else(|| {
+            Error::unsupported(format!(
+                "Cannot {operation} in a transaction that creates the table"
+            ))
+        })
+    }
+
+    // Fill in the latest configuration of every domain removed by this transaction, which the
+    // protocol requires tombstones to preserve. Fails if a removed domain doesn't exist.
+    fn resolve_removed_domains(&mut self, engine: &dyn Engine) -> DeltaResult<()> {
+        let mut resolved = Vec::with_capacity(self.domain_metadatas.len());
+        for domain_metadata in std::mem::take(&mut self.domain_metadatas) {
+            if !domain_metadata.is_removed() {
+                resolved.push(domain_metadata);
+                continue;
+            }
+            let domain = domain_metadata.domain().to_string();
+            let configuration = match &self.read_snapshot {
+                Some(snapshot) => {
+                    domain_metadata_configuration(snapshot.log_segment(), &domain, engine)?
+                }
+                None => None,
+            };
+            let Some(configuration) = configuration else {
+                return Err(Error::generic(format!(
+                    "Cannot remove domain {domain} since it does not exist"
+                )));
+            };
+            resolved.push(DomainMetadata::remove(domain, configuration));
+        }
+        self.domain_metadatas = resolved;
+        Ok(())
+    }
+
+    // The path and deletion vector of every file removed by this transaction.
+    fn removed_files(&self) -> DeltaResult<Vec<FileActionKey>> {
+        let mut visitor = RemovedFileVisitor::default();
+        for remove_metadata in &self.remove_files_metadata {
+            visitor.visit_rows_of(remove_metadata.as_ref())?;
+        }
+        Ok(visitor.removed_files)
+    }
+
+    /// Check that every file being removed is an active file in the read snapshot, and that no
+    /// file is removed twice.
+    fn validate_removed_files(
+        &self,
+        engine: &dyn Engine,
+        removed_files: &[FileActionKey],
+    ) -> DeltaResult<()> {
+        fn collect_file_key(
+            files: &mut HashSet<FileActionKey>,
+            path: &str,
+            _: i64,
+            _: Option<Stats>,
+            dv_info: DvInfo,
+            _: Option<ExpressionRef>,
+            _: HashMap<String, String>,
+        ) {
+            let dv_unique_id = dv_info.deletion_vector.map(|dv| dv.unique_id());
+            files.insert(FileActionKey::new(path, dv_unique_id));
+        }
+        let read_snapshot = self.read_snapshot("remove files")?;
+        let scan = read_snapshot.clone().scan_builder().build()?;
+        let mut snapshot_files = HashSet::new();
+        for scan_metadata in scan.scan_metadata(engine)? {
+            snapshot_files = scan_metadata?.visit_scan_files(snapshot_files, collect_file_key)?;
+        }
+
+        let mut seen_files = HashSet::new();
+        for key in removed_files {
+            require!(
+                snapshot_files.contains(key),
+                Error::generic(format!(
+                    "Cannot remove file {} (deletion vector: {:?}): it is not present in the table at version {}",
+                    key.path,
+                    key.dv_unique_id,
+                    read_snapshot.version()
+                ))
+            );
+            require!(
+                seen_files.insert(key),
+                Error::generic(format!("File {} is removed more than once", key.path))
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Extracts the path and deletion vector unique id of each file in the remove_files metadata.
+#[derive(Default)]
+struct RemovedFileVisitor {
+    removed_files: Vec<FileActionKey>,
+}
+
+impl RowVisitor for RemovedFileVisitor {
+    fn selected_column_names_and_types(&self) -> (&'static [ColumnName], &'static [DataType]) {
+        static NAMES_AND_TYPES: LazyLock<ColumnNamesAndTypes> = LazyLock::new(|| {
+            let names = vec![
+                column_name!("path"),
+                column_name!("deletionVector.storageType"),
+                column_name!("deletionVector.pathOrInlineDv"),
+                column_name!("deletionVector.offset"),
+            ];
+            let types = vec![
+                DataType::STRING,
+                DataType::STRING,
+                DataType::STRING,
+                DataType::INTEGER,
+            ];
+            (names, types).into()
+        });
+        NAMES_AND_TYPES.as_ref()
+    }
+
+    fn visit<'a>(&mut self, row_count: usize, getters: &[&'a dyn GetData<'a>]) -> DeltaResult<()> {
+        require!(
+            getters.len() == 4,
+            Error::InternalError(format!(
+                "Wrong number of RemovedFileVisitor getters: {}",
+                getters.len()
+            ))
+        );
+        for i in 0..row_count {
+            let path: String = getters[0].get(i, "path")?;
+            let storage_type: Option<String> =
+                getters[1].get_opt(i, "deletionVector.storageType")?;
+            let dv_unique_id = match storage_type {
+                Some(storage_type) => {
+                    let path_or_inline_dv: String =
+                        getters[2].get(i, "deletionVector.pathOrInlineDv")?;
+                    let offset: Option<i32> = getters[3].get_opt(i, "deletionVector.offset")?;
+                    Some(DeletionVectorDescriptor::unique_id_from_parts(
+                        &storage_type,
+                        &path_or_inline_dv,
+                        offset,
+                    ))
+                }
+                None => None,
+            };
+            self.removed_files
+                .push(FileActionKey::new(path, dv_unique_id));
+        }
+        Ok(())
+    }
+}
+
+/// The map type of the partition values of the actions the kernel writes, whose values may be null.
+fn string_map_type() -> MapType {
+    MapType::new(DataType::STRING, DataType::STRING, true)
+}
+
+/// An `add` action built by the kernel (rather than taken from the add files metadata written by
+/// the engine), e.g. for a file whose deletion vector was replaced by a delete.
+#[derive(Clone, Debug)]
+pub(crate) struct AddAction {
+    pub(crate) path: String,
+    /// A map of every partition column (by physical name) to its value, which may be null.
+    pub(crate) partition_values: MapData,
+    pub(crate) size: i64,
+    pub(crate) modification_time: i64,
+    pub(crate) data_change: bool,
+    pub(crate) stats: Option<String>,
+    pub(crate) tags: Option<HashMap<String, String>>,
+    pub(crate) deletion_vector: Option<DeletionVectorDescriptor>,
+    pub(crate) base_row_id: Option<i64>,
+    pub(crate) default_row_commit_version: Option<Version>,
+    pub(crate) clustering_provider: Option<String>,
+}
+
+impl AddAction {
+    /// Create a single row of engine data holding this add action, in the log schema.
+    pub(crate) fn to_engine_data(&self, engine: &dyn Engine) -> DeltaResult<Box<dyn EngineData>> {
+        let dv = self.deletion_vector.as_ref();
+        let default_row_commit_version = self
+            .default_row_commit_version
+            .map(i64::try_from)
+            .transpose()
+            .map_err(|_| Error::generic("Commit version does not fit in a long"))?;
+        // the type of the tags must match the log schema, whose tag values are not nullable
+        let tags_type = MapType::new(DataType::STRING, DataType::STRING, false);
+        let tags = match &self.tags {
+            Some(tags) => Scalar::Map(MapData::try_new(tags_type, tags.clone())?),
+            None => Scalar::Null(tags_type.into()),
+        };
+        // NB: the order of the values must match the leaves of the log schema of the add action
+        let values = [
+            self.path.as_str().into(),
+            Scalar::Map(self.partition_values.clone()),
+            self.size.into(),
+            self.modification_time.into(),
+            self.data_change.into(),
+            self.stats.clone().into(),
+            tags,
+            dv.map(|dv| dv.storage_type.clone()).into(),
+            dv.map(|dv| dv.path_or_inline_dv.clone()).into(),
+            dv.and_then(|dv| dv.offset).into(),
+            dv.map(|dv| dv.size_in_bytes).into(),
+            dv.map(|dv| dv.cardinality).into(),
+            self.base_row_id.into(),
+            default_row_commit_version.into(),
+            self.clustering_provider.clone().into(),
+        ];
+        engine
+            .evaluation_handler()
+            .create_one(get_log_add_schema().clone(), &values)
+    }
+}
+
+// convert add_files_metadata into add actions using an expression to transform the data in a single
+// pass
+fn generate_adds<'a>(
+    engine: &dyn Engine,
+    add_files_metadata: impl Iterator<Item = &'a dyn EngineData> + Send + 'a,
+) -> impl Iterator<Item = DeltaResult<Box<dyn EngineData>>> + Send + 'a {
+    let evaluation_handler = engine.evaluation_handler();
+    let add_files_schema = add_files_schema();
+    let log_schema = get_log_add_schema();
+
+    add_files_metadata.map(move |add_files_batch| {
+        let adds_expr = Expression::struct_from([Expression::struct_from(
+            add_files_schema
+                .fields()
+                .map(|f| Expression::column([f.name()])),
+        )]);
+        let adds_evaluator = evaluation_handler.new_expression_evaluator(
+            add_files_schema.clone(),
+            adds_expr,
+            log_schema.clone().into(),
+        );
+        adds_evaluator.evaluate(add_files_batch)
+    })
+}
+
+// convert remove_files_metadata into remove actions using an expression to transform the data in a
+// single pass. The deletion timestamp of every remove action is the commit timestamp.
+fn generate_removes<'a>(
+    engine: &dyn Engine,
+    deletion_timestamp: i64,
+    remove_files_metadata: impl Iterator<Item = &'a dyn EngineData> + Send + 'a,
+) -> impl Iterator<Item = DeltaResult<Box<dyn EngineData>>> + Send + 'a {
+    let evaluation_handler = engine.evaluation_handler();
+    let remove_files_schema = remove_files_schema();
+    let log_schema = get_log_remove_schema();
+
+    // NB: the expressions are paired with the fields of the log schema by position, so they are
+    // derived from the fields of the remove action. All other fields are passed through.
+    let remove_fields = Remove::to_schema()
+        .fields()
+        .map(|field| match field.name().as_str() {
+            "deletionTimestamp" => Expression::literal(deletion_timestamp),
+            // partitionValues, size and tags are always present
+            "extendedFileMetadata" => Expression::literal(true),
+            name => Expression::column([name]),
+        })
+        .collect::<Vec<_>>();
+    let removes_expr = Expression::struct_from([Expression::struct_from(remove_fields)]);
+
+    remove_files_metadata.map(move |remove_files_batch| {
+        let removes_evaluator = evaluation_handler.new_expression_evaluator(
+            remove_files_schema.clone(),
+            removes_expr.clone(),
+            log_schema.clone().into(),
+        );
+        removes_evaluator.evaluate(remove_files_batch)
+    })
+}
+
+/// WriteContext is data derived from a [`Transaction`] that can be provided to writers in order to
+/// write table data.
+///
+/// [`Transaction`]: struct.Transaction.html
+pub struct WriteContext {
+    target_dir: Url,
+    schema: SchemaRef,
+    physical_schema: SchemaRef,
+    logical_to_physical: Expression,
+    stats_columns: Vec<ColumnName>,
+}
+
+impl WriteContext {
+    fn new(
+        target_dir: Url,
+        schema: SchemaRef,
+        physical_schema: SchemaRef,
+        logical_to_physical: Expression,
+        stats_columns: Vec<ColumnName>,
+    ) -> Self {
+        WriteContext {
+            target_dir,
+            schema,
+            physical_schema,
+            logical_to_physical,
+            stats_columns,
+        }
+    }
+
+    pub fn target_dir(&self) -> &Url {
+        &self.target_dir
+    }
+
+    pub fn schema(&self) -> &SchemaRef {
+        &self.schema
+    }
+
+    /// The schema of the data files to write: the non-partition columns of the table, renamed to
+    /// their physical names. With column mapping, each field's metadata includes its parquet field
+    /// id (under the `PARQUET:field_id` key), which writers should embed in the data files.
+    pub fn physical_schema(&self) -> &SchemaRef {
+        &self.physical_schema
+    }
+
+    /// The expression that transforms logical data into physical data, to be evaluated with the
+    /// [`physical_schema`](Self::physical_schema) as its output schema.
+    pub fn logical_to_physical(&self) -> &Expression {
+        &self.logical_to_physical
+    }
+
+    /// The physical names of the columns for which writers should collect file statistics (the
+    /// `stats` of [`add_files_schema`]). Statistics of a struct column cover all of its nested
+    /// columns.
+    pub fn stats_columns(&self) -> &[ColumnName] {
+        &self.stats_columns
+    }
+}
+
+/// Result after committing a transaction. If 'committed', the version is the new version written
+/// to the log. If 'conflict', the transaction is returned so the caller can resolve the conflict
+/// (along with the version which conflicted).
+// TODO(zach): in order to make the returning of a transaction useful, we need to add APIs to
+// update the transaction to a new version etc.
+#[derive(Debug)]
+pub enum CommitResult {
+    /// The transaction was successfully committed at the version.
+    Committed(Version),
+    /// This transaction conflicted with an existing version (at the version given).
+    Conflict(Transaction, Version),
+}
+
+// given the engine's commit info we want to create commitInfo action to commit (and append more actions to)
+fn generate_commit_info(
+    engine: &dyn Engine,
+    operation: Option<&str>,
+    timestamp: i64,
+    in_commit_timestamp: Option<i64>,
+    engine_commit_info: &dyn EngineData,
+) -> DeltaResult<Box<dyn EngineData>> {
+    if engine_commit_info.len() != 1 {
+        return Err(Error::InvalidCommitInfo(format!(
+            "Engine commit info should have exactly one row, found {}",
+            engine_commit_info.len()
+        )));
+    }
+
+    let commit_info_exprs = [Expression::literal(timestamp)]
+        .into_iter()
+        .chain(in_commit_timestamp.map(Expression::literal))
+        .chain([
+            Expression::literal(operation.unwrap_or(UNKNOWN_OPERATION)),
+            // HACK (part 1/2): since we don't have proper map support, we create a literal struct with
+            // one null field to create data that serializes as "operationParameters": {}
+            Expression::literal(Scalar::Struct(StructData::try_new(
+                vec![StructField::nullable(
+                    "operation_parameter_int",
+                    DataType::INTEGER,
+                )],
+                vec![Scalar::Null(DataType::INTEGER)],
+            )?)),
+            Expression::literal(format!("v{KERNEL_VERSION}")),
+            column_expr!("engineCommitInfo"),
+        ]);
+    let commit_info_expr = Expression::struct_from([Expression::struct_from(commit_info_exprs)]);
+    let commit_info_schema = get_log_commit_info_schema().as_ref();
+
+    // HACK (part 2/2): we need to modify the commit info schema to match the expression above (a
+    // struct with a single null int field).
+    let mut commit_info_empty_struct_schema = commit_info_schema.clone();
+    let commit_info_field = commit_info_empty_struct_schema
+        .fields
+        .get_mut(COMMIT_INFO_NAME)
+        .ok_or_else(|| Error::missing_column(COMMIT_INFO_NAME))?;
+    let DataType::Struct(mut commit_info_data_type) = commit_info_field.data_type().clone() else {
+        return Err(Error::internal_error(
+            "commit_info_field should be a struct",
+        ));
+    };
+    let engine_commit_info_schema =
+        commit_info_data_type.project_as_struct(&["engineCommitInfo"])?;
+    let hack_data_type = DataType::Struct(Box::new(StructType::new(vec![StructField::nullable(
+        "hack_operation_parameter_int",
+        DataType::INTEGER,
+    )])));
+
+    commit_info_data_type
+        .fields
+        .get_mut("operationParameters")
+        .ok_or_else(|| Error::missing_column("operationParameters"))?
+        .data_type = hack_data_type;
+
+    // The in-commit timestamp is only written if in-commit timestamps are enabled
+    if in_commit_timestamp.is_none() {
+        commit_info_data_type
+            .fields
+            .shift_remove("inCommitTimestamp");
+    }
+    // Kernel does not write the engineInfo, txnId, or operationMetrics fields
+    for field in ["engineInfo", "txnId", "operationMetrics"] {
+        commit_info_data_type.fields.shift_remove(field);
+    }
+    commit_info_field.data_type = DataType::Struct(commit_info_data_type);
+
+    let commit_info_evaluator = engine.evaluation_handler().new_expression_evaluator(
+        engine_commit_info_schema.into(),
+        commit_info_expr,
+        commit_info_empty_struct_schema.into(),
+    );
+
+    commit_info_evaluator.evaluate(engine_commit_info)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::engine::arrow_data::ArrowEngineData;
+    use crate::engine::arrow_expression::ArrowEvaluationHandler;
+    use crate::schema::MapType;
+    use crate::{EvaluationHandler, JsonHandler, ParquetHandler, StorageHandler};
+
+    use crate::arrow::array::{MapArray, MapBuilder, MapFieldNames, StringArray, StringBuilder};
+    use crate::arrow::datatypes::{DataType as ArrowDataType, Field, Schema as ArrowSchema};
+    use crate::arrow::error::ArrowError;
+    use crate::arrow::json::writer::LineDelimitedWriter;
+    use crate::arrow::record_batch::RecordBatch;
+
+    struct ExprEngine(Arc<dyn EvaluationHandler>);
+
+    impl ExprEngine {
+        fn new() -> Self {
+            ExprEngine(Arc::new(ArrowEvaluationHandler))
+        }
+    }
+
+    impl Engine for ExprEngine {
+        fn evaluation_handler(&self) -> Arc<dyn EvaluationHandler> {
+            self.0.clone()
+        }
+
+        fn json_handler(&self) -> Arc<dyn JsonHandler> {
+            unimplemented!()
+        }
+
+        fn parquet_handler(&self) -> Arc<dyn ParquetHandler> {
+            unimplemented!()
+        }
+
+        fn storage_handler(&self) -> Arc<dyn StorageHandler> {
+            unimplemented!()
+        }
+    }
+
+    fn build_map(entries: Vec<(&str, &str)>) -> MapArray {
+        let key_builder = StringBuilder::new();
+        let val_builder = StringBuilder::new();
+        let names = MapFieldNames {
+            entry: "entries".to_string(),
+            key: "key".to_string(),
+            value: "value".to_string(),
+        };
+        let mut builder = MapBuilder::new(Some(names), key_builder, val_builder);
+        for (key, val) in entries {
+            builder.keys().append_value(key);
+            builder.values().append_value(val);
+            builder.append(true).unwrap();
+        }
+        builder.finish()
+    }
+
+    // convert it to JSON just for ease of comparison (and since we ultimately persist as JSON)
+    fn as_json(data: Box<dyn EngineData>) -> serde_json::Value {
+        let record_batch: RecordBatch = data
+            .into_any()
+            .downcast::<ArrowEngineData>()
+            .unwrap()
+            .into();
+
+        let buf = Vec::new();
+        let mut writer = LineDelimitedWriter::new(buf);
+        writer.write_batches(&[&record_batch]).unwrap();
+        writer.finish().unwrap();
+        let buf = writer.into_inner();
+
+        serde_json::from_slice(&buf).unwrap()
+    }
+
+    #[test]
+    fn test_generate_commit_info() -> DeltaResult<()> {
+        let engine = ExprEngine::new();
+        let engine_commit_info_schema = Arc::new(ArrowSchema::new(vec![Field::new(
+            "engineCommitInfo",
+            ArrowDataType::Map(
+                Arc::new(Field::new(
+                    "entries",
+                    ArrowDataType::Struct(
+                        vec![
+                            Field::new("key", ArrowDataType::Utf8, false),
+                            Field::new("value", ArrowDataType::Utf8, true),
+                        ]
+                        .into(),
+                    ),
+                    false,
+                )),
+                false,
+            ),
+            false,
+        )]));
+
+        let map_array = build_map(vec![("engineInfo", "default engine")]);
+        let commit_info_batch =
+            RecordBatch::try_new(engine_commit_info_schema, vec![Arc::new(map_array)])?;
+
+        let actions = generate_commit_info(
+            &engine,
+            Some("test operation"),
+            123456789,
+            Some(123456790),
+            &ArrowEngineData::new(commit_info_batch),
+        )?;
+
+        let expected = serde_json::json!({
+            "commitInfo": {
+                "timestamp": 123456789,
+                "inCommitTimestamp": 123456790,
+                "operation": "test operation",
+                "kernelVersion": format!("v{}", env!("CARGO_PKG_VERSION")),
+                "operationParameters": {},
+                "engineCommitInfo": {
+                    "engineInfo": "default engine"
+                }
+            }
+        });
+
+        assert_eq!(actions.len(), 1);
+        let result = as_json(actions);
+        assert_eq!(result, expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_commit_info_with_multiple_columns() -> DeltaResult<()> {
+        let engine = ExprEngine::new();
+        let engine_commit_info_schema = Arc::new(ArrowSchema::new(vec![
+            Field::new(
+                "engineCommitInfo",
+                ArrowDataType::Map(
+                    Arc::new(Field::new(
+                        "entries",
+                        ArrowDataType::Struct(
+                            vec![
+                                Field::new("key", ArrowDataType::Utf8, false),
+                                Field::new("value", ArrowDataType::Utf8, true),
+                            ]
+                            .into(),
+                        ),
+                        false,
+                    )),
+                    false,
+                ),
+                false,
+            ),
+            Field::new("operation", ArrowDataType::Utf8, true),
+        ]));
+
+        let map_array = build_map(vec![("engineInfo", "default engine")]);
+
+        let commit_info_batch = RecordBatch::try_new(
+            engine_commit_info_schema,
+            vec![
+                Arc::new(map_array),
+                Arc::new(StringArray::from(vec!["some_string"])),
+            ],
+        )?;
+
+        let actions = generate_commit_info(
+            &engine,
+            Some("test operation"),
+            123456789,
+            None,
+            &ArrowEngineData::new(commit_info_batch),
+        )?;
+
+        let expected = serde_json::json!({
+            "commitInfo": {
+                "timestamp": 123456789,
+                "operation": "test operation",
+                "kernelVersion": format!("v{}", env!("CARGO_PKG_VERSION")),
+                "operationParameters": {},
+                "engineCommitInfo": {
+                    "engineInfo": "default engine"
+                }
+            }
+        });
+
+        assert_eq!(actions.len(), 1);
+        let result = as_json(actions);
+        assert_eq!(result, expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_commit_info_missing_column() -> DeltaResult<()> {
+        let engine = ExprEngine::new();
+        let engine_commit_info_schema = Arc::new(ArrowSchema::new(vec![Field::new(
+            "some_column_name",
+            ArrowDataType::Utf8,
+            true,
+        )]));
+        let commit_info_batch = RecordBatch::try_new(
+            engine_commit_info_schema,
+            vec![Arc::new(StringArray::new_null(1))],
+        )?;
+
+        let _ = generate_commit_info(
+            &engine,
+            Some("test operation"),
+            123456789,
+            None,
+            &ArrowEngineData::new(commit_info_batch),
+        )
+        .map_err(|e| match e {
+            Error::Arrow(ArrowError::SchemaError(_)) => (),
+            Error::Backtraced { source, .. }
+                if matches!(&*source, Error::Arrow(ArrowError::SchemaError(_))) => {}
+            _ => panic!("expected arrow schema error error, got {e:?}"),
+        });
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_commit_info_invalid_column_type() -> DeltaResult<()> {
+        let engine = ExprEngine::new();
+        let engine_commit_info_schema = Arc::new(ArrowSchema::new(vec![Field::new(
+            "engineCommitInfo",
+            ArrowDataType::Utf8,
+            true,
+        )]));
+        let commit_info_batch = RecordBatch::try_new(
+            engine_commit_info_schema,
+            vec![Arc::new(StringArray::new_null(1))],
+        )?;
+
+        let _ = generate_commit_info(
+            &engine,
+            Some("test operation"),
+            123456789,
+            None,
+            &ArrowEngineData::new(commit_info_batch),
+        )
+        .map_err(|e| match e {
+            Error::Arrow(ArrowError::InvalidArgumentError(_)) => (),
+            Error::Backtraced { source, .. }
+                if matches!(&*source, Error::Arrow(ArrowError::InvalidArgumentError(_))) => {}
+            _ => panic!("expected arrow invalid arg error, got {e:?}"),
+        });
+
+        Ok(())
+    }
+
+    fn assert_empty_commit_info(
+        data: Box<dyn EngineData>,
+        write_engine_commit_info: bool,
+        timestamp: i64,
+    ) -> DeltaResult<()> {
+        assert_eq!(data.len(), 1);
+        let expected = if write_engine_commit_info {
+            serde_json::json!({
+                "commitInfo": {
+                    "timestamp": timestamp,
+                    "operation": "test operation",
+                    "kernelVersion": format!("v{}", env!("CARGO_PKG_VERSION")),
+                    "operationParameters": {},
+                    "engineCommitInfo": {}
+                }
+            })
+        } else {
+            serde_json::json!({
+                "commitInfo": {
+                    "timestamp": timestamp,
+                    "operation": "test operation",
+                    "kernelVersion": format!("v{}", env!("CARGO_PKG_VERSION")),
+                    "operationParameters": {},
+                }
+            })
+        };
+        let result = as_json(data);
+        assert_eq!(result, expected);
+        Ok(())
+    }
+
+    // Three cases for empty commit info:
+    // 1. `engineCommitInfo` column with an empty Map<string, string>
+    // 2. `engineCommitInfo` null column of type Map<string, string>
+    // 3. a column that has a name other than `engineCommitInfo`; Delta can detect that the column
+    //    is missing and substitute a null literal in its place. The type of that column doesn't
+    //    matter, Delta will ignore it.
+    #[test]
+    fn test_empty_commit_info() -> DeltaResult<()> {
+        // test with null map and empty map
+        for is_null in [true, false] {
+            let engine = ExprEngine::new();
+            let engine_commit_info_schema = Arc::new(ArrowSchema::new(vec![Field::new(
+                "engineCommitInfo",
+                ArrowDataType::Map(
+                    Arc::new(Field::new(
+                        "entries",
+                        ArrowDataType::Struct(
+                            vec![
+                                Field::new("key", ArrowDataType::Utf8, false),
+                                Field::new("value", ArrowDataType::Utf8, true),
+                            ]
+                            .into(),
+                        ),
+                        false,
+                    )),
+                    false,
+                ),
+                true,
+            )]));
+
+            let key_builder = StringBuilder::new();
+            let val_builder = StringBuilder::new();
+            let names = crate::arrow::array::MapFieldNames {
+                entry: "entries".to_string(),
+                key: "key".to_string(),
+                value: "value".to_string(),
+            };
+            let mut builder =
+                crate::arrow::array::MapBuilder::new(Some(names), key_builder, val_builder);
+            builder.append(is_null).unwrap();
+            let array = builder.finish();
+
+            let commit_info_batch =
+                RecordBatch::try_new(engine_commit_info_schema, vec![Arc::new(array)])?;
+
+            let timestamp = 123456;
+            let actions = generate_commit_info(
+                &engine,
+                Some("test operation"),
+                timestamp,
+                None,
+                &ArrowEngineData::new(commit_info_batch),
+            )?;
+
+            assert_empty_commit_info(actions, is_null, timestamp)?;
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_add_files_schema() {
+        let schema = add_files_schema();
+        let expected = StructType::new(vec![
+            StructField::not_null("path", DataType::STRING),
+            StructField::not_null(
+                "partitionValues",
+                MapType::new(DataType::STRING, DataType::STRING, true),
+            ),
+            StructField::not_null("size", DataType::LONG),
+            StructField::not_null("modificationTime", DataType::LONG),
+            StructField::not_null("dataChange", DataType::BOOLEAN),
+            StructField::nullable("stats", DataType::STRING),
+        ]);
+        assert_eq!(*schema, expected.into());
+    }
+
+    #[test]
+    fn test_remove_files_schema() {
+        let schema = remove_files_schema();
+        let expected = StructType::new(vec![
+            StructField::not_null("path", DataType::STRING),
+            StructField::not_null(
+                "partitionValues",
+                MapType::new(DataType::STRING, DataType::STRING, true),
+            ),
+            StructField::not_null("size", DataType::LONG),
+            StructField::not_null("dataChange", DataType::BOOLEAN),
+            StructField::nullable(
+                "tags",
+                MapType::new(DataType::STRING, DataType::STRING, true),
+            ),
+            StructField::nullable("deletionVector", DeletionVectorDescriptor::to_schema()),
+            StructField::nullable("baseRowId", DataType::LONG),
+            StructField::nullable("defaultRowCommitVersion", DataType::LONG),
+        ]);
+        assert_eq!(*schema, expected.into());
+    }
+
+    #[test]
+    fn test_validate_removed_files_with_deletion_vector() -> DeltaResult<()> {
+        use crate::arrow::json::ReaderBuilder;
+        use crate::engine::arrow_conversion::TryIntoArrow as _;
+        use crate::engine::sync::SyncEngine;
+
+        let path = std::fs::canonicalize("./tests/data/table-with-dv-small/").unwrap();
+        let url = Url::from_directory_path(path).unwrap();
+        let engine = SyncEngine::new();
+        let snapshot = Arc::new(Snapshot::try_new(url, &engine, None)?);
+
+        let remove_files_data = |deletion_vector: serde_json::Value| -> DeltaResult<_> {
+            let row = serde_json::json!({
+                "path": "part-00000-fae5310a-a37d-4e51-827b-c3d5516560ca-c000.snappy.parquet",
+                "partitionValues": {},
+                "size": 635,
+                "dataChange": true,
+                "deletionVector": deletion_vector,
+            });
+            let schema: ArrowSchema = remove_files_schema().as_ref().try_into_arrow()?;
+            let batch = ReaderBuilder::new(Arc::new(schema))
+                .build(row.to_string().as_bytes())?
+                .next()
+                .unwrap()?;
+            Ok(Box::new(ArrowEngineData::new(batch)))
+        };
+        let deletion_vector = serde_json::json!({
+            "storageType": "u",
+            "pathOrInlineDv": "vBn[lx{q8@P<9BNH/isA",
+            "offset": 1,
+            "sizeInBytes": 36,
+            "cardinality": 2,
+        });
+
+        // the file and its deletion vector match the snapshot
+        let mut txn = snapshot.clone().transaction()?;
+        txn.remove_files(remove_files_data(deletion_vector.clone())?)?;
+        txn.validate_removed_files(&engine, &txn.removed_files()?)?;
+
+        // the file must be removed along with its current deletion vector
+        let mut txn = snapshot.clone().transaction()?;
+        txn.remove_files(remove_files_data(serde_json::Value::Null)?)?;
+        assert!(txn
+            .validate_removed_files(&engine, &txn.removed_files()?)
+            .is_err());
+
+        let mut other_deletion_vector = deletion_vector;
+        other_deletion_vector["offset"] = 2.into();
+        let mut txn = snapshot.transaction()?;
+        txn.remove_files(remove_files_data(other_deletion_vector)?)?;
+        assert!(txn
+            .validate_removed_files(&engine, &txn.removed_files()?)
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_commit_fails_without_winning_commits() -> DeltaResult<()> {
+        use crate::engine::sync::SyncEngine;
+
+        // a json handler that fails every write as if the file already exists
+        struct ExistingFileJsonHandler(Arc<dyn JsonHandler>);
+
+        impl JsonHandler for ExistingFileJsonHandler {
+            fn parse_json(
+                &self,
+                json_strings: Box<dyn EngineData>,
+                output_schema: SchemaRef,
+            ) -> DeltaResult<Box<dyn EngineData>> {
+                self.0.parse_json(json_strings, output_schema)
+            }
+
+            fn read_json_files(
+                &self,
+                files: &[crate::FileMeta],
+                physical_schema: SchemaRef,
+                predicate: Option<crate::PredicateRef>,
+            ) -> DeltaResult<crate::FileDataReadResultIterator> {
+                self.0.read_json_files(files, physical_schema, predicate)
+            }
+
+            fn write_json_file(
+                &self,
+                path: &Url,
+                _data: Box<dyn Iterator<Item = DeltaResult<Box<dyn EngineData>>> + Send + '_>,
+                _overwrite: bool,
+            ) -> DeltaResult<()> {
+                Err(Error::FileAlreadyExists(path.to_string()))
+            }
+        }
+
+        struct ExistingFileEngine(SyncEngine);
+
+        impl Engine for ExistingFileEngine {
+            fn evaluation_handler(&self) -> Arc<dyn EvaluationHandler> {
+                self.0.evaluation_handler()
+            }
+
+            fn json_handler(&self) -> Arc<dyn JsonHandler> {
+                Arc::new(ExistingFileJsonHandler(self.0.json_handler()))
+            }
+
+            fn parquet_handler(&self) -> Arc<dyn ParquetHandler> {
+                self.0.parquet_handler()
+            }
+
+            fn storage_handler(&self) -> Arc<dyn StorageHandler> {
+                self.0.storage_handler()
+            }
+        }
+
+        // the commit file can't be written, but no winning commit exists: this must fail instead
+        // of retrying the same version forever
+        let path = std::fs::canonicalize("./tests/data/table-with-dv-small/").unwrap();
+        let url = Url::from_directory_path(path).unwrap();
+        let engine = ExistingFileEngine(SyncEngine::new());
+        let snapshot = Arc::new(Snapshot::try_new(url, &engine, None)?);
+        let commit_info = RecordBatch::try_new(
+            Arc::new(ArrowSchema::new(vec![Field::new(
+                "engineInfo",
+                ArrowDataType::Utf8,
+                true,
+            )])),
+            vec![Arc::new(StringArray::from(vec!["test"]))],
+        )?;
+        let txn = snapshot
+            .transaction()?
+            .with_commit_info(Box::new(ArrowEngineData::new(commit_info)));
+        let err = txn.commit(&engine).unwrap_err();
+        assert!(err.to_string().contains(
+            "Commit version 2 already exists, but no commits after version 1 were found"
+        ));
+        Ok(())
+    }
+}
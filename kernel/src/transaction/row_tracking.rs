@@ -0,0 +1,285 @@
+//! Assigning row ids to the files added by a commit to a table that supports row tracking.
+//!
+//! Every file added to such a table gets a `baseRowId`: the row id of its first (physical) row.
+//! The row ids of the other rows in the file follow from their physical row index. Base row ids are
+//! handed out from the row id high-water mark, which is the highest row id assigned so far and is
+//! stored in the `delta.rowTracking` metadata domain. A commit that adds files updates the
+//! high-water mark along with its add actions. Every added file also gets a
+//! `defaultRowCommitVersion`: the version of the commit that adds it.
+//!
+//! See the [row tracking] section of the protocol for details.
+//!
+//! [row tracking]: https://github.com/delta-io/delta/blob/master/PROTOCOL.md#row-tracking
+
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+use serde::{Deserialize, Serialize};
+
+use crate::actions::domain_metadata::domain_metadata_configuration;
+use crate::actions::DomainMetadata;
+use crate::engine_data::{GetData, RowVisitor, TypedGetData as _};
+use crate::expressions::MapData;
+use crate::scan::state::Stats;
+use crate::schema::{ColumnName, ColumnNamesAndTypes, DataType};
+use crate::snapshot::Snapshot;
+use crate::utils::require;
+use crate::{DeltaResult, Engine, EngineData, Error, Version};
+
+use super::{add_files_schema, string_map_type, AddAction};
+
+/// The metadata domain holding the row id high-water mark of a table with row tracking.
+pub(crate) const ROW_TRACKING_DOMAIN_NAME: &str = "delta.rowTracking";
+
+/// The table property naming the column that materializes the row ids of a table with row
+/// tracking.
+pub(crate) const MATERIALIZED_ROW_ID_COLUMN_PROPERTY: &str =
+    "delta.rowTracking.materializedRowIdColumnName";
+
+/// The table property naming the column that materializes the row commit versions of a table with
+/// row tracking.
+pub(crate) const MATERIALIZED_ROW_COMMIT_VERSION_COLUMN_PROPERTY: &str =
+    "delta.rowTracking.materializedRowCommitVersionColumnName";
+
+/// The configuration of the `delta.rowTracking` metadata domain.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RowTrackingDomain {
+    row_id_high_water_mark: i64,
+}
+
+/// Hands out base row ids to the files added by a commit.
+#[derive(Debug)]
+pub(crate) struct RowIdAssigner {
+    // the first row id available to the commit: one past the high-water mark of the read snapshot
+    first_row_id: i64,
+    next_row_id: i64,
+    commit_version: Version,
+}
+
+impl RowIdAssigner {
+    /// Create an assigner for the commit at `commit_version` on top of `read_snapshot` (`None` if
+    /// the commit creates the table).
+    pub(crate) fn try_new(
+        engine: &dyn Engine,
+        read_snapshot: Option<&Snapshot>,
+        commit_version: Version,
+    ) -> DeltaResult<Self> {
+        let high_water_mark = match read_snapshot {
+            Some(snapshot) => row_id_high_water_mark(engine, snapshot)?,
+            None => None,
+        };
+        Ok(Self::new(high_water_mark, commit_version))
+    }
+
+    fn new(high_water_mark: Option<i64>, commit_version: Version) -> Self {
+        let first_row_id = high_water_mark.map_or(0, |mark| mark + 1);
+        Self {
+            first_row_id,
+            next_row_id: first_row_id,
+            commit_version,
+        }
+    }
+
+    /// Assign base row ids to the files described by `add_files_metadata` (in the
+    /// [`add_files_schema`]), returning one add action for each of them.
+    pub(crate) fn assign_to_new_files(
+        &mut self,
+        add_files_metadata: &dyn EngineData,
+    ) -> DeltaResult<Vec<AddAction>> {
+        let mut visitor = AddFilesVisitor::default();
+        visitor.visit_rows_of(add_files_metadata)?;
+        visitor
+            .adds
+            .into_iter()
+            .map(|file| {
+                Ok(AddAction {
+                    base_row_id: Some(self.assign(&file.path, file.stats.as_deref())?),
+                    default_row_commit_version: Some(self.commit_version),
+                    path: file.path,
+                    // the engine already keyed the partition values by physical name
+                    partition_values: MapData::try_new(string_map_type(), file.partition_values)?,
+                    size: file.size,
+                    modification_time: file.modification_time,
+                    data_change: file.data_change,
+                    stats: file.stats,
+                    tags: None,
+                    deletion_vector: None,
+                    clustering_provider: None,
+                })
+            })
+            .collect()
+    }
+
+    /// Assign a base row id to a file whose deletion vector was replaced by a delete. Deleting rows
+    /// doesn't change the row ids or row commit versions of the remaining rows, so a file that
+    /// already has a base row id (or default row commit version) keeps it.
+    pub(crate) fn assign_to_deletion_vector_add(
+        &mut self,
+        add: &AddAction,
+    ) -> DeltaResult<AddAction> {
+        let base_row_id = match add.base_row_id {
+            Some(base_row_id) => base_row_id,
+            None => self.assign(&add.path, add.stats.as_deref())?,
+        };
+        Ok(AddAction {
+            base_row_id: Some(base_row_id),
+            default_row_commit_version: Some(
+                add.default_row_commit_version
+                    .unwrap_or(self.commit_version),
+            ),
+            ..add.clone()
+        })
+    }
+
+    /// The `delta.rowTracking` domain metadata action recording the new row id high-water mark,
+    /// or `None` if no row ids were assigned.
+    pub(crate) fn high_water_mark_domain_metadata(&self) -> DeltaResult<Option<DomainMetadata>> {
+        if self.next_row_id == self.first_row_id {
+            return Ok(None);
+        }
+        let configuration = serde_json::to_string(&RowTrackingDomain {
+            row_id_high_water_mark: self.next_row_id - 1,
+        })?;
+        Ok(Some(DomainMetadata::new(
+            ROW_TRACKING_DOMAIN_NAME.to_string(),
+            configuration,
+        )))
+    }
+
+    // Reserve a row id for each (physical) row of a file, returning the file's base row id.
+    fn assign(&mut self, path: &str, stats: Option<&str>) -> DeltaResult<i64> {
+        let num_records = stats
+            .and_then(|stats| serde_json::from_str::<Stats>(stats).ok())
+            .ok_or_else(|| {
+                Error::generic(format!(
+                    "Cannot assign row ids to file {path}: missing numRecords statistic"
+                ))
+            })?
+            .num_records;
+        let base_row_id = self.next_row_id;
+        self.next_row_id += i64::try_from(num_records)
+            .map_err(|_| Error::generic(format!("Too many records in file {path}")))?;
+        Ok(base_row_id)
+    }
+}
+
+/// Read the row id high-water mark of `snapshot`, or `None` if no row ids were assigned yet.
+pub(crate) fn row_id_high_water_mark(
+    engine: &dyn Engine,
+    snapshot: &Snapshot,
+) -> DeltaResult<Option<i64>> {
+    let configuration =
+        domain_metadata_configuration(snapshot.log_segment(), ROW_TRACKING_DOMAIN_NAME, engine)?;
+    configuration
+        .map(|configuration| {
+            let domain: RowTrackingDomain = serde_json::from_str(&configuration)?;
+            Ok(domain.row_id_high_water_mark)
+        })
+        .transpose()
+}
+
+/// A row of add files metadata (in the [`add_files_schema`]).
+struct AddFile {
+    path: String,
+    partition_values: HashMap<String, String>,
+    size: i64,
+    modification_time: i64,
+    data_change: bool,
+    stats: Option<String>,
+}
+
+#[derive(Default)]
+struct AddFilesVisitor {
+    adds: Vec<AddFile>,
+}
+
+impl RowVisitor for AddFilesVisitor {
+    fn selected_column_names_and_types(&self) -> (&'static [ColumnName], &'static [DataType]) {
+        static NAMES_AND_TYPES: LazyLock<ColumnNamesAndTypes> =
+            LazyLock::new(|| add_files_schema().leaves(None));
+        NAMES_AND_TYPES.as_ref()
+    }
+
+    fn visit<'a>(&mut self, row_count: usize, getters: &[&'a dyn GetData<'a>]) -> DeltaResult<()> {
+        require!(
+            getters.len() == 6,
+            Error::InternalError(format!(
+                "Wrong number of AddFilesVisitor getters: {}",
+                getters.len()
+            ))
+        );
+        for i in 0..row_count {
+            self.adds.push(AddFile {
+                path: getters[0].get(i, "path")?,
+                partition_values: getters[1].get(i, "partitionValues")?,
+                size: getters[2].get(i, "size")?,
+                modification_time: getters[3].get(i, "modificationTime")?,
+                data_change: getters[4].get(i, "dataChange")?,
+                stats: getters[5].get_opt(i, "stats")?,
+            });
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_assign_row_ids() {
+        let mut assigner = RowIdAssigner::new(None, 3);
+        assert!(assigner
+            .high_water_mark_domain_metadata()
+            .unwrap()
+            .is_none());
+        assert_eq!(
+            assigner.assign("a", Some(r#"{"numRecords":3}"#)).unwrap(),
+            0
+        );
+        assert_eq!(
+            assigner.assign("b", Some(r#"{"numRecords":0}"#)).unwrap(),
+            3
+        );
+        assert_eq!(
+            assigner.assign("c", Some(r#"{"numRecords":2}"#)).unwrap(),
+            3
+        );
+        assert_eq!(
+            assigner.high_water_mark_domain_metadata().unwrap(),
+            Some(DomainMetadata::new(
+                ROW_TRACKING_DOMAIN_NAME.to_string(),
+                r#"{"rowIdHighWaterMark":4}"#.to_string()
+            ))
+        );
+
+        // files without numRecords can't get row ids
+        assert!(assigner.assign("d", None).is_err());
+        assert!(assigner.assign("d", Some(r#"{"minValues":{}}"#)).is_err());
+    }
+
+    #[test]
+    fn test_assign_row_ids_after_high_water_mark() {
+        let mut assigner = RowIdAssigner::new(Some(9), 3);
+        assert_eq!(
+            assigner.assign("a", Some(r#"{"numRecords":0}"#)).unwrap(),
+            10
+        );
+        assert!(assigner
+            .high_water_mark_domain_metadata()
+            .unwrap()
+            .is_none());
+        assert_eq!(
+            assigner.assign("b", Some(r#"{"numRecords":5}"#)).unwrap(),
+            10
+        );
+        assert_eq!(
+            assigner.high_water_mark_domain_metadata().unwrap(),
+            Some(DomainMetadata::new(
+                ROW_TRACKING_DOMAIN_NAME.to_string(),
+                r#"{"rowIdHighWaterMark":14}"#.to_string()
+            ))
+        );
+    }
+}
@@ -101,13 +101,7 @@ pub(crate) fn calculate_transaction_expiration_timestamp(
     table_properties
         .set_transaction_retention_duration
         .map(|duration| -> DeltaResult<i64> {
-            let now = SystemTime::now()
-                .duration_since(UNIX_EPOCH)
-                .map_err(|e| Error::generic(format!("Failed to get current time: {e}")))?;
-
-            let now_ms = i64::try_from(now.as_millis())
-                .map_err(|_| Error::generic("Current timestamp exceeds i64 millisecond range"))?;
-
+            let now_ms = current_time_ms()?;
             let expiration_ms = i64::try_from(duration.as_millis())
                 .map_err(|_| Error::generic("Retention duration exceeds i64 millisecond range"))?;
 
@@ -116,6 +110,15 @@ pub(crate) fn calculate_transaction_expiration_timestamp(
         .transpose()
 }
 
+/// The current time, in milliseconds since the unix epoch.
+pub(crate) fn current_time_ms() -> DeltaResult<i64> {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(|e| Error::generic(format!("Failed to get current time: {e}")))?;
+    i64::try_from(now.as_millis())
+        .map_err(|_| Error::generic("Current timestamp exceeds i64 millisecond range"))
+}
+
 // Extension trait for Cow<'_, T>
 pub(crate) trait CowExt<T: ToOwned + ?Sized> {
     /// The owned type that corresopnds to Self
@@ -157,14 +160,18 @@ pub(crate) mod test_utils {
     use crate::arrow::array::{RecordBatch, StringArray};
     use crate::arrow::datatypes::{DataType, Field, Schema as ArrowSchema};
     use crate::engine::arrow_data::ArrowEngineData;
+    use crate::engine::default::{executor::tokio::TokioBackgroundExecutor, DefaultEngine};
     use crate::engine::sync::SyncEngine;
     use crate::Engine;
-    use crate::EngineData;
+    use crate::{DeltaResult, EngineData};
 
     use crate::object_store::local::LocalFileSystem;
+    use crate::object_store::memory::InMemory;
     use crate::object_store::ObjectStore;
     use itertools::Itertools;
     use serde::Serialize;
+    use serde_json::{json, Value};
+    use std::future::Future;
     use std::{path::Path, sync::Arc};
     use tempfile::TempDir;
     use test_utils::delta_path_for_version;
@@ -226,6 +233,105 @@ pub(crate) mod test_utils {
         }
     }
 
+    /// Runs the future to completion on a new tokio runtime.
+    pub(crate) fn block_on<T>(future: impl Future<Output = T>) -> T {
+        tokio::runtime::Runtime::new()
+            .expect("create tokio runtime")
+            .block_on(future)
+    }
+
+    /// Creates an empty in-memory store, and a default engine that reads and writes it.
+    pub(crate) fn in_memory_engine() -> (Arc<InMemory>, DefaultEngine<TokioBackgroundExecutor>) {
+        let store = Arc::new(InMemory::new());
+        let engine = DefaultEngine::new(store.clone(), Arc::new(TokioBackgroundExecutor::new()));
+        (store, engine)
+    }
+
+    /// Writes the JSON actions as the commit file of `version` of the table at `table_path` in the
+    /// store, where `table_path` is either empty (the root of the store) or ends with a `/`.
+    pub(crate) fn write_json_commit(
+        store: &InMemory,
+        table_path: &str,
+        version: u64,
+        actions: &[Value],
+    ) -> DeltaResult<()> {
+        let content = actions.iter().map(Value::to_string).join("\n");
+        let path = format!("{table_path}{}", delta_path_for_version(version, "json"));
+        block_on(store.put(&path.into(), content.into()))?;
+        Ok(())
+    }
+
+    /// A protocol action with reader version 3 and writer version 7 (i.e. using table features).
+    pub(crate) fn protocol_json(reader_features: &[&str], writer_features: &[&str]) -> Value {
+        json!({"protocol": {
+            "minReaderVersion": 3,
+            "minWriterVersion": 7,
+            "readerFeatures": reader_features,
+            "writerFeatures": writer_features,
+        }})
+    }
+
+    /// A metadata action of a table with an integer `value` column and the given partition columns
+    /// (all strings) and configuration.
+    pub(crate) fn metadata_json(partition_columns: &[&str], configuration: Value) -> Value {
+        let fields = std::iter::once(("value", "integer"))
+            .chain(partition_columns.iter().map(|column| (*column, "string")))
+            .map(|(name, data_type)| {
+                json!({"name": name, "type": data_type, "nullable": true, "metadata": {}})
+            })
+            .collect_vec();
+        json!({"metaData": {
+            "id": "test-table",
+            "format": {"provider": "parquet", "options": {}},
+            "schemaString": json!({"type": "struct", "fields": fields}).to_string(),
+            "partitionColumns": partition_columns,
+            "configuration": configuration,
+        }})
+    }
+
+    /// An add action of a file of size 1 with the given partition values.
+    pub(crate) fn add_json(path: &str, partition_values: Value) -> Value {
+        json!({"add": {
+            "path": path,
+            "partitionValues": partition_values,
+            "size": 1,
+            "modificationTime": 1,
+            "dataChange": true,
+        }})
+    }
+
+    /// A remove action of a file deleted at `deletion_timestamp`.
+    pub(crate) fn remove_json(path: &str, deletion_timestamp: i64) -> Value {
+        json!({"remove": {
+            "path": path,
+            "deletionTimestamp": deletion_timestamp,
+            "dataChange": true,
+        }})
+    }
+
+    /// Copies the log of the `table-with-cdf` test table (commits 0 to 4) to a temporary directory,
+    /// setting the modification time of commit `v` to `(v + 1) * 1000` milliseconds since the unix
+    /// epoch. Returns the directory, which must outlive the table, and the table root.
+    pub(crate) fn table_with_cdf_commit_timestamps() -> (TempDir, url::Url) {
+        let source_log = Path::new("./tests/data/table-with-cdf/_delta_log");
+        let dir = tempfile::tempdir().unwrap();
+        let log_root = dir.path().join("_delta_log");
+        std::fs::create_dir(&log_root).unwrap();
+        for version in 0..=4u64 {
+            let file_name = format!("{version:020}.json");
+            std::fs::copy(source_log.join(&file_name), log_root.join(&file_name)).unwrap();
+            let mtime =
+                std::time::UNIX_EPOCH + std::time::Duration::from_millis((version + 1) * 1000);
+            let file = std::fs::File::options()
+                .write(true)
+                .open(log_root.join(&file_name))
+                .unwrap();
+            file.set_modified(mtime).unwrap();
+        }
+        let table_root = url::Url::from_directory_path(dir.path()).unwrap();
+        (dir, table_root)
+    }
+
     /// Try to convert an `EngineData` into a `RecordBatch`. Panics if not using `ArrowEngineData` from
     /// the default module
     fn into_record_batch(engine_data: Box<dyn EngineData>) -> RecordBatch {
@@ -0,0 +1,245 @@
+//! Planning of VACUUM, i.e. finding the files under a table root that the table no longer needs
+//! and that are therefore eligible for deletion.
+//!
+//! The entry point for this API is [`Snapshot::vacuum_plan`], which returns a [`VacuumPlan`]. Given
+//! all the files under the table root, the plan yields the ones eligible for deletion: the files
+//! that are not
+//! - referenced by the snapshot (data files and deletion vectors of `add` actions),
+//! - referenced by a tombstone (`remove` action) deleted within the retention period set by the
+//!   `delta.deletedFileRetentionDuration` table property (default 7 days),
+//! - modified within the retention period, since they may belong to an ongoing write,
+//! - hidden, i.e. in the `_delta_log` directory or any other file or directory whose name starts
+//!   with `_` or `.` (except for the `_change_data` directory and partition directories).
+//!
+//! Listing the files under the table root and deleting the files is up to the engine. Collecting
+//! the deletable files without deleting them amounts to a dry run. The default engine implements
+//! both, see `DefaultEngine::vacuum`.
+//!
+//! ```no_run
+//! # use delta_kernel::Engine;
+//! # use delta_kernel::Snapshot;
+//! # use delta_kernel::DeltaResult;
+//! # use delta_kernel::Error;
+//! # use delta_kernel::FileMeta;
+//! # use url::Url;
+//! fn list_files(table_root: &Url) -> Vec<DeltaResult<FileMeta>> {
+//!     todo!() /* engine-specific logic to list all files under the table root */
+//! }
+//!
+//! let engine: &dyn Engine = todo!(); /* create engine instance */
+//! let snapshot = Snapshot::try_from_uri("./tests/data/table-with-dv-small", engine, None)?;
+//! let plan = snapshot.vacuum_plan(engine)?;
+//! for path in plan.deletable_files(list_files(snapshot.table_root())) {
+//!     println!("{}", path?);
+//! }
+//! # Ok::<_, Error>(())
+//! ```
+use std::collections::HashSet;
+use std::sync::LazyLock;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::actions::visitors::visit_deletion_vector_at;
+use crate::checkpoint::deleted_file_retention_timestamp_with_time;
+use crate::checkpoint::log_replay::{CheckpointBatch, CheckpointLogReplayProcessor};
+use crate::checkpoint::CHECKPOINT_ACTIONS_SCHEMA;
+use crate::engine_data::{GetData, RowVisitor};
+use crate::log_replay::LogReplayProcessor as _;
+use crate::schema::{column_name, ColumnName, ColumnNamesAndTypes, DataType};
+use crate::utils::require;
+use crate::{DeltaResult, Engine, Error, FileMeta, Snapshot};
+
+use url::Url;
+
+/// The name of the directory holding the change data files, which is vacuumed despite its name
+const CHANGE_DATA_DIR: &str = "_change_data";
+
+/// The files under a table root to keep when vacuuming the table. See the module-level
+/// documentation for more details.
+#[derive(Debug)]
+pub struct VacuumPlan {
+    table_root: Url,
+    /// The files referenced by the snapshot and its unexpired tombstones
+    referenced_files: HashSet<Url>,
+    /// The prefixes (`<column>=`) of partition directory names, which are never hidden
+    partition_dir_prefixes: Vec<String>,
+    /// Files modified after this timestamp (in milliseconds since the unix epoch) are kept
+    retention_timestamp: i64,
+}
+
+impl VacuumPlan {
+    pub(crate) fn try_new(snapshot: &Snapshot, engine: &dyn Engine) -> DeltaResult<Self> {
+        let table_configuration = snapshot.table_configuration();
+        table_configuration.ensure_vacuum_supported()?;
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(|e| Error::generic(format!("Failed to calculate system time: {e}")))?;
+        let retention_timestamp = deleted_file_retention_timestamp_with_time(
+            snapshot.table_properties().deleted_file_retention_duration,
+            now,
+        )?;
+
+        // Checkpoint log replay selects exactly the add actions of the snapshot and the tombstones
+        // deleted after the retention timestamp
+        let actions = snapshot.log_segment().read_actions(
+            engine,
+            CHECKPOINT_ACTIONS_SCHEMA.clone(),
+            CHECKPOINT_ACTIONS_SCHEMA.clone(),
+            None,
+        )?;
+        let mut visitor = ReferencedFilesVisitor {
+            table_root: snapshot.table_root(),
+            selection_vector: vec![],
+            referenced_files: HashSet::new(),
+        };
+        for batch in CheckpointLogReplayProcessor::new(retention_timestamp, None)
+            .process_actions_iter(actions)
+        {
+            let CheckpointBatch { filtered_data, .. } = batch?;
+            visitor.selection_vector = filtered_data.selection_vector;
+            visitor.visit_rows_of(filtered_data.data.as_ref())?;
+        }
+        let referenced_files = visitor.referenced_files;
+
+        let schema = table_configuration.schema();
+        let partition_dir_prefixes = table_configuration
+            .metadata()
+            .partition_columns()
+            .iter()
+            .flat_map(|name| {
+                let physical_name = schema.field(name).map(|field| field.physical_name());
+                [Some(name.as_str()), physical_name]
+            })
+            .flatten()
+            .map(|name| format!("{name}="))
+            .collect();
+
+        Ok(Self {
+            table_root: snapshot.table_root().clone(),
+            referenced_files,
+            partition_dir_prefixes,
+            retention_timestamp,
+        })
+    }
+
+    /// The root of the table to vacuum.
+    pub fn table_root(&self) -> &Url {
+        &self.table_root
+    }
+
+    /// Files (and tombstones) older than this timestamp, in milliseconds since the unix epoch,
+    /// are eligible for deletion.
+    pub fn retention_timestamp(&self) -> i64 {
+        self.retention_timestamp
+    }
+
+    /// Returns the paths of the files eligible for deletion among `files`, which should be all the
+    /// files under the table root (recursively). Files outside the table root are ignored.
+    pub fn deletable_files<'a>(
+        &'a self,
+        files: impl IntoIterator<Item = DeltaResult<FileMeta>> + 'a,
+    ) -> impl Iterator<Item = DeltaResult<Url>> + 'a {
+        files.into_iter().filter_map(|file| match file {
+            Ok(file) => self.is_deletable(&file).then_some(Ok(file.location)),
+            Err(e) => Some(Err(e)),
+        })
+    }
+
+    fn is_deletable(&self, file: &FileMeta) -> bool {
+        let Some(relative_path) = file
+            .location
+            .as_str()
+            .strip_prefix(self.table_root.as_str())
+        else {
+            return false;
+        };
+        file.last_modified <= self.retention_timestamp
+            && !relative_path.split('/').any(|name| self.is_hidden(name))
+            && !self.referenced_files.contains(&file.location)
+    }
+
+    /// Whether the file or directory `name` is hidden from vacuum.
+    fn is_hidden(&self, name: &str) -> bool {
+        (name.starts_with('_') || name.starts_with('.'))
+            && name != CHANGE_DATA_DIR
+            && !self
+                .partition_dir_prefixes
+                .iter()
+                .any(|prefix| name.starts_with(prefix))
+    }
+}
+
+/// Collects the data files and deletion vector files of the selected add and remove actions.
+struct ReferencedFilesVisitor<'a> {
+    table_root: &'a Url,
+    selection_vector: Vec<bool>,
+    referenced_files: HashSet<Url>,
+}
+
+impl ReferencedFilesVisitor<'_> {
+    /// Records the file of the action whose path and deletion vector columns start at `getters[0]`,
+    /// if there is one.
+    fn visit_file_action<'a>(
+        &mut self,
+        i: usize,
+        getters: &[&'a dyn GetData<'a>],
+    ) -> DeltaResult<bool> {
+        let Some(path) = getters[0].get_str(i, "path")? else {
+            return Ok(false);
+        };
+        self.referenced_files.insert(self.table_root.join(path)?);
+        if let Some(dv) = visit_deletion_vector_at(i, &getters[1..])? {
+            if let Some(dv_path) = dv.absolute_path(self.table_root)? {
+                self.referenced_files.insert(dv_path);
+            }
+        }
+        Ok(true)
+    }
+}
+
+impl RowVisitor for ReferencedFilesVisitor<'_> {
+    fn selected_column_names_and_types(&self) -> (&'static [ColumnName], &'static [DataType]) {
+        static NAMES_AND_TYPES: LazyLock<ColumnNamesAndTypes> = LazyLock::new(|| {
+            const STRING: DataType = DataType::STRING;
+            const INTEGER: DataType = DataType::INTEGER;
+            const LONG: DataType = DataType::LONG;
+            let types_and_names = vec![
+                (STRING, column_name!("add.path")),
+                (STRING, column_name!("add.deletionVector.storageType")),
+                (STRING, column_name!("add.deletionVector.pathOrInlineDv")),
+                (INTEGER, column_name!("add.deletionVector.offset")),
+                (INTEGER, column_name!("add.deletionVector.sizeInBytes")),
+                (LONG, column_name!("add.deletionVector.cardinality")),
+                (STRING, column_name!("remove.path")),
+                (STRING, column_name!("remove.deletionVector.storageType")),
+                (STRING, column_name!("remove.deletionVector.pathOrInlineDv")),
+                (INTEGER, column_name!("remove.deletionVector.offset")),
+                (INTEGER, column_name!("remove.deletionVector.sizeInBytes")),
+                (LONG, column_name!("remove.deletionVector.cardinality")),
+            ];
+            let (types, names) = types_and_names.into_iter().unzip();
+            (names, types).into()
+        });
+        NAMES_AND_TYPES.as_ref()
+    }
+
+    fn visit<'a>(&mut self, row_count: usize, getters: &[&'a dyn GetData<'a>]) -> DeltaResult<()> {
+        require!(
+            getters.len() == 12,
+            Error::InternalError(format!(
+                "Wrong number of visitor getters: {}",
+                getters.len()
+            ))
+        );
+
+        for i in 0..row_count {
+            if self.selection_vector[i] && !self.visit_file_action(i, &getters[..6])? {
+                self.visit_file_action(i, &getters[6..])?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests;
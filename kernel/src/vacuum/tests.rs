@@ -0,0 +1,189 @@
+use std::sync::Arc;
+
+use crate::engine::default::{executor::tokio::TokioBackgroundExecutor, DefaultEngine};
+use crate::object_store::{memory::InMemory, path::Path, ObjectStore as _};
+use crate::utils::current_time_ms;
+use crate::utils::test_utils::{
+    add_json, block_on, in_memory_engine, metadata_json, protocol_json, remove_json,
+    write_json_commit,
+};
+use crate::{DeltaResult, FileMeta, Snapshot};
+
+use itertools::Itertools as _;
+use serde_json::{json, Value};
+use url::Url;
+
+type TestEngine = DefaultEngine<TokioBackgroundExecutor>;
+
+const TABLE_PATH: &str = "table/";
+const TABLE_ROOT: &str = "memory:///table/";
+const DV_FILE: &str = "ab/deletion_vector_d2c639aa-8816-431a-aaf6-d3fe2512ff61.bin";
+
+fn add(path: &str) -> Value {
+    add_json(path, json!({"_part": "a"}))
+}
+
+/// Writes a table partitioned by `_part`, with the following commits:
+/// - 0: protocol and metadata with the given writer features and configuration
+/// - 1: adds `_part=a/file1.parquet` (with a deletion vector) and `_part=a/file2.parquet`
+/// - 2: removes `_part=a/recent.parquet` now and `_part=b/expired.parquet` long ago
+fn write_test_table(
+    writer_features: &[&str],
+    configuration: Value,
+) -> DeltaResult<(Arc<InMemory>, TestEngine)> {
+    let (store, engine) = in_memory_engine();
+    let protocol = protocol_json(&["deletionVectors"], writer_features);
+    let metadata = metadata_json(&["_part"], configuration);
+    write_json_commit(&store, TABLE_PATH, 0, &[protocol, metadata])?;
+    let mut add_with_dv = add("_part=a/file1.parquet");
+    add_with_dv["add"]["deletionVector"] = json!({
+        "storageType": "u",
+        "pathOrInlineDv": "ab^-aqEH.-t@S}K{vb[*k^",
+        "offset": 4,
+        "sizeInBytes": 40,
+        "cardinality": 6,
+    });
+    let adds = [add_with_dv, add("_part=a/file2.parquet")];
+    write_json_commit(&store, TABLE_PATH, 1, &adds)?;
+    let removes = [
+        remove_json("_part=a/recent.parquet", current_time_ms()?),
+        remove_json("_part=b/expired.parquet", 1),
+    ];
+    write_json_commit(&store, TABLE_PATH, 2, &removes)?;
+    Ok((store, engine))
+}
+
+fn file_meta(path: &str, last_modified: i64) -> DeltaResult<FileMeta> {
+    Ok(FileMeta {
+        location: Url::parse(path)?,
+        last_modified,
+        size: 1,
+    })
+}
+
+#[test]
+fn test_deletable_files() -> DeltaResult<()> {
+    let (_, engine) = write_test_table(&["deletionVectors"], json!({}))?;
+    let snapshot = Snapshot::try_new(Url::parse(TABLE_ROOT)?, &engine, None)?;
+    let plan = snapshot.vacuum_plan(&engine)?;
+    assert_eq!(plan.table_root().as_str(), TABLE_ROOT);
+
+    let now = current_time_ms()?;
+    let files = [
+        // referenced by the snapshot
+        "_part=a/file1.parquet",
+        "_part=a/file2.parquet",
+        DV_FILE,
+        // referenced by a tombstone within the retention period
+        "_part=a/recent.parquet",
+        // hidden
+        "_delta_log/00000000000000000000.json",
+        "_part=a/.file1.parquet.crc",
+        "_tmp/file.parquet",
+        ".hidden/file.parquet",
+        // deletable
+        "_part=b/expired.parquet",
+        "_part=b/untracked.parquet",
+        "_change_data/cdc.parquet",
+        "untracked.parquet",
+    ]
+    .into_iter()
+    .map(|path| file_meta(&format!("{TABLE_ROOT}{path}"), 0))
+    .chain([
+        // modified within the retention period
+        file_meta(&format!("{TABLE_ROOT}recent.parquet"), now),
+        // outside the table root
+        file_meta("memory:///other/file.parquet", 0),
+    ]);
+    let deletable: Vec<_> = plan.deletable_files(files).try_collect()?;
+    assert_eq!(
+        deletable.iter().map(Url::as_str).collect_vec(),
+        [
+            "memory:///table/_part=b/expired.parquet",
+            "memory:///table/_part=b/untracked.parquet",
+            "memory:///table/_change_data/cdc.parquet",
+            "memory:///table/untracked.parquet",
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_deletable_files_propagates_listing_errors() -> DeltaResult<()> {
+    let (_, engine) = write_test_table(&["deletionVectors"], json!({}))?;
+    let snapshot = Snapshot::try_new(Url::parse(TABLE_ROOT)?, &engine, None)?;
+    let plan = snapshot.vacuum_plan(&engine)?;
+
+    let files = [Err(crate::Error::generic("listing failed"))];
+    let result: DeltaResult<Vec<_>> = plan.deletable_files(files).try_collect();
+    assert!(result.unwrap_err().to_string().contains("listing failed"));
+    Ok(())
+}
+
+#[test]
+fn test_vacuum_unsupported_protocol() -> DeltaResult<()> {
+    let (_, engine) = write_test_table(&["deletionVectors", "checkConstraints"], json!({}))?;
+    let snapshot = Snapshot::try_new(Url::parse(TABLE_ROOT)?, &engine, None)?;
+    assert!(snapshot.vacuum_plan(&engine).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_default_engine_vacuum() -> DeltaResult<()> {
+    // Without retention, every unreferenced file (and tombstone) is eligible for deletion
+    let configuration = json!({"delta.deletedFileRetentionDuration": "interval 0 seconds"});
+    let (store, engine) = write_test_table(&["deletionVectors"], configuration)?;
+    for path in [
+        "_part=a/file1.parquet",
+        "_part=a/file2.parquet",
+        "_part=a/recent.parquet",
+        "_part=b/untracked.parquet",
+        DV_FILE,
+    ] {
+        block_on(store.put(&Path::from(format!("{TABLE_PATH}{path}")), "data".into()))?;
+    }
+    block_on(store.put(&Path::from("other/file.parquet"), "data".into()))?;
+
+    let snapshot = Snapshot::try_new(Url::parse(TABLE_ROOT)?, &engine, None)?;
+    let plan = snapshot.vacuum_plan(&engine)?;
+    let list_store = || -> DeltaResult<Vec<String>> {
+        let files: Vec<_> = block_on(futures::TryStreamExt::try_collect(store.list(None)))?;
+        Ok(files
+            .into_iter()
+            .map(|meta| meta.location.to_string())
+            .sorted()
+            .collect())
+    };
+    let files_before = list_store()?;
+
+    // A dry run returns the deletable files without deleting them
+    let deletable = block_on(engine.vacuum(&plan, true))?;
+    let expected = [
+        "memory:///table/_part=a/recent.parquet",
+        "memory:///table/_part=b/untracked.parquet",
+    ];
+    assert_eq!(
+        deletable.iter().map(Url::as_str).sorted().collect_vec(),
+        expected
+    );
+    assert_eq!(list_store()?, files_before);
+
+    let deleted = block_on(engine.vacuum(&plan, false))?;
+    assert_eq!(
+        deleted.iter().map(Url::as_str).sorted().collect_vec(),
+        expected
+    );
+    assert_eq!(
+        list_store()?,
+        [
+            "other/file.parquet",
+            "table/_delta_log/00000000000000000000.json",
+            "table/_delta_log/00000000000000000001.json",
+            "table/_delta_log/00000000000000000002.json",
+            "table/_part=a/file1.parquet",
+            "table/_part=a/file2.parquet",
+            &format!("table/{DV_FILE}"),
+        ]
+    );
+    Ok(())
+}
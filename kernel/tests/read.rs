@@ -1023,6 +1023,144 @@ fn with_predicate_and_removes() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+#[test]
+fn exact_filtering() -> Result<(), Box<dyn std::error::Error>> {
+    let read_exact =
+        |path: &str, select_cols: Option<&[&str]>, predicate, expected: Vec<String>| {
+            let path = std::fs::canonicalize(PathBuf::from(path))?;
+            let url = url::Url::from_directory_path(path).unwrap();
+            let engine = Arc::new(DefaultEngine::try_new(
+                &url,
+                std::iter::empty::<(&str, &str)>(),
+                Arc::new(TokioBackgroundExecutor::new()),
+            )?);
+            let snapshot = Snapshot::try_new(url, engine.as_ref(), None)?;
+            let read_schema = select_cols.map(|select_cols| {
+                let table_schema = snapshot.schema();
+                let selected_fields = select_cols
+                    .iter()
+                    .map(|col| table_schema.field(col).cloned().unwrap());
+                Arc::new(Schema::new(selected_fields))
+            });
+            let scan = snapshot
+                .into_scan_builder()
+                .with_schema_opt(read_schema)
+                .with_predicate(Arc::new(predicate))
+                .with_exact_filtering(true)
+                .build()?;
+            read_with_execute(engine, &scan, &expected)
+        };
+
+    // rows of files that can't be skipped are filtered too, along with deleted rows
+    let expected = vec![
+        "+-------+",
+        "| value |",
+        "+-------+",
+        "| 4     |",
+        "| 5     |",
+        "| 6     |",
+        "| 7     |",
+        "| 8     |",
+        "+-------+",
+    ];
+    read_exact(
+        "./tests/data/table-with-dv-small/",
+        None,
+        Pred::gt(column_expr!("value"), Expr::literal(3)),
+        expected.into_iter().map(String::from).collect(),
+    )?;
+
+    // predicates over data and partition columns
+    let select_cols: Option<&[&str]> = Some(&["a_float", "number"]);
+    let cases = vec![
+        (
+            Pred::lt(column_expr!("a_float"), Expr::literal(3.5f64)),
+            table_for_numbers(vec![1, 2, 3]),
+        ),
+        (
+            Pred::and(
+                Pred::gt(column_expr!("number"), Expr::literal(1i64)),
+                Pred::lt(column_expr!("a_float"), Expr::literal(5.5f64)),
+            ),
+            table_for_numbers(vec![2, 3, 4]),
+        ),
+        // NULL is not TRUE, so rows for which the predicate is NULL are not selected
+        (
+            Pred::or(
+                Pred::gt(column_expr!("number"), Expr::literal(5i64)),
+                Pred::gt(
+                    column_expr!("a_float"),
+                    Expr::null_literal(DataType::DOUBLE),
+                ),
+            ),
+            table_for_numbers(vec![6]),
+        ),
+    ];
+    for (pred, mut expected) in cases {
+        sort_lines!(expected);
+        read_exact(
+            "./tests/data/basic_partitioned",
+            select_cols,
+            pred,
+            expected,
+        )?;
+    }
+    Ok(())
+}
+
+#[test]
+fn scan_with_limit() -> Result<(), Box<dyn std::error::Error>> {
+    let scan_limited = |path: &str, predicate: Option<Pred>, exact_filtering, limit| {
+        let path = std::fs::canonicalize(PathBuf::from(path))?;
+        let url = url::Url::from_directory_path(path).unwrap();
+        let engine = Arc::new(DefaultEngine::try_new(
+            &url,
+            std::iter::empty::<(&str, &str)>(),
+            Arc::new(TokioBackgroundExecutor::new()),
+        )?);
+        let snapshot = Snapshot::try_new(url, engine.as_ref(), None)?;
+        let scan = snapshot
+            .into_scan_builder()
+            .with_predicate(predicate.map(Arc::new))
+            .with_exact_filtering(exact_filtering)
+            .with_limit(limit)
+            .build()?;
+        let num_files: usize = scan
+            .scan_metadata(engine.as_ref())?
+            .map_ok(|scan_metadata| {
+                let sv = scan_metadata.scan_files.selection_vector;
+                sv.into_iter().filter(|selected| *selected).count()
+            })
+            .sum::<Result<_, _>>()?;
+        let num_rows: usize = read_scan(&scan, engine)?
+            .iter()
+            .map(|batch| batch.num_rows())
+            .sum();
+        Ok::<_, Box<dyn std::error::Error>>((num_files, num_rows))
+    };
+
+    // basic_partitioned has 6 files with 1 row each
+    let table = "./tests/data/basic_partitioned";
+    assert_eq!(scan_limited(table, None, false, None)?, (6, 6));
+    assert_eq!(scan_limited(table, None, false, Some(2))?, (2, 2));
+    assert_eq!(scan_limited(table, None, false, Some(10))?, (6, 6));
+
+    // the limit is ignored if the predicate isn't applied exactly
+    let predicate = Pred::gt(column_expr!("number"), Expr::literal(1i64));
+    assert_eq!(
+        scan_limited(table, Some(predicate.clone()), false, Some(2))?,
+        (5, 5)
+    );
+    // otherwise, all files may be needed but `execute` stops after `limit` rows
+    assert_eq!(scan_limited(table, Some(predicate), true, Some(2))?, (5, 2));
+
+    // table-with-dv-small has a single file with 10 rows, 2 of which are deleted
+    let table = "./tests/data/table-with-dv-small";
+    assert_eq!(scan_limited(table, None, false, Some(5))?, (1, 5));
+    assert_eq!(scan_limited(table, None, false, Some(9))?, (1, 8));
+    Ok(())
+}
+
 #[tokio::test]
 async fn predicate_on_non_nullable_partition_column() -> Result<(), Box<dyn std::error::Error>> {
     // Test for https://github.com/delta-io/delta-kernel-rs/issues/698
@@ -1141,6 +1279,50 @@ async fn predicate_on_non_nullable_column_missing_stats() -> Result<(), Box<dyn
     Ok(())
 }
 
+#[tokio::test]
+async fn predicate_with_deletion_vector_skips_row_groups() -> Result<(), Box<dyn std::error::Error>>
+{
+    // Two row groups, [1, 2, 3] and [4, 5, 6]. The deletion vector deletes row 4 (the value 5), so
+    // it must be applied by row index once the first row group is skipped.
+    let batch = generate_batch(vec![("val", vec![1, 2, 3, 4, 5, 6].into_array())])?;
+    let writer_props = WriterProperties::builder()
+        .set_max_row_group_size(3)
+        .build();
+
+    let storage = Arc::new(InMemory::new());
+    let actions = [
+        r#"{"protocol":{"minReaderVersion":3,"minWriterVersion":7,"readerFeatures":["deletionVectors"],"writerFeatures":["deletionVectors"]}}"#.to_string(),
+        r#"{"commitInfo":{"timestamp":1587968586154,"operation":"WRITE","operationParameters":{"mode":"ErrorIfExists","partitionBy":"[]"},"isBlindAppend":true}}"#.to_string(),
+        r#"{"metaData":{"id":"5fba94ed-9794-4965-ba6e-6ee3c0d22af9","format":{"provider":"parquet","options":{}},"schemaString":"{\"type\":\"struct\",\"fields\":[{\"name\":\"val\",\"type\":\"integer\",\"nullable\":true,\"metadata\":{}}]}","partitionColumns":[],"configuration":{"delta.enableDeletionVectors":"true"},"createdTime":1587968585495}}"#.to_string(),
+        format!(r#"{{"add":{{"path":"{PARQUET_FILE1}","partitionValues":{{}},"size":0,"modificationTime":1587968586000,"dataChange":true,"stats":"{{\"numRecords\":6,\"nullCount\":{{\"val\":0}},\"minValues\":{{\"val\":1}},\"maxValues\":{{\"val\":6}}}}","deletionVector":{{"storageType":"i","pathOrInlineDv":"^Bg9^0rr910000000000iXQKl0rr91000005c8Xg1onA4","sizeInBytes":34,"cardinality":1}}}}}}"#),
+    ];
+
+    add_commit(storage.as_ref(), 0, actions.iter().join("\n")).await?;
+    storage
+        .put(
+            &Path::from(PARQUET_FILE1),
+            record_batch_to_bytes_with_props(&batch, writer_props).into(),
+        )
+        .await?;
+
+    let location = Url::parse("memory:///")?;
+    let engine = Arc::new(DefaultEngine::new(
+        storage.clone(),
+        Arc::new(TokioBackgroundExecutor::new()),
+    ));
+    let snapshot = Arc::new(Snapshot::try_new(location, engine.as_ref(), None)?);
+
+    let predicate = Pred::gt(column_expr!("val"), Expr::literal(3));
+    let scan = snapshot
+        .scan_builder()
+        .with_predicate(Arc::new(predicate))
+        .build()?;
+    let batches = read_scan(&scan, engine)?;
+    let expected = generate_batch(vec![("val", vec![4, 6].into_array())])?;
+    assert_eq!(concat_batches(&expected.schema(), &batches)?, expected);
+    Ok(())
+}
+
 #[test]
 fn short_dv() -> Result<(), Box<dyn std::error::Error>> {
     let expected = vec![
@@ -2,10 +2,13 @@ use std::collections::HashMap;
 use std::sync::Arc;
 
 use delta_kernel::arrow::array::{
-    Int32Array, MapBuilder, MapFieldNames, StringArray, StringBuilder, TimestampMicrosecondArray,
+    cast::AsArray as _, Int32Array, MapBuilder, MapFieldNames, StringArray, StringBuilder,
+    TimestampMicrosecondArray,
 };
 use delta_kernel::arrow::datatypes::{DataType as ArrowDataType, Field, Schema as ArrowSchema};
+use delta_kernel::arrow::datatypes::{Int32Type, Int64Type};
 use delta_kernel::arrow::error::ArrowError;
+use delta_kernel::arrow::json::ReaderBuilder;
 use delta_kernel::arrow::record_batch::RecordBatch;
 
 use delta_kernel::object_store::path::Path;
@@ -16,12 +19,19 @@ use serde_json::Deserializer;
 
 use delta_kernel::engine::arrow_conversion::TryIntoArrow as _;
 use delta_kernel::engine::arrow_data::ArrowEngineData;
-use delta_kernel::schema::{DataType, StructField, StructType};
+use delta_kernel::engine::default::executor::tokio::TokioBackgroundExecutor;
+use delta_kernel::engine::default::DefaultEngine;
+use delta_kernel::expressions::{column_expr, Expression as Expr, Predicate as Pred};
+use delta_kernel::schema::{DataType, MetadataColumnSpec, StructField, StructType};
+use delta_kernel::transaction::{
+    remove_files_schema, CommitResult, CreateTableBuilder, Transaction,
+};
 use delta_kernel::DeltaResult;
 use delta_kernel::Error as KernelError;
 use delta_kernel::Snapshot;
 
-use test_utils::{create_table, engine_store_setup, setup_test_tables};
+use test_utils::{create_table, engine_store_setup, read_scan, setup_test_tables};
+use url::Url;
 
 mod common;
 use test_utils::test_read;
@@ -343,7 +353,8 @@ async fn test_append() -> Result<(), Box<dyn std::error::Error>> {
                     "partitionValues": {},
                     "size": size,
                     "modificationTime": 0,
-                    "dataChange": true
+                    "dataChange": true,
+                    "stats": r#"{"maxValues":{"number":3},"minValues":{"number":1},"nullCount":{"number":0},"numRecords":3}"#
                 }
             }),
             json!({
@@ -352,7 +363,8 @@ async fn test_append() -> Result<(), Box<dyn std::error::Error>> {
                     "partitionValues": {},
                     "size": size,
                     "modificationTime": 0,
-                    "dataChange": true
+                    "dataChange": true,
+                    "stats": r#"{"maxValues":{"number":6},"minValues":{"number":4},"nullCount":{"number":0},"numRecords":3}"#
                 }
             }),
         ];
@@ -482,7 +494,8 @@ async fn test_append_partitioned() -> Result<(), Box<dyn std::error::Error>> {
                     },
                     "size": size,
                     "modificationTime": 0,
-                    "dataChange": true
+                    "dataChange": true,
+                    "stats": r#"{"maxValues":{"number":3},"minValues":{"number":1},"nullCount":{"number":0},"numRecords":3}"#
                 }
             }),
             json!({
@@ -493,7 +506,8 @@ async fn test_append_partitioned() -> Result<(), Box<dyn std::error::Error>> {
                     },
                     "size": size,
                     "modificationTime": 0,
-                    "dataChange": true
+                    "dataChange": true,
+                    "stats": r#"{"maxValues":{"number":6},"minValues":{"number":4},"nullCount":{"number":0},"numRecords":3}"#
                 }
             }),
         ];
@@ -791,3 +805,1364 @@ async fn test_append_timestamp_ntz() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+// build remove_files metadata from json rows of the form {"path": .., "size": .., ..}
+fn remove_files_data(
+    rows: &[serde_json::Value],
+) -> Result<Box<ArrowEngineData>, Box<dyn std::error::Error>> {
+    let schema: ArrowSchema = remove_files_schema().as_ref().try_into_arrow()?;
+    let json = rows.iter().map(|row| row.to_string()).join("\n");
+    let batch = ReaderBuilder::new(Arc::new(schema))
+        .build(json.as_bytes())?
+        .next()
+        .ok_or("no remove_files data")??;
+    Ok(Box::new(ArrowEngineData::new(batch)))
+}
+
+#[tokio::test]
+async fn test_remove_files() -> Result<(), Box<dyn std::error::Error>> {
+    // setup tracing
+    let _ = tracing_subscriber::fmt::try_init();
+
+    // create a simple table: one int column named 'number'
+    let schema = Arc::new(StructType::new(vec![StructField::nullable(
+        "number",
+        DataType::INTEGER,
+    )]));
+
+    for (table_url, engine, store, table_name) in setup_test_tables(schema.clone(), &[]).await? {
+        let engine = Arc::new(engine);
+
+        // append two files in version 1
+        let snapshot = Arc::new(Snapshot::try_new(table_url.clone(), engine.as_ref(), None)?);
+        let mut txn = snapshot.transaction()?.with_commit_info(new_commit_info()?);
+        let write_context = txn.get_write_context();
+        for data in [[1, 2, 3], [4, 5, 6]] {
+            let data = RecordBatch::try_new(
+                Arc::new(schema.as_ref().try_into_arrow()?),
+                vec![Arc::new(Int32Array::from(data.to_vec()))],
+            )?;
+            let add_files_metadata = engine
+                .write_parquet(
+                    &ArrowEngineData::new(data),
+                    &write_context,
+                    HashMap::new(),
+                    true,
+                )
+                .await?;
+            txn.add_files(add_files_metadata);
+        }
+        txn.commit(engine.as_ref())?;
+
+        let commit1 = parsed_commits_of(&store, table_name, 1).await?;
+        let first_add = &commit1[1]["add"];
+        let remove_row = json!({
+            "path": first_add["path"],
+            "partitionValues": {},
+            "size": first_add["size"],
+            "dataChange": true,
+            "tags": {"tag": "value"},
+            "baseRowId": 10,
+            "defaultRowCommitVersion": 1,
+        });
+
+        // remove the first file in version 2. The tags and row tracking fields are carried over.
+        let snapshot = Arc::new(Snapshot::try_new(table_url.clone(), engine.as_ref(), None)?);
+        let mut txn = snapshot
+            .transaction()?
+            .with_operation("DELETE".to_string())
+            .with_commit_info(new_commit_info()?);
+        txn.remove_files(remove_files_data(&[remove_row.clone()])?)?;
+        txn.commit(engine.as_ref())?;
+
+        let mut parsed_commits = parsed_commits_of(&store, table_name, 2).await?;
+
+        // the deletion timestamp is the commit timestamp
+        let commit_timestamp = parsed_commits[0]["commitInfo"]["timestamp"].clone();
+        assert_eq!(
+            parsed_commits[1]["remove"]["deletionTimestamp"],
+            commit_timestamp
+        );
+        set_value(&mut parsed_commits[0], "commitInfo.timestamp", json!(0))?;
+        set_value(&mut parsed_commits[1], "remove.deletionTimestamp", json!(0))?;
+
+        let expected_commit = vec![
+            json!({
+                "commitInfo": {
+                    "timestamp": 0,
+                    "operation": "DELETE",
+                    "kernelVersion": format!("v{}", env!("CARGO_PKG_VERSION")),
+                    "operationParameters": {},
+                    "engineCommitInfo": {
+                        "engineInfo": "default engine"
+                    }
+                }
+            }),
+            json!({
+                "remove": {
+                    "path": first_add["path"],
+                    "deletionTimestamp": 0,
+                    "dataChange": true,
+                    "extendedFileMetadata": true,
+                    "partitionValues": {},
+                    "size": first_add["size"],
+                    "tags": {"tag": "value"},
+                    "baseRowId": 10,
+                    "defaultRowCommitVersion": 1,
+                }
+            }),
+        ];
+        assert_eq!(parsed_commits, expected_commit);
+
+        test_read(
+            &ArrowEngineData::new(RecordBatch::try_new(
+                Arc::new(schema.as_ref().try_into_arrow()?),
+                vec![Arc::new(Int32Array::from(vec![4, 5, 6]))],
+            )?),
+            &table_url,
+            engine.clone(),
+        )?;
+
+        // the file was already removed, so it cannot be removed again
+        let snapshot = Arc::new(Snapshot::try_new(table_url.clone(), engine.as_ref(), None)?);
+        let mut txn = snapshot.transaction()?.with_commit_info(new_commit_info()?);
+        txn.remove_files(remove_files_data(&[remove_row])?)?;
+        assert!(matches!(
+            txn.commit(engine.as_ref()),
+            Err(KernelError::Generic(msg)) if msg.contains("not present in the table at version 2")
+        ));
+
+        // a file cannot be removed twice in the same transaction
+        let second_add = &commit1[2]["add"];
+        let remove_row = json!({
+            "path": second_add["path"],
+            "partitionValues": {},
+            "size": second_add["size"],
+            "dataChange": true,
+        });
+        let snapshot = Arc::new(Snapshot::try_new(table_url.clone(), engine.as_ref(), None)?);
+        let mut txn = snapshot.transaction()?.with_commit_info(new_commit_info()?);
+        txn.remove_files(remove_files_data(&[remove_row.clone(), remove_row])?)?;
+        assert!(matches!(
+            txn.commit(engine.as_ref()),
+            Err(KernelError::Generic(msg)) if msg.contains("is removed more than once")
+        ));
+    }
+    Ok(())
+}
+
+async fn parsed_commits_of(
+    store: &Arc<dyn ObjectStore>,
+    table_name: &str,
+    version: u64,
+) -> Result<Vec<serde_json::Value>, Box<dyn std::error::Error>> {
+    let commit = store
+        .get(&Path::from(format!(
+            "/{table_name}/_delta_log/{version:020}.json"
+        )))
+        .await?;
+    Ok(Deserializer::from_slice(&commit.bytes().await?)
+        .into_iter::<serde_json::Value>()
+        .try_collect()?)
+}
+
+#[tokio::test]
+async fn test_remove_files_append_only() -> Result<(), Box<dyn std::error::Error>> {
+    let (store, engine, table_url) = engine_store_setup("test_table_append_only", true);
+    let actions = [
+        json!({ "protocol": { "minReaderVersion": 1, "minWriterVersion": 2 } }),
+        json!({
+            "metaData": {
+                "id": "test_id",
+                "format": { "provider": "parquet", "options": {} },
+                "schemaString": "{\"type\":\"struct\",\"fields\":[{\"name\":\"number\",\"type\":\"integer\",\"nullable\":true,\"metadata\":{}}]}",
+                "partitionColumns": [],
+                "configuration": { "delta.appendOnly": "true" },
+                "createdTime": 1677811175819u64
+            }
+        }),
+    ];
+    store
+        .put(
+            &Path::from("test_table_append_only/_delta_log/00000000000000000000.json"),
+            actions.iter().join("\n").into(),
+        )
+        .await?;
+
+    let snapshot = Arc::new(Snapshot::try_new(table_url, &engine, None)?);
+    let mut txn = snapshot.transaction()?.with_commit_info(new_commit_info()?);
+    let remove_row = json!({
+        "path": "part-00000.parquet",
+        "partitionValues": {},
+        "size": 100,
+        "dataChange": true,
+    });
+    assert!(matches!(
+        txn.remove_files(remove_files_data(&[remove_row])?),
+        Err(KernelError::Unsupported(_))
+    ));
+    Ok(())
+}
+
+// create an empty table (one int column named 'number') with deletion vectors enabled
+async fn create_deletion_vector_table(
+    table_name: &str,
+) -> Result<
+    (
+        Arc<dyn ObjectStore>,
+        Arc<DefaultEngine<TokioBackgroundExecutor>>,
+        Url,
+    ),
+    Box<dyn std::error::Error>,
+> {
+    let (store, engine, table_url) = engine_store_setup(table_name, true);
+    let actions = [
+        json!({
+            "protocol": {
+                "minReaderVersion": 3,
+                "minWriterVersion": 7,
+                "readerFeatures": ["deletionVectors"],
+                "writerFeatures": ["deletionVectors"]
+            }
+        }),
+        json!({
+            "metaData": {
+                "id": "test_id",
+                "format": { "provider": "parquet", "options": {} },
+                "schemaString": "{\"type\":\"struct\",\"fields\":[{\"name\":\"number\",\"type\":\"integer\",\"nullable\":true,\"metadata\":{}}]}",
+                "partitionColumns": [],
+                "configuration": { "delta.enableDeletionVectors": "true" },
+                "createdTime": 1677811175819u64
+            }
+        }),
+    ];
+    store
+        .put(
+            &Path::from(format!("{table_name}/_delta_log/00000000000000000000.json")),
+            actions.iter().join("\n").into(),
+        )
+        .await?;
+    Ok((store, Arc::new(engine), table_url))
+}
+
+#[tokio::test]
+async fn test_delete_with_deletion_vectors() -> Result<(), Box<dyn std::error::Error>> {
+    // setup tracing
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let table_name = "test_table_delete";
+    let (store, engine, table_url) = create_deletion_vector_table(table_name).await?;
+    let schema = Arc::new(StructType::new(vec![StructField::nullable(
+        "number",
+        DataType::INTEGER,
+    )]));
+
+    // append two files in version 1
+    let snapshot = Arc::new(Snapshot::try_new(table_url.clone(), engine.as_ref(), None)?);
+    let mut txn = snapshot.transaction()?.with_commit_info(new_commit_info()?);
+    let write_context = txn.get_write_context();
+    for data in [[1, 2, 3], [4, 5, 6]] {
+        let data = RecordBatch::try_new(
+            Arc::new(schema.as_ref().try_into_arrow()?),
+            vec![Arc::new(Int32Array::from(data.to_vec()))],
+        )?;
+        let add_files_metadata = engine
+            .write_parquet(
+                &ArrowEngineData::new(data),
+                &write_context,
+                HashMap::new(),
+                true,
+            )
+            .await?;
+        txn.add_files(add_files_metadata);
+    }
+    txn.commit(engine.as_ref())?;
+    let commit1 = parsed_commits_of(&store, table_name, 1).await?;
+
+    // delete one row of the first file and two rows of the second file in version 2
+    let snapshot = Arc::new(Snapshot::try_new(table_url.clone(), engine.as_ref(), None)?);
+    let mut txn = snapshot
+        .transaction()?
+        .with_operation("DELETE".to_string())
+        .with_commit_info(new_commit_info()?);
+    let predicate = Pred::or(
+        Pred::eq(column_expr!("number"), Expr::literal(2)),
+        Pred::ge(column_expr!("number"), Expr::literal(5)),
+    );
+    assert_eq!(txn.delete(engine.as_ref(), Arc::new(predicate))?, 3);
+    txn.commit(engine.as_ref())?;
+
+    let commit2 = parsed_commits_of(&store, table_name, 2).await?;
+    assert_eq!(commit2.len(), 5);
+    for (add, old_add, cardinality) in
+        [(&commit2[1], &commit1[1], 1), (&commit2[2], &commit1[2], 2)]
+    {
+        let add = &add["add"];
+        let old_add = &old_add["add"];
+        assert_eq!(add["path"], old_add["path"]);
+        assert_eq!(add["size"], old_add["size"]);
+        assert_eq!(add["modificationTime"], old_add["modificationTime"]);
+        assert_eq!(add["deletionVector"]["storageType"], "u");
+        assert_eq!(add["deletionVector"]["cardinality"], cardinality);
+    }
+    // both deletion vectors are written to the same file
+    let (first_dv, second_dv) = (
+        &commit2[1]["add"]["deletionVector"],
+        &commit2[2]["add"]["deletionVector"],
+    );
+    assert_eq!(first_dv["pathOrInlineDv"], second_dv["pathOrInlineDv"]);
+    assert_eq!(first_dv["offset"], 1);
+    assert_eq!(
+        second_dv["offset"].as_i64(),
+        Some(1 + 4 + first_dv["sizeInBytes"].as_i64().unwrap() + 4)
+    );
+    for (remove, old_add) in [(&commit2[3], &commit1[1]), (&commit2[4], &commit1[2])] {
+        let remove = &remove["remove"];
+        assert_eq!(remove["path"], old_add["add"]["path"]);
+        assert_eq!(remove.get("deletionVector"), None);
+    }
+    test_read(
+        &ArrowEngineData::new(RecordBatch::try_new(
+            Arc::new(schema.as_ref().try_into_arrow()?),
+            vec![Arc::new(Int32Array::from(vec![1, 3, 4]))],
+        )?),
+        &table_url,
+        engine.clone(),
+    )?;
+
+    // delete the remaining rows of the first file in version 3. Rows that are already deleted are
+    // not counted again, and the file is removed without a replacement.
+    let snapshot = Arc::new(Snapshot::try_new(table_url.clone(), engine.as_ref(), None)?);
+    let mut txn = snapshot.transaction()?.with_commit_info(new_commit_info()?);
+    let predicate = Pred::le(column_expr!("number"), Expr::literal(3));
+    assert_eq!(txn.delete(engine.as_ref(), Arc::new(predicate))?, 2);
+    txn.commit(engine.as_ref())?;
+
+    let commit3 = parsed_commits_of(&store, table_name, 3).await?;
+    assert_eq!(commit3.len(), 2);
+    assert_eq!(commit3[1]["remove"]["path"], commit1[1]["add"]["path"]);
+    assert_eq!(
+        commit3[1]["remove"]["deletionVector"],
+        commit2[1]["add"]["deletionVector"]
+    );
+    test_read(
+        &ArrowEngineData::new(RecordBatch::try_new(
+            Arc::new(schema.as_ref().try_into_arrow()?),
+            vec![Arc::new(Int32Array::from(vec![4]))],
+        )?),
+        &table_url,
+        engine.clone(),
+    )?;
+
+    // deleting rows that don't exist is a no-op
+    let snapshot = Arc::new(Snapshot::try_new(table_url.clone(), engine.as_ref(), None)?);
+    let mut txn = snapshot.transaction()?.with_commit_info(new_commit_info()?);
+    let predicate = Pred::eq(column_expr!("number"), Expr::literal(2));
+    assert_eq!(txn.delete(engine.as_ref(), Arc::new(predicate))?, 0);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_delete_keeps_tags_and_clustering_provider() -> Result<(), Box<dyn std::error::Error>>
+{
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let table_name = "test_table_delete_tags";
+    let (store, engine, table_url) = create_deletion_vector_table(table_name).await?;
+    let schema = Arc::new(StructType::new(vec![StructField::nullable(
+        "number",
+        DataType::INTEGER,
+    )]));
+
+    // append a file in version 1
+    let snapshot = Arc::new(Snapshot::try_new(table_url.clone(), engine.as_ref(), None)?);
+    let mut txn = snapshot.transaction()?.with_commit_info(new_commit_info()?);
+    let data = RecordBatch::try_new(
+        Arc::new(schema.as_ref().try_into_arrow()?),
+        vec![Arc::new(Int32Array::from(vec![1, 2, 3]))],
+    )?;
+    let add_files_metadata = engine
+        .write_parquet(
+            &ArrowEngineData::new(data),
+            &txn.get_write_context(),
+            HashMap::new(),
+            true,
+        )
+        .await?;
+    txn.add_files(add_files_metadata);
+    txn.commit(engine.as_ref())?;
+
+    // re-add the file with tags and a clustering provider in version 2
+    let commit1 = parsed_commits_of(&store, table_name, 1).await?;
+    let mut add = commit1[1]["add"].clone();
+    add["dataChange"] = json!(false);
+    add["tags"] = json!({ "INSERTION_TIME": "1677811178336000", "OPTIMIZE_TARGET_SIZE": "1" });
+    add["clusteringProvider"] = json!("liquid");
+    store
+        .put(
+            &Path::from(format!("{table_name}/_delta_log/00000000000000000002.json")),
+            json!({ "add": add }).to_string().into(),
+        )
+        .await?;
+
+    // delete a row in version 3. Both the remove and the replacement add keep the tags, and the
+    // add keeps the clustering provider.
+    let snapshot = Arc::new(Snapshot::try_new(table_url.clone(), engine.as_ref(), None)?);
+    let mut txn = snapshot.transaction()?.with_commit_info(new_commit_info()?);
+    let predicate = Pred::eq(column_expr!("number"), Expr::literal(2));
+    assert_eq!(txn.delete(engine.as_ref(), Arc::new(predicate))?, 1);
+    txn.commit(engine.as_ref())?;
+
+    let commit3 = parsed_commits_of(&store, table_name, 3).await?;
+    assert_eq!(commit3.len(), 3);
+    let new_add = &commit3[1]["add"];
+    assert_eq!(new_add["path"], add["path"]);
+    assert_eq!(new_add["tags"], add["tags"]);
+    assert_eq!(new_add["clusteringProvider"], "liquid");
+    assert_eq!(new_add["deletionVector"]["cardinality"], 1);
+    let remove = &commit3[2]["remove"];
+    assert_eq!(remove["path"], add["path"]);
+    assert_eq!(remove["tags"], add["tags"]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_delete_requires_deletion_vectors() -> Result<(), Box<dyn std::error::Error>> {
+    let schema = Arc::new(StructType::new(vec![StructField::nullable(
+        "number",
+        DataType::INTEGER,
+    )]));
+    for (table_url, engine, _, _) in setup_test_tables(schema, &[]).await? {
+        let snapshot = Arc::new(Snapshot::try_new(table_url, &engine, None)?);
+        let mut txn = snapshot.transaction()?.with_commit_info(new_commit_info()?);
+        let predicate = Pred::eq(column_expr!("number"), Expr::literal(2));
+        assert!(matches!(
+            txn.delete(&engine, Arc::new(predicate)),
+            Err(KernelError::Unsupported(_))
+        ));
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_create_table() -> Result<(), Box<dyn std::error::Error>> {
+    let _ = tracing_subscriber::fmt::try_init();
+    let (store, engine, table_url) = engine_store_setup("test_create_table", true);
+    let schema = Arc::new(StructType::new(vec![
+        StructField::nullable("number", DataType::INTEGER),
+        StructField::nullable("partition", DataType::STRING),
+    ]));
+    let create_table = || {
+        CreateTableBuilder::new(table_url.clone(), schema.clone())
+            .with_partition_columns(["partition"])
+            .with_table_properties([
+                ("delta.enableDeletionVectors", "true"),
+                ("delta.feature.appendOnly", "supported"),
+            ])
+            .build()
+    };
+
+    // write a file as part of creating the table
+    let engine = Arc::new(engine);
+    let mut txn = create_table()?.with_commit_info(new_commit_info()?);
+    let data = RecordBatch::try_new(
+        Arc::new(ArrowSchema::new(vec![Field::new(
+            "number",
+            ArrowDataType::Int32,
+            true,
+        )])),
+        vec![Arc::new(Int32Array::from(vec![1, 2, 3]))],
+    )?;
+    let partition_values = HashMap::from([("partition".to_string(), "a".to_string())]);
+    let add_metadata = engine
+        .write_parquet(
+            &ArrowEngineData::new(data),
+            &txn.get_write_context(),
+            partition_values,
+            true,
+        )
+        .await?;
+    txn.add_files(add_metadata);
+    assert!(matches!(
+        txn.commit(engine.as_ref())?,
+        CommitResult::Committed(0)
+    ));
+
+    let commits = parsed_commits_of(&store, "test_create_table", 0).await?;
+    assert_eq!(commits.len(), 4);
+    assert_eq!(
+        commits[1],
+        json!({
+            "protocol": {
+                "minReaderVersion": 3,
+                "minWriterVersion": 7,
+                "readerFeatures": ["deletionVectors"],
+                "writerFeatures": ["appendOnly", "deletionVectors"]
+            }
+        })
+    );
+    let metadata = &commits[2]["metaData"];
+    assert_eq!(metadata["partitionColumns"], json!(["partition"]));
+    assert_eq!(
+        metadata["configuration"],
+        json!({ "delta.enableDeletionVectors": "true" })
+    );
+    assert_eq!(
+        metadata["createdTime"],
+        commits[0]["commitInfo"]["timestamp"]
+    );
+    assert!(commits[3]["add"].is_object());
+
+    let snapshot = Snapshot::try_new(table_url.clone(), engine.as_ref(), None)?;
+    assert_eq!(snapshot.version(), 0);
+    assert_eq!(snapshot.schema(), schema);
+    let expected = RecordBatch::try_new(
+        Arc::new(schema.as_ref().try_into_arrow()?),
+        vec![
+            Arc::new(Int32Array::from(vec![1, 2, 3])),
+            Arc::new(StringArray::from(vec!["a"; 3])),
+        ],
+    )?;
+    test_read(&ArrowEngineData::new(expected), &table_url, engine.clone())?;
+
+    // the table now exists, so creating it again conflicts
+    let txn = create_table()?.with_commit_info(new_commit_info()?);
+    assert!(matches!(
+        txn.commit(engine.as_ref())?,
+        CommitResult::Conflict(_, 0)
+    ));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_create_table_invalid() -> Result<(), Box<dyn std::error::Error>> {
+    let table_url = Url::parse("memory:///test_create_table_invalid/")?;
+    let schema = Arc::new(StructType::new(vec![StructField::nullable(
+        "number",
+        DataType::INTEGER,
+    )]));
+    let builder = || CreateTableBuilder::new(table_url.clone(), schema.clone());
+
+    // the kernel can't write change data feed, so it can't create a table that enables it
+    let result = builder()
+        .with_table_properties([("delta.enableChangeDataFeed", "true")])
+        .build();
+    assert!(matches!(result, Err(KernelError::Unsupported(_))));
+
+    let result = builder().with_table_features(["someNewFeature"]).build();
+    assert!(matches!(result, Err(KernelError::Unsupported(_))));
+
+    let result = builder().with_partition_columns(["missing"]).build();
+    assert!(matches!(result, Err(KernelError::Generic(_))));
+
+    // a transaction that creates a table has no files to remove
+    let mut txn = builder().build()?;
+    let remove_row = json!({
+        "path": "part-00000.parquet",
+        "partitionValues": {},
+        "size": 100,
+        "dataChange": true,
+    });
+    assert!(matches!(
+        txn.remove_files(remove_files_data(&[remove_row])?),
+        Err(KernelError::Unsupported(_))
+    ));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_concurrent_commits() -> Result<(), Box<dyn std::error::Error>> {
+    // setup tracing
+    let _ = tracing_subscriber::fmt::try_init();
+
+    // create a simple table: one int column named 'number'
+    let schema = Arc::new(StructType::new(vec![StructField::nullable(
+        "number",
+        DataType::INTEGER,
+    )]));
+
+    for (table_url, engine, store, table_name) in setup_test_tables(schema.clone(), &[]).await? {
+        let engine = Arc::new(engine);
+        let snapshot = Arc::new(Snapshot::try_new(table_url.clone(), engine.as_ref(), None)?);
+        let new_txn = || -> DeltaResult<_> {
+            Ok(snapshot
+                .clone()
+                .transaction()?
+                .with_commit_info(new_commit_info()?))
+        };
+
+        // three transactions read version 0; the first one to commit wins version 1
+        let first = new_txn()?.with_transaction_id("app".to_string(), 1);
+        let same_app_id = new_txn()?.with_transaction_id("app".to_string(), 2);
+        let mut blind_append = new_txn()?;
+        let data = RecordBatch::try_new(
+            Arc::new(schema.as_ref().try_into_arrow()?),
+            vec![Arc::new(Int32Array::from(vec![1, 2, 3]))],
+        )?;
+        let add_files_metadata = engine
+            .write_parquet(
+                &ArrowEngineData::new(data),
+                &blind_append.get_write_context(),
+                HashMap::new(),
+                true,
+            )
+            .await?;
+        blind_append.add_files(add_files_metadata);
+        assert!(matches!(
+            first.commit(engine.as_ref())?,
+            CommitResult::Committed(1)
+        ));
+
+        // a blind append doesn't conflict, so it is retried at the next version
+        assert!(matches!(
+            blind_append.commit(engine.as_ref())?,
+            CommitResult::Committed(2)
+        ));
+
+        // transactions with the same app id conflict
+        assert!(matches!(
+            same_app_id.commit(engine.as_ref())?,
+            CommitResult::Conflict(_, 1)
+        ));
+
+        // transactions that remove the same file conflict
+        let commit2 = parsed_commits_of(&store, table_name, 2).await?;
+        let add = &commit2[1]["add"];
+        let remove_row = json!({
+            "path": add["path"],
+            "partitionValues": {},
+            "size": add["size"],
+            "dataChange": true,
+        });
+        let snapshot = Arc::new(Snapshot::try_new(table_url.clone(), engine.as_ref(), None)?);
+        let mut txns = (0..2)
+            .map(|_| -> Result<_, Box<dyn std::error::Error>> {
+                let mut txn = snapshot
+                    .clone()
+                    .transaction()?
+                    .with_commit_info(new_commit_info()?);
+                txn.remove_files(remove_files_data(&[remove_row.clone()])?)?;
+                Ok(txn)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let second = txns.pop().unwrap();
+        let first = txns.pop().unwrap();
+        assert!(matches!(
+            first.commit(engine.as_ref())?,
+            CommitResult::Committed(3)
+        ));
+        assert!(matches!(
+            second.commit(engine.as_ref())?,
+            CommitResult::Conflict(_, 3)
+        ));
+
+        let snapshot = Snapshot::try_new(table_url.clone(), engine.as_ref(), None)?;
+        assert_eq!(snapshot.version(), 3);
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_in_commit_timestamps() -> Result<(), Box<dyn std::error::Error>> {
+    // setup tracing
+    let _ = tracing_subscriber::fmt::try_init();
+
+    // create a simple table: one int column named 'number'
+    let schema = Arc::new(StructType::new(vec![StructField::nullable(
+        "number",
+        DataType::INTEGER,
+    )]));
+
+    for (table_url, engine, store, table_name) in setup_test_tables(schema, &[]).await? {
+        // enable in-commit timestamps in version 1
+        let snapshot = Arc::new(Snapshot::try_new(table_url.clone(), &engine, None)?);
+        assert_eq!(snapshot.get_in_commit_timestamp(&engine)?, None);
+        let txn = snapshot
+            .transaction()?
+            .with_commit_info(new_commit_info()?)
+            .with_in_commit_timestamps_enabled();
+        // the in-commit timestamp is taken when the commit is written, not when the transaction
+        // is created
+        std::thread::sleep(std::time::Duration::from_millis(50));
+        let commit_start: i64 = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)?
+            .as_millis()
+            .try_into()?;
+        txn.commit(&engine)?;
+
+        let commit1 = parsed_commits_of(&store, table_name, 1).await?;
+        let enablement_timestamp = commit1[0]["commitInfo"]["inCommitTimestamp"]
+            .as_i64()
+            .ok_or("missing in-commit timestamp")?;
+        assert!(enablement_timestamp >= commit_start);
+        assert!(commit1[1]["protocol"]["writerFeatures"]
+            .as_array()
+            .ok_or("missing writer features")?
+            .contains(&json!("inCommitTimestamp")));
+        let configuration = &commit1[2]["metaData"]["configuration"];
+        assert_eq!(configuration["delta.enableInCommitTimestamps"], "true");
+        assert_eq!(
+            configuration["delta.inCommitTimestampEnablementVersion"],
+            "1"
+        );
+        assert_eq!(
+            configuration["delta.inCommitTimestampEnablementTimestamp"],
+            enablement_timestamp.to_string()
+        );
+
+        let snapshot = Arc::new(Snapshot::try_new(table_url.clone(), &engine, None)?);
+        assert_eq!(
+            snapshot.get_in_commit_timestamp(&engine)?,
+            Some(enablement_timestamp)
+        );
+
+        // in-commit timestamps are monotonic, even if the previous commit is in the future
+        let future_timestamp = enablement_timestamp + 3_600_000;
+        let commit2 = json!({ "commitInfo": { "inCommitTimestamp": future_timestamp } });
+        store
+            .put(
+                &Path::from(format!(
+                    "/{table_name}/_delta_log/00000000000000000002.json"
+                )),
+                commit2.to_string().into(),
+            )
+            .await?;
+        let snapshot = Arc::new(Snapshot::try_new(table_url.clone(), &engine, None)?);
+        let txn = snapshot.transaction()?.with_commit_info(new_commit_info()?);
+        txn.commit(&engine)?;
+
+        let commit3 = parsed_commits_of(&store, table_name, 3).await?;
+        assert_eq!(commit3.len(), 1);
+        assert_eq!(
+            commit3[0]["commitInfo"]["inCommitTimestamp"],
+            json!(future_timestamp + 1)
+        );
+
+        // time travel uses the in-commit timestamps
+        let snapshot =
+            Snapshot::try_new_at_timestamp(table_url.clone(), &engine, future_timestamp)?;
+        assert_eq!(snapshot.version(), 2);
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_create_table_with_in_commit_timestamps() -> Result<(), Box<dyn std::error::Error>> {
+    let _ = tracing_subscriber::fmt::try_init();
+    let (store, engine, table_url) = engine_store_setup("test_create_table_ict", true);
+    let schema = Arc::new(StructType::new(vec![StructField::nullable(
+        "number",
+        DataType::INTEGER,
+    )]));
+    let txn = CreateTableBuilder::new(table_url.clone(), schema)
+        .with_table_properties([("delta.enableInCommitTimestamps", "true")])
+        .build()?
+        .with_commit_info(new_commit_info()?);
+    assert!(matches!(txn.commit(&engine)?, CommitResult::Committed(0)));
+
+    let commit0 = parsed_commits_of(&store, "test_create_table_ict", 0).await?;
+    let in_commit_timestamp = &commit0[0]["commitInfo"]["inCommitTimestamp"];
+    assert_eq!(
+        commit0[1]["protocol"],
+        json!({
+            "minReaderVersion": 1,
+            "minWriterVersion": 7,
+            "writerFeatures": ["inCommitTimestamp"],
+        })
+    );
+    let configuration = &commit0[2]["metaData"]["configuration"];
+    assert_eq!(configuration["delta.enableInCommitTimestamps"], "true");
+    assert_eq!(
+        configuration["delta.inCommitTimestampEnablementVersion"],
+        "0"
+    );
+    assert_eq!(
+        configuration["delta.inCommitTimestampEnablementTimestamp"],
+        in_commit_timestamp.to_string()
+    );
+
+    let snapshot = Snapshot::try_new(table_url, &engine, None)?;
+    assert_eq!(
+        snapshot
+            .get_in_commit_timestamp(&engine)?
+            .map(|ts| json!(ts)),
+        Some(in_commit_timestamp.clone())
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_column_mapping_write() -> Result<(), Box<dyn std::error::Error>> {
+    use delta_kernel::parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+    let _ = tracing_subscriber::fmt::try_init();
+    let (store, engine, table_url) = engine_store_setup("test_column_mapping_write", true);
+    let schema = Arc::new(StructType::new(vec![
+        StructField::nullable("number", DataType::INTEGER),
+        StructField::nullable("partition", DataType::STRING),
+    ]));
+    let mut txn = CreateTableBuilder::new(table_url.clone(), schema.clone())
+        .with_partition_columns(["partition"])
+        .with_table_properties([("delta.columnMapping.mode", "name")])
+        .build()?
+        .with_commit_info(new_commit_info()?);
+
+    // the engine writes data with the logical schema
+    let engine = Arc::new(engine);
+    let data = RecordBatch::try_new(
+        Arc::new(ArrowSchema::new(vec![Field::new(
+            "number",
+            ArrowDataType::Int32,
+            true,
+        )])),
+        vec![Arc::new(Int32Array::from(vec![1, 2, 3]))],
+    )?;
+    let partition_values = HashMap::from([("partition".to_string(), "a".to_string())]);
+    let add_metadata = engine
+        .write_parquet(
+            &ArrowEngineData::new(data),
+            &txn.get_write_context(),
+            partition_values,
+            true,
+        )
+        .await?;
+    txn.add_files(add_metadata);
+    assert!(matches!(
+        txn.commit(engine.as_ref())?,
+        CommitResult::Committed(0)
+    ));
+
+    let snapshot = Snapshot::try_new(table_url.clone(), engine.as_ref(), None)?;
+    let physical_name = |name: &str| {
+        snapshot
+            .schema()
+            .field(name)
+            .unwrap()
+            .physical_name()
+            .to_string()
+    };
+    let number = physical_name("number");
+    let partition = physical_name("partition");
+    assert_ne!(number, "number");
+    assert_ne!(partition, "partition");
+
+    // partition values and stats use physical names
+    let commits = parsed_commits_of(&store, "test_column_mapping_write", 0).await?;
+    let add = &commits[3]["add"];
+    assert_eq!(add["partitionValues"], json!({ partition: "a" }));
+    let stats: serde_json::Value = serde_json::from_str(add["stats"].as_str().unwrap())?;
+    assert_eq!(stats["minValues"], json!({ &number: 1 }));
+
+    // the parquet file uses physical names and field ids
+    let path = table_url.join(add["path"].as_str().unwrap())?;
+    let bytes = store.get(&Path::from(path.path())).await?.bytes().await?;
+    let reader = ParquetRecordBatchReaderBuilder::try_new(bytes)?;
+    let fields = reader.parquet_schema().root_schema().get_fields();
+    assert_eq!(fields.len(), 1);
+    assert_eq!(fields[0].name(), number);
+    assert_eq!(fields[0].get_basic_info().id(), 1);
+
+    let expected = RecordBatch::try_new(
+        Arc::new(schema.as_ref().try_into_arrow()?),
+        vec![
+            Arc::new(Int32Array::from(vec![1, 2, 3])),
+            Arc::new(StringArray::from(vec!["a"; 3])),
+        ],
+    )?;
+    test_read(&ArrowEngineData::new(expected), &table_url, engine)?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_delete_partitioned_column_mapping() -> Result<(), Box<dyn std::error::Error>> {
+    let _ = tracing_subscriber::fmt::try_init();
+    let table_name = "test_delete_partitioned_column_mapping";
+    let (store, engine, table_url) = engine_store_setup(table_name, true);
+    let schema = Arc::new(StructType::new(vec![
+        StructField::nullable("number", DataType::INTEGER),
+        StructField::nullable("partition", DataType::STRING),
+    ]));
+    let mut txn = CreateTableBuilder::new(table_url.clone(), schema.clone())
+        .with_partition_columns(["partition"])
+        .with_table_properties([
+            ("delta.columnMapping.mode", "name"),
+            ("delta.enableDeletionVectors", "true"),
+        ])
+        .build()?
+        .with_commit_info(new_commit_info()?);
+    let engine = Arc::new(engine);
+    let data = RecordBatch::try_new(
+        Arc::new(ArrowSchema::new(vec![Field::new(
+            "number",
+            ArrowDataType::Int32,
+            true,
+        )])),
+        vec![Arc::new(Int32Array::from(vec![1, 2, 3]))],
+    )?;
+    let partition_values = HashMap::from([("partition".to_string(), "a".to_string())]);
+    let add_metadata = engine
+        .write_parquet(
+            &ArrowEngineData::new(data),
+            &txn.get_write_context(),
+            partition_values,
+            true,
+        )
+        .await?;
+    txn.add_files(add_metadata);
+    txn.commit(engine.as_ref())?;
+
+    let snapshot = Arc::new(Snapshot::try_new(table_url.clone(), engine.as_ref(), None)?);
+    let partition = snapshot
+        .schema()
+        .field("partition")
+        .unwrap()
+        .physical_name()
+        .to_string();
+    assert_ne!(partition, "partition");
+    let mut txn = snapshot.transaction()?.with_commit_info(new_commit_info()?);
+    let predicate = Pred::eq(column_expr!("number"), Expr::literal(2));
+    assert_eq!(txn.delete(engine.as_ref(), Arc::new(predicate))?, 1);
+    txn.commit(engine.as_ref())?;
+
+    // the add and remove actions keep the partition values keyed by the physical name
+    let commit1 = parsed_commits_of(&store, table_name, 1).await?;
+    assert_eq!(commit1.len(), 3);
+    assert_eq!(
+        commit1[1]["add"]["partitionValues"],
+        json!({ &partition: "a" })
+    );
+    assert_eq!(
+        commit1[2]["remove"]["partitionValues"],
+        json!({ &partition: "a" })
+    );
+
+    let expected = RecordBatch::try_new(
+        Arc::new(schema.as_ref().try_into_arrow()?),
+        vec![
+            Arc::new(Int32Array::from(vec![1, 3])),
+            Arc::new(StringArray::from(vec!["a"; 2])),
+        ],
+    )?;
+    test_read(&ArrowEngineData::new(expected), &table_url, engine)?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_domain_metadata() -> Result<(), Box<dyn std::error::Error>> {
+    let _ = tracing_subscriber::fmt::try_init();
+    let schema = Arc::new(StructType::new(vec![StructField::nullable(
+        "number",
+        DataType::INTEGER,
+    )]));
+
+    for (table_url, engine, store, table_name) in setup_test_tables(schema, &[]).await? {
+        let snapshot = Arc::new(Snapshot::try_new(table_url.clone(), &engine, None)?);
+        let new_txn = || -> DeltaResult<_> {
+            Ok(snapshot
+                .clone()
+                .transaction()?
+                .with_commit_info(new_commit_info()?))
+        };
+
+        // system-controlled domains can't be changed, and a domain can only be changed once
+        let txn = new_txn()?.with_domain_metadata("delta.rowTracking".to_string(), "{}".into());
+        assert!(txn.commit(&engine).is_err());
+        let txn = new_txn()?
+            .with_domain_metadata("app".to_string(), "1".to_string())
+            .remove_domain_metadata("app".to_string());
+        assert!(txn.commit(&engine).is_err());
+        let txn = new_txn()?.remove_domain_metadata("app".to_string());
+        assert!(txn.commit(&engine).is_err());
+
+        // writing a domain adds the domainMetadata feature
+        let txn = new_txn()?.with_domain_metadata("app".to_string(), "1".to_string());
+        assert!(matches!(txn.commit(&engine)?, CommitResult::Committed(1)));
+        let commit1 = parsed_commits_of(&store, table_name, 1).await?;
+        let writer_features = &commit1[1]["protocol"]["writerFeatures"];
+        assert!(writer_features
+            .as_array()
+            .unwrap()
+            .contains(&json!("domainMetadata")));
+        assert_eq!(
+            commit1[2],
+            json!({
+                "domainMetadata": { "domain": "app", "configuration": "1", "removed": false }
+            })
+        );
+
+        // a concurrent write to the same domain conflicts, while writes to other domains are
+        // retried
+        let snapshot = Arc::new(Snapshot::try_new(table_url.clone(), &engine, None)?);
+        let new_txn = || -> DeltaResult<_> {
+            Ok(snapshot
+                .clone()
+                .transaction()?
+                .with_commit_info(new_commit_info()?))
+        };
+        let first = new_txn()?.with_domain_metadata("app".to_string(), "2".to_string());
+        let same_domain = new_txn()?.with_domain_metadata("app".to_string(), "3".to_string());
+        let other_domain = new_txn()?.with_domain_metadata("other".to_string(), "4".to_string());
+        assert!(matches!(first.commit(&engine)?, CommitResult::Committed(2)));
+        assert!(matches!(
+            same_domain.commit(&engine)?,
+            CommitResult::Conflict(_, 2)
+        ));
+        assert!(matches!(
+            other_domain.commit(&engine)?,
+            CommitResult::Committed(3)
+        ));
+        let snapshot = Snapshot::try_new(table_url.clone(), &engine, None)?;
+        assert_eq!(
+            snapshot.get_domain_metadata("app", &engine)?,
+            Some("2".to_string())
+        );
+        assert_eq!(
+            snapshot.get_domain_metadata("other", &engine)?,
+            Some("4".to_string())
+        );
+
+        // removing a domain writes a tombstone with its latest configuration
+        let txn = Arc::new(snapshot)
+            .transaction()?
+            .with_commit_info(new_commit_info()?)
+            .remove_domain_metadata("app".to_string());
+        assert!(matches!(txn.commit(&engine)?, CommitResult::Committed(4)));
+        let commit4 = parsed_commits_of(&store, table_name, 4).await?;
+        assert_eq!(
+            commit4[1],
+            json!({
+                "domainMetadata": { "domain": "app", "configuration": "2", "removed": true }
+            })
+        );
+        let snapshot = Snapshot::try_new(table_url, &engine, None)?;
+        assert_eq!(snapshot.get_domain_metadata("app", &engine)?, None);
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_row_tracking() -> Result<(), Box<dyn std::error::Error>> {
+    let _ = tracing_subscriber::fmt::try_init();
+    let (store, engine, table_url) = engine_store_setup("test_row_tracking", true);
+    let engine = Arc::new(engine);
+    let schema = Arc::new(StructType::new(vec![StructField::nullable(
+        "number",
+        DataType::INTEGER,
+    )]));
+    async fn write_data(
+        engine: &DefaultEngine<TokioBackgroundExecutor>,
+        txn: &mut Transaction,
+        numbers: Vec<i32>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let data = RecordBatch::try_new(
+            Arc::new(ArrowSchema::new(vec![Field::new(
+                "number",
+                ArrowDataType::Int32,
+                true,
+            )])),
+            vec![Arc::new(Int32Array::from(numbers))],
+        )?;
+        let add_metadata = engine
+            .write_parquet(
+                &ArrowEngineData::new(data),
+                &txn.get_write_context(),
+                HashMap::new(),
+                true,
+            )
+            .await?;
+        txn.add_files(add_metadata);
+        Ok(())
+    }
+    let row_tracking = |commit: &[serde_json::Value]| {
+        commit
+            .iter()
+            .filter_map(|action| {
+                let add = action.get("add")?;
+                Some((
+                    add["baseRowId"].clone(),
+                    add["defaultRowCommitVersion"].clone(),
+                ))
+            })
+            .collect_vec()
+    };
+    let high_water_mark = |commit: &[serde_json::Value]| {
+        let domain = commit
+            .iter()
+            .find_map(|action| action.get("domainMetadata"))
+            .unwrap();
+        assert_eq!(domain["domain"], "delta.rowTracking");
+        let configuration: serde_json::Value =
+            serde_json::from_str(domain["configuration"].as_str().unwrap()).unwrap();
+        configuration["rowIdHighWaterMark"].clone()
+    };
+
+    // creating the table assigns row ids starting at 0
+    let mut txn = CreateTableBuilder::new(table_url.clone(), schema.clone())
+        .with_table_properties([
+            ("delta.enableRowTracking", "true"),
+            ("delta.enableDeletionVectors", "true"),
+        ])
+        .build()?
+        .with_commit_info(new_commit_info()?);
+    write_data(&engine, &mut txn, vec![1, 2, 3]).await?;
+    assert!(matches!(
+        txn.commit(engine.as_ref())?,
+        CommitResult::Committed(0)
+    ));
+    let commit0 = parsed_commits_of(&store, "test_row_tracking", 0).await?;
+    let protocol = &commit0[1]["protocol"];
+    assert_eq!(
+        protocol["writerFeatures"],
+        json!(["deletionVectors", "rowTracking", "domainMetadata"])
+    );
+    let configuration = &commit0[2]["metaData"]["configuration"];
+    assert!(configuration["delta.rowTracking.materializedRowIdColumnName"].is_string());
+    assert!(configuration["delta.rowTracking.materializedRowCommitVersionColumnName"].is_string());
+    assert_eq!(row_tracking(&commit0), [(json!(0), json!(0))]);
+    assert_eq!(high_water_mark(&commit0), json!(2));
+
+    // later commits continue from the high-water mark
+    let snapshot = Arc::new(Snapshot::try_new(table_url.clone(), engine.as_ref(), None)?);
+    let mut txn = snapshot
+        .clone()
+        .transaction()?
+        .with_commit_info(new_commit_info()?);
+    write_data(&engine, &mut txn, vec![4, 5]).await?;
+    write_data(&engine, &mut txn, vec![6]).await?;
+    assert!(matches!(
+        txn.commit(engine.as_ref())?,
+        CommitResult::Committed(1)
+    ));
+    let commit1 = parsed_commits_of(&store, "test_row_tracking", 1).await?;
+    assert_eq!(
+        row_tracking(&commit1),
+        [(json!(3), json!(1)), (json!(5), json!(1))]
+    );
+    assert_eq!(high_water_mark(&commit1), json!(5));
+
+    // a concurrent append doesn't conflict, but is assigned row ids after the winning commit's
+    let mut txn = snapshot.transaction()?.with_commit_info(new_commit_info()?);
+    write_data(&engine, &mut txn, vec![7, 8]).await?;
+    assert!(matches!(
+        txn.commit(engine.as_ref())?,
+        CommitResult::Committed(2)
+    ));
+    let commit2 = parsed_commits_of(&store, "test_row_tracking", 2).await?;
+    assert_eq!(row_tracking(&commit2), [(json!(6), json!(2))]);
+    assert_eq!(high_water_mark(&commit2), json!(7));
+
+    // a commit without adds doesn't change the high-water mark
+    let snapshot = Arc::new(Snapshot::try_new(table_url.clone(), engine.as_ref(), None)?);
+    let txn = snapshot.transaction()?.with_commit_info(new_commit_info()?);
+    assert!(matches!(
+        txn.commit(engine.as_ref())?,
+        CommitResult::Committed(3)
+    ));
+    let commit3 = parsed_commits_of(&store, "test_row_tracking", 3).await?;
+    assert_eq!(commit3.len(), 1);
+
+    // deleting rows keeps the row ids and row commit versions of the remaining rows
+    let snapshot = Arc::new(Snapshot::try_new(table_url.clone(), engine.as_ref(), None)?);
+    let mut txn = snapshot.transaction()?.with_commit_info(new_commit_info()?);
+    let predicate = Pred::eq(column_expr!("number"), Expr::literal(2));
+    assert_eq!(txn.delete(engine.as_ref(), Arc::new(predicate))?, 1);
+    assert!(matches!(
+        txn.commit(engine.as_ref())?,
+        CommitResult::Committed(4)
+    ));
+    let commit4 = parsed_commits_of(&store, "test_row_tracking", 4).await?;
+    assert_eq!(row_tracking(&commit4), [(json!(0), json!(0))]);
+    assert!(commit4
+        .iter()
+        .all(|action| action.get("domainMetadata").is_none()));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_row_tracking_partitioned_column_mapping() -> Result<(), Box<dyn std::error::Error>> {
+    let _ = tracing_subscriber::fmt::try_init();
+    let table_name = "test_row_tracking_partitioned_column_mapping";
+    let (store, engine, table_url) = engine_store_setup(table_name, true);
+    let engine = Arc::new(engine);
+    let schema = Arc::new(StructType::new(vec![
+        StructField::nullable("number", DataType::INTEGER),
+        StructField::nullable("partition", DataType::STRING),
+    ]));
+    let mut txn = CreateTableBuilder::new(table_url.clone(), schema.clone())
+        .with_partition_columns(["partition"])
+        .with_table_properties([
+            ("delta.columnMapping.mode", "name"),
+            ("delta.enableRowTracking", "true"),
+        ])
+        .build()?
+        .with_commit_info(new_commit_info()?);
+    let data = RecordBatch::try_new(
+        Arc::new(ArrowSchema::new(vec![Field::new(
+            "number",
+            ArrowDataType::Int32,
+            true,
+        )])),
+        vec![Arc::new(Int32Array::from(vec![1, 2, 3]))],
+    )?;
+    let partition_values = HashMap::from([("partition".to_string(), "a".to_string())]);
+    let add_metadata = engine
+        .write_parquet(
+            &ArrowEngineData::new(data),
+            &txn.get_write_context(),
+            partition_values,
+            true,
+        )
+        .await?;
+    txn.add_files(add_metadata);
+    txn.commit(engine.as_ref())?;
+
+    // the add action keeps the partition values keyed by the physical name
+    let snapshot = Snapshot::try_new(table_url.clone(), engine.as_ref(), None)?;
+    let partition = snapshot
+        .schema()
+        .field("partition")
+        .unwrap()
+        .physical_name()
+        .to_string();
+    assert_ne!(partition, "partition");
+    let commit0 = parsed_commits_of(&store, table_name, 0).await?;
+    let add = commit0.iter().find_map(|action| action.get("add")).unwrap();
+    assert_eq!(add["partitionValues"], json!({ &partition: "a" }));
+    assert_eq!(add["baseRowId"], 0);
+
+    let expected = RecordBatch::try_new(
+        Arc::new(schema.as_ref().try_into_arrow()?),
+        vec![
+            Arc::new(Int32Array::from(vec![1, 2, 3])),
+            Arc::new(StringArray::from(vec!["a"; 3])),
+        ],
+    )?;
+    test_read(&ArrowEngineData::new(expected), &table_url, engine)?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_read_row_tracking_columns() -> Result<(), Box<dyn std::error::Error>> {
+    let _ = tracing_subscriber::fmt::try_init();
+    let (_store, engine, table_url) = engine_store_setup("test_read_row_tracking_columns", true);
+    let engine = Arc::new(engine);
+    let schema = Arc::new(StructType::new(vec![StructField::nullable(
+        "number",
+        DataType::INTEGER,
+    )]));
+    async fn write_data(
+        engine: &DefaultEngine<TokioBackgroundExecutor>,
+        txn: &mut Transaction,
+        numbers: Vec<i32>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let data = RecordBatch::try_new(
+            Arc::new(ArrowSchema::new(vec![Field::new(
+                "number",
+                ArrowDataType::Int32,
+                true,
+            )])),
+            vec![Arc::new(Int32Array::from(numbers))],
+        )?;
+        let add_metadata = engine
+            .write_parquet(
+                &ArrowEngineData::new(data),
+                &txn.get_write_context(),
+                HashMap::new(),
+                true,
+            )
+            .await?;
+        txn.add_files(add_metadata);
+        Ok(())
+    }
+    let read_schema = Arc::new(StructType::new(vec![
+        StructField::nullable("number", DataType::INTEGER),
+        StructField::create_metadata_column("row_id", MetadataColumnSpec::RowId),
+        StructField::create_metadata_column(
+            "row_commit_version",
+            MetadataColumnSpec::RowCommitVersion,
+        ),
+        StructField::create_metadata_column("row_index", MetadataColumnSpec::RowIndex),
+    ]));
+    let read_rows = || -> DeltaResult<Vec<(i32, i64, i64, i64)>> {
+        let snapshot = Snapshot::try_new(table_url.clone(), engine.as_ref(), None)?;
+        let scan = snapshot
+            .into_scan_builder()
+            .with_schema(read_schema.clone())
+            .build()?;
+        let mut rows = vec![];
+        for batch in read_scan(&scan, engine.clone())? {
+            let column = |i: usize| batch.column(i).as_primitive::<Int64Type>();
+            let numbers = batch.column(0).as_primitive::<Int32Type>();
+            for row in 0..batch.num_rows() {
+                rows.push((
+                    numbers.value(row),
+                    column(1).value(row),
+                    column(2).value(row),
+                    column(3).value(row),
+                ));
+            }
+        }
+        rows.sort();
+        Ok(rows)
+    };
+
+    let mut txn = CreateTableBuilder::new(table_url.clone(), schema.clone())
+        .with_table_properties([
+            ("delta.enableRowTracking", "true"),
+            ("delta.enableDeletionVectors", "true"),
+        ])
+        .build()?
+        .with_commit_info(new_commit_info()?);
+    write_data(&engine, &mut txn, vec![1, 2, 3]).await?;
+    txn.commit(engine.as_ref())?;
+    let snapshot = Arc::new(Snapshot::try_new(table_url.clone(), engine.as_ref(), None)?);
+    let mut txn = snapshot.transaction()?.with_commit_info(new_commit_info()?);
+    write_data(&engine, &mut txn, vec![4, 5]).await?;
+    txn.commit(engine.as_ref())?;
+
+    // row ids are the file's base row id plus the row index
+    assert_eq!(
+        read_rows()?,
+        [
+            (1, 0, 0, 0),
+            (2, 1, 0, 1),
+            (3, 2, 0, 2),
+            (4, 3, 1, 0),
+            (5, 4, 1, 1)
+        ]
+    );
+
+    // deleting a row doesn't change the row ids of the remaining rows
+    let snapshot = Arc::new(Snapshot::try_new(table_url.clone(), engine.as_ref(), None)?);
+    let mut txn = snapshot.transaction()?.with_commit_info(new_commit_info()?);
+    let predicate = Pred::eq(column_expr!("number"), Expr::literal(2));
+    assert_eq!(txn.delete(engine.as_ref(), Arc::new(predicate))?, 1);
+    txn.commit(engine.as_ref())?;
+    assert_eq!(
+        read_rows()?,
+        [(1, 0, 0, 0), (3, 2, 0, 2), (4, 3, 1, 0), (5, 4, 1, 1)]
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_read_row_tracking_columns_requires_row_tracking(
+) -> Result<(), Box<dyn std::error::Error>> {
+    let schema = Arc::new(StructType::new(vec![StructField::nullable(
+        "number",
+        DataType::INTEGER,
+    )]));
+    for (table_url, engine, _store, _table_name) in setup_test_tables(schema, &[]).await? {
+        let snapshot = Snapshot::try_new(table_url, &engine, None)?;
+        let read_schema = Arc::new(StructType::new(vec![StructField::create_metadata_column(
+            "row_id",
+            MetadataColumnSpec::RowId,
+        )]));
+        let result = snapshot
+            .into_scan_builder()
+            .with_schema(read_schema)
+            .build();
+        assert!(matches!(result, Err(KernelError::Unsupported(_))));
+    }
+    Ok(())
+}